@@ -1,15 +1,40 @@
+use std::sync::Arc;
 use sqlx::PgPool;
 use crate::config::Config;
+use crate::utils::{jwt::JwtKeys, storage::{self, StorageBackend}, token_denylist::TokenDenylist};
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub config: Config,
+    /// Access token signing and verification keys
+    pub jwt_keys: Arc<JwtKeys>,
+    /// Revoked access tokens (jti), kept in memory
+    pub token_denylist: Arc<TokenDenylist>,
+    /// Outbound HTTP client (connection pool shared by all handlers)
+    pub http: reqwest::Client,
+    /// Where uploaded receipts are kept
+    pub storage: Arc<dyn StorageBackend>,
 }
 
 impl AppState {
-    pub fn new(db: PgPool, config: Config) -> Self {
-        Self { db, config }
+    pub fn new(
+        db: PgPool,
+        config: Config,
+        jwt_keys: Arc<JwtKeys>,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> Self {
+        let http = reqwest::Client::new();
+        let storage = storage::from_config(&config.attachments.storage, http.clone());
+
+        Self {
+            db,
+            config,
+            jwt_keys,
+            token_denylist,
+            http,
+            storage,
+        }
     }
 }
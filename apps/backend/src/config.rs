@@ -1,10 +1,29 @@
-use std::env;
+use std::{env, str::FromStr};
+use chrono::{DateTime, Utc};
+use ipnet::IpNet;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub port: u16,
     pub database_url: String,
+    /// Externally reachable base URL of this API, used to build download links
+    pub public_url: String,
+    /// Reverse proxies whose X-Forwarded-For / X-Real-IP headers are believed; requests
+    /// from anywhere else are attributed to their socket address
+    pub trusted_proxies: Vec<IpNet>,
     pub jwt: JwtConfig,
+    pub login_protection: LoginProtectionConfig,
+    pub rate_limit: RateLimitConfig,
+    pub idempotency: IdempotencyConfig,
+    pub oidc_providers: Vec<OidcProviderConfig>,
+    pub export: ExportConfig,
+    pub account_deletion: AccountDeletionConfig,
+    pub exchange_rates: ExchangeRateConfig,
+    pub attachments: AttachmentConfig,
+    pub ocr: OcrConfig,
+    pub anomalies: AnomalyConfig,
+    pub messaging: MessagingConfig,
+    pub webhooks: WebhookConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +32,223 @@ pub struct JwtConfig {
     pub refresh_secret: String,
     pub access_expiry_seconds: i64,
     pub refresh_expiry_seconds: i64,
+    /// Directory of `<kid>.pem` private keys (RSA or Ed25519) for signing access tokens.
+    /// When unset, access tokens are signed with HS256 using `access_secret`.
+    pub keys_dir: Option<String>,
+    /// Key id used to sign new access tokens; required with `keys_dir`
+    pub active_kid: Option<String>,
+    /// Keys rotated out at the given time; they keep verifying tokens issued before it
+    /// until those tokens expire, then disappear from verification and the JWKS
+    pub retired_keys: Vec<(String, DateTime<Utc>)>,
+    /// How often each replica pulls revoked access tokens from the database
+    pub denylist_sync_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginProtectionConfig {
+    /// Consecutive failures before an account is locked
+    pub max_failed_attempts: i32,
+    /// Lockout duration for the first lock; doubles on every further failure
+    pub lockout_base_seconds: i64,
+    pub lockout_max_seconds: i64,
+    /// Failed attempts allowed from a single IP within the window
+    pub ip_max_failed_attempts: i64,
+    pub ip_window_seconds: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub backend: RateLimitBackendKind,
+    pub ip: RateLimitPolicy,
+    pub user: RateLimitPolicy,
+    pub api_key: RateLimitPolicy,
+}
+
+/// Where token buckets are stored; Postgres keeps limits consistent across replicas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitBackendKind {
+    Memory,
+    Postgres,
+}
+
+/// Token bucket policy: `burst` tokens, refilled at `per_minute` tokens per minute
+#[derive(Debug, Clone)]
+pub struct RateLimitPolicy {
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    pub enabled: bool,
+    /// How long a stored response is replayed for its key
+    pub ttl_seconds: i64,
+    /// Largest request body accepted together with an `Idempotency-Key`
+    pub max_request_bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    /// Directory where generated export archives are written
+    pub dir: String,
+    /// HMAC key for signing download links
+    pub signing_secret: String,
+    /// Lifetime of a signed download link
+    pub link_expiry_seconds: i64,
+    /// How long a finished archive stays downloadable
+    pub retention_seconds: i64,
+    /// Exports still queued or running after this long were interrupted (e.g. by a restart)
+    /// and are marked failed, so the user can request a new one
+    pub stale_seconds: i64,
+    /// How often interrupted exports and expired archives are cleaned up
+    pub cleanup_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountDeletionConfig {
+    /// Days a deleted account can still be restored by logging in
+    pub grace_period_days: i64,
+    /// How often the purge of expired accounts runs
+    pub purge_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExchangeRateConfig {
+    pub provider: ExchangeRateProviderKind,
+    /// How often rates are pulled from the provider
+    pub sync_interval_seconds: u64,
+    /// Oldest rate (in days before a transaction date) still used for conversion
+    pub max_age_days: i64,
+}
+
+/// Where exchange rates come from besides admin uploads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeRateProviderKind {
+    None,
+    /// CSV file (`date,base,quote,rate`) re-read on every sync
+    File { path: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct AttachmentConfig {
+    pub storage: StorageBackendKind,
+    /// Largest accepted receipt upload
+    pub max_bytes: usize,
+    /// Most receipts kept per transaction
+    pub max_per_transaction: i64,
+    /// HMAC key for signing download links
+    pub signing_secret: String,
+    /// Lifetime of a signed download link
+    pub link_expiry_seconds: i64,
+}
+
+/// Where uploaded files are kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageBackendKind {
+    /// A directory on the local filesystem
+    Local { dir: String },
+    /// An S3-compatible bucket (AWS S3, MinIO, ...)
+    S3(S3Config),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Config {
+    /// e.g. https://s3.eu-central-1.amazonaws.com or http://localhost:9000
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Address the bucket as `{endpoint}/{bucket}` rather than `{bucket}.{endpoint host}`;
+    /// needed by most self-hosted stores
+    pub path_style: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrConfig {
+    pub engine: OcrEngineKind,
+    /// Drafts with a lower overall confidence go to the review queue
+    pub review_threshold: f64,
+    /// How often the worker looks for queued receipts
+    pub poll_interval_seconds: u64,
+    /// OCR errors tolerated per receipt before its draft is marked failed
+    pub max_attempts: i32,
+    /// Wait before the first retry; doubles on every further one up to the maximum
+    pub backoff_base_seconds: i64,
+    pub backoff_max_seconds: i64,
+}
+
+/// What reads text from receipt images
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrEngineKind {
+    /// Receipts are stored but not read
+    None,
+    /// The `tesseract` command line tool with the given language packs (e.g. `ind+eng`)
+    Tesseract { command: String, languages: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Run the delivery worker; events are queued either way
+    pub enabled: bool,
+    /// How often the worker looks for due deliveries
+    pub poll_interval_seconds: u64,
+    pub timeout_seconds: u64,
+    /// Attempts before a delivery is marked failed
+    pub max_attempts: i32,
+    /// Wait before the first retry; doubles on every further one up to the maximum
+    pub backoff_base_seconds: i64,
+    pub backoff_max_seconds: i64,
+    /// Accept plain `http://` endpoint URLs (testing only; private and local hosts are refused either way)
+    pub allow_http: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    /// How often new transactions are scanned
+    pub scan_interval_seconds: u64,
+    /// How far back amounts are compared
+    pub history_days: i64,
+    /// Fewest earlier amounts needed before an amount can be called unusual
+    pub min_history: usize,
+    /// Standard deviations above the mean that make a category amount unusual
+    pub z_score: f64,
+    /// Multiple of the median amount that makes an amount unusual or large
+    pub large_amount_multiple: f64,
+    /// Identical transactions entered within this many seconds look like duplicates
+    pub duplicate_window_seconds: i64,
+    /// Tell the author about new anomalies through the messaging gateway (not for imports)
+    pub notify: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessagingConfig {
+    pub gateway: MessagingGatewayKind,
+}
+
+/// How outbound messages reach users' chat clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessagingGatewayKind {
+    /// Nothing is sent
+    None,
+    /// The chatbot service, which delivers a JSON message to the client's channel
+    Http { url: String, token: Option<String> },
+}
+
+/// OpenID Connect provider used for social login (authorization code + PKCE)
+#[derive(Debug, Clone)]
+pub struct OidcProviderConfig {
+    /// Name used in routes and stored on user identities (e.g., google)
+    pub name: String,
+    /// Issuer URL; metadata is discovered from `{issuer}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Dashboard URL the provider redirects back to with `code` and `state`
+    pub redirect_uri: String,
+    pub scopes: String,
 }
 
 impl Config {
@@ -25,6 +261,20 @@ impl Config {
         let database_url = env::var("DATABASE_URL")
             .map_err(|_| "DATABASE_URL must be set")?;
 
+        // TRUSTED_PROXIES=10.0.0.0/8,127.0.0.1 (a bare address is a single host)
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<std::net::IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("Invalid TRUSTED_PROXIES entry: {}", entry))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
         let jwt = JwtConfig {
             access_secret: env::var("JWT_ACCESS_SECRET")
                 .map_err(|_| "JWT_ACCESS_SECRET must be set")?,
@@ -38,12 +288,228 @@ impl Config {
                 .unwrap_or_else(|_| "604800".to_string())
                 .parse()
                 .map_err(|_| "Invalid JWT_REFRESH_EXPIRY_SECONDS")?,
+            keys_dir: env::var("JWT_KEYS_DIR").ok().filter(|dir| !dir.is_empty()),
+            active_kid: env::var("JWT_ACTIVE_KID").ok().filter(|kid| !kid.is_empty()),
+            // JWT_RETIRED_KEYS=2025-11=2025-12-07T00:00:00Z,2025-10=2025-11-01T00:00:00Z
+            retired_keys: env::var("JWT_RETIRED_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| {
+                    let (kid, retired_at) = entry
+                        .split_once('=')
+                        .ok_or("Invalid JWT_RETIRED_KEYS entry (expected kid=RFC3339)")?;
+                    let retired_at = DateTime::parse_from_rfc3339(retired_at)
+                        .map_err(|_| "Invalid JWT_RETIRED_KEYS timestamp")?;
+                    Ok((kid.to_string(), retired_at.with_timezone(&Utc)))
+                })
+                .collect::<Result<Vec<_>, &str>>()?,
+            denylist_sync_seconds: env_or("JWT_DENYLIST_SYNC_SECONDS", 15)?,
+        };
+
+        let login_protection = LoginProtectionConfig {
+            max_failed_attempts: env_or("LOGIN_MAX_FAILED_ATTEMPTS", 5)?,
+            lockout_base_seconds: env_or("LOGIN_LOCKOUT_BASE_SECONDS", 60)?,
+            lockout_max_seconds: env_or("LOGIN_LOCKOUT_MAX_SECONDS", 3600)?,
+            ip_max_failed_attempts: env_or("LOGIN_IP_MAX_FAILED_ATTEMPTS", 20)?,
+            ip_window_seconds: env_or("LOGIN_IP_WINDOW_SECONDS", 900)?,
+        };
+
+        let rate_limit = RateLimitConfig {
+            enabled: env_or("RATE_LIMIT_ENABLED", true)?,
+            backend: match env::var("RATE_LIMIT_BACKEND").as_deref() {
+                Ok("postgres") => RateLimitBackendKind::Postgres,
+                Ok("memory") | Err(_) => RateLimitBackendKind::Memory,
+                Ok(_) => return Err("Invalid RATE_LIMIT_BACKEND (expected memory or postgres)".to_string()),
+            },
+            ip: RateLimitPolicy {
+                burst: env_or("RATE_LIMIT_IP_BURST", 30)?,
+                per_minute: env_or("RATE_LIMIT_IP_PER_MINUTE", 60)?,
+            },
+            user: RateLimitPolicy {
+                burst: env_or("RATE_LIMIT_USER_BURST", 60)?,
+                per_minute: env_or("RATE_LIMIT_USER_PER_MINUTE", 120)?,
+            },
+            api_key: RateLimitPolicy {
+                burst: env_or("RATE_LIMIT_API_KEY_BURST", 100)?,
+                per_minute: env_or("RATE_LIMIT_API_KEY_PER_MINUTE", 300)?,
+            },
+        };
+
+        let idempotency = IdempotencyConfig {
+            enabled: env_or("IDEMPOTENCY_ENABLED", true)?,
+            ttl_seconds: env_or("IDEMPOTENCY_TTL_SECONDS", 86400)?,
+            max_request_bytes: env_or("IDEMPOTENCY_MAX_REQUEST_BYTES", 16 * 1024 * 1024)?,
+        };
+
+        // OIDC_PROVIDERS=google,okta → OIDC_GOOGLE_ISSUER_URL, OIDC_GOOGLE_CLIENT_ID, ...
+        let oidc_providers = env::var("OIDC_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(OidcProviderConfig::from_env)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let export = ExportConfig {
+            dir: env_or("EXPORT_DIR", "./exports".to_string())?,
+            signing_secret: env::var("EXPORT_SIGNING_SECRET")
+                .map_err(|_| "EXPORT_SIGNING_SECRET must be set".to_string())?,
+            link_expiry_seconds: env_or("EXPORT_LINK_EXPIRY_SECONDS", 3600)?,
+            retention_seconds: env_or("EXPORT_RETENTION_SECONDS", 604800)?,
+            stale_seconds: env_or("EXPORT_STALE_SECONDS", 1800)?,
+            cleanup_interval_seconds: env_or("EXPORT_CLEANUP_INTERVAL_SECONDS", 300)?,
+        };
+
+        let account_deletion = AccountDeletionConfig {
+            grace_period_days: env_or("ACCOUNT_DELETION_GRACE_DAYS", 30)?,
+            purge_interval_seconds: env_or("ACCOUNT_PURGE_INTERVAL_SECONDS", 3600)?,
+        };
+
+        let exchange_rates = ExchangeRateConfig {
+            provider: match env::var("EXCHANGE_RATE_PROVIDER").as_deref() {
+                Ok("file") => ExchangeRateProviderKind::File {
+                    path: env::var("EXCHANGE_RATE_FILE")
+                        .map_err(|_| "EXCHANGE_RATE_FILE must be set for the file provider".to_string())?,
+                },
+                Ok("none") | Ok("") | Err(_) => ExchangeRateProviderKind::None,
+                Ok(_) => return Err("Invalid EXCHANGE_RATE_PROVIDER (expected none or file)".to_string()),
+            },
+            sync_interval_seconds: env_or("EXCHANGE_RATE_SYNC_SECONDS", 3600)?,
+            max_age_days: env_or("EXCHANGE_RATE_MAX_AGE_DAYS", 7)?,
+        };
+
+        let attachments = AttachmentConfig {
+            storage: match env::var("STORAGE_BACKEND").as_deref() {
+                Ok("s3") => StorageBackendKind::S3(S3Config::from_env()?),
+                Ok("local") | Ok("") | Err(_) => StorageBackendKind::Local {
+                    dir: env_or("STORAGE_DIR", "./uploads".to_string())?,
+                },
+                Ok(_) => return Err("Invalid STORAGE_BACKEND (expected local or s3)".to_string()),
+            },
+            max_bytes: env_or("ATTACHMENT_MAX_BYTES", 10 * 1024 * 1024)?,
+            max_per_transaction: env_or("ATTACHMENT_MAX_PER_TRANSACTION", 10)?,
+            // Falls back to the export link key so existing deployments keep starting
+            signing_secret: env_or("ATTACHMENT_SIGNING_SECRET", export.signing_secret.clone())?,
+            link_expiry_seconds: env_or("ATTACHMENT_LINK_EXPIRY_SECONDS", 900)?,
+        };
+
+        let ocr = OcrConfig {
+            engine: match env::var("OCR_ENGINE").as_deref() {
+                Ok("tesseract") => OcrEngineKind::Tesseract {
+                    command: env_or("TESSERACT_COMMAND", "tesseract".to_string())?,
+                    languages: env_or("TESSERACT_LANGUAGES", "ind+eng".to_string())?,
+                },
+                Ok("none") | Ok("") | Err(_) => OcrEngineKind::None,
+                Ok(_) => return Err("Invalid OCR_ENGINE (expected none or tesseract)".to_string()),
+            },
+            review_threshold: env_or("OCR_REVIEW_THRESHOLD", 0.8)?,
+            poll_interval_seconds: env_or("OCR_POLL_SECONDS", 5)?,
+            max_attempts: env_or("OCR_MAX_ATTEMPTS", 3)?,
+            backoff_base_seconds: env_or("OCR_BACKOFF_BASE_SECONDS", 60)?,
+            backoff_max_seconds: env_or("OCR_BACKOFF_MAX_SECONDS", 3600)?,
+        };
+
+        let anomalies = AnomalyConfig {
+            enabled: env_or("ANOMALY_DETECTION_ENABLED", true)?,
+            scan_interval_seconds: env_or("ANOMALY_SCAN_SECONDS", 60)?,
+            history_days: env_or("ANOMALY_HISTORY_DAYS", 180)?,
+            min_history: env_or("ANOMALY_MIN_HISTORY", 5)?,
+            z_score: env_or("ANOMALY_Z_SCORE", 3.0)?,
+            large_amount_multiple: env_or("ANOMALY_LARGE_AMOUNT_MULTIPLE", 3.0)?,
+            duplicate_window_seconds: env_or("ANOMALY_DUPLICATE_WINDOW_SECONDS", 600)?,
+            notify: env_or("ANOMALY_NOTIFY", false)?,
+        };
+
+        let webhooks = WebhookConfig {
+            enabled: env_or("WEBHOOKS_ENABLED", true)?,
+            poll_interval_seconds: env_or("WEBHOOK_POLL_SECONDS", 10)?,
+            timeout_seconds: env_or("WEBHOOK_TIMEOUT_SECONDS", 10)?,
+            max_attempts: env_or("WEBHOOK_MAX_ATTEMPTS", 8)?,
+            backoff_base_seconds: env_or("WEBHOOK_BACKOFF_BASE_SECONDS", 30)?,
+            backoff_max_seconds: env_or("WEBHOOK_BACKOFF_MAX_SECONDS", 21600)?,
+            allow_http: env_or("WEBHOOK_ALLOW_HTTP", false)?,
+        };
+
+        let messaging = MessagingConfig {
+            gateway: match env::var("MESSAGING_GATEWAY").as_deref() {
+                Ok("http") => MessagingGatewayKind::Http {
+                    url: env::var("MESSAGING_GATEWAY_URL")
+                        .map_err(|_| "MESSAGING_GATEWAY_URL must be set when MESSAGING_GATEWAY=http")?,
+                    token: env::var("MESSAGING_GATEWAY_TOKEN").ok().filter(|token| !token.is_empty()),
+                },
+                Ok("none") | Ok("") | Err(_) => MessagingGatewayKind::None,
+                Ok(_) => return Err("Invalid MESSAGING_GATEWAY (expected none or http)".to_string()),
+            },
         };
 
         Ok(Config {
             port,
             database_url,
+            public_url: env_or("PUBLIC_URL", format!("http://localhost:{}", port))?,
+            trusted_proxies,
             jwt,
+            login_protection,
+            rate_limit,
+            idempotency,
+            oidc_providers,
+            export,
+            account_deletion,
+            exchange_rates,
+            attachments,
+            ocr,
+            anomalies,
+            messaging,
+            webhooks,
+        })
+    }
+
+    /// Look up a configured OIDC provider by name
+    pub fn oidc_provider(&self, name: &str) -> Option<&OidcProviderConfig> {
+        self.oidc_providers.iter().find(|provider| provider.name == name)
+    }
+}
+
+impl OidcProviderConfig {
+    fn from_env(name: &str) -> Result<Self, String> {
+        let prefix = format!("OIDC_{}", name.to_uppercase());
+        let required = |key: &str| {
+            env::var(format!("{}_{}", prefix, key))
+                .map_err(|_| format!("{}_{} must be set", prefix, key))
+        };
+
+        Ok(OidcProviderConfig {
+            name: name.to_lowercase(),
+            issuer_url: required("ISSUER_URL")?,
+            client_id: required("CLIENT_ID")?,
+            client_secret: required("CLIENT_SECRET")?,
+            redirect_uri: required("REDIRECT_URI")?,
+            scopes: env_or(&format!("{}_SCOPES", prefix), "openid email profile".to_string())?,
         })
     }
 }
+
+impl S3Config {
+    fn from_env() -> Result<Self, String> {
+        let required = |key: &str| env::var(key).map_err(|_| format!("{} must be set for the s3 backend", key));
+        let region = env_or("S3_REGION", "us-east-1".to_string())?;
+
+        Ok(S3Config {
+            endpoint: env_or("S3_ENDPOINT", format!("https://s3.{}.amazonaws.com", region))?,
+            bucket: required("S3_BUCKET")?,
+            region,
+            access_key_id: required("S3_ACCESS_KEY_ID")?,
+            secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+            path_style: env_or("S3_PATH_STYLE", false)?,
+        })
+    }
+}
+
+/// Read an optional environment variable, falling back to `default` when unset
+fn env_or<T: FromStr>(key: &str, default: T) -> Result<T, String> {
+    match env::var(key) {
+        Ok(value) => value.parse().map_err(|_| format!("Invalid {}", key)),
+        Err(_) => Ok(default),
+    }
+}
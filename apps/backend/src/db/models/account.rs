@@ -0,0 +1,56 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::{Currency, TransactionType};
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct Account {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub r#type: AccountType,
+    /// Transactions paid with this payment method are booked to the account
+    pub payment_method_id: Option<Uuid>,
+    pub currency: Currency,
+    pub opening_balance: Decimal,
+    pub is_archived: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// An account with its current balance
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct AccountWithBalance {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub account: Account,
+    pub balance: Decimal,
+}
+
+/// One transaction as it affects an account, with the balance right after it
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct AccountEntry {
+    pub transaction_id: Uuid,
+    pub transaction_date: NaiveDate,
+    pub r#type: TransactionType,
+    /// Positive when money came into the account
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub merchant_name: Option<String>,
+    pub description: Option<String>,
+    /// The other account of a transfer
+    pub counter_account_id: Option<Uuid>,
+    pub running_balance: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    Cash,
+    Bank,
+    DigitalWallet,
+    CreditCard,
+    Other,
+}
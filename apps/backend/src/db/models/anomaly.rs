@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::Currency;
+
+/// A transaction flagged as unusual for its ledger
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TransactionAnomaly {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub kind: AnomalyKind,
+    /// Figures behind the flag, depending on the kind
+    pub details: sqlx::types::JsonValue,
+    /// The earlier transaction a possible duplicate repeats
+    pub related_transaction_id: Option<Uuid>,
+    pub status: AnomalyStatus,
+    pub dismissed_by: Option<Uuid>,
+    pub dismissed_at: Option<NaiveDateTime>,
+    /// When the author was told through their messaging channel
+    pub notified_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Far above the amounts usually recorded in the category
+    UnusualAmount,
+    /// First transaction with a merchant, and large for the ledger
+    NewMerchantLargeAmount,
+    /// Same amount and merchant entered minutes after another transaction
+    PossibleDuplicate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyStatus {
+    Open,
+    /// Checked by a user and found to be fine
+    Dismissed,
+}
+
+/// An anomaly with the transaction it flags, for listings
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct AnomalyWithTransaction {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub anomaly: TransactionAnomaly,
+    pub transaction_date: NaiveDate,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub merchant_name: Option<String>,
+    pub category_id: Option<Uuid>,
+}
+
+/// Fields for recording an anomaly
+#[derive(Debug, Clone)]
+pub struct NewTransactionAnomaly {
+    pub transaction_id: Uuid,
+    pub kind: AnomalyKind,
+    pub details: sqlx::types::JsonValue,
+    pub related_transaction_id: Option<Uuid>,
+}
@@ -1,13 +1,15 @@
 use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct ApiKey {
     pub id: Uuid,
     pub user_id: Uuid,
     pub name: String,
     pub key_prefix: String,
+    #[serde(skip_serializing)]
     pub key_hash: String,
     pub scopes: JsonValue,
     pub last_used_at: Option<NaiveDateTime>,
@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A receipt uploaded for a transaction; the file is in the storage backend under `storage_key`
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TransactionAttachment {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub uploaded_by: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Fields for recording an uploaded file
+#[derive(Debug, Clone)]
+pub struct NewTransactionAttachment {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub uploaded_by: Uuid,
+    pub storage_key: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+}
@@ -1,7 +1,8 @@
 use chrono::NaiveDateTime;
+use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct AuditLog {
     pub id: Uuid,
     pub user_id: Option<Uuid>,
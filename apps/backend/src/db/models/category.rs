@@ -1,10 +1,13 @@
 use chrono::NaiveDateTime;
+use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct Category {
     pub id: Uuid,
     pub user_id: Option<Uuid>,
+    /// Shared ledger the category belongs to
+    pub workspace_id: Option<Uuid>,
     pub name: String,
     pub r#type: CategoryType,
     pub icon: Option<String>,
@@ -16,15 +19,16 @@ pub struct Category {
     pub updated_at: NaiveDateTime,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum CategoryType {
     Income,
     Expense,
     Both,
 }
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct CategoryAlias {
     pub id: Uuid,
     pub category_id: Uuid,
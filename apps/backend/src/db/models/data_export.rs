@@ -0,0 +1,37 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: ExportStatus,
+    pub file_path: Option<String>,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+}
+
+impl DataExport {
+    /// Whether the archive exists and may still be downloaded
+    pub fn is_downloadable(&self) -> bool {
+        let now = Utc::now().naive_utc();
+
+        self.status == ExportStatus::Completed
+            && self.file_path.is_some()
+            && self.expires_at.map(|exp| exp > now).unwrap_or(false)
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::Currency;
+
+/// A debt or settle-up between two users, or between a user and a contact who is not a user.
+/// Entries between two users are visible to both of them; when one deletes their account
+/// the other keeps the entry.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct Debt {
+    pub id: Uuid,
+    /// Always one of the parties; the only one who can delete the entry. None once their
+    /// account is deleted, after which the remaining party can delete it
+    pub created_by: Option<Uuid>,
+    pub kind: DebtKind,
+    /// None when the creditor is the contact or a deleted user
+    pub creditor_user_id: Option<Uuid>,
+    /// None when the debtor is the contact or a deleted user
+    pub debtor_user_id: Option<Uuid>,
+    pub contact_name: Option<String>,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub description: Option<String>,
+    pub entry_date: NaiveDate,
+    /// Transaction of the creator the entry belongs to
+    pub transaction_id: Option<Uuid>,
+    /// Set when the party who did not record the entry disputes it
+    pub disputed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Debt {
+    /// Which way the entry goes as seen by `user_id`
+    pub fn direction_for(&self, user_id: Uuid) -> DebtDirection {
+        if self.creditor_user_id == Some(user_id) {
+            DebtDirection::ToMe
+        } else {
+            DebtDirection::FromMe
+        }
+    }
+
+    /// The other party's user ID, or None when it is the contact or a deleted user
+    pub fn counterparty_user_id(&self, user_id: Uuid) -> Option<Uuid> {
+        match self.direction_for(user_id) {
+            DebtDirection::ToMe => self.debtor_user_id,
+            DebtDirection::FromMe => self.creditor_user_id,
+        }
+    }
+
+    /// What the entry adds to the amount the counterparty owes `user_id`; negative when it
+    /// adds to what `user_id` owes them. Disputed entries do not count.
+    pub fn balance_effect(&self, user_id: Uuid) -> Decimal {
+        if self.disputed_at.is_some() {
+            return Decimal::ZERO;
+        }
+
+        match (&self.kind, self.direction_for(user_id)) {
+            (DebtKind::Debt, DebtDirection::ToMe) | (DebtKind::Settlement, DebtDirection::FromMe) => self.amount,
+            (DebtKind::Debt, DebtDirection::FromMe) | (DebtKind::Settlement, DebtDirection::ToMe) => -self.amount,
+        }
+    }
+}
+
+/// Fields of a debt entry to insert
+#[derive(Debug, Clone)]
+pub struct NewDebt {
+    pub created_by: Uuid,
+    pub kind: DebtKind,
+    pub creditor_user_id: Option<Uuid>,
+    pub debtor_user_id: Option<Uuid>,
+    pub contact_name: Option<String>,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub description: Option<String>,
+    pub entry_date: NaiveDate,
+    pub transaction_id: Option<Uuid>,
+}
+
+/// A debt entry with the display names of its user parties (their name when the entry was
+/// recorded if their account is gone)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DebtWithParties {
+    #[sqlx(flatten)]
+    pub debt: Debt,
+    pub creditor_name: Option<String>,
+    pub debtor_name: Option<String>,
+}
+
+/// What a user and one counterparty owe each other in one currency
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct DebtBalance {
+    /// None for a contact
+    pub counterparty_user_id: Option<Uuid>,
+    /// The user's full name, or the contact name
+    pub counterparty_name: String,
+    pub currency: Currency,
+    /// Positive when the counterparty owes the user, negative when the user owes them
+    pub balance: Decimal,
+    pub entry_count: i64,
+    pub last_entry_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DebtKind {
+    /// The debtor owes the creditor
+    Debt,
+    /// The debtor paid the creditor back
+    Settlement,
+}
+
+/// Direction of a debt entry relative to the user looking at it: money owed or paid to
+/// them, or by them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebtDirection {
+    ToMe,
+    FromMe,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn debt(kind: DebtKind, creditor: Option<Uuid>, debtor: Option<Uuid>) -> Debt {
+        let now = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        Debt {
+            id: Uuid::new_v4(),
+            created_by: creditor.or(debtor),
+            kind,
+            creditor_user_id: creditor,
+            debtor_user_id: debtor,
+            contact_name: None,
+            amount: Decimal::from_str("75000").unwrap(),
+            currency: Currency::IDR,
+            description: None,
+            entry_date: now.date(),
+            transaction_id: None,
+            disputed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_direction_and_balance_effect_per_party() {
+        let (ana, budi) = (Uuid::new_v4(), Uuid::new_v4());
+        let amount = Decimal::from_str("75000").unwrap();
+
+        // Budi owes Ana for dinner
+        let dinner = debt(DebtKind::Debt, Some(ana), Some(budi));
+        assert_eq!(dinner.direction_for(ana), DebtDirection::ToMe);
+        assert_eq!(dinner.direction_for(budi), DebtDirection::FromMe);
+        assert_eq!(dinner.counterparty_user_id(ana), Some(budi));
+        assert_eq!(dinner.counterparty_user_id(budi), Some(ana));
+        assert_eq!(dinner.balance_effect(ana), amount);
+        assert_eq!(dinner.balance_effect(budi), -amount);
+
+        // Budi pays Ana back, cancelling the dinner out
+        let settle_up = debt(DebtKind::Settlement, Some(ana), Some(budi));
+        assert_eq!(dinner.balance_effect(ana) + settle_up.balance_effect(ana), Decimal::ZERO);
+        assert_eq!(dinner.balance_effect(budi) + settle_up.balance_effect(budi), Decimal::ZERO);
+
+        // Ana owes a contact
+        let loan = debt(DebtKind::Debt, None, Some(ana));
+        assert_eq!(loan.direction_for(ana), DebtDirection::FromMe);
+        assert_eq!(loan.counterparty_user_id(ana), None);
+        assert_eq!(loan.balance_effect(ana), -amount);
+
+        // Budi disputes the dinner; it no longer counts for either of them
+        let disputed = Debt { disputed_at: Some(dinner.created_at), ..dinner };
+        assert_eq!(disputed.balance_effect(ana), Decimal::ZERO);
+        assert_eq!(disputed.balance_effect(budi), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+use super::Currency;
+
+/// `rate` units of `quote_currency` buy one unit of `base_currency` on `rate_date`
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct ExchangeRate {
+    pub id: Uuid,
+    pub base_currency: Currency,
+    pub quote_currency: Currency,
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+    pub source: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A rate to store, from an admin upload or a rate provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateQuote {
+    pub base_currency: Currency,
+    pub quote_currency: Currency,
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+}
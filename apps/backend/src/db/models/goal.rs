@@ -0,0 +1,43 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+use super::Currency;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct Goal {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub target_amount: Decimal,
+    pub currency: Currency,
+    pub start_date: NaiveDate,
+    pub deadline: NaiveDate,
+    /// Savings account whose net inflow since the start date counts towards the goal
+    pub account_id: Option<Uuid>,
+    /// Transactions with this tag since the start date count towards the goal
+    pub tag_id: Option<Uuid>,
+    pub is_archived: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A goal with the amount saved towards it so far
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct GoalWithSaved {
+    #[sqlx(flatten)]
+    pub goal: Goal,
+    pub saved: Decimal,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct GoalContribution {
+    pub id: Uuid,
+    pub goal_id: Uuid,
+    /// Negative for a withdrawal
+    pub amount: Decimal,
+    pub contribution_date: NaiveDate,
+    pub transaction_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+}
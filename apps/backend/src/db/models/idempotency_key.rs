@@ -0,0 +1,11 @@
+/// Stored outcome of a request made with an `Idempotency-Key`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    /// Unset while the first request is still being processed
+    pub status_code: Option<i16>,
+    /// `[name, value]` pairs
+    pub response_headers: Option<sqlx::types::JsonValue>,
+    /// Unset for completed requests whose response is not replayed (`no-store`)
+    pub response_body: Option<Vec<u8>>,
+}
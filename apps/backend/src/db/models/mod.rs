@@ -9,16 +9,50 @@ pub mod conversation;
 pub mod audit_log;
 pub mod refresh_token;
 pub mod api_key;
+pub mod user_identity;
+pub mod data_export;
+pub mod currency;
+pub mod exchange_rate;
+pub mod account;
+pub mod debt;
+pub mod goal;
+pub mod workspace;
+pub mod attachment;
+pub mod receipt_draft;
+pub mod anomaly;
+pub mod idempotency_key;
+pub mod webhook;
 
 // Re-export commonly used types
 pub use user::{SafeUser, User, UserRole, UserStatus};
 pub use client::{Client, Channel};
 pub use category::{Category, CategoryAlias, CategoryType};
 pub use payment_method::{PaymentMethod, PaymentMethodType};
-pub use transaction::{Transaction, TransactionType, TransactionSource};
+pub use transaction::{
+    NewTransaction, ReconciliationCandidate, Transaction, TransactionExportRow, TransactionFilter,
+    NewTransactionSplit, TransactionSearchHit, TransactionSplit, TransactionSummaryRow,
+    TransactionTimeseriesRow, TransactionType, TransactionSource,
+};
 pub use merchant::Merchant;
 pub use tag::{Tag, TransactionTag};
 pub use conversation::{Conversation, MessageDirection};
 pub use audit_log::AuditLog;
 pub use refresh_token::RefreshToken;
 pub use api_key::ApiKey;
+pub use user_identity::{OidcLoginState, UserIdentity};
+pub use data_export::{DataExport, ExportStatus};
+pub use currency::Currency;
+pub use exchange_rate::{ExchangeRate, RateQuote};
+pub use account::{Account, AccountEntry, AccountType, AccountWithBalance};
+pub use debt::{Debt, DebtBalance, DebtDirection, DebtKind, DebtWithParties, NewDebt};
+pub use goal::{Goal, GoalContribution, GoalWithSaved};
+pub use workspace::{LedgerScope, Workspace, WorkspaceMember, WorkspaceRole, WorkspaceWithRole};
+pub use attachment::{NewTransactionAttachment, TransactionAttachment};
+pub use receipt_draft::{ReceiptDraft, ReceiptDraftResult, ReceiptDraftStatus, ReceiptOcrJob};
+pub use anomaly::{
+    AnomalyKind, AnomalyStatus, AnomalyWithTransaction, NewTransactionAnomaly, TransactionAnomaly,
+};
+pub use idempotency_key::IdempotencyRecord;
+pub use webhook::{
+    WebhookAttempt, WebhookDelivery, WebhookDeliveryStatus, WebhookEndpoint, WebhookEvent, WebhookJob,
+};
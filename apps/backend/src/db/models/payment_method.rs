@@ -1,7 +1,8 @@
 use chrono::NaiveDateTime;
+use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct PaymentMethod {
     pub id: Uuid,
     pub user_id: Option<Uuid>,
@@ -14,8 +15,9 @@ pub struct PaymentMethod {
     pub updated_at: NaiveDateTime,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
-#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum PaymentMethodType {
     Cash,
     Card,
@@ -0,0 +1,82 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::Currency;
+
+/// Transaction values read from a receipt image, to be confirmed onto the transaction the
+/// receipt is attached to
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct ReceiptDraft {
+    pub id: Uuid,
+    pub attachment_id: Uuid,
+    pub transaction_id: Uuid,
+    pub status: ReceiptDraftStatus,
+    pub attempts: i32,
+    /// OCR engine that read the receipt
+    pub engine: Option<String>,
+    pub ocr_text: Option<String>,
+    pub amount: Option<Decimal>,
+    pub currency: Option<Currency>,
+    pub transaction_date: Option<NaiveDate>,
+    pub merchant_name: Option<String>,
+    /// Between 0 and 1; drafts below the review threshold need a person to check them
+    pub confidence: Option<f64>,
+    /// `{"amount": .., "transaction_date": .., "merchant_name": ..}`
+    pub field_confidence: Option<sqlx::types::JsonValue>,
+    pub error: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub processed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptDraftStatus {
+    /// Waiting for OCR
+    Pending,
+    /// Taken by a worker
+    Processing,
+    /// Read with enough confidence to confirm as is
+    Ready,
+    /// Read with low confidence; waiting in the review queue
+    NeedsReview,
+    /// OCR gave up after repeated errors
+    Failed,
+    /// Applied to the transaction
+    Confirmed,
+    Dismissed,
+}
+
+impl ReceiptDraftStatus {
+    /// Whether a user can still confirm or dismiss the draft
+    pub fn is_reviewable(self) -> bool {
+        matches!(self, Self::Ready | Self::NeedsReview)
+    }
+}
+
+/// A draft taken off the queue by an OCR worker, with where its image is stored
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReceiptOcrJob {
+    pub draft_id: Uuid,
+    pub attempts: i32,
+    pub storage_key: String,
+    /// Currency of the transaction, used when the receipt does not name one
+    pub transaction_currency: Currency,
+}
+
+/// What OCR produced for a draft
+#[derive(Debug, Clone)]
+pub struct ReceiptDraftResult {
+    pub status: ReceiptDraftStatus,
+    pub engine: String,
+    pub ocr_text: String,
+    pub amount: Option<Decimal>,
+    pub currency: Currency,
+    pub transaction_date: Option<NaiveDate>,
+    pub merchant_name: Option<String>,
+    pub confidence: f64,
+    pub field_confidence: sqlx::types::JsonValue,
+}
@@ -1,16 +1,19 @@
 use chrono::NaiveDateTime;
+use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct Tag {
     pub id: Uuid,
     pub user_id: Uuid,
+    /// Shared ledger the tag belongs to
+    pub workspace_id: Option<Uuid>,
     pub name: String,
     pub color: Option<String>,
     pub created_at: NaiveDateTime,
 }
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct TransactionTag {
     pub transaction_id: Uuid,
     pub tag_id: Uuid,
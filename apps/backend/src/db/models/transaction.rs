@@ -1,21 +1,32 @@
 use chrono::{NaiveDateTime, NaiveDate};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use super::Currency;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct Transaction {
     pub id: Uuid,
+    /// The author; also the owner of the ledger unless `workspace_id` is set
     pub user_id: Uuid,
+    /// Shared ledger the transaction belongs to
+    pub workspace_id: Option<Uuid>,
     pub r#type: TransactionType,
     pub amount: Decimal,
-    pub currency: String,
+    pub currency: Currency,
     pub category_id: Option<Uuid>,
     pub payment_method_id: Option<Uuid>,
+    /// Account credited (income) or debited (expense, transfer source)
+    pub account_id: Option<Uuid>,
+    /// Account credited by a transfer
+    pub transfer_account_id: Option<Uuid>,
     pub merchant_name: Option<String>,
     pub location: Option<String>,
     pub description: Option<String>,
     pub transaction_date: NaiveDate,
     pub source: TransactionSource,
+    /// Chat client and message the transaction was sent in; a message is recorded once
+    pub client_id: Option<Uuid>,
     pub source_message_id: Option<String>,
     pub attachment_urls: Option<sqlx::types::JsonValue>,
     pub metadata: Option<sqlx::types::JsonValue>,
@@ -24,15 +35,151 @@ pub struct Transaction {
     pub deleted_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
+/// Fields for inserting a transaction
+#[derive(Debug, Clone)]
+pub struct NewTransaction {
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+    /// When unset, the account linked to the payment method (if any) is used
+    pub account_id: Option<Uuid>,
+    /// Destination of a transfer
+    pub transfer_account_id: Option<Uuid>,
+    pub merchant_name: Option<String>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub transaction_date: NaiveDate,
+    pub source: TransactionSource,
+    /// Chat client and message the transaction was sent in; a message is recorded once
+    pub client_id: Option<Uuid>,
+    pub source_message_id: Option<String>,
+    pub metadata: Option<sqlx::types::JsonValue>,
+}
+
+/// Optional criteria for selecting a user's transactions
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub r#type: Option<TransactionType>,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+}
+
+/// A transaction matching a full-text search, with how well it matched
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TransactionSearchHit {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    /// Higher is a better match; merchant matches weigh most
+    pub rank: f32,
+}
+
+/// A transaction with category, payment method and tag names resolved, for exports
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransactionExportRow {
+    pub id: Uuid,
+    pub transaction_date: NaiveDate,
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub account: Option<String>,
+    /// Destination account of a transfer
+    pub transfer_account: Option<String>,
+    pub merchant_name: Option<String>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    /// Tag names joined with `;`
+    pub tags: Option<String>,
+    pub source: TransactionSource,
+    pub created_at: NaiveDateTime,
+}
+
+/// An unreconciled WhatsApp-entered transaction that a bank statement entry may match
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReconciliationCandidate {
+    pub id: Uuid,
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub transaction_date: NaiveDate,
+}
+
+/// One category line of a split transaction
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TransactionSplit {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub position: i16,
+    pub category_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A split line to store; lines are numbered in the order given
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewTransactionSplit {
+    pub category_id: Uuid,
+    pub amount: Decimal,
+    pub description: Option<String>,
+}
+
+/// Total of a user's transactions sharing type, currency, date and category, for reports.
+/// Split transactions contribute each line to its own category
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransactionSummaryRow {
+    pub r#type: TransactionType,
+    pub currency: Currency,
+    pub transaction_date: NaiveDate,
+    pub category_id: Option<Uuid>,
+    pub category_name: Option<String>,
+    pub total: Decimal,
+    /// Transactions with (a line in) this category
+    pub count: i64,
+    /// Transactions counted once across categories: unsplit ones and split ones by
+    /// their first line
+    pub transaction_count: i64,
+}
+
+/// One bucket of a time-series report series, amounts in the base currency
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransactionTimeseriesRow {
+    pub bucket_start: NaiveDate,
+    /// Category or tag of the series; `None` for uncategorized or untagged amounts, or
+    /// when the report is not grouped
+    pub series_id: Option<Uuid>,
+    pub series_name: Option<String>,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub transaction_count: i64,
+    /// Average over this bucket and the ones before it, per the report window
+    pub income_moving_average: Decimal,
+    pub expense_moving_average: Decimal,
+    /// Percentage change from the previous bucket; `None` when that was zero
+    pub income_change_pct: Option<Decimal>,
+    pub expense_change_pct: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum TransactionType {
     Income,
     Expense,
+    /// Money moved between two of the user's accounts; neither income nor expense
+    Transfer,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum TransactionSource {
     Whatsapp,
     Web,
@@ -1,6 +1,7 @@
-use chrono::NaiveDateTime;
-use serde::Serialize;
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use super::Currency;
 
 /// User account model
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -12,12 +13,18 @@ pub struct User {
     pub password_hash: Option<String>,
     pub role: UserRole,
     pub status: UserStatus,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    pub token_version: i32,
+    /// Currency that reports convert amounts into
+    pub base_currency: Currency,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub purge_after: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
@@ -25,7 +32,7 @@ pub enum UserRole {
     User,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum UserStatus {
@@ -39,10 +46,25 @@ impl User {
         self.deleted_at.is_none() && self.status == UserStatus::Active
     }
 
+    /// Deleted by the user and still within the grace period (login restores it)
+    pub fn is_pending_deletion(&self) -> bool {
+        self.status == UserStatus::Deleted
+            && self.purge_after.is_some_and(|purge_after| purge_after > Utc::now().naive_utc())
+    }
+
     pub fn is_admin(&self) -> bool {
         self.role == UserRole::Admin
     }
 
+    /// Seconds remaining on an active lockout, if any
+    pub fn lockout_remaining_seconds(&self) -> Option<i64> {
+        let now = Utc::now().naive_utc();
+
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| (until - now).num_seconds().max(1))
+    }
+
     pub fn has_dashboard_access(&self) -> bool {
         self.email.is_some() && self.password_hash.is_some()
     }
@@ -56,6 +78,7 @@ impl User {
             full_name: self.full_name.clone(),
             role: self.role.clone(),
             status: self.status.clone(),
+            base_currency: self.base_currency,
             created_at: self.created_at,
         }
     }
@@ -70,5 +93,6 @@ pub struct SafeUser {
     pub full_name: String,
     pub role: UserRole,
     pub status: UserStatus,
+    pub base_currency: Currency,
     pub created_at: NaiveDateTime,
 }
@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// External identity provider account linked to a user
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Pending authorization-code login awaiting its callback
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OidcLoginState {
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub expires_at: NaiveDateTime,
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A URL told about a user's events
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    pub description: Option<String>,
+    /// Subscribed event types (`WebhookEvent` names)
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Events an endpoint can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "transaction.created")]
+    TransactionCreated,
+    /// Deleted, including merged into another transaction as a duplicate
+    #[serde(rename = "transaction.deleted")]
+    TransactionDeleted,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TransactionCreated => "transaction.created",
+            WebhookEvent::TransactionDeleted => "transaction.deleted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first or next attempt
+    Pending,
+    Succeeded,
+    /// Gave up after the last attempt
+    Failed,
+}
+
+/// One event sent to one endpoint, with the outcome of its last attempt
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    /// Shared by every delivery of the same event, including redeliveries
+    pub event_id: Uuid,
+    pub event: String,
+    pub payload: sqlx::types::JsonValue,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub last_attempt_at: Option<NaiveDateTime>,
+    pub response_status: Option<i16>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+/// A due delivery claimed by the worker, with where to send it
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookJob {
+    pub delivery_id: Uuid,
+    pub event_id: Uuid,
+    pub event: String,
+    pub payload: sqlx::types::JsonValue,
+    /// Including this one
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Outcome of one delivery attempt
+#[derive(Debug, Clone, Default)]
+pub struct WebhookAttempt {
+    pub response_status: Option<i16>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A ledger shared by several users, e.g. a household or a small business
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A workspace with the role of the user looking at it
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct WorkspaceWithRole {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub workspace: Workspace,
+    pub role: WorkspaceRole,
+    pub member_count: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct WorkspaceMember {
+    pub workspace_id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub full_name: String,
+    pub role: WorkspaceRole,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Roles are ordered by what they allow: an owner can do everything an editor can, an
+/// editor everything a viewer can
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceRole {
+    /// Read only
+    Viewer,
+    /// Add and change records
+    Editor,
+    /// Manage the workspace and its members
+    Owner,
+}
+
+/// Which ledger a query works on: the personal ledger of `user_id`, or the workspace
+/// `workspace_id`. Repository queries on ledger records filter with
+/// `(workspace_id = $ws OR ($ws IS NULL AND workspace_id IS NULL AND user_id = $user))`;
+/// new records get `user_id` as their author.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerScope {
+    pub user_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+}
+
+impl LedgerScope {
+    pub fn personal(user_id: Uuid) -> Self {
+        Self { user_id, workspace_id: None }
+    }
+}
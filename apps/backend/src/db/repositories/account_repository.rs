@@ -0,0 +1,270 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Account, AccountEntry, AccountType, AccountWithBalance, Currency};
+use crate::error::AppError;
+
+pub struct AccountRepository;
+
+impl AccountRepository {
+    /// List a user's accounts with their current balances
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<AccountWithBalance>, AppError> {
+        // Income and incoming transfers add to the balance; expenses and outgoing transfers
+        // subtract from it
+        let accounts = sqlx::query_as::<_, AccountWithBalance>(
+            r#"
+            SELECT a.id, a.user_id, a.name, a.type, a.payment_method_id, a.currency,
+                   a.opening_balance, a.is_archived, a.created_at, a.updated_at,
+                   a.opening_balance + COALESCE(SUM(
+                       CASE
+                           WHEN t.type = 'income' THEN t.amount
+                           WHEN t.type = 'transfer' AND t.transfer_account_id = a.id THEN t.amount
+                           ELSE -t.amount
+                       END
+                   ), 0) AS balance
+            FROM accounts a
+            LEFT JOIN transactions t
+                   ON (t.account_id = a.id OR t.transfer_account_id = a.id)
+                  AND t.deleted_at IS NULL
+            WHERE a.user_id = $1
+              AND ($2 OR NOT a.is_archived)
+            GROUP BY a.id
+            ORDER BY a.is_archived, a.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(include_archived)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// Find one of a user's accounts
+    pub async fn find_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<Account>, AppError> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            SELECT id, user_id, name, type, payment_method_id, currency, opening_balance,
+                   is_archived, created_at, updated_at
+            FROM accounts
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Whether the user already has an account with this name (case-insensitive)
+    pub async fn name_exists(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        except_id: Option<Uuid>,
+    ) -> Result<bool, AppError> {
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM accounts
+                WHERE user_id = $1 AND lower(name) = lower($2)
+                  AND ($3::uuid IS NULL OR id <> $3)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(except_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Whether another of the user's accounts is already linked to the payment method
+    pub async fn payment_method_linked(
+        pool: &PgPool,
+        user_id: Uuid,
+        payment_method_id: Uuid,
+        except_id: Option<Uuid>,
+    ) -> Result<bool, AppError> {
+        let linked: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM accounts
+                WHERE user_id = $1 AND payment_method_id = $2
+                  AND ($3::uuid IS NULL OR id <> $3)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(payment_method_id)
+        .bind(except_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(linked)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        r#type: &AccountType,
+        payment_method_id: Option<Uuid>,
+        currency: Currency,
+        opening_balance: Decimal,
+    ) -> Result<Account, AppError> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            INSERT INTO accounts (user_id, name, type, payment_method_id, currency, opening_balance)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, type, payment_method_id, currency, opening_balance,
+                      is_archived, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(r#type)
+        .bind(payment_method_id)
+        .bind(currency)
+        .bind(opening_balance)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Save changed fields of an account
+    pub async fn update(pool: &PgPool, account: &Account) -> Result<Account, AppError> {
+        let account = sqlx::query_as::<_, Account>(
+            r#"
+            UPDATE accounts
+            SET name = $3, type = $4, payment_method_id = $5, opening_balance = $6,
+                is_archived = $7, updated_at = now()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, type, payment_method_id, currency, opening_balance,
+                      is_archived, created_at, updated_at
+            "#,
+        )
+        .bind(account.id)
+        .bind(account.user_id)
+        .bind(&account.name)
+        .bind(&account.r#type)
+        .bind(account.payment_method_id)
+        .bind(account.opening_balance)
+        .bind(account.is_archived)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Whether any transaction, deleted or not, is booked to the account
+    pub async fn has_transactions(pool: &PgPool, account_id: Uuid) -> Result<bool, AppError> {
+        let used: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM transactions
+                WHERE account_id = $1 OR transfer_account_id = $1
+            )
+            "#,
+        )
+        .bind(account_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(used)
+    }
+
+    pub async fn delete(pool: &PgPool, user_id: Uuid, account_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM accounts WHERE id = $1 AND user_id = $2")
+            .bind(account_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// An account's transactions in a date range, oldest first, each with the balance
+    /// after it. The running balance includes everything before `from`
+    pub async fn list_entries(
+        pool: &PgPool,
+        account: &Account,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<AccountEntry>, AppError> {
+        let entries = sqlx::query_as::<_, AccountEntry>(
+            r#"
+            SELECT transaction_id, transaction_date, type, amount, currency, merchant_name,
+                   description, counter_account_id, running_balance
+            FROM (
+                SELECT t.id AS transaction_id, t.transaction_date, t.created_at, t.type,
+                       CASE
+                           WHEN t.type = 'income' THEN t.amount
+                           WHEN t.type = 'transfer' AND t.transfer_account_id = a.id THEN t.amount
+                           ELSE -t.amount
+                       END AS amount,
+                       t.currency, t.merchant_name, t.description,
+                       CASE
+                           WHEN t.type <> 'transfer' THEN NULL
+                           WHEN t.account_id = a.id THEN t.transfer_account_id
+                           ELSE t.account_id
+                       END AS counter_account_id,
+                       a.opening_balance + SUM(
+                           CASE
+                               WHEN t.type = 'income' THEN t.amount
+                               WHEN t.type = 'transfer' AND t.transfer_account_id = a.id THEN t.amount
+                               ELSE -t.amount
+                           END
+                       ) OVER (ORDER BY t.transaction_date, t.created_at, t.id) AS running_balance
+                FROM accounts a
+                JOIN transactions t
+                  ON (t.account_id = a.id OR t.transfer_account_id = a.id)
+                 AND t.deleted_at IS NULL
+                WHERE a.id = $1
+            ) entries
+            WHERE ($2::date IS NULL OR transaction_date >= $2)
+              AND ($3::date IS NULL OR transaction_date <= $3)
+            ORDER BY transaction_date, created_at, transaction_id
+            "#,
+        )
+        .bind(account.id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Every account of a user, for the data export
+    pub async fn list_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Account>, AppError> {
+        let accounts = sqlx::query_as::<_, Account>(
+            r#"
+            SELECT id, user_id, name, type, payment_method_id, currency, opening_balance,
+                   is_archived, created_at, updated_at
+            FROM accounts
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(accounts)
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{
+    AnomalyKind, AnomalyStatus, AnomalyWithTransaction, LedgerScope, NewTransactionAnomaly, Transaction,
+    TransactionAnomaly,
+};
+use crate::error::AppError;
+
+pub struct AnomalyRepository;
+
+impl AnomalyRepository {
+    /// Take up to `limit` unchecked transactions for the anomaly scan, oldest first, for
+    /// `lease_seconds`. Concurrent scans never get the same transaction; one left unchecked
+    /// (the scan failed or stopped) is taken again once its lease runs out.
+    pub async fn claim_unchecked(pool: &PgPool, limit: i64, lease_seconds: i64) -> Result<Vec<Transaction>, AppError> {
+        let transactions = sqlx::query_as::<_, Transaction>(
+            r#"
+            UPDATE transactions
+            SET anomaly_claimed_at = now()
+            WHERE id IN (
+                SELECT id
+                FROM transactions
+                WHERE anomaly_checked_at IS NULL
+                  AND (anomaly_claimed_at IS NULL
+                       OR anomaly_claimed_at < now() - make_interval(secs => $2))
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                      account_id, transfer_account_id, merchant_name, location, description,
+                      transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                      created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    /// Record that the anomaly scan has looked at a transaction
+    pub async fn mark_checked(pool: &PgPool, transaction_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("UPDATE transactions SET anomaly_checked_at = now() WHERE id = $1")
+            .bind(transaction_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Amounts of other active transactions in the same ledger, type and currency dated
+    /// between `since` and the transaction, optionally only those in its category
+    pub async fn list_comparable_amounts(
+        pool: &PgPool,
+        transaction: &Transaction,
+        since: NaiveDate,
+        same_category: bool,
+    ) -> Result<Vec<Decimal>, AppError> {
+        let amounts = sqlx::query_scalar::<_, Decimal>(
+            r#"
+            SELECT amount
+            FROM transactions
+            WHERE (workspace_id = $2 OR ($2::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND id <> $3
+              AND type = $4
+              AND currency = $5
+              AND (NOT $6 OR category_id = $7)
+              AND transaction_date BETWEEN $8 AND $9
+              AND deleted_at IS NULL
+            "#,
+        )
+        .bind(transaction.user_id)
+        .bind(transaction.workspace_id)
+        .bind(transaction.id)
+        .bind(&transaction.r#type)
+        .bind(transaction.currency)
+        .bind(same_category)
+        .bind(transaction.category_id)
+        .bind(since)
+        .bind(transaction.transaction_date)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(amounts)
+    }
+
+    /// Whether the ledger recorded the transaction's merchant before it
+    pub async fn merchant_seen_before(pool: &PgPool, transaction: &Transaction) -> Result<bool, AppError> {
+        let seen: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM transactions
+                WHERE (workspace_id = $2 OR ($2::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+                  AND id <> $3
+                  AND lower(merchant_name) = lower($4)
+                  AND created_at < $5
+                  AND deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(transaction.user_id)
+        .bind(transaction.workspace_id)
+        .bind(transaction.id)
+        .bind(&transaction.merchant_name)
+        .bind(transaction.created_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(seen)
+    }
+
+    /// An earlier active transaction of the ledger with the same date, type, amount and
+    /// merchant, entered at most `window_seconds` before this one
+    pub async fn find_duplicate_of(
+        pool: &PgPool,
+        transaction: &Transaction,
+        window_seconds: i64,
+    ) -> Result<Option<Transaction>, AppError> {
+        let duplicate = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE (workspace_id = $2 OR ($2::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND id <> $3
+              AND type = $4
+              AND amount = $5
+              AND currency = $6
+              AND transaction_date = $7
+              AND lower(merchant_name) IS NOT DISTINCT FROM lower($8)
+              AND (created_at < $9 OR (created_at = $9 AND id < $3))
+              AND created_at >= $9 - make_interval(secs => $10)
+              AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(transaction.user_id)
+        .bind(transaction.workspace_id)
+        .bind(transaction.id)
+        .bind(&transaction.r#type)
+        .bind(transaction.amount)
+        .bind(transaction.currency)
+        .bind(transaction.transaction_date)
+        .bind(&transaction.merchant_name)
+        .bind(transaction.created_at)
+        .bind(window_seconds as f64)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(duplicate)
+    }
+
+    /// Record an anomaly; None when the transaction already has one of that kind
+    pub async fn create(
+        pool: &PgPool,
+        anomaly: &NewTransactionAnomaly,
+    ) -> Result<Option<TransactionAnomaly>, AppError> {
+        let anomaly = sqlx::query_as::<_, TransactionAnomaly>(
+            r#"
+            INSERT INTO transaction_anomalies (transaction_id, kind, details, related_transaction_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (transaction_id, kind) DO NOTHING
+            RETURNING id, transaction_id, kind, details, related_transaction_id, status,
+                      dismissed_by, dismissed_at, notified_at, created_at
+            "#,
+        )
+        .bind(anomaly.transaction_id)
+        .bind(anomaly.kind)
+        .bind(&anomaly.details)
+        .bind(anomaly.related_transaction_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(anomaly)
+    }
+
+    pub async fn mark_notified(pool: &PgPool, anomaly_ids: &[Uuid]) -> Result<(), AppError> {
+        sqlx::query("UPDATE transaction_anomalies SET notified_at = now() WHERE id = ANY($1)")
+            .bind(anomaly_ids)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Anomalies of a ledger's active transactions, newest first
+    pub async fn list_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+        status: Option<AnomalyStatus>,
+        kind: Option<AnomalyKind>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AnomalyWithTransaction>, i64), AppError> {
+        let anomalies = sqlx::query_as::<_, AnomalyWithTransaction>(
+            r#"
+            SELECT a.id, a.transaction_id, a.kind, a.details, a.related_transaction_id, a.status,
+                   a.dismissed_by, a.dismissed_at, a.notified_at, a.created_at,
+                   t.transaction_date, t.amount, t.currency, t.merchant_name, t.category_id
+            FROM transaction_anomalies a
+            JOIN transactions t ON t.id = a.transaction_id
+            WHERE (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND ($3::text IS NULL OR a.status = $3)
+              AND ($4::text IS NULL OR a.kind = $4)
+            ORDER BY a.created_at DESC
+            LIMIT $5 OFFSET $6
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(status)
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM transaction_anomalies a
+            JOIN transactions t ON t.id = a.transaction_id
+            WHERE (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND ($3::text IS NULL OR a.status = $3)
+              AND ($4::text IS NULL OR a.kind = $4)
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(status)
+        .bind(kind)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((anomalies, total))
+    }
+
+    /// An anomaly of one of a ledger's active transactions
+    pub async fn find_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+        anomaly_id: Uuid,
+    ) -> Result<Option<AnomalyWithTransaction>, AppError> {
+        let anomaly = sqlx::query_as::<_, AnomalyWithTransaction>(
+            r#"
+            SELECT a.id, a.transaction_id, a.kind, a.details, a.related_transaction_id, a.status,
+                   a.dismissed_by, a.dismissed_at, a.notified_at, a.created_at,
+                   t.transaction_date, t.amount, t.currency, t.merchant_name, t.category_id
+            FROM transaction_anomalies a
+            JOIN transactions t ON t.id = a.transaction_id
+            WHERE a.id = $3
+              AND (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(anomaly_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(anomaly)
+    }
+
+    pub async fn dismiss(pool: &PgPool, anomaly_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE transaction_anomalies
+            SET status = 'dismissed', dismissed_by = $2, dismissed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(anomaly_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -78,6 +78,24 @@ impl ApiKeyRepository {
         Ok(api_keys)
     }
 
+    /// List every API key of a user, including revoked and expired ones
+    pub async fn list_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        let api_keys = sqlx::query_as::<_, ApiKey>(
+            r#"
+            SELECT id, user_id, name, key_prefix, key_hash, scopes,
+                   last_used_at, expires_at, revoked_at, created_at, updated_at
+            FROM api_keys
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(api_keys)
+    }
+
     /// Revoke an API key (user must own it)
     pub async fn revoke(pool: &PgPool, key_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
         let now = Utc::now().naive_utc();
@@ -104,6 +122,26 @@ impl ApiKeyRepository {
         Ok(())
     }
 
+    /// Revoke all active API keys for a user
+    pub async fn revoke_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<u64, AppError> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = $1, updated_at = $1
+            WHERE user_id = $2
+              AND revoked_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Update last_used_at timestamp for an API key
     pub async fn update_last_used(pool: &PgPool, key_hash: &str) -> Result<(), AppError> {
         let now = Utc::now().naive_utc();
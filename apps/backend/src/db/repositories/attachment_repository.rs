@@ -0,0 +1,145 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{NewTransactionAttachment, TransactionAttachment};
+use crate::error::AppError;
+
+pub struct AttachmentRepository;
+
+impl AttachmentRepository {
+    pub async fn create(
+        pool: &PgPool,
+        attachment: &NewTransactionAttachment,
+    ) -> Result<TransactionAttachment, AppError> {
+        let attachment = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            INSERT INTO transaction_attachments
+                (id, transaction_id, uploaded_by, storage_key, file_name, content_type, size_bytes, sha256)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, transaction_id, uploaded_by, storage_key, file_name, content_type,
+                      size_bytes, sha256, created_at
+            "#,
+        )
+        .bind(attachment.id)
+        .bind(attachment.transaction_id)
+        .bind(attachment.uploaded_by)
+        .bind(&attachment.storage_key)
+        .bind(&attachment.file_name)
+        .bind(&attachment.content_type)
+        .bind(attachment.size_bytes)
+        .bind(&attachment.sha256)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Attachments of a transaction, oldest first
+    pub async fn list_for_transaction(
+        pool: &PgPool,
+        transaction_id: Uuid,
+    ) -> Result<Vec<TransactionAttachment>, AppError> {
+        let attachments = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            SELECT id, transaction_id, uploaded_by, storage_key, file_name, content_type,
+                   size_bytes, sha256, created_at
+            FROM transaction_attachments
+            WHERE transaction_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    pub async fn count_for_transaction(pool: &PgPool, transaction_id: Uuid) -> Result<i64, AppError> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM transaction_attachments WHERE transaction_id = $1")
+                .bind(transaction_id)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(count)
+    }
+
+    pub async fn find_by_id(pool: &PgPool, attachment_id: Uuid) -> Result<Option<TransactionAttachment>, AppError> {
+        let attachment = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            SELECT id, transaction_id, uploaded_by, storage_key, file_name, content_type,
+                   size_bytes, sha256, created_at
+            FROM transaction_attachments
+            WHERE id = $1
+            "#,
+        )
+        .bind(attachment_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Delete an attachment of a transaction; returns it, or None when there is none
+    pub async fn delete(
+        pool: &PgPool,
+        transaction_id: Uuid,
+        attachment_id: Uuid,
+    ) -> Result<Option<TransactionAttachment>, AppError> {
+        let attachment = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            DELETE FROM transaction_attachments
+            WHERE id = $1 AND transaction_id = $2
+            RETURNING id, transaction_id, uploaded_by, storage_key, file_name, content_type,
+                      size_bytes, sha256, created_at
+            "#,
+        )
+        .bind(attachment_id)
+        .bind(transaction_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Attachments of all transactions a user entered
+    pub async fn list_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<TransactionAttachment>, AppError> {
+        let attachments = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            SELECT a.id, a.transaction_id, a.uploaded_by, a.storage_key, a.file_name, a.content_type,
+                   a.size_bytes, a.sha256, a.created_at
+            FROM transaction_attachments a
+            JOIN transactions t ON t.id = a.transaction_id
+            WHERE t.user_id = $1
+            ORDER BY a.created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    /// Attachments of all transactions in a workspace
+    pub async fn list_for_workspace(
+        pool: &PgPool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<TransactionAttachment>, AppError> {
+        let attachments = sqlx::query_as::<_, TransactionAttachment>(
+            r#"
+            SELECT a.id, a.transaction_id, a.uploaded_by, a.storage_key, a.file_name, a.content_type,
+                   a.size_bytes, a.sha256, a.created_at
+            FROM transaction_attachments a
+            JOIN transactions t ON t.id = a.transaction_id
+            WHERE t.workspace_id = $1
+            ORDER BY a.created_at
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+}
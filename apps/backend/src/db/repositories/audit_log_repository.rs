@@ -0,0 +1,61 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use serde_json::Value as JsonValue;
+use crate::db::models::AuditLog;
+use crate::error::AppError;
+
+pub struct AuditLogRepository;
+
+impl AuditLogRepository {
+    /// Append an entry to the audit log
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        action: &str,
+        entity_type: &str,
+        entity_id: Uuid,
+        old_values: Option<JsonValue>,
+        new_values: Option<JsonValue>,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (user_id, action, entity_type, entity_id,
+                                    old_values, new_values, ip_address, user_agent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(user_id)
+        .bind(action)
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(old_values)
+        .bind(new_values)
+        .bind(ip_address)
+        .bind(user_agent)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List audit log entries recorded for a user's own actions, oldest first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<AuditLog>, AppError> {
+        let logs = sqlx::query_as::<_, AuditLog>(
+            r#"
+            SELECT id, user_id, action, entity_type, entity_id, old_values, new_values,
+                   ip_address, user_agent, created_at
+            FROM audit_logs
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(logs)
+    }
+}
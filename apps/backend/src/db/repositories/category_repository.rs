@@ -0,0 +1,77 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Category, CategoryAlias, LedgerScope};
+use crate::error::AppError;
+
+pub struct CategoryRepository;
+
+impl CategoryRepository {
+    /// List categories visible to a user (system categories and their own)
+    pub async fn list_available_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<Category>, AppError> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT id, user_id, workspace_id, name, type, icon, color, parent_category_id,
+                   is_system, is_active, created_at, updated_at
+            FROM categories
+            WHERE user_id = $1 OR user_id IS NULL
+            ORDER BY is_system DESC, name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(categories)
+    }
+
+    /// List categories usable in a ledger: system categories and the ledger's own
+    pub async fn list_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+    ) -> Result<Vec<Category>, AppError> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT id, user_id, workspace_id, name, type, icon, color, parent_category_id,
+                   is_system, is_active, created_at, updated_at
+            FROM categories
+            WHERE (user_id IS NULL AND workspace_id IS NULL)
+               OR workspace_id = $2
+               OR ($2::uuid IS NULL AND workspace_id IS NULL AND user_id = $1)
+            ORDER BY is_system DESC, name
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(categories)
+    }
+
+    /// List aliases of the categories usable in a ledger
+    pub async fn list_aliases_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+    ) -> Result<Vec<CategoryAlias>, AppError> {
+        let aliases = sqlx::query_as::<_, CategoryAlias>(
+            r#"
+            SELECT a.id, a.category_id, a.alias, a.created_at
+            FROM category_aliases a
+            JOIN categories c ON c.id = a.category_id
+            WHERE (c.user_id IS NULL AND c.workspace_id IS NULL)
+               OR c.workspace_id = $2
+               OR ($2::uuid IS NULL AND c.workspace_id IS NULL AND c.user_id = $1)
+            ORDER BY c.is_system DESC
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(aliases)
+    }
+}
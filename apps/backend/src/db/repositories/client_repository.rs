@@ -0,0 +1,26 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::Client;
+use crate::error::AppError;
+
+pub struct ClientRepository;
+
+impl ClientRepository {
+    /// List all messaging clients linked to a user
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Client>, AppError> {
+        let clients = sqlx::query_as::<_, Client>(
+            r#"
+            SELECT id, user_id, phone_number, country_code, channel, is_verified,
+                   is_active, last_interaction_at, created_at, updated_at
+            FROM clients
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(clients)
+    }
+}
@@ -0,0 +1,29 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::Conversation;
+use crate::error::AppError;
+
+pub struct ConversationRepository;
+
+impl ConversationRepository {
+    /// List all conversation messages of a user, oldest first
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<Conversation>, AppError> {
+        let conversations = sqlx::query_as::<_, Conversation>(
+            r#"
+            SELECT id, user_id, client_id, message_id, direction, message_text, intent,
+                   extracted_data, confidence_score, transaction_id, created_at
+            FROM conversations
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(conversations)
+    }
+}
@@ -0,0 +1,205 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::db::models::DataExport;
+use crate::error::AppError;
+
+pub struct DataExportRepository;
+
+impl DataExportRepository {
+    /// Queue a new export job
+    pub async fn create(pool: &PgPool, user_id: Uuid) -> Result<DataExport, AppError> {
+        let export = sqlx::query_as::<_, DataExport>(
+            r#"
+            INSERT INTO data_exports (user_id)
+            VALUES ($1)
+            RETURNING id, user_id, status, file_path, file_size, error,
+                      expires_at, created_at, completed_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    /// Find an export by id
+    pub async fn find_by_id(pool: &PgPool, export_id: Uuid) -> Result<Option<DataExport>, AppError> {
+        let export = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, file_path, file_size, error,
+                   expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE id = $1
+            "#,
+        )
+        .bind(export_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    /// Find an export owned by the given user
+    pub async fn find_for_user(
+        pool: &PgPool,
+        export_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<DataExport>, AppError> {
+        let export = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, file_path, file_size, error,
+                   expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(export_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    /// List a user's exports, newest first
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<DataExport>, AppError> {
+        let exports = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, file_path, file_size, error,
+                   expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(exports)
+    }
+
+    /// Whether the user already has an export queued or running
+    pub async fn has_active_for_user(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
+        let active: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM data_exports
+                WHERE user_id = $1 AND status IN ('pending', 'processing')
+            )
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(active)
+    }
+
+    /// Mark an export as being processed
+    pub async fn mark_processing(pool: &PgPool, export_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("UPDATE data_exports SET status = 'processing' WHERE id = $1")
+            .bind(export_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record the generated archive
+    pub async fn mark_completed(
+        pool: &PgPool,
+        export_id: Uuid,
+        file_path: &str,
+        file_size: i64,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'completed',
+                file_path = $2,
+                file_size = $3,
+                expires_at = $4,
+                completed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(export_id)
+        .bind(file_path)
+        .bind(file_size)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record why an export failed
+    pub async fn mark_failed(pool: &PgPool, export_id: Uuid, error: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'failed', error = $2, completed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(export_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fail exports queued or running for longer than `stale_seconds`; their job was lost.
+    /// Returns the number of exports failed
+    pub async fn fail_stale(pool: &PgPool, stale_seconds: i64) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = 'failed',
+                error = 'Export was interrupted; please request a new one',
+                completed_at = now()
+            WHERE status IN ('pending', 'processing')
+              AND created_at < now() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(stale_seconds as f64)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Exports whose archive has expired but is still on disk
+    pub async fn list_expired_files(pool: &PgPool, limit: i64) -> Result<Vec<DataExport>, AppError> {
+        let exports = sqlx::query_as::<_, DataExport>(
+            r#"
+            SELECT id, user_id, status, file_path, file_size, error,
+                   expires_at, created_at, completed_at
+            FROM data_exports
+            WHERE file_path IS NOT NULL AND expires_at <= now()
+            ORDER BY expires_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(exports)
+    }
+
+    /// Forget the archive of an export once its file has been removed
+    pub async fn clear_file(pool: &PgPool, export_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("UPDATE data_exports SET file_path = NULL WHERE id = $1")
+            .bind(export_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Debt, DebtBalance, DebtKind, DebtWithParties, NewDebt};
+use crate::error::AppError;
+
+pub struct DebtRepository;
+
+impl DebtRepository {
+    /// Insert an entry, keeping the user parties' current names
+    pub async fn create(pool: &PgPool, debt: &NewDebt) -> Result<Debt, AppError> {
+        let debt = sqlx::query_as::<_, Debt>(
+            r#"
+            INSERT INTO debts (
+                created_by, kind, creditor_user_id, debtor_user_id, contact_name,
+                amount, currency, description, entry_date, transaction_id,
+                creditor_name, debtor_name
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                (SELECT full_name FROM users WHERE id = $3),
+                (SELECT full_name FROM users WHERE id = $4)
+            )
+            RETURNING id, created_by, kind, creditor_user_id, debtor_user_id, contact_name,
+                      amount, currency, description, entry_date, transaction_id,
+                      disputed_at, created_at, updated_at
+            "#,
+        )
+        .bind(debt.created_by)
+        .bind(&debt.kind)
+        .bind(debt.creditor_user_id)
+        .bind(debt.debtor_user_id)
+        .bind(&debt.contact_name)
+        .bind(debt.amount)
+        .bind(debt.currency)
+        .bind(&debt.description)
+        .bind(debt.entry_date)
+        .bind(debt.transaction_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(debt)
+    }
+
+    /// Find an entry the user is a party to
+    pub async fn find_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        debt_id: Uuid,
+    ) -> Result<Option<DebtWithParties>, AppError> {
+        let debt = sqlx::query_as::<_, DebtWithParties>(
+            r#"
+            SELECT d.id, d.created_by, d.kind, d.creditor_user_id, d.debtor_user_id, d.contact_name,
+                   d.amount, d.currency, d.description, d.entry_date, d.transaction_id,
+                   d.disputed_at, d.created_at, d.updated_at,
+                   COALESCE(cu.full_name, d.creditor_name) AS creditor_name,
+                   COALESCE(du.full_name, d.debtor_name) AS debtor_name
+            FROM debts d
+            LEFT JOIN users cu ON cu.id = d.creditor_user_id
+            LEFT JOIN users du ON du.id = d.debtor_user_id
+            WHERE d.id = $1 AND (d.creditor_user_id = $2 OR d.debtor_user_id = $2)
+            "#,
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(debt)
+    }
+
+    /// Entries the user is a party to, newest first, optionally limited to one counterparty
+    /// (a user, or a contact or deleted user by case-insensitive name) and a date range
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search(
+        pool: &PgPool,
+        user_id: Uuid,
+        counterparty_user_id: Option<Uuid>,
+        contact_name: Option<&str>,
+        kind: Option<&DebtKind>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<DebtWithParties>, i64), AppError> {
+        let debts = sqlx::query_as::<_, DebtWithParties>(
+            r#"
+            SELECT d.id, d.created_by, d.kind, d.creditor_user_id, d.debtor_user_id, d.contact_name,
+                   d.amount, d.currency, d.description, d.entry_date, d.transaction_id,
+                   d.disputed_at, d.created_at, d.updated_at,
+                   COALESCE(cu.full_name, d.creditor_name) AS creditor_name,
+                   COALESCE(du.full_name, d.debtor_name) AS debtor_name
+            FROM debts d
+            LEFT JOIN users cu ON cu.id = d.creditor_user_id
+            LEFT JOIN users du ON du.id = d.debtor_user_id
+            WHERE (d.creditor_user_id = $1 OR d.debtor_user_id = $1)
+              AND ($2::uuid IS NULL OR d.creditor_user_id = $2 OR d.debtor_user_id = $2)
+              AND ($3::text IS NULL OR lower(COALESCE(
+                  d.contact_name,
+                  CASE WHEN d.creditor_user_id IS NULL THEN d.creditor_name
+                       WHEN d.debtor_user_id IS NULL THEN d.debtor_name END
+              )) = lower($3))
+              AND ($4::text IS NULL OR d.kind = $4)
+              AND ($5::date IS NULL OR d.entry_date >= $5)
+              AND ($6::date IS NULL OR d.entry_date <= $6)
+            ORDER BY d.entry_date DESC, d.created_at DESC
+            LIMIT $7 OFFSET $8
+            "#,
+        )
+        .bind(user_id)
+        .bind(counterparty_user_id)
+        .bind(contact_name)
+        .bind(kind)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM debts d
+            WHERE (d.creditor_user_id = $1 OR d.debtor_user_id = $1)
+              AND ($2::uuid IS NULL OR d.creditor_user_id = $2 OR d.debtor_user_id = $2)
+              AND ($3::text IS NULL OR lower(COALESCE(
+                  d.contact_name,
+                  CASE WHEN d.creditor_user_id IS NULL THEN d.creditor_name
+                       WHEN d.debtor_user_id IS NULL THEN d.debtor_name END
+              )) = lower($3))
+              AND ($4::text IS NULL OR d.kind = $4)
+              AND ($5::date IS NULL OR d.entry_date >= $5)
+              AND ($6::date IS NULL OR d.entry_date <= $6)
+            "#,
+        )
+        .bind(user_id)
+        .bind(counterparty_user_id)
+        .bind(contact_name)
+        .bind(kind)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((debts, total))
+    }
+
+    /// Net balance with each counterparty per currency. Contacts and deleted users are
+    /// grouped by case-insensitive name. Disputed entries do not count. Settled
+    /// counterparties (zero balance) are left out unless `include_settled` is set.
+    pub async fn balances(
+        pool: &PgPool,
+        user_id: Uuid,
+        include_settled: bool,
+    ) -> Result<Vec<DebtBalance>, AppError> {
+        // Debts owed to the user and settlements they paid count up; debts they owe and
+        // settlements paid to them count down
+        let balances = sqlx::query_as::<_, DebtBalance>(
+            r#"
+            SELECT e.counterparty_user_id,
+                   COALESCE(MIN(u.full_name), MIN(e.name)) AS counterparty_name,
+                   e.currency,
+                   SUM(e.signed_amount) AS balance,
+                   COUNT(*) AS entry_count,
+                   MAX(e.entry_date) AS last_entry_date
+            FROM (
+                SELECT CASE WHEN d.creditor_user_id = $1 THEN d.debtor_user_id ELSE d.creditor_user_id END
+                           AS counterparty_user_id,
+                       COALESCE(
+                           d.contact_name,
+                           CASE WHEN d.creditor_user_id = $1 THEN d.debtor_name ELSE d.creditor_name END
+                       ) AS name,
+                       d.currency,
+                       d.entry_date,
+                       CASE WHEN (d.creditor_user_id IS NOT DISTINCT FROM $1) = (d.kind = 'debt')
+                            THEN d.amount ELSE -d.amount END AS signed_amount
+                FROM debts d
+                WHERE (d.creditor_user_id = $1 OR d.debtor_user_id = $1)
+                  AND d.disputed_at IS NULL
+            ) e
+            LEFT JOIN users u ON u.id = e.counterparty_user_id
+            GROUP BY e.counterparty_user_id,
+                     CASE WHEN e.counterparty_user_id IS NULL THEN lower(e.name) END,
+                     e.currency
+            HAVING $2 OR SUM(e.signed_amount) <> 0
+            ORDER BY counterparty_name, e.currency
+            "#,
+        )
+        .bind(user_id)
+        .bind(include_settled)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(balances)
+    }
+
+    /// Every entry the user is a party to, oldest first
+    pub async fn list_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Debt>, AppError> {
+        let debts = sqlx::query_as::<_, Debt>(
+            r#"
+            SELECT id, created_by, kind, creditor_user_id, debtor_user_id, contact_name,
+                   amount, currency, description, entry_date, transaction_id,
+                   disputed_at, created_at, updated_at
+            FROM debts
+            WHERE creditor_user_id = $1 OR debtor_user_id = $1
+            ORDER BY entry_date, created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(debts)
+    }
+
+    /// Mark an entry the other party recorded as disputed. Returns false if the user did
+    /// not record it, or it was already disputed.
+    pub async fn dispute(pool: &PgPool, user_id: Uuid, debt_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE debts
+            SET disputed_at = now(), updated_at = now()
+            WHERE id = $1
+              AND (creditor_user_id = $2 OR debtor_user_id = $2)
+              AND created_by IS DISTINCT FROM $2
+              AND disputed_at IS NULL
+            "#,
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete an entry the user created, or one whose creator's account is gone
+    pub async fn delete(pool: &PgPool, user_id: Uuid, debt_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM debts
+            WHERE id = $1
+              AND (created_by = $2
+                   OR (created_by IS NULL AND (creditor_user_id = $2 OR debtor_user_id = $2)))
+            "#,
+        )
+        .bind(debt_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
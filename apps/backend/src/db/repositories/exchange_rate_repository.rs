@@ -0,0 +1,136 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Currency, ExchangeRate, RateQuote};
+use crate::error::AppError;
+
+pub struct ExchangeRateRepository;
+
+impl ExchangeRateRepository {
+    /// Insert rates, replacing any already stored for the same pair and day.
+    /// Returns the number of rows written
+    pub async fn upsert_many(
+        pool: &PgPool,
+        quotes: &[RateQuote],
+        source: &str,
+    ) -> Result<u64, AppError> {
+        let bases: Vec<String> = quotes.iter().map(|q| q.base_currency.to_string()).collect();
+        let quote_currencies: Vec<String> = quotes.iter().map(|q| q.quote_currency.to_string()).collect();
+        let rates: Vec<Decimal> = quotes.iter().map(|q| q.rate).collect();
+        let dates: Vec<NaiveDate> = quotes.iter().map(|q| q.rate_date).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO exchange_rates (base_currency, quote_currency, rate, rate_date, source)
+            SELECT base_currency, quote_currency, rate, rate_date, $5
+            FROM UNNEST($1::varchar[], $2::varchar[], $3::numeric[], $4::date[])
+                 AS input(base_currency, quote_currency, rate, rate_date)
+            ON CONFLICT (base_currency, quote_currency, rate_date)
+            DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source, updated_at = now()
+            "#,
+        )
+        .bind(&bases)
+        .bind(&quote_currencies)
+        .bind(&rates)
+        .bind(&dates)
+        .bind(source)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Rates involving any of `currencies` (as base or quote) within a date range
+    pub async fn list_for_currencies(
+        pool: &PgPool,
+        currencies: &[Currency],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ExchangeRate>, AppError> {
+        let codes: Vec<String> = currencies.iter().map(Currency::to_string).collect();
+
+        let rates = sqlx::query_as::<_, ExchangeRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, rate_date, source, created_at, updated_at
+            FROM exchange_rates
+            WHERE (base_currency = ANY($1) OR quote_currency = ANY($1))
+              AND rate_date BETWEEN $2 AND $3
+            "#,
+        )
+        .bind(&codes)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    /// Search stored rates, newest first
+    pub async fn search(
+        pool: &PgPool,
+        base_currency: Option<Currency>,
+        quote_currency: Option<Currency>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ExchangeRate>, i64), AppError> {
+        let rates = sqlx::query_as::<_, ExchangeRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, rate_date, source, created_at, updated_at
+            FROM exchange_rates
+            WHERE ($1::text IS NULL OR base_currency = $1)
+              AND ($2::text IS NULL OR quote_currency = $2)
+              AND ($3::date IS NULL OR rate_date >= $3)
+              AND ($4::date IS NULL OR rate_date <= $4)
+            ORDER BY rate_date DESC, base_currency, quote_currency
+            LIMIT $5 OFFSET $6
+            "#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM exchange_rates
+            WHERE ($1::text IS NULL OR base_currency = $1)
+              AND ($2::text IS NULL OR quote_currency = $2)
+              AND ($3::date IS NULL OR rate_date >= $3)
+              AND ($4::date IS NULL OR rate_date <= $4)
+            "#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((rates, total))
+    }
+
+    /// Delete a rate, returning it if it existed
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<Option<ExchangeRate>, AppError> {
+        let rate = sqlx::query_as::<_, ExchangeRate>(
+            r#"
+            DELETE FROM exchange_rates
+            WHERE id = $1
+            RETURNING id, base_currency, quote_currency, rate, rate_date, source, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(rate)
+    }
+}
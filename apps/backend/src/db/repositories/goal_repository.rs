@@ -0,0 +1,308 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Currency, Goal, GoalContribution, GoalWithSaved};
+use crate::error::AppError;
+
+pub struct GoalRepository;
+
+impl GoalRepository {
+    /// List a user's goals with the amount saved towards each
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<GoalWithSaved>, AppError> {
+        Self::fetch_with_saved(pool, user_id, None, include_archived).await
+    }
+
+    /// Find one of a user's goals with the amount saved towards it
+    pub async fn find_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        goal_id: Uuid,
+    ) -> Result<Option<GoalWithSaved>, AppError> {
+        let goals = Self::fetch_with_saved(pool, user_id, Some(goal_id), true).await?;
+
+        Ok(goals.into_iter().next())
+    }
+
+    async fn fetch_with_saved(
+        pool: &PgPool,
+        user_id: Uuid,
+        goal_id: Option<Uuid>,
+        include_archived: bool,
+    ) -> Result<Vec<GoalWithSaved>, AppError> {
+        // Saved = contributions, plus the net inflow of the linked account or the tagged
+        // transactions since the start date. Transactions already recorded as a
+        // contribution are not counted twice.
+        let goals = sqlx::query_as::<_, GoalWithSaved>(
+            r#"
+            SELECT g.id, g.user_id, g.name, g.target_amount, g.currency, g.start_date, g.deadline,
+                   g.account_id, g.tag_id, g.is_archived, g.created_at, g.updated_at,
+                   COALESCE((
+                       SELECT SUM(c.amount) FROM goal_contributions c WHERE c.goal_id = g.id
+                   ), 0)
+                   + COALESCE((
+                       SELECT SUM(
+                           CASE
+                               WHEN t.type = 'income' THEN t.amount
+                               WHEN t.type = 'transfer' AND t.transfer_account_id = g.account_id THEN t.amount
+                               ELSE -t.amount
+                           END)
+                       FROM transactions t
+                       WHERE (t.account_id = g.account_id OR t.transfer_account_id = g.account_id)
+                         AND t.deleted_at IS NULL
+                         AND t.transaction_date >= g.start_date
+                         AND NOT EXISTS (
+                             SELECT 1 FROM goal_contributions c
+                             WHERE c.goal_id = g.id AND c.transaction_id = t.id
+                         )
+                   ), 0)
+                   + COALESCE((
+                       SELECT SUM(t.amount)
+                       FROM transactions t
+                       JOIN transaction_tags tt ON tt.transaction_id = t.id
+                       WHERE tt.tag_id = g.tag_id
+                         AND t.deleted_at IS NULL
+                         AND t.currency = g.currency
+                         AND t.transaction_date >= g.start_date
+                         AND NOT EXISTS (
+                             SELECT 1 FROM goal_contributions c
+                             WHERE c.goal_id = g.id AND c.transaction_id = t.id
+                         )
+                   ), 0) AS saved
+            FROM goals g
+            WHERE g.user_id = $1
+              AND ($2::uuid IS NULL OR g.id = $2)
+              AND ($3 OR NOT g.is_archived)
+            ORDER BY g.is_archived, g.deadline, g.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(goal_id)
+        .bind(include_archived)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(goals)
+    }
+
+    /// Whether the user already has a goal with this name (case-insensitive)
+    pub async fn name_exists(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        except_id: Option<Uuid>,
+    ) -> Result<bool, AppError> {
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM goals
+                WHERE user_id = $1 AND lower(name) = lower($2)
+                  AND ($3::uuid IS NULL OR id <> $3)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(except_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        target_amount: Decimal,
+        currency: Currency,
+        start_date: NaiveDate,
+        deadline: NaiveDate,
+        account_id: Option<Uuid>,
+        tag_id: Option<Uuid>,
+    ) -> Result<Goal, AppError> {
+        let goal = sqlx::query_as::<_, Goal>(
+            r#"
+            INSERT INTO goals (user_id, name, target_amount, currency, start_date, deadline, account_id, tag_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, name, target_amount, currency, start_date, deadline,
+                      account_id, tag_id, is_archived, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(target_amount)
+        .bind(currency)
+        .bind(start_date)
+        .bind(deadline)
+        .bind(account_id)
+        .bind(tag_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(goal)
+    }
+
+    /// Save changed fields of a goal
+    pub async fn update(pool: &PgPool, goal: &Goal) -> Result<Goal, AppError> {
+        let goal = sqlx::query_as::<_, Goal>(
+            r#"
+            UPDATE goals
+            SET name = $3, target_amount = $4, start_date = $5, deadline = $6, account_id = $7,
+                tag_id = $8, is_archived = $9, updated_at = now()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, target_amount, currency, start_date, deadline,
+                      account_id, tag_id, is_archived, created_at, updated_at
+            "#,
+        )
+        .bind(goal.id)
+        .bind(goal.user_id)
+        .bind(&goal.name)
+        .bind(goal.target_amount)
+        .bind(goal.start_date)
+        .bind(goal.deadline)
+        .bind(goal.account_id)
+        .bind(goal.tag_id)
+        .bind(goal.is_archived)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(goal)
+    }
+
+    /// Delete a goal and its contributions
+    pub async fn delete(pool: &PgPool, user_id: Uuid, goal_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM goals WHERE id = $1 AND user_id = $2")
+            .bind(goal_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Contributions to a goal, newest first
+    pub async fn list_contributions(pool: &PgPool, goal_id: Uuid) -> Result<Vec<GoalContribution>, AppError> {
+        let contributions = sqlx::query_as::<_, GoalContribution>(
+            r#"
+            SELECT id, goal_id, amount, contribution_date, transaction_id, note, created_at
+            FROM goal_contributions
+            WHERE goal_id = $1
+            ORDER BY contribution_date DESC, created_at DESC
+            "#,
+        )
+        .bind(goal_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(contributions)
+    }
+
+    /// Contributions to all of a user's goals, oldest first
+    pub async fn list_contributions_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<GoalContribution>, AppError> {
+        let contributions = sqlx::query_as::<_, GoalContribution>(
+            r#"
+            SELECT c.id, c.goal_id, c.amount, c.contribution_date, c.transaction_id, c.note, c.created_at
+            FROM goal_contributions c
+            JOIN goals g ON g.id = c.goal_id
+            WHERE g.user_id = $1
+            ORDER BY c.contribution_date, c.created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(contributions)
+    }
+
+    /// All of a user's goals, archived ones included
+    pub async fn list_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Goal>, AppError> {
+        let goals = sqlx::query_as::<_, Goal>(
+            r#"
+            SELECT id, user_id, name, target_amount, currency, start_date, deadline,
+                   account_id, tag_id, is_archived, created_at, updated_at
+            FROM goals
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(goals)
+    }
+
+    /// Whether the transaction is already recorded as a contribution to the goal
+    pub async fn transaction_contributed(
+        pool: &PgPool,
+        goal_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM goal_contributions WHERE goal_id = $1 AND transaction_id = $2)",
+        )
+        .bind(goal_id)
+        .bind(transaction_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn add_contribution(
+        pool: &PgPool,
+        goal_id: Uuid,
+        amount: Decimal,
+        contribution_date: NaiveDate,
+        transaction_id: Option<Uuid>,
+        note: Option<&str>,
+    ) -> Result<GoalContribution, AppError> {
+        let contribution = sqlx::query_as::<_, GoalContribution>(
+            r#"
+            INSERT INTO goal_contributions (goal_id, amount, contribution_date, transaction_id, note)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, goal_id, amount, contribution_date, transaction_id, note, created_at
+            "#,
+        )
+        .bind(goal_id)
+        .bind(amount)
+        .bind(contribution_date)
+        .bind(transaction_id)
+        .bind(note)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(contribution)
+    }
+
+    /// Delete a contribution, returning it if it existed
+    pub async fn delete_contribution(
+        pool: &PgPool,
+        goal_id: Uuid,
+        contribution_id: Uuid,
+    ) -> Result<Option<GoalContribution>, AppError> {
+        let contribution = sqlx::query_as::<_, GoalContribution>(
+            r#"
+            DELETE FROM goal_contributions
+            WHERE id = $1 AND goal_id = $2
+            RETURNING id, goal_id, amount, contribution_date, transaction_id, note, created_at
+            "#,
+        )
+        .bind(contribution_id)
+        .bind(goal_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(contribution)
+    }
+}
@@ -0,0 +1,115 @@
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use crate::db::models::IdempotencyRecord;
+use crate::error::AppError;
+
+pub struct IdempotencyRepository;
+
+impl IdempotencyRepository {
+    /// Reserve a key for a request. Succeeds when the key is new, expired, or held by a
+    /// request that never finished within `stale_seconds`; false when it is taken
+    pub async fn claim(
+        pool: &PgPool,
+        principal: &str,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+        stale_seconds: i64,
+    ) -> Result<bool, AppError> {
+        let claimed = sqlx::query_scalar::<_, bool>(
+            r#"
+            INSERT INTO idempotency_keys (principal, key, request_hash, expires_at)
+            VALUES ($1, $2, $3, now() + make_interval(secs => $4))
+            ON CONFLICT (principal, key) DO UPDATE
+            SET request_hash = EXCLUDED.request_hash,
+                status_code = NULL,
+                response_headers = NULL,
+                response_body = NULL,
+                created_at = now(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= now()
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.created_at < now() - make_interval(secs => $5))
+            RETURNING true
+            "#,
+        )
+        .bind(principal)
+        .bind(key)
+        .bind(request_hash)
+        .bind(ttl_seconds as f64)
+        .bind(stale_seconds as f64)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Find an unexpired key of a caller
+    pub async fn find(
+        pool: &PgPool,
+        principal: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, AppError> {
+        let record = sqlx::query_as::<_, IdempotencyRecord>(
+            r#"
+            SELECT request_hash, status_code, response_headers, response_body
+            FROM idempotency_keys
+            WHERE principal = $1 AND key = $2 AND expires_at > now()
+            "#,
+        )
+        .bind(principal)
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Store the response of a claimed key, to be replayed until the key expires. Without
+    /// headers and body the key only records that the request completed
+    pub async fn complete(
+        pool: &PgPool,
+        principal: &str,
+        key: &str,
+        status_code: i16,
+        headers: Option<&JsonValue>,
+        body: Option<&[u8]>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $3, response_headers = $4, response_body = $5
+            WHERE principal = $1 AND key = $2
+            "#,
+        )
+        .bind(principal)
+        .bind(key)
+        .bind(status_code)
+        .bind(headers)
+        .bind(body)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Give up a claimed key so the request can be retried
+    pub async fn release(pool: &PgPool, principal: &str, key: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE principal = $1 AND key = $2 AND status_code IS NULL")
+            .bind(principal)
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Delete expired keys
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= now()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
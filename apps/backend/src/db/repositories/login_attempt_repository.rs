@@ -0,0 +1,84 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{Duration, NaiveDateTime, Utc};
+use crate::error::AppError;
+
+pub struct LoginAttemptRepository;
+
+impl LoginAttemptRepository {
+    /// Record a login attempt
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        email: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        succeeded: bool,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_attempts (user_id, email, ip_address, user_agent, succeeded)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(succeeded)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count failed attempts for an email since its last successful login, with the latest
+    /// one's timestamp
+    pub async fn failures_since_success_for_email(
+        pool: &PgPool,
+        email: &str,
+    ) -> Result<(i64, Option<NaiveDateTime>), AppError> {
+        let row: (i64, Option<NaiveDateTime>) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), MAX(created_at)
+            FROM login_attempts
+            WHERE email = $1
+              AND succeeded = false
+              AND created_at > COALESCE(
+                (SELECT MAX(created_at) FROM login_attempts WHERE email = $1 AND succeeded),
+                '-infinity'
+              )
+            "#,
+        )
+        .bind(email)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Count failed attempts from an IP within the window, with the oldest one's timestamp
+    pub async fn recent_failures_for_ip(
+        pool: &PgPool,
+        ip_address: &str,
+        window_seconds: i64,
+    ) -> Result<(i64, Option<NaiveDateTime>), AppError> {
+        let since = Utc::now().naive_utc() - Duration::seconds(window_seconds);
+
+        let row: (i64, Option<NaiveDateTime>) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), MIN(created_at)
+            FROM login_attempts
+            WHERE ip_address = $1
+              AND succeeded = false
+              AND created_at > $2
+            "#,
+        )
+        .bind(ip_address)
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row)
+    }
+}
@@ -0,0 +1,26 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::Merchant;
+use crate::error::AppError;
+
+pub struct MerchantRepository;
+
+impl MerchantRepository {
+    /// List merchants owned by a user (shared merchants excluded)
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Merchant>, AppError> {
+        let merchants = sqlx::query_as::<_, Merchant>(
+            r#"
+            SELECT id, user_id, name, default_category_id, location, tags,
+                   created_at, updated_at
+            FROM merchants
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(merchants)
+    }
+}
@@ -1,7 +1,51 @@
 pub mod user_repository;
 pub mod refresh_token_repository;
 pub mod api_key_repository;
+pub mod audit_log_repository;
+pub mod login_attempt_repository;
+pub mod user_identity_repository;
+pub mod revoked_token_repository;
+pub mod client_repository;
+pub mod category_repository;
+pub mod payment_method_repository;
+pub mod transaction_repository;
+pub mod merchant_repository;
+pub mod tag_repository;
+pub mod conversation_repository;
+pub mod data_export_repository;
+pub mod exchange_rate_repository;
+pub mod account_repository;
+pub mod debt_repository;
+pub mod goal_repository;
+pub mod workspace_repository;
+pub mod attachment_repository;
+pub mod receipt_draft_repository;
+pub mod anomaly_repository;
+pub mod idempotency_repository;
+pub mod webhook_repository;
 
 pub use user_repository::UserRepository;
 pub use refresh_token_repository::RefreshTokenRepository;
 pub use api_key_repository::ApiKeyRepository;
+pub use audit_log_repository::AuditLogRepository;
+pub use login_attempt_repository::LoginAttemptRepository;
+pub use user_identity_repository::UserIdentityRepository;
+pub use revoked_token_repository::RevokedTokenRepository;
+pub use client_repository::ClientRepository;
+pub use category_repository::CategoryRepository;
+pub use payment_method_repository::PaymentMethodRepository;
+pub use transaction_repository::TransactionRepository;
+pub use merchant_repository::MerchantRepository;
+pub use tag_repository::TagRepository;
+pub use conversation_repository::ConversationRepository;
+pub use data_export_repository::DataExportRepository;
+pub use exchange_rate_repository::ExchangeRateRepository;
+pub use account_repository::AccountRepository;
+pub use debt_repository::DebtRepository;
+pub use goal_repository::GoalRepository;
+pub use workspace_repository::WorkspaceRepository;
+pub use attachment_repository::AttachmentRepository;
+pub use receipt_draft_repository::ReceiptDraftRepository;
+pub use anomaly_repository::AnomalyRepository;
+pub use idempotency_repository::IdempotencyRepository;
+pub use webhook_repository::WebhookRepository;
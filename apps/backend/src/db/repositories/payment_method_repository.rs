@@ -0,0 +1,51 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::PaymentMethod;
+use crate::error::AppError;
+
+pub struct PaymentMethodRepository;
+
+impl PaymentMethodRepository {
+    /// List payment methods visible to a user (system defaults and their own)
+    pub async fn list_available_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<PaymentMethod>, AppError> {
+        let payment_methods = sqlx::query_as::<_, PaymentMethod>(
+            r#"
+            SELECT id, user_id, name, type, last_4_digits, is_system, is_active,
+                   created_at, updated_at
+            FROM payment_methods
+            WHERE user_id = $1 OR user_id IS NULL
+            ORDER BY is_system DESC, name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(payment_methods)
+    }
+
+    /// Find a payment method visible to a user
+    pub async fn find_available_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        payment_method_id: Uuid,
+    ) -> Result<Option<PaymentMethod>, AppError> {
+        let payment_method = sqlx::query_as::<_, PaymentMethod>(
+            r#"
+            SELECT id, user_id, name, type, last_4_digits, is_system, is_active,
+                   created_at, updated_at
+            FROM payment_methods
+            WHERE id = $1 AND (user_id = $2 OR user_id IS NULL)
+            "#,
+        )
+        .bind(payment_method_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(payment_method)
+    }
+}
@@ -0,0 +1,242 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{
+    LedgerScope, ReceiptDraft, ReceiptDraftResult, ReceiptDraftStatus, ReceiptOcrJob, Transaction,
+};
+use crate::error::AppError;
+
+pub struct ReceiptDraftRepository;
+
+impl ReceiptDraftRepository {
+    /// Queue an uploaded receipt image for OCR
+    pub async fn create(pool: &PgPool, attachment_id: Uuid, transaction_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO receipt_drafts (attachment_id, transaction_id) VALUES ($1, $2)")
+            .bind(attachment_id)
+            .bind(transaction_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Take the queued draft that has been due longest, or one a worker claimed more than
+    /// `stale_seconds` ago without finishing. Drafts waiting out a retry delay are skipped.
+    /// Concurrent workers never get the same draft.
+    pub async fn claim_next(pool: &PgPool, stale_seconds: i64) -> Result<Option<ReceiptOcrJob>, AppError> {
+        let job = sqlx::query_as::<_, ReceiptOcrJob>(
+            r#"
+            WITH claimed AS (
+                UPDATE receipt_drafts
+                SET status = 'processing', attempts = attempts + 1, claimed_at = now()
+                WHERE id = (
+                    SELECT id
+                    FROM receipt_drafts
+                    WHERE (status = 'pending' AND next_attempt_at <= now())
+                       OR (status = 'processing' AND claimed_at < now() - make_interval(secs => $1))
+                    ORDER BY next_attempt_at
+                    LIMIT 1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, attempts, attachment_id, transaction_id
+            )
+            SELECT c.id AS draft_id, c.attempts, a.storage_key, t.currency AS transaction_currency
+            FROM claimed c
+            JOIN transaction_attachments a ON a.id = c.attachment_id
+            JOIN transactions t ON t.id = c.transaction_id
+            "#,
+        )
+        .bind(stale_seconds as f64)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Store what OCR read
+    pub async fn complete(pool: &PgPool, draft_id: Uuid, result: &ReceiptDraftResult) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE receipt_drafts
+            SET status = $2, engine = $3, ocr_text = $4, amount = $5, currency = $6,
+                transaction_date = $7, merchant_name = $8, confidence = $9, field_confidence = $10,
+                error = NULL, processed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(draft_id)
+        .bind(result.status)
+        .bind(&result.engine)
+        .bind(&result.ocr_text)
+        .bind(result.amount)
+        .bind(result.currency)
+        .bind(result.transaction_date)
+        .bind(&result.merchant_name)
+        .bind(result.confidence)
+        .bind(&result.field_confidence)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record an OCR error; the draft is queued again for `retry_at`, or marked failed
+    /// without one
+    pub async fn record_error(
+        pool: &PgPool,
+        draft_id: Uuid,
+        error: &str,
+        retry_at: Option<NaiveDateTime>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE receipt_drafts
+            SET status = CASE WHEN $3::timestamp IS NULL THEN 'failed' ELSE 'pending' END,
+                error = $2, claimed_at = NULL,
+                next_attempt_at = COALESCE($3, next_attempt_at),
+                processed_at = CASE WHEN $3::timestamp IS NULL THEN now() ELSE processed_at END
+            WHERE id = $1
+            "#,
+        )
+        .bind(draft_id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drafts of a ledger's transactions, newest first, optionally narrowed to a status or
+    /// a transaction
+    pub async fn list_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+        status: Option<ReceiptDraftStatus>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<Vec<ReceiptDraft>, AppError> {
+        let drafts = sqlx::query_as::<_, ReceiptDraft>(
+            r#"
+            SELECT d.id, d.attachment_id, d.transaction_id, d.status, d.attempts, d.engine, d.ocr_text,
+                   d.amount, d.currency, d.transaction_date, d.merchant_name, d.confidence,
+                   d.field_confidence, d.error, d.reviewed_by, d.reviewed_at, d.created_at, d.processed_at
+            FROM receipt_drafts d
+            JOIN transactions t ON t.id = d.transaction_id
+            WHERE (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND ($3::text IS NULL OR d.status = $3)
+              AND ($4::uuid IS NULL OR d.transaction_id = $4)
+            ORDER BY d.created_at DESC
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(status)
+        .bind(transaction_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(drafts)
+    }
+
+    /// A draft of one of a ledger's transactions
+    pub async fn find_for_ledger(
+        pool: &PgPool,
+        scope: LedgerScope,
+        draft_id: Uuid,
+    ) -> Result<Option<ReceiptDraft>, AppError> {
+        let draft = sqlx::query_as::<_, ReceiptDraft>(
+            r#"
+            SELECT d.id, d.attachment_id, d.transaction_id, d.status, d.attempts, d.engine, d.ocr_text,
+                   d.amount, d.currency, d.transaction_date, d.merchant_name, d.confidence,
+                   d.field_confidence, d.error, d.reviewed_by, d.reviewed_at, d.created_at, d.processed_at
+            FROM receipt_drafts d
+            JOIN transactions t ON t.id = d.transaction_id
+            WHERE d.id = $3
+              AND (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(draft_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(draft)
+    }
+
+    /// Apply the (possibly corrected) draft values to its transaction and mark the draft
+    /// confirmed. The transaction keeps a `metadata.receipt` note of where the values came from.
+    pub async fn confirm(
+        pool: &PgPool,
+        draft: &ReceiptDraft,
+        reviewer_id: Uuid,
+        amount: Decimal,
+        transaction_date: NaiveDate,
+        merchant_name: Option<&str>,
+    ) -> Result<Transaction, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let receipt = json!({
+            "draft_id": draft.id,
+            "attachment_id": draft.attachment_id,
+            "engine": draft.engine,
+            "confidence": draft.confidence,
+        });
+        let transaction = sqlx::query_as::<_, Transaction>(
+            r#"
+            UPDATE transactions
+            SET amount = $2, transaction_date = $3, merchant_name = COALESCE($4, merchant_name),
+                metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('receipt', $5::jsonb),
+                updated_at = now()
+            WHERE id = $1
+            RETURNING id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                      account_id, transfer_account_id, merchant_name, location, description,
+                      transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                      created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(draft.transaction_id)
+        .bind(amount)
+        .bind(transaction_date)
+        .bind(merchant_name)
+        .bind(receipt)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE receipt_drafts
+            SET status = 'confirmed', reviewed_by = $2, reviewed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(draft.id)
+        .bind(reviewer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(transaction)
+    }
+
+    pub async fn dismiss(pool: &PgPool, draft_id: Uuid, reviewer_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE receipt_drafts
+            SET status = 'dismissed', reviewed_by = $2, reviewed_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(draft_id)
+        .bind(reviewer_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::error::AppError;
+
+pub struct RevokedTokenRepository;
+
+impl RevokedTokenRepository {
+    /// Add an access token to the denylist
+    pub async fn create(
+        pool: &PgPool,
+        jti: Uuid,
+        user_id: Uuid,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_access_tokens (jti, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(jti)
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unexpired denylist entries revoked at or after `since` (all of them when None)
+    pub async fn list_active_since(
+        pool: &PgPool,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<(Uuid, NaiveDateTime, NaiveDateTime)>, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, NaiveDateTime, NaiveDateTime)>(
+            r#"
+            SELECT jti, expires_at, revoked_at
+            FROM revoked_access_tokens
+            WHERE expires_at > now()
+              AND ($1::timestamp IS NULL OR revoked_at >= $1)
+            "#,
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Remove entries whose tokens have expired anyway
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM revoked_access_tokens WHERE expires_at <= now()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
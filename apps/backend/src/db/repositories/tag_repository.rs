@@ -0,0 +1,65 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{LedgerScope, Tag, TransactionTag};
+use crate::error::AppError;
+
+pub struct TagRepository;
+
+impl TagRepository {
+    /// List all tags of a user
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Tag>, AppError> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, user_id, workspace_id, name, color, created_at
+            FROM tags
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find a tag of a ledger
+    pub async fn find_for_ledger(pool: &PgPool, scope: LedgerScope, tag_id: Uuid) -> Result<Option<Tag>, AppError> {
+        let tag = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, user_id, workspace_id, name, color, created_at
+            FROM tags
+            WHERE id = $1
+              AND (workspace_id = $3 OR ($3::uuid IS NULL AND workspace_id IS NULL AND user_id = $2))
+            "#,
+        )
+        .bind(tag_id)
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(tag)
+    }
+
+    /// List tag assignments on all transactions of a user
+    pub async fn list_transaction_tags_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<TransactionTag>, AppError> {
+        let transaction_tags = sqlx::query_as::<_, TransactionTag>(
+            r#"
+            SELECT tt.transaction_id, tt.tag_id, tt.created_at
+            FROM transaction_tags tt
+            JOIN tags t ON t.id = tt.tag_id
+            WHERE t.user_id = $1
+            ORDER BY tt.created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transaction_tags)
+    }
+}
@@ -0,0 +1,896 @@
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
+use serde_json::{json, Value as JsonValue};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use crate::db::models::{
+    Currency, LedgerScope, NewTransaction, ReconciliationCandidate, Transaction, TransactionExportRow, TransactionFilter,
+    TransactionSearchHit, NewTransactionSplit, TransactionSplit, TransactionSummaryRow,
+    TransactionTimeseriesRow, TransactionType, WebhookEvent,
+};
+use crate::error::AppError;
+use crate::utils::timeseries::{BucketInterval, SeriesGrouping};
+use crate::utils::webhooks;
+
+pub struct TransactionRepository;
+
+impl TransactionRepository {
+    /// List every transaction a user authored, in any ledger, including soft-deleted ones
+    pub async fn list_all_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<Transaction>, AppError> {
+        let transactions = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE user_id = $1
+            ORDER BY transaction_date, created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    /// Find an active transaction of a ledger
+    pub async fn find_by_id(
+        pool: &PgPool,
+        scope: LedgerScope,
+        id: Uuid,
+    ) -> Result<Option<Transaction>, AppError> {
+        let transaction = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE id = $1
+              AND (workspace_id = $3 OR ($3::uuid IS NULL AND workspace_id IS NULL AND user_id = $2))
+              AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    /// Insert a single transaction into a ledger and queue its `transaction.created` webhooks
+    pub async fn create(
+        pool: &PgPool,
+        scope: LedgerScope,
+        transaction: &NewTransaction,
+    ) -> Result<Transaction, AppError> {
+        let mut tx = pool.begin().await?;
+        let id = Self::insert(&mut tx, scope, transaction)
+            .await?
+            .ok_or_else(|| AppError::Conflict("This message has already been recorded".to_string()))?;
+        webhooks::publish_created_transactions(&mut tx, scope.user_id, &[id]).await?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, scope, id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Created transaction not found".to_string()))
+    }
+
+    /// Stream a ledger's active transactions matching the filter, oldest first, with
+    /// category, payment method and tag names joined in. Rows are fetched lazily
+    pub fn stream_export_rows<'a>(
+        pool: &'a PgPool,
+        scope: LedgerScope,
+        filter: &'a TransactionFilter,
+    ) -> BoxStream<'a, Result<TransactionExportRow, AppError>> {
+        sqlx::query_as::<_, TransactionExportRow>(
+            r#"
+            SELECT t.id, t.transaction_date, t.type, t.amount, t.currency,
+                   c.name AS category, pm.name AS payment_method, a.name AS account,
+                   ta.name AS transfer_account, t.merchant_name, t.location, t.description,
+                   string_agg(tg.name, ';' ORDER BY tg.name) AS tags,
+                   t.source, t.created_at
+            FROM transactions t
+            LEFT JOIN categories c ON c.id = t.category_id
+            LEFT JOIN payment_methods pm ON pm.id = t.payment_method_id
+            LEFT JOIN accounts a ON a.id = t.account_id
+            LEFT JOIN accounts ta ON ta.id = t.transfer_account_id
+            LEFT JOIN transaction_tags tt ON tt.transaction_id = t.id
+            LEFT JOIN tags tg ON tg.id = tt.tag_id
+            WHERE (t.workspace_id = $7 OR ($7::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND ($2::date IS NULL OR t.transaction_date >= $2)
+              AND ($3::date IS NULL OR t.transaction_date <= $3)
+              AND ($4::text IS NULL OR t.type = $4)
+              AND ($5::uuid IS NULL OR t.category_id = $5)
+              AND ($6::uuid IS NULL OR t.payment_method_id = $6)
+            GROUP BY t.id, c.name, pm.name, a.name, ta.name
+            ORDER BY t.transaction_date, t.created_at
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.r#type.as_ref())
+        .bind(filter.category_id)
+        .bind(filter.payment_method_id)
+        .bind(scope.workspace_id)
+        .fetch(pool)
+        .map_err(AppError::from)
+        .boxed()
+    }
+
+    /// Full-text search over a ledger's active transactions matching the filter, best
+    /// matches first. Every term must match a word, stemmed or as a prefix.
+    pub async fn search(
+        pool: &PgPool,
+        scope: LedgerScope,
+        terms: &[String],
+        filter: &TransactionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<TransactionSearchHit>, i64), AppError> {
+        let hits = sqlx::query_as::<_, TransactionSearchHit>(
+            r#"
+            SELECT t.id, t.user_id, t.workspace_id, t.type, t.amount, t.currency, t.category_id,
+                   t.payment_method_id, t.account_id, t.transfer_account_id, t.merchant_name,
+                   t.location, t.description, t.transaction_date, t.source, t.client_id, t.source_message_id,
+                   t.attachment_urls, t.metadata, t.created_at, t.updated_at, t.deleted_at,
+                   ts_rank_cd(t.search_vector, q.query) AS rank
+            FROM transactions t, transaction_search_query($8) q(query)
+            WHERE (t.workspace_id = $7 OR ($7::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND t.search_vector @@ q.query
+              AND ($2::date IS NULL OR t.transaction_date >= $2)
+              AND ($3::date IS NULL OR t.transaction_date <= $3)
+              AND ($4::text IS NULL OR t.type = $4)
+              AND ($5::uuid IS NULL OR t.category_id = $5)
+              AND ($6::uuid IS NULL OR t.payment_method_id = $6)
+            ORDER BY rank DESC, t.transaction_date DESC, t.created_at DESC
+            LIMIT $9 OFFSET $10
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.r#type.as_ref())
+        .bind(filter.category_id)
+        .bind(filter.payment_method_id)
+        .bind(scope.workspace_id)
+        .bind(terms)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM transactions t, transaction_search_query($8) q(query)
+            WHERE (t.workspace_id = $7 OR ($7::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.deleted_at IS NULL
+              AND t.search_vector @@ q.query
+              AND ($2::date IS NULL OR t.transaction_date >= $2)
+              AND ($3::date IS NULL OR t.transaction_date <= $3)
+              AND ($4::text IS NULL OR t.type = $4)
+              AND ($5::uuid IS NULL OR t.category_id = $5)
+              AND ($6::uuid IS NULL OR t.payment_method_id = $6)
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.r#type.as_ref())
+        .bind(filter.category_id)
+        .bind(filter.payment_method_id)
+        .bind(scope.workspace_id)
+        .bind(terms)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((hits, total))
+    }
+
+    /// Date, amount and merchant of active transactions in a date range (duplicate detection)
+    pub async fn list_duplicate_keys(
+        pool: &PgPool,
+        scope: LedgerScope,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, TransactionType, Currency, Decimal, Option<String>)>, AppError> {
+        let keys = sqlx::query_as::<_, (NaiveDate, TransactionType, Currency, Decimal, Option<String>)>(
+            r#"
+            SELECT transaction_date, type, currency, amount, merchant_name
+            FROM transactions
+            WHERE (workspace_id = $4 OR ($4::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND transaction_date BETWEEN $2 AND $3
+              AND deleted_at IS NULL
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(from)
+        .bind(to)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Find the transaction recorded from a chat message, including soft-deleted ones
+    pub async fn find_by_client_message(
+        pool: &PgPool,
+        client_id: Uuid,
+        source_message_id: &str,
+    ) -> Result<Option<Transaction>, AppError> {
+        let transaction = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE client_id = $1 AND source_message_id = $2
+            "#,
+        )
+        .bind(client_id)
+        .bind(source_message_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(transaction)
+    }
+
+    /// Transactions with the given ids, in the order they were recorded
+    pub async fn list_by_ids(conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<Transaction>, AppError> {
+        let transactions = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE id = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    /// Active transactions of a ledger in a date range, oldest first
+    pub async fn list_in_range(
+        pool: &PgPool,
+        scope: LedgerScope,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Transaction>, AppError> {
+        let transactions = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT id, user_id, workspace_id, type, amount, currency, category_id, payment_method_id,
+                   account_id, transfer_account_id, merchant_name, location, description,
+                   transaction_date, source, client_id, source_message_id, attachment_urls, metadata,
+                   created_at, updated_at, deleted_at
+            FROM transactions
+            WHERE (workspace_id = $4 OR ($4::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND transaction_date BETWEEN $2 AND $3
+              AND deleted_at IS NULL
+            ORDER BY transaction_date, created_at
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(from)
+        .bind(to)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    /// Merge a duplicate into the transaction kept, atomically: tags, conversation links,
+    /// attachments, receipt drafts, debt entries and goal contributions move over, as do
+    /// the duplicate's splits when the kept one has none; details the kept one lacks are
+    /// copied, and the duplicate is soft-deleted pointing at it. A contribution to a goal
+    /// the kept transaction already counts towards is dropped. `user_id` gets the
+    /// duplicate's `transaction.deleted` webhooks. Returns the kept transaction
+    pub async fn merge(
+        pool: &PgPool,
+        user_id: Uuid,
+        keep_id: Uuid,
+        duplicate: &Transaction,
+    ) -> Result<Transaction, AppError> {
+        let duplicate_id = duplicate.id;
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO transaction_tags (transaction_id, tag_id)
+            SELECT $1, tag_id FROM transaction_tags WHERE transaction_id = $2
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(keep_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM transaction_tags WHERE transaction_id = $1")
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE conversations SET transaction_id = $1 WHERE transaction_id = $2")
+            .bind(keep_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE transaction_attachments SET transaction_id = $1 WHERE transaction_id = $2")
+            .bind(keep_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE receipt_drafts SET transaction_id = $1 WHERE transaction_id = $2")
+            .bind(keep_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE debts SET transaction_id = $1 WHERE transaction_id = $2")
+            .bind(keep_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE goal_contributions AS g
+            SET transaction_id = $1
+            WHERE g.transaction_id = $2
+              AND NOT EXISTS (
+                  SELECT 1 FROM goal_contributions k WHERE k.goal_id = g.goal_id AND k.transaction_id = $1
+              )
+            "#,
+        )
+        .bind(keep_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM goal_contributions WHERE transaction_id = $1")
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE transaction_splits
+            SET transaction_id = $1
+            WHERE transaction_id = $2
+              AND NOT EXISTS (SELECT 1 FROM transaction_splits WHERE transaction_id = $1)
+            "#,
+        )
+        .bind(keep_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let transaction = sqlx::query_as::<_, Transaction>(
+            r#"
+            UPDATE transactions AS t
+            SET merchant_name = COALESCE(t.merchant_name, d.merchant_name),
+                location = COALESCE(t.location, d.location),
+                description = COALESCE(t.description, d.description),
+                category_id = COALESCE(t.category_id, d.category_id),
+                payment_method_id = COALESCE(t.payment_method_id, d.payment_method_id),
+                updated_at = now()
+            FROM transactions AS d
+            WHERE t.id = $1 AND d.id = $2
+            RETURNING t.id, t.user_id, t.workspace_id, t.type, t.amount, t.currency, t.category_id,
+                      t.payment_method_id, t.account_id, t.transfer_account_id, t.merchant_name,
+                      t.location, t.description, t.transaction_date, t.source, t.client_id, t.source_message_id,
+                      t.attachment_urls, t.metadata, t.created_at, t.updated_at, t.deleted_at
+            "#,
+        )
+        .bind(keep_id)
+        .bind(duplicate_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE transactions
+            SET merged_into_id = $1, deleted_at = now(), updated_at = now()
+            WHERE id = $2
+            "#,
+        )
+        .bind(keep_id)
+        .bind(duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+
+        webhooks::publish(
+            &mut tx,
+            user_id,
+            WebhookEvent::TransactionDeleted,
+            vec![json!({ "transaction": duplicate, "merged_into_id": transaction.id })],
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(transaction)
+    }
+
+    /// Insert several transactions into a ledger atomically and queue their
+    /// `transaction.created` webhooks; returns their ids in order
+    pub async fn create_batch(
+        pool: &PgPool,
+        scope: LedgerScope,
+        transactions: &[NewTransaction],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut ids = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            let id = Self::insert(&mut tx, scope, transaction)
+                .await?
+                .ok_or_else(|| AppError::Conflict("This message has already been recorded".to_string()))?;
+            ids.push(id);
+        }
+
+        webhooks::publish_created_transactions(&mut tx, scope.user_id, &ids).await?;
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    /// Statement entries already imported into the ledger, out of `entries` given as
+    /// `(account, transaction id)` with `""` for no account. Ids are only unique within an
+    /// account. Deleted transactions count, so they are not re-imported
+    pub async fn list_imported_statement_entries(
+        pool: &PgPool,
+        scope: LedgerScope,
+        entries: &[(String, String)],
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let (accounts, ids): (Vec<String>, Vec<String>) = entries.iter().cloned().unzip();
+
+        let imported = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT COALESCE(metadata->'statement'->>'account', ''), metadata->'statement'->>'transaction_id'
+            FROM transactions
+            WHERE COALESCE(workspace_id, user_id) = COALESCE($3, $1)
+              AND (workspace_id IS NULL) = ($3::uuid IS NULL)
+              AND metadata ? 'statement'
+              AND (COALESCE(metadata->'statement'->>'account', ''), metadata->'statement'->>'transaction_id')
+                  IN (SELECT * FROM unnest($2::varchar[], $4::varchar[]))
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(&accounts)
+        .bind(scope.workspace_id)
+        .bind(&ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(imported)
+    }
+
+    /// Active WhatsApp-entered transactions in a date range not yet matched to a statement
+    pub async fn list_reconciliation_candidates(
+        pool: &PgPool,
+        scope: LedgerScope,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ReconciliationCandidate>, AppError> {
+        let candidates = sqlx::query_as::<_, ReconciliationCandidate>(
+            r#"
+            SELECT id, type, amount, currency, transaction_date
+            FROM transactions
+            WHERE (workspace_id = $4 OR ($4::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND source = 'whatsapp'
+              AND transaction_date BETWEEN $2 AND $3
+              AND deleted_at IS NULL
+              AND (metadata IS NULL OR NOT metadata ? 'statement')
+            ORDER BY transaction_date, created_at
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(from)
+        .bind(to)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Per-day income and expense totals by currency and category over a date range
+    /// (transfers excluded), using the split lines of split transactions. Amounts stay in
+    /// their original currency; conversion happens with the rate of each day.
+    pub async fn summarize(
+        pool: &PgPool,
+        scope: LedgerScope,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TransactionSummaryRow>, AppError> {
+        let rows = sqlx::query_as::<_, TransactionSummaryRow>(
+            r#"
+            SELECT t.type, t.currency, t.transaction_date, line.category_id,
+                   c.name AS category_name,
+                   SUM(line.amount) AS total,
+                   COUNT(DISTINCT t.id) AS count,
+                   COUNT(DISTINCT t.id) FILTER (WHERE s.id IS NULL OR s.position = 1) AS transaction_count
+            FROM transactions t
+            LEFT JOIN transaction_splits s ON s.transaction_id = t.id
+            CROSS JOIN LATERAL (
+                SELECT CASE WHEN s.id IS NULL THEN t.category_id ELSE s.category_id END AS category_id,
+                       COALESCE(s.amount, t.amount) AS amount
+            ) line
+            LEFT JOIN categories c ON c.id = line.category_id
+            WHERE (t.workspace_id = $4 OR ($4::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+              AND t.transaction_date BETWEEN $2 AND $3
+              AND t.type <> 'transfer'
+              AND t.deleted_at IS NULL
+            GROUP BY t.type, t.currency, t.transaction_date, line.category_id, c.name
+            ORDER BY t.transaction_date
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(from)
+        .bind(to)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Currencies other than `base` used by a ledger's transactions, with the dates they
+    /// were used on, for looking up the rates a time-series report needs
+    pub async fn list_foreign_currency_dates(
+        pool: &PgPool,
+        scope: LedgerScope,
+        base: Currency,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(Currency, NaiveDate)>, AppError> {
+        let rows = sqlx::query_as::<_, (Currency, NaiveDate)>(
+            r#"
+            SELECT DISTINCT currency, transaction_date
+            FROM transactions
+            WHERE (workspace_id = $5 OR ($5::uuid IS NULL AND workspace_id IS NULL AND user_id = $1))
+              AND transaction_date BETWEEN $2 AND $3
+              AND currency <> $4
+              AND type <> 'transfer'
+              AND deleted_at IS NULL
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(from)
+        .bind(to)
+        .bind(base)
+        .bind(scope.workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Income and expense per bucket between `from` and `to`, zero-filled, per series,
+    /// with moving averages over `window` buckets and the change from the previous bucket.
+    /// Amounts are converted into `base` with `rates` (currency, date, rate); amounts
+    /// without a rate are left out. Buckets from `history_from` on feed the moving
+    /// averages and changes of the first buckets but are not returned.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn timeseries(
+        pool: &PgPool,
+        scope: LedgerScope,
+        interval: BucketInterval,
+        grouping: SeriesGrouping,
+        history_from: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+        window: u32,
+        base: Currency,
+        rates: &[(Currency, NaiveDate, Decimal)],
+    ) -> Result<Vec<TransactionTimeseriesRow>, AppError> {
+        let rate_currencies: Vec<String> = rates.iter().map(|(currency, _, _)| currency.to_string()).collect();
+        let rate_dates: Vec<NaiveDate> = rates.iter().map(|(_, date, _)| *date).collect();
+        let rate_values: Vec<Decimal> = rates.iter().map(|(_, _, rate)| *rate).collect();
+
+        let rows = sqlx::query_as::<_, TransactionTimeseriesRow>(
+            r#"
+            WITH rates AS (
+                SELECT *
+                FROM unnest($9::text[], $10::date[], $11::numeric[]) AS r(currency, rate_date, rate)
+            ),
+            lines AS (
+                SELECT t.id, t.type,
+                       date_trunc($6::text, t.transaction_date::timestamp)::date AS bucket_start,
+                       CASE $7::text WHEN 'category' THEN line.category_id WHEN 'tag' THEN tt.tag_id END AS series_id,
+                       CASE $7::text WHEN 'category' THEN c.name WHEN 'tag' THEN tg.name END AS series_name,
+                       line.amount * CASE WHEN t.currency = $8 THEN 1 ELSE r.rate END AS amount
+                FROM transactions t
+                LEFT JOIN transaction_splits s ON $7::text = 'category' AND s.transaction_id = t.id
+                CROSS JOIN LATERAL (
+                    SELECT CASE WHEN s.id IS NULL THEN t.category_id ELSE s.category_id END AS category_id,
+                           COALESCE(s.amount, t.amount) AS amount
+                ) line
+                LEFT JOIN categories c ON $7::text = 'category' AND c.id = line.category_id
+                LEFT JOIN transaction_tags tt ON $7::text = 'tag' AND tt.transaction_id = t.id
+                LEFT JOIN tags tg ON tg.id = tt.tag_id
+                LEFT JOIN rates r ON r.currency = t.currency AND r.rate_date = t.transaction_date
+                WHERE (t.workspace_id = $2 OR ($2::uuid IS NULL AND t.workspace_id IS NULL AND t.user_id = $1))
+                  AND t.transaction_date BETWEEN $3 AND $5
+                  AND t.type <> 'transfer'
+                  AND t.deleted_at IS NULL
+            ),
+            totals AS (
+                SELECT bucket_start, series_id, series_name,
+                       ROUND(COALESCE(SUM(amount) FILTER (WHERE type = 'income'), 0), $13) AS income,
+                       ROUND(COALESCE(SUM(amount) FILTER (WHERE type = 'expense'), 0), $13) AS expense,
+                       COUNT(DISTINCT id) AS transaction_count
+                FROM lines
+                WHERE amount IS NOT NULL
+                GROUP BY bucket_start, series_id, series_name
+            ),
+            series AS (
+                -- Series with something to show in the reported range
+                SELECT DISTINCT series_id, series_name FROM totals WHERE bucket_start >= $4
+                UNION
+                SELECT NULL::uuid, NULL::text WHERE $7::text = 'none'
+            ),
+            buckets AS (
+                SELECT generate_series($3::timestamp, $5::timestamp, ('1 ' || $6::text)::interval)::date AS bucket_start
+            ),
+            grid AS (
+                SELECT b.bucket_start, s.series_id, s.series_name,
+                       COALESCE(t.income, 0) AS income,
+                       COALESCE(t.expense, 0) AS expense,
+                       COALESCE(t.transaction_count, 0) AS transaction_count
+                FROM buckets b
+                CROSS JOIN series s
+                LEFT JOIN totals t ON t.bucket_start = b.bucket_start AND t.series_id IS NOT DISTINCT FROM s.series_id
+            ),
+            windowed AS (
+                SELECT g.*,
+                       ROUND(AVG(income) OVER moving, $13) AS income_moving_average,
+                       ROUND(AVG(expense) OVER moving, $13) AS expense_moving_average,
+                       LAG(income) OVER ordered AS previous_income,
+                       LAG(expense) OVER ordered AS previous_expense
+                FROM grid g
+                WINDOW ordered AS (PARTITION BY series_id ORDER BY bucket_start),
+                       moving AS (ordered ROWS BETWEEN $12 PRECEDING AND CURRENT ROW)
+            )
+            SELECT bucket_start, series_id, series_name, income, expense, income - expense AS net,
+                   transaction_count, income_moving_average, expense_moving_average,
+                   ROUND((income - previous_income) * 100 / NULLIF(previous_income, 0), 2) AS income_change_pct,
+                   ROUND((expense - previous_expense) * 100 / NULLIF(previous_expense, 0), 2) AS expense_change_pct
+            FROM windowed
+            WHERE bucket_start >= $4
+            ORDER BY series_name NULLS LAST, series_id, bucket_start
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(scope.workspace_id)
+        .bind(history_from)
+        .bind(from)
+        .bind(to)
+        .bind(interval.sql_unit())
+        .bind(grouping.as_str())
+        .bind(base)
+        .bind(&rate_currencies)
+        .bind(&rate_dates)
+        .bind(&rate_values)
+        .bind(window.saturating_sub(1) as i64)
+        .bind(base.minor_units() as i32)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Split lines of a transaction, in order
+    pub async fn list_splits(
+        pool: &PgPool,
+        transaction_id: Uuid,
+    ) -> Result<Vec<TransactionSplit>, AppError> {
+        let splits = sqlx::query_as::<_, TransactionSplit>(
+            r#"
+            SELECT id, transaction_id, position, category_id, amount, description, created_at
+            FROM transaction_splits
+            WHERE transaction_id = $1
+            ORDER BY position
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(splits)
+    }
+
+    /// Split lines of all of a user's transactions (data export)
+    pub async fn list_splits_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<TransactionSplit>, AppError> {
+        let splits = sqlx::query_as::<_, TransactionSplit>(
+            r#"
+            SELECT s.id, s.transaction_id, s.position, s.category_id, s.amount, s.description,
+                   s.created_at
+            FROM transaction_splits s
+            JOIN transactions t ON t.id = s.transaction_id
+            WHERE t.user_id = $1
+            ORDER BY s.transaction_id, s.position
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(splits)
+    }
+
+    /// Replace the split lines of a transaction atomically; no lines removes the split
+    pub async fn replace_splits(
+        pool: &PgPool,
+        transaction_id: Uuid,
+        lines: &[NewTransactionSplit],
+    ) -> Result<Vec<TransactionSplit>, AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM transaction_splits WHERE transaction_id = $1")
+            .bind(transaction_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut splits = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            let split = sqlx::query_as::<_, TransactionSplit>(
+                r#"
+                INSERT INTO transaction_splits (transaction_id, position, category_id, amount, description)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id, transaction_id, position, category_id, amount, description, created_at
+                "#,
+            )
+            .bind(transaction_id)
+            .bind(index as i16 + 1)
+            .bind(line.category_id)
+            .bind(line.amount)
+            .bind(&line.description)
+            .fetch_one(&mut *tx)
+            .await?;
+            splits.push(split);
+        }
+
+        sqlx::query("UPDATE transactions SET updated_at = now() WHERE id = $1")
+            .bind(transaction_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(splits)
+    }
+
+    /// Atomically insert new statement transactions and link matched existing ones to
+    /// their statement entries (`links` pairs a transaction id with its statement metadata),
+    /// queueing `transaction.created` webhooks for the inserted ones. Returns their ids in order
+    pub async fn import_statement(
+        pool: &PgPool,
+        scope: LedgerScope,
+        transactions: &[NewTransaction],
+        links: &[(Uuid, JsonValue)],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut ids = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            let id = Self::insert(&mut tx, scope, transaction)
+                .await
+                .map_err(statement_entry_conflict)?
+                .ok_or_else(|| AppError::Conflict("This message has already been recorded".to_string()))?;
+            ids.push(id);
+        }
+
+        for (id, statement) in links {
+            sqlx::query(
+                r#"
+                UPDATE transactions
+                SET metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('statement', $3::jsonb),
+                    updated_at = now()
+                WHERE id = $1
+                  AND (workspace_id = $4 OR ($4::uuid IS NULL AND workspace_id IS NULL AND user_id = $2))
+                "#,
+            )
+            .bind(id)
+            .bind(scope.user_id)
+            .bind(statement)
+            .bind(scope.workspace_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| statement_entry_conflict(e.into()))?;
+        }
+
+        webhooks::publish_created_transactions(&mut tx, scope.user_id, &ids).await?;
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    /// Insert a transaction into a ledger, authored by the scope's user. Without an
+    /// explicit account, a personal transaction is booked to the author's account linked
+    /// to its payment method, if there is one in the transaction's currency; workspace
+    /// transactions never touch the author's personal accounts
+    async fn insert(
+        conn: &mut PgConnection,
+        scope: LedgerScope,
+        transaction: &NewTransaction,
+    ) -> Result<Option<Uuid>, AppError> {
+        let id = sqlx::query_scalar(
+            r#"
+            INSERT INTO transactions (user_id, type, amount, currency, category_id,
+                                      payment_method_id, account_id, transfer_account_id,
+                                      merchant_name, location, description,
+                                      transaction_date, source, metadata, workspace_id,
+                                      client_id, source_message_id)
+            VALUES ($1, $2, $3, $4, $5, $6,
+                    COALESCE($7, CASE WHEN $15::uuid IS NULL THEN
+                                     (SELECT id FROM accounts
+                                      WHERE user_id = $1 AND payment_method_id = $6 AND currency = $4)
+                                 END),
+                    $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (client_id, source_message_id)
+                WHERE client_id IS NOT NULL AND source_message_id IS NOT NULL
+                DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(scope.user_id)
+        .bind(&transaction.r#type)
+        .bind(transaction.amount)
+        .bind(transaction.currency)
+        .bind(transaction.category_id)
+        .bind(transaction.payment_method_id)
+        .bind(transaction.account_id)
+        .bind(transaction.transfer_account_id)
+        .bind(&transaction.merchant_name)
+        .bind(&transaction.location)
+        .bind(&transaction.description)
+        .bind(transaction.transaction_date)
+        .bind(&transaction.source)
+        .bind(&transaction.metadata)
+        .bind(scope.workspace_id)
+        .bind(transaction.client_id)
+        .bind(&transaction.source_message_id)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(id)
+    }
+}
+
+/// Another import recorded the same statement entry first
+fn statement_entry_conflict(error: AppError) -> AppError {
+    match error {
+        AppError::Database(sqlx::Error::Database(e)) if e.constraint() == Some("uq_transactions_statement_entry") => {
+            AppError::Conflict("Some statement entries were imported by another request; retry the import".to_string())
+        }
+        error => error,
+    }
+}
@@ -0,0 +1,140 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{Duration, Utc};
+use crate::db::models::{OidcLoginState, UserIdentity};
+use crate::error::AppError;
+
+pub struct UserIdentityRepository;
+
+impl UserIdentityRepository {
+    /// Find an identity by provider and subject
+    pub async fn find_by_provider_subject(
+        pool: &PgPool,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>, AppError> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            r#"
+            SELECT id, user_id, provider, subject, email, last_login_at, created_at, updated_at
+            FROM user_identities
+            WHERE provider = $1 AND subject = $2
+            "#,
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(identity)
+    }
+
+    /// List identities linked to a user
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<UserIdentity>, AppError> {
+        let identities = sqlx::query_as::<_, UserIdentity>(
+            r#"
+            SELECT id, user_id, provider, subject, email, last_login_at, created_at, updated_at
+            FROM user_identities
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(identities)
+    }
+
+    /// Link a new identity to a user
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<UserIdentity, AppError> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email, last_login_at)
+            VALUES ($1, $2, $3, $4, now())
+            RETURNING id, user_id, provider, subject, email, last_login_at, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(email)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(identity)
+    }
+
+    /// Record a successful login through an identity
+    pub async fn update_last_login(
+        pool: &PgPool,
+        identity_id: Uuid,
+        email: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE user_identities
+            SET last_login_at = now(), email = COALESCE($1, email), updated_at = now()
+            WHERE id = $2
+            "#,
+        )
+        .bind(email)
+        .bind(identity_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a pending login's state, nonce and PKCE verifier
+    pub async fn create_login_state(
+        pool: &PgPool,
+        state: &str,
+        provider: &str,
+        code_verifier: &str,
+        nonce: &str,
+        expires_in_seconds: i64,
+    ) -> Result<(), AppError> {
+        let expires_at = Utc::now().naive_utc() + Duration::seconds(expires_in_seconds);
+
+        sqlx::query(
+            r#"
+            INSERT INTO oidc_login_states (state, provider, code_verifier, nonce, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(state)
+        .bind(provider)
+        .bind(code_verifier)
+        .bind(nonce)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Consume a pending login state (single use; expired states are not returned)
+    pub async fn take_login_state(
+        pool: &PgPool,
+        state: &str,
+    ) -> Result<Option<OidcLoginState>, AppError> {
+        let login_state = sqlx::query_as::<_, OidcLoginState>(
+            r#"
+            DELETE FROM oidc_login_states
+            WHERE state = $1
+            RETURNING provider, code_verifier, nonce, expires_at
+            "#,
+        )
+        .bind(state)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(login_state.filter(|s| s.expires_at > Utc::now().naive_utc()))
+    }
+}
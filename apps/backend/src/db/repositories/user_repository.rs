@@ -1,20 +1,25 @@
 use sqlx::PgPool;
 use uuid::Uuid;
-use crate::db::models::{User, UserRole, UserStatus};
+use chrono::NaiveDateTime;
+use crate::db::models::{Currency, User, UserRole, UserStatus};
 use crate::error::AppError;
 
 pub struct UserRepository;
 
 impl UserRepository {
-    /// Find user by email
-    pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<User>, AppError> {
+    /// Find user by email for login, including accounts still within their deletion grace period
+    pub async fn find_by_email_for_login(
+        pool: &PgPool,
+        email: &str,
+    ) -> Result<Option<User>, AppError> {
         let user = sqlx::query_as::<_, User>(
             r#"
             SELECT id, email, username, full_name, password_hash,
                    role::text as role, status::text as status,
-                   created_at, updated_at, deleted_at
+                   failed_login_attempts, locked_until, token_version, base_currency,
+                   created_at, updated_at, deleted_at, purge_after
             FROM users
-            WHERE email = $1 AND deleted_at IS NULL
+            WHERE email = $1 AND (deleted_at IS NULL OR purge_after > now())
             "#,
         )
         .bind(email)
@@ -30,7 +35,8 @@ impl UserRepository {
             r#"
             SELECT id, email, username, full_name, password_hash,
                    role::text as role, status::text as status,
-                   created_at, updated_at, deleted_at
+                   failed_login_attempts, locked_until, token_version, base_currency,
+                   created_at, updated_at, deleted_at, purge_after
             FROM users
             WHERE id = $1 AND deleted_at IS NULL
             "#,
@@ -42,6 +48,25 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Find an active user by username (case-insensitive)
+    pub async fn find_active_by_username(pool: &PgPool, username: &str) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, username, full_name, password_hash,
+                   role::text as role, status::text as status,
+                   failed_login_attempts, locked_until, token_version, base_currency,
+                   created_at, updated_at, deleted_at, purge_after
+            FROM users
+            WHERE lower(username) = lower($1) AND status = 'active' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
     /// Create new user with email/password
     pub async fn create_with_password(
         pool: &PgPool,
@@ -56,7 +81,8 @@ impl UserRepository {
             VALUES ($1, $2, $3, $4)
             RETURNING id, email, username, full_name, password_hash,
                       role::text as role, status::text as status,
-                      created_at, updated_at, deleted_at
+                      failed_login_attempts, locked_until, token_version, base_currency,
+                      created_at, updated_at, deleted_at, purge_after
             "#,
         )
         .bind(email)
@@ -69,10 +95,36 @@ impl UserRepository {
         Ok(user)
     }
 
-    /// Check if email exists
+    /// Create new user without a password (signs in through an external identity)
+    pub async fn create_without_password(
+        pool: &PgPool,
+        email: &str,
+        username: &str,
+        full_name: &str,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (email, username, full_name)
+            VALUES ($1, $2, $3)
+            RETURNING id, email, username, full_name, password_hash,
+                      role::text as role, status::text as status,
+                      failed_login_attempts, locked_until, token_version, base_currency,
+                      created_at, updated_at, deleted_at, purge_after
+            "#,
+        )
+        .bind(email)
+        .bind(username)
+        .bind(full_name)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Check if email is taken (deleted accounts keep theirs until purged)
     pub async fn email_exists(pool: &PgPool, email: &str) -> Result<bool, AppError> {
         let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE email = $1 AND deleted_at IS NULL)",
+            "SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)",
         )
         .bind(email)
         .fetch_one(pool)
@@ -81,10 +133,10 @@ impl UserRepository {
         Ok(exists)
     }
 
-    /// Check if username exists
+    /// Check if username is taken (deleted accounts keep theirs until purged)
     pub async fn username_exists(pool: &PgPool, username: &str) -> Result<bool, AppError> {
         let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE username = $1 AND deleted_at IS NULL)",
+            "SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)",
         )
         .bind(username)
         .fetch_one(pool)
@@ -92,4 +144,350 @@ impl UserRepository {
 
         Ok(exists)
     }
+
+    /// Record a failed login and return the new consecutive failure count
+    pub async fn increment_failed_logins(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
+        let count: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = failed_login_attempts + 1
+            WHERE id = $1
+            RETURNING failed_login_attempts
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Lock the account until the given time
+    pub async fn lock_until(
+        pool: &PgPool,
+        user_id: Uuid,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET locked_until = $1, updated_at = now()
+            WHERE id = $2
+            "#,
+        )
+        .bind(locked_until)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Clear failed login counter and any lockout
+    pub async fn reset_failed_logins(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = 0, locked_until = NULL
+            WHERE id = $1
+              AND (failed_login_attempts <> 0 OR locked_until IS NOT NULL)
+            "#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find user by ID, including soft-deleted users (admin use)
+    pub async fn find_by_id_including_deleted(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, username, full_name, password_hash,
+                   role::text as role, status::text as status,
+                   failed_login_attempts, locked_until, token_version, base_currency,
+                   created_at, updated_at, deleted_at, purge_after
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Search users by email, username or full name with optional status/role filters
+    pub async fn search(
+        pool: &PgPool,
+        query: Option<&str>,
+        status: Option<&UserStatus>,
+        role: Option<&UserRole>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<User>, i64), AppError> {
+        let pattern = query.map(|q| format!("%{}%", q.replace('%', "\\%").replace('_', "\\_")));
+
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, username, full_name, password_hash,
+                   role::text as role, status::text as status,
+                   failed_login_attempts, locked_until, token_version, base_currency,
+                   created_at, updated_at, deleted_at, purge_after
+            FROM users
+            WHERE ($1::text IS NULL
+                   OR email ILIKE $1 OR username ILIKE $1 OR full_name ILIKE $1)
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::text IS NULL OR role = $3)
+            ORDER BY created_at DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(&pattern)
+        .bind(status)
+        .bind(role)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM users
+            WHERE ($1::text IS NULL
+                   OR email ILIKE $1 OR username ILIKE $1 OR full_name ILIKE $1)
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::text IS NULL OR role = $3)
+            "#,
+        )
+        .bind(&pattern)
+        .bind(status)
+        .bind(role)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((users, total))
+    }
+
+    /// Set a user's status; reactivating also clears any soft delete, while
+    /// suspending or deleting invalidates all issued tokens
+    pub async fn update_status(
+        pool: &PgPool,
+        user_id: Uuid,
+        status: &UserStatus,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET status = $1,
+                deleted_at = CASE
+                    WHEN $1 = 'deleted' THEN COALESCE(deleted_at, now())
+                    WHEN $1 = 'active' THEN NULL
+                    ELSE deleted_at
+                END,
+                purge_after = CASE WHEN $1 = 'active' THEN NULL ELSE purge_after END,
+                token_version = CASE
+                    WHEN $1 IN ('suspended', 'deleted') THEN token_version + 1
+                    ELSE token_version
+                END,
+                updated_at = now()
+            WHERE id = $2
+            "#,
+        )
+        .bind(status)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Change the currency reports are converted into
+    pub async fn update_base_currency(
+        pool: &PgPool,
+        user_id: Uuid,
+        base_currency: Currency,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET base_currency = $2, updated_at = now()
+            WHERE id = $1
+            RETURNING id, email, username, full_name, password_hash,
+                      role::text as role, status::text as status,
+                      failed_login_attempts, locked_until, token_version, base_currency,
+                      created_at, updated_at, deleted_at, purge_after
+            "#,
+        )
+        .bind(user_id)
+        .bind(base_currency)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Invalidate every token issued to the user so far
+    pub async fn bump_token_version(pool: &PgPool, user_id: Uuid) -> Result<i32, AppError> {
+        let version: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE users
+            SET token_version = token_version + 1, updated_at = now()
+            WHERE id = $1
+            RETURNING token_version
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Replace the password hash and invalidate all existing tokens
+    pub async fn update_password(
+        pool: &PgPool,
+        user_id: Uuid,
+        password_hash: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET password_hash = $1, token_version = token_version + 1, updated_at = now()
+            WHERE id = $2
+            "#,
+        )
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Soft-delete an account at the user's request and schedule its hard purge
+    pub async fn schedule_deletion(
+        pool: &PgPool,
+        user_id: Uuid,
+        purge_after: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET status = 'deleted',
+                deleted_at = now(),
+                purge_after = $2,
+                token_version = token_version + 1,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(purge_after)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cancel a pending deletion and reactivate the account
+    pub async fn restore(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET status = 'active',
+                deleted_at = NULL,
+                purge_after = NULL,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Accounts whose deletion grace period has ended
+    pub async fn list_due_for_purge(pool: &PgPool, limit: i64) -> Result<Vec<Uuid>, AppError> {
+        let user_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id
+            FROM users
+            WHERE status = 'deleted' AND purge_after <= now()
+            ORDER BY purge_after
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(user_ids)
+    }
+
+    /// Hard-delete a user whose grace period has ended. Owned rows go with it via
+    /// ON DELETE CASCADE; audit log entries are kept but stripped of personal data, and
+    /// debts with other users stay with them.
+    /// Returns false if the account was restored (or purged) in the meantime.
+    pub async fn purge(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
+        let mut tx = pool.begin().await?;
+
+        // Lock the row so a concurrent login cannot restore it mid-purge
+        let due: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            SELECT email
+            FROM users
+            WHERE id = $1 AND status = 'deleted' AND purge_after <= now()
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(email) = due else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE audit_logs
+            SET old_values = NULL, new_values = NULL, ip_address = NULL, user_agent = NULL
+            WHERE user_id = $1 OR (entity_type = 'user' AND entity_id = $1)
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Login attempts only keep user_id via SET NULL; the email would remain
+        sqlx::query("DELETE FROM login_attempts WHERE user_id = $1 OR email = $2")
+            .bind(user_id)
+            .bind(email)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Debts with other users are kept for them; entries nobody is left to see
+        // (with a contact, or whose other party is also gone) go
+        sqlx::query("DELETE FROM debts WHERE creditor_user_id IS NULL AND debtor_user_id IS NULL")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
 }
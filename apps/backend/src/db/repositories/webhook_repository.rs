@@ -0,0 +1,302 @@
+use chrono::NaiveDateTime;
+use serde_json::Value as JsonValue;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use crate::db::models::{
+    WebhookAttempt, WebhookDelivery, WebhookDeliveryStatus, WebhookEndpoint, WebhookJob,
+};
+use crate::error::AppError;
+
+pub struct WebhookRepository;
+
+impl WebhookRepository {
+    /// List a user's webhook endpoints, oldest first
+    pub async fn list_endpoints(pool: &PgPool, user_id: Uuid) -> Result<Vec<WebhookEndpoint>, AppError> {
+        let endpoints = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            SELECT id, user_id, url, description, events, is_active, created_at, updated_at
+            FROM webhook_endpoints
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// Find a webhook endpoint of a user
+    pub async fn find_endpoint(
+        pool: &PgPool,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<Option<WebhookEndpoint>, AppError> {
+        let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            SELECT id, user_id, url, description, events, is_active, created_at, updated_at
+            FROM webhook_endpoints
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    pub async fn create_endpoint(
+        pool: &PgPool,
+        user_id: Uuid,
+        url: &str,
+        description: Option<&str>,
+        secret: &str,
+        events: &[String],
+    ) -> Result<WebhookEndpoint, AppError> {
+        let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            INSERT INTO webhook_endpoints (user_id, url, description, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, url, description, events, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(url)
+        .bind(description)
+        .bind(secret)
+        .bind(events)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    pub async fn update_endpoint(pool: &PgPool, endpoint: &WebhookEndpoint) -> Result<WebhookEndpoint, AppError> {
+        let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            UPDATE webhook_endpoints
+            SET url = $3, description = $4, events = $5, is_active = $6, updated_at = now()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, url, description, events, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(endpoint.id)
+        .bind(endpoint.user_id)
+        .bind(&endpoint.url)
+        .bind(&endpoint.description)
+        .bind(&endpoint.events)
+        .bind(endpoint.is_active)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    /// Delete an endpoint together with its deliveries
+    pub async fn delete_endpoint(pool: &PgPool, user_id: Uuid, endpoint_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1 AND user_id = $2")
+            .bind(endpoint_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether any active endpoint of the user subscribes to `event`
+    pub async fn has_subscribers(conn: &mut PgConnection, user_id: Uuid, event: &str) -> Result<bool, AppError> {
+        let subscribed: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM webhook_endpoints
+                WHERE user_id = $1 AND is_active AND $2 = ANY(events)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(event)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(subscribed)
+    }
+
+    /// Queue events (`(event id, payload)` pairs) for every active endpoint of the user
+    /// subscribed to `event`. Returns the number of deliveries queued
+    pub async fn enqueue(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        event: &str,
+        events: &[(Uuid, JsonValue)],
+    ) -> Result<u64, AppError> {
+        let (event_ids, payloads): (Vec<Uuid>, Vec<JsonValue>) = events.iter().cloned().unzip();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (endpoint_id, event_id, event, payload)
+            SELECT e.id, p.event_id, $2, p.payload
+            FROM webhook_endpoints e
+            CROSS JOIN unnest($3::uuid[], $4::jsonb[]) AS p(event_id, payload)
+            WHERE e.user_id = $1 AND e.is_active AND $2 = ANY(e.events)
+            "#,
+        )
+        .bind(user_id)
+        .bind(event)
+        .bind(&event_ids)
+        .bind(&payloads)
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deliveries to an endpoint, newest first, with the total count
+    pub async fn list_deliveries(
+        pool: &PgPool,
+        endpoint_id: Uuid,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<WebhookDelivery>, i64), AppError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, endpoint_id, event_id, event, payload, status, attempts, next_attempt_at,
+                   last_attempt_at, response_status, error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE endpoint_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            ORDER BY created_at DESC, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM webhook_deliveries
+            WHERE endpoint_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(status)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((deliveries, total))
+    }
+
+    pub async fn find_delivery(
+        pool: &PgPool,
+        endpoint_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<Option<WebhookDelivery>, AppError> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, endpoint_id, event_id, event, payload, status, attempts, next_attempt_at,
+                   last_attempt_at, response_status, error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE id = $1 AND endpoint_id = $2
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(endpoint_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Queue the event of a delivery again, as a new delivery sent right away
+    pub async fn redeliver(pool: &PgPool, delivery: &WebhookDelivery) -> Result<WebhookDelivery, AppError> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            INSERT INTO webhook_deliveries (endpoint_id, event_id, event, payload)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, endpoint_id, event_id, event, payload, status, attempts, next_attempt_at,
+                      last_attempt_at, response_status, error, created_at, delivered_at
+            "#,
+        )
+        .bind(delivery.endpoint_id)
+        .bind(delivery.event_id)
+        .bind(&delivery.event)
+        .bind(&delivery.payload)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Take up to `limit` due deliveries of active endpoints, oldest first. Each counts an
+    /// attempt and is held back `lease_seconds`, so concurrent workers (or a worker that
+    /// stops mid-send) never send it twice at once
+    pub async fn claim_due(pool: &PgPool, limit: i64, lease_seconds: i64) -> Result<Vec<WebhookJob>, AppError> {
+        let jobs = sqlx::query_as::<_, WebhookJob>(
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries
+                SET attempts = attempts + 1,
+                    last_attempt_at = now(),
+                    next_attempt_at = now() + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT d.id
+                    FROM webhook_deliveries d
+                    JOIN webhook_endpoints e ON e.id = d.endpoint_id
+                    WHERE d.status = 'pending' AND d.next_attempt_at <= now() AND e.is_active
+                    ORDER BY d.next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE OF d SKIP LOCKED
+                )
+                RETURNING id, endpoint_id, event_id, event, payload, attempts
+            )
+            SELECT c.id AS delivery_id, c.event_id, c.event, c.payload, c.attempts, e.url, e.secret
+            FROM claimed c
+            JOIN webhook_endpoints e ON e.id = c.endpoint_id
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Record an attempt. A successful one completes the delivery; a failed one is retried
+    /// at `retry_at`, or the delivery is given up without one
+    pub async fn record_attempt(
+        pool: &PgPool,
+        delivery_id: Uuid,
+        attempt: &WebhookAttempt,
+        succeeded: bool,
+        retry_at: Option<NaiveDateTime>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = CASE WHEN $4 THEN 'succeeded' WHEN $5::timestamp IS NULL THEN 'failed' ELSE 'pending' END,
+                response_status = $2,
+                error = $3,
+                next_attempt_at = COALESCE($5, next_attempt_at),
+                delivered_at = CASE WHEN $4 THEN now() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(attempt.response_status)
+        .bind(&attempt.error)
+        .bind(succeeded)
+        .bind(retry_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::db::models::{Workspace, WorkspaceMember, WorkspaceRole, WorkspaceWithRole};
+use crate::error::AppError;
+
+pub struct WorkspaceRepository;
+
+impl WorkspaceRepository {
+    /// Workspaces the user is a member of, with their role
+    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<WorkspaceWithRole>, AppError> {
+        let workspaces = sqlx::query_as::<_, WorkspaceWithRole>(
+            r#"
+            SELECT w.id, w.name, w.created_by, w.created_at, w.updated_at, m.role,
+                   (SELECT COUNT(*) FROM workspace_members o WHERE o.workspace_id = w.id) AS member_count
+            FROM workspaces w
+            JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = $1
+            ORDER BY w.name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(workspaces)
+    }
+
+    /// A workspace the user is a member of, with their role
+    pub async fn find_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Option<WorkspaceWithRole>, AppError> {
+        let workspace = sqlx::query_as::<_, WorkspaceWithRole>(
+            r#"
+            SELECT w.id, w.name, w.created_by, w.created_at, w.updated_at, m.role,
+                   (SELECT COUNT(*) FROM workspace_members o WHERE o.workspace_id = w.id) AS member_count
+            FROM workspaces w
+            JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = $1
+            WHERE w.id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(workspace)
+    }
+
+    /// The user's role in a workspace, or None when they are not a member
+    pub async fn find_role(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceRole>, AppError> {
+        let role = sqlx::query_scalar::<_, WorkspaceRole>(
+            "SELECT role FROM workspace_members WHERE workspace_id = $1 AND user_id = $2",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Create a workspace with its creator as the owner
+    pub async fn create(pool: &PgPool, user_id: Uuid, name: &str) -> Result<Workspace, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let workspace = sqlx::query_as::<_, Workspace>(
+            r#"
+            INSERT INTO workspaces (name, created_by)
+            VALUES ($1, $2)
+            RETURNING id, name, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, 'owner')")
+            .bind(workspace.id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(workspace)
+    }
+
+    pub async fn rename(pool: &PgPool, workspace_id: Uuid, name: &str) -> Result<Workspace, AppError> {
+        let workspace = sqlx::query_as::<_, Workspace>(
+            r#"
+            UPDATE workspaces
+            SET name = $2, updated_at = now()
+            WHERE id = $1
+            RETURNING id, name, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(workspace)
+    }
+
+    /// Delete a workspace with its memberships, transactions, categories and tags
+    pub async fn delete(pool: &PgPool, workspace_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM workspaces WHERE id = $1")
+            .bind(workspace_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Members of a workspace, owners first
+    pub async fn list_members(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>, AppError> {
+        let members = sqlx::query_as::<_, WorkspaceMember>(
+            r#"
+            SELECT m.workspace_id, m.user_id, u.username, u.full_name, m.role, m.created_at, m.updated_at
+            FROM workspace_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.workspace_id = $1
+            ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END, u.full_name
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    pub async fn find_member(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<WorkspaceMember>, AppError> {
+        let member = sqlx::query_as::<_, WorkspaceMember>(
+            r#"
+            SELECT m.workspace_id, m.user_id, u.username, u.full_name, m.role, m.created_at, m.updated_at
+            FROM workspace_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.workspace_id = $1 AND m.user_id = $2
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(member)
+    }
+
+    pub async fn add_member(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, $3)")
+            .bind(workspace_id)
+            .bind(user_id)
+            .bind(role)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_member_role(
+        pool: &PgPool,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE workspace_members
+            SET role = $3, updated_at = now()
+            WHERE workspace_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(pool: &PgPool, workspace_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM workspace_members WHERE workspace_id = $1 AND user_id = $2")
+            .bind(workspace_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn count_owners(pool: &PgPool, workspace_id: Uuid) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM workspace_members WHERE workspace_id = $1 AND role = 'owner'",
+        )
+        .bind(workspace_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
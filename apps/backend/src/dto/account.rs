@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use crate::db::models::{Account, AccountEntry, AccountType, Currency};
+
+// ============================================================================
+// Account Deletion
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeleteAccountRequest {
+    /// Required for accounts with a password; social-login accounts have none
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteAccountResponse {
+    pub message: String,
+    /// Logging in before this time restores the account
+    pub purge_after: NaiveDateTime,
+}
+
+// ============================================================================
+// Account Settings
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingsRequest {
+    /// Currency that reports convert every transaction into
+    pub base_currency: Option<Currency>,
+}
+
+// ============================================================================
+// Accounts (where money is held)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListAccountsQuery {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAccountRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+    pub r#type: AccountType,
+    /// Book transactions paid with this payment method to the account
+    pub payment_method_id: Option<Uuid>,
+    /// Defaults to the user's base currency
+    pub currency: Option<Currency>,
+    #[serde(default)]
+    pub opening_balance: Decimal,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAccountRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+    pub r#type: Option<AccountType>,
+    pub payment_method_id: Option<Uuid>,
+    /// Remove the payment method link
+    #[serde(default)]
+    pub unlink_payment_method: bool,
+    pub opening_balance: Option<Decimal>,
+    pub is_archived: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDeletedResponse {
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountEntriesQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountEntriesResponse {
+    pub account: Account,
+    pub entries: Vec<AccountEntry>,
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::db::models::{Currency, ExchangeRate, User, UserIdentity, UserRole, UserStatus};
+use crate::dto::auth::UserResponse;
+
+// ============================================================================
+// User Search
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListUsersQuery {
+    /// Matches email, username or full name (case-insensitive, substring)
+    pub q: Option<String>,
+    pub status: Option<UserStatus>,
+    pub role: Option<UserRole>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListUsersResponse {
+    pub users: Vec<UserResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+// ============================================================================
+// User Detail
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    /// Scheduled hard purge of an account deleted by its owner
+    pub purge_after: Option<NaiveDateTime>,
+    pub identities: Vec<UserIdentityResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserIdentityResponse {
+    pub id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl AdminUserResponse {
+    pub fn new(user: &User, identities: Vec<UserIdentity>) -> Self {
+        AdminUserResponse {
+            user: UserResponse::from(user.to_safe_user()),
+            failed_login_attempts: user.failed_login_attempts,
+            locked_until: user.locked_until,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+            purge_after: user.purge_after,
+            identities: identities
+                .into_iter()
+                .map(|identity| UserIdentityResponse {
+                    id: identity.id,
+                    provider: identity.provider,
+                    subject: identity.subject,
+                    email: identity.email,
+                    last_login_at: identity.last_login_at,
+                    created_at: identity.created_at,
+                    updated_at: identity.updated_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+// ============================================================================
+// Exchange Rates
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListExchangeRatesQuery {
+    pub base: Option<Currency>,
+    pub quote: Option<Currency>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListExchangeRatesResponse {
+    pub rates: Vec<ExchangeRate>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadExchangeRatesResponse {
+    pub upload_id: Uuid,
+    /// Rates inserted or replaced
+    pub stored_rates: u64,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+// ============================================================================
+// Admin Actions
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct AdminActionResponse {
+    pub message: String,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use crate::db::models::{AnomalyKind, AnomalyStatus, AnomalyWithTransaction};
+
+// ============================================================================
+// Anomalies
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListAnomaliesQuery {
+    /// Defaults to `open`
+    pub status: Option<AnomalyStatus>,
+    pub kind: Option<AnomalyKind>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAnomaliesResponse {
+    pub anomalies: Vec<AnomalyWithTransaction>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnomalyDismissedResponse {
+    pub message: String,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 use chrono::NaiveDateTime;
+use crate::db::models::{Currency, SafeUser};
 
 // ============================================================================
 // Registration
@@ -80,6 +81,24 @@ pub struct LogoutResponse {
     pub message: String,
 }
 
+// ============================================================================
+// Change Password
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangePasswordResponse {
+    pub message: String,
+}
+
 // ============================================================================
 // Me (Current User)
 // ============================================================================
@@ -151,5 +170,21 @@ pub struct UserResponse {
     pub full_name: String,
     pub role: String,
     pub status: String,
+    pub base_currency: Currency,
     pub created_at: NaiveDateTime,
 }
+
+impl From<SafeUser> for UserResponse {
+    fn from(user: SafeUser) -> Self {
+        UserResponse {
+            id: user.id,
+            email: user.email,
+            username: user.username,
+            full_name: user.full_name,
+            role: format!("{:?}", user.role).to_lowercase(),
+            status: format!("{:?}", user.status).to_lowercase(),
+            base_currency: user.base_currency,
+            created_at: user.created_at,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use crate::db::models::{Currency, DebtBalance, DebtDirection, DebtKind, DebtWithParties};
+
+// ============================================================================
+// Debt Entries
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListDebtsQuery {
+    /// Only entries with this user
+    pub counterparty_user_id: Option<Uuid>,
+    /// Only entries with this contact or deleted user (case-insensitive)
+    pub contact_name: Option<String>,
+    pub kind: Option<DebtKind>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDebtsResponse {
+    pub entries: Vec<DebtResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// Record a debt with another user (by username) or with a contact (by name)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDebtRequest {
+    /// to_me: the counterparty owes the user; from_me: the user owes the counterparty
+    pub direction: DebtDirection,
+    pub counterparty_username: Option<String>,
+    #[validate(length(min = 1, max = 100, message = "Contact name must be between 1 and 100 characters"))]
+    pub contact_name: Option<String>,
+    pub amount: Decimal,
+    /// Defaults to the user's base currency
+    pub currency: Option<Currency>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+    /// Defaults to today
+    pub entry_date: Option<NaiveDate>,
+    /// One of the user's transactions, e.g. the bill they paid for everyone
+    pub transaction_id: Option<Uuid>,
+}
+
+/// Record a repayment to or from a counterparty with an outstanding balance
+#[derive(Debug, Deserialize, Validate)]
+pub struct SettleUpRequest {
+    pub counterparty_user_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 100, message = "Contact name must be between 1 and 100 characters"))]
+    pub contact_name: Option<String>,
+    /// Defaults to the user's base currency
+    pub currency: Option<Currency>,
+    /// Defaults to the full outstanding balance
+    pub amount: Option<Decimal>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+    /// Defaults to today
+    pub entry_date: Option<NaiveDate>,
+    /// One of the user's transactions, e.g. the bank transfer that paid the debt
+    pub transaction_id: Option<Uuid>,
+}
+
+/// A debt entry as seen by one of its parties
+#[derive(Debug, Serialize)]
+pub struct DebtResponse {
+    pub id: Uuid,
+    pub kind: DebtKind,
+    pub direction: DebtDirection,
+    pub counterparty: DebtCounterparty,
+    pub amount: Decimal,
+    pub currency: Currency,
+    /// Change to what the counterparty owes the user (negative: to what the user owes them)
+    pub balance_effect: Decimal,
+    pub description: Option<String>,
+    pub entry_date: NaiveDate,
+    /// Only shown to the user who recorded the entry
+    pub transaction_id: Option<Uuid>,
+    pub created_by_me: bool,
+    /// Set when the party who did not record the entry disputed it; it then no longer
+    /// counts towards the balance
+    pub disputed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebtCounterparty {
+    /// None for a contact or a deleted user
+    pub user_id: Option<Uuid>,
+    pub name: String,
+}
+
+impl DebtResponse {
+    pub fn for_user(entry: DebtWithParties, user_id: Uuid) -> Self {
+        let DebtWithParties { debt, creditor_name, debtor_name } = entry;
+        let direction = debt.direction_for(user_id);
+        let counterparty_name = match direction {
+            DebtDirection::ToMe => debtor_name,
+            DebtDirection::FromMe => creditor_name,
+        };
+        let created_by_me = debt.created_by == Some(user_id);
+
+        DebtResponse {
+            id: debt.id,
+            balance_effect: debt.balance_effect(user_id),
+            direction,
+            counterparty: DebtCounterparty {
+                user_id: debt.counterparty_user_id(user_id),
+                name: counterparty_name.or(debt.contact_name).unwrap_or_default(),
+            },
+            kind: debt.kind,
+            amount: debt.amount,
+            currency: debt.currency,
+            description: debt.description,
+            entry_date: debt.entry_date,
+            transaction_id: debt.transaction_id.filter(|_| created_by_me),
+            created_by_me,
+            disputed_at: debt.disputed_at,
+            created_at: debt.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebtDeletedResponse {
+    pub message: String,
+}
+
+// ============================================================================
+// Balances
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct DebtBalancesQuery {
+    /// Also list counterparties the user is all square with
+    #[serde(default)]
+    pub include_settled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebtBalancesResponse {
+    pub balances: Vec<DebtBalance>,
+}
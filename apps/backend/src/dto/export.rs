@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::db::models::{DataExport, ExportStatus};
+
+// ============================================================================
+// Data Export Jobs
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ExportResponse {
+    pub id: Uuid,
+    pub status: ExportStatus,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    /// Signed link, present while the archive is downloadable
+    pub download_url: Option<String>,
+    pub download_url_expires_at: Option<NaiveDateTime>,
+    /// When the archive stops being downloadable
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl ExportResponse {
+    pub fn new(export: DataExport, download: Option<(String, NaiveDateTime)>) -> Self {
+        let (download_url, download_url_expires_at) = download.unzip();
+
+        Self {
+            id: export.id,
+            status: export.status,
+            file_size: export.file_size,
+            error: export.error,
+            download_url,
+            download_url_expires_at,
+            expires_at: export.expires_at,
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListExportsResponse {
+    pub exports: Vec<ExportResponse>,
+}
+
+// ============================================================================
+// Signed Download
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadExportQuery {
+    pub expires: i64,
+    pub signature: String,
+}
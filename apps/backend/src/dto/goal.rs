@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use crate::{
+    db::models::{Currency, Goal, GoalContribution},
+    utils::goals::GoalProgress,
+};
+
+// ============================================================================
+// Goals
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListGoalsQuery {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateGoalRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+    pub target_amount: Decimal,
+    /// Defaults to the user's base currency, or the linked account's currency
+    pub currency: Option<Currency>,
+    /// Defaults to today
+    pub start_date: Option<NaiveDate>,
+    pub deadline: NaiveDate,
+    /// Count the net inflow of this account towards the goal
+    pub account_id: Option<Uuid>,
+    /// Count transactions with this tag towards the goal
+    pub tag_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateGoalRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+    pub target_amount: Option<Decimal>,
+    pub start_date: Option<NaiveDate>,
+    pub deadline: Option<NaiveDate>,
+    pub account_id: Option<Uuid>,
+    /// Remove the account link
+    #[serde(default)]
+    pub unlink_account: bool,
+    pub tag_id: Option<Uuid>,
+    /// Remove the tag link
+    #[serde(default)]
+    pub unlink_tag: bool,
+    pub is_archived: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalResponse {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub progress: GoalProgress,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalDetailResponse {
+    #[serde(flatten)]
+    pub goal: GoalResponse,
+    /// Entered by hand or from transactions; linked account and tag transactions are
+    /// counted in the progress without being listed here
+    pub contributions: Vec<GoalContribution>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalDeletedResponse {
+    pub message: String,
+}
+
+// ============================================================================
+// Contributions
+// ============================================================================
+
+/// A manual contribution (amount) or one taken from a transaction (transaction_id, with
+/// the amount defaulting to the transaction amount)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateContributionRequest {
+    /// Negative for a withdrawal
+    pub amount: Option<Decimal>,
+    pub transaction_id: Option<Uuid>,
+    /// Defaults to the transaction date, or today
+    pub contribution_date: Option<NaiveDate>,
+    #[validate(length(max = 255, message = "Note must be at most 255 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContributionDeletedResponse {
+    pub message: String,
+}
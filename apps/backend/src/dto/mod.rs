@@ -1 +1,13 @@
 pub mod auth;
+pub mod admin;
+pub mod oidc;
+pub mod export;
+pub mod account;
+pub mod transaction;
+pub mod report;
+pub mod debt;
+pub mod goal;
+pub mod workspace;
+pub mod receipt_draft;
+pub mod anomaly;
+pub mod webhook;
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// ============================================================================
+// Authorize
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+    pub state: String,
+}
+
+// ============================================================================
+// Callback
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1, message = "Authorization code is required"))]
+    pub code: String,
+
+    #[validate(length(min = 1, message = "State is required"))]
+    pub state: String,
+}
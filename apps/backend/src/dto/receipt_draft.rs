@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use crate::db::models::{ReceiptDraftStatus, Transaction};
+
+// ============================================================================
+// Receipt Drafts
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListReceiptDraftsQuery {
+    /// e.g. `needs_review` for the review queue
+    pub status: Option<ReceiptDraftStatus>,
+    pub transaction_id: Option<Uuid>,
+}
+
+/// Confirm a draft onto its transaction; given fields replace what OCR read
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmReceiptDraftRequest {
+    pub amount: Option<Decimal>,
+    pub transaction_date: Option<NaiveDate>,
+    #[validate(length(min = 1, max = 255, message = "Merchant name must be between 1 and 255 characters"))]
+    pub merchant_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfirmReceiptDraftResponse {
+    pub message: String,
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptDraftDismissedResponse {
+    pub message: String,
+}
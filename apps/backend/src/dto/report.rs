@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use crate::{
+    db::models::{Currency, TransactionType},
+    utils::timeseries::{BucketInterval, SeriesGrouping},
+};
+
+// ============================================================================
+// Summary
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ReportSummaryQuery {
+    /// Defaults to the first day of the current month
+    pub from: Option<NaiveDate>,
+    /// Defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+/// Totals in the user's base currency, each transaction converted with the rate of its
+/// `transaction_date`. Amounts without a usable rate are listed but left out of totals.
+#[derive(Debug, Serialize)]
+pub struct ReportSummaryResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub base_currency: Currency,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub transaction_count: i64,
+    pub by_category: Vec<CategorySummary>,
+    pub by_currency: Vec<CurrencySummary>,
+    /// Currencies with transactions that could not be converted for lack of a rate
+    pub unconverted_currencies: Vec<Currency>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategorySummary {
+    pub category_id: Option<Uuid>,
+    pub category_name: Option<String>,
+    pub r#type: TransactionType,
+    /// In the base currency
+    pub amount: Decimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurrencySummary {
+    pub currency: Currency,
+    pub r#type: TransactionType,
+    /// Original amount, in `currency`
+    pub amount: Decimal,
+    /// Amount in the base currency; `None` when some of it could not be converted
+    pub converted_amount: Option<Decimal>,
+    pub count: i64,
+}
+
+// ============================================================================
+// Time Series
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ReportTimeseriesQuery {
+    /// Defaults to the start of the bucket eleven buckets before `to`
+    pub from: Option<NaiveDate>,
+    /// Defaults to today
+    pub to: Option<NaiveDate>,
+    /// `day`, `week` or `month` (default)
+    #[serde(default)]
+    pub interval: BucketInterval,
+    /// `none` (default), `category` or `tag`
+    #[serde(default)]
+    pub group_by: SeriesGrouping,
+    /// Buckets averaged into each moving average (default 3)
+    pub window: Option<u32>,
+}
+
+/// Income and expense per bucket in the base currency, zero-filled. Buckets are whole
+/// days, weeks (from Monday) or months, so `from` is moved back to the start of its bucket.
+#[derive(Debug, Serialize)]
+pub struct ReportTimeseriesResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub interval: BucketInterval,
+    pub group_by: SeriesGrouping,
+    pub window: u32,
+    pub base_currency: Currency,
+    pub series: Vec<TimeseriesSeries>,
+    /// Currencies with transactions that could not be converted for lack of a rate
+    pub unconverted_currencies: Vec<Currency>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesSeries {
+    /// Category or tag id; `None` for uncategorized or untagged amounts, or when the report
+    /// is not grouped
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+    pub points: Vec<TimeseriesPoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesPoint {
+    pub bucket_start: NaiveDate,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub transaction_count: i64,
+    pub income_moving_average: Decimal,
+    pub expense_moving_average: Decimal,
+    /// Percentage change from the previous bucket (month-over-month for monthly buckets);
+    /// `None` when the previous bucket was zero
+    pub income_change_pct: Option<Decimal>,
+    pub expense_change_pct: Option<Decimal>,
+}
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use uuid::Uuid;
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use crate::{
+    db::models::{
+        Currency, NewTransactionSplit, Transaction, TransactionAttachment, TransactionFilter,
+        TransactionSearchHit, TransactionSplit, TransactionType,
+    },
+    utils::{statement_import::StatementFormat, transaction_export::ExportFormat},
+};
+
+// ============================================================================
+// Transfers
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTransferRequest {
+    /// Account the money leaves
+    pub from_account_id: Uuid,
+    /// Account the money arrives in
+    pub to_account_id: Uuid,
+    pub amount: Decimal,
+    /// Defaults to today
+    pub transaction_date: Option<NaiveDate>,
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+}
+
+// ============================================================================
+// Splits
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSplitsRequest {
+    /// Lines in display order; an empty list removes the split
+    pub lines: Vec<NewTransactionSplit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionSplitsResponse {
+    pub transaction_id: Uuid,
+    pub amount: Decimal,
+    pub currency: Currency,
+    /// Empty when the transaction is not split
+    pub splits: Vec<TransactionSplit>,
+}
+
+// ============================================================================
+// CSV Import
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Validate and report without saving anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Leave out CSV rows matching an existing transaction; when false they are imported
+    /// with a warning. Statement entries are always matched by their own ids
+    #[serde(default = "default_skip_duplicates")]
+    pub skip_duplicates: bool,
+}
+
+fn default_skip_duplicates() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Tags every transaction created by this import (stored in its metadata)
+    pub batch_id: Option<Uuid>,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub invalid_rows: usize,
+    pub duplicate_rows: usize,
+    pub imported_rows: usize,
+    pub rows: Vec<ImportRowReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowReport {
+    /// Line number in the uploaded file (the header is line 1)
+    pub line: usize,
+    pub status: ImportRowStatus,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub transaction: Option<ImportedTransaction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportRowStatus {
+    /// Would be imported (dry run)
+    Valid,
+    Invalid,
+    /// Matches an existing transaction or an earlier row by date, amount and merchant
+    Duplicate,
+    Imported,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedTransaction {
+    pub id: Option<Uuid>,
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub transaction_date: NaiveDate,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+    pub merchant_name: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+}
+
+// ============================================================================
+// Statement Import
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct StatementImportReport {
+    pub dry_run: bool,
+    pub format: StatementFormat,
+    /// Tags every transaction created or reconciled by this import
+    pub batch_id: Option<Uuid>,
+    pub total_rows: usize,
+    /// Entries that are (or would be) imported as new transactions
+    pub new_rows: usize,
+    /// Entries that match (or would be linked to) a WhatsApp-entered transaction
+    pub matched_rows: usize,
+    pub duplicate_rows: usize,
+    pub imported_rows: usize,
+    pub reconciled_rows: usize,
+    pub rows: Vec<StatementRowReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementRowReport {
+    /// The bank's id for the entry, stored in `metadata.statement.transaction_id`
+    pub statement_transaction_id: String,
+    pub account: Option<String>,
+    pub status: StatementRowStatus,
+    /// The created transaction, or the existing one the entry matched
+    pub transaction_id: Option<Uuid>,
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub transaction_date: NaiveDate,
+    pub merchant_name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementRowStatus {
+    /// Would be imported (dry run)
+    New,
+    Imported,
+    /// Already imported from an earlier statement, or repeated within this one
+    Duplicate,
+    /// Would be linked to a matching WhatsApp-entered transaction (dry run)
+    Matched,
+    /// Linked to a matching WhatsApp-entered transaction instead of creating a new one
+    Reconciled,
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionExportQuery {
+    /// `csv` (default), `xlsx` or `ofx`
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Inclusive date range
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub r#type: Option<TransactionType>,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+}
+
+impl TransactionExportQuery {
+    pub fn filter(&self) -> TransactionFilter {
+        TransactionFilter {
+            from: self.from,
+            to: self.to,
+            r#type: self.r#type.clone(),
+            category_id: self.category_id,
+            payment_method_id: self.payment_method_id,
+        }
+    }
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchTransactionsQuery {
+    /// Words to look for in the description, merchant, location and tags; each word
+    /// also matches longer words it is the start of
+    pub q: String,
+    /// Inclusive date range
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub r#type: Option<TransactionType>,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl SearchTransactionsQuery {
+    pub fn filter(&self) -> TransactionFilter {
+        TransactionFilter {
+            from: self.from,
+            to: self.to,
+            r#type: self.r#type.clone(),
+            category_id: self.category_id,
+            payment_method_id: self.payment_method_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchTransactionsResponse {
+    pub results: Vec<TransactionSearchHit>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+// ============================================================================
+// Duplicates
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListDuplicatesQuery {
+    /// Inclusive date range; defaults to the last 90 days
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicatePairResponse {
+    /// The one recorded first, suggested to keep
+    pub transaction: Transaction,
+    pub duplicate: Transaction,
+    /// Likelihood between 0 and 1 that both record the same spend
+    pub score: f64,
+    /// Both were recorded from the same chat message
+    pub same_message: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDuplicatesResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub pairs: Vec<DuplicatePairResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeDuplicateRequest {
+    pub keep_id: Uuid,
+    /// Soft-deleted after its tags and links move to the one kept
+    pub duplicate_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeDuplicateResponse {
+    pub message: String,
+    pub transaction: Transaction,
+}
+
+// ============================================================================
+// Chat agent
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordMessageTransactionRequest {
+    /// Messaging client the message arrived from
+    pub client_id: Uuid,
+    /// Channel message id; a message is recorded once, so retries return the first result
+    #[validate(length(min = 1, max = 255, message = "Message id must be between 1 and 255 characters"))]
+    pub source_message_id: String,
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    /// Defaults to the user's base currency
+    pub currency: Option<Currency>,
+    pub category_id: Option<Uuid>,
+    pub payment_method_id: Option<Uuid>,
+    #[validate(length(max = 255, message = "Merchant name must be at most 255 characters"))]
+    pub merchant_name: Option<String>,
+    #[validate(length(max = 255, message = "Location must be at most 255 characters"))]
+    pub location: Option<String>,
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+    /// Defaults to today
+    pub transaction_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PossibleDuplicate {
+    pub transaction: Transaction,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordMessageTransactionResponse {
+    pub transaction: Transaction,
+    /// False when the message had already been recorded
+    pub created: bool,
+    /// Other transactions that look like the same spend, for the agent to ask about
+    pub possible_duplicates: Vec<PossibleDuplicate>,
+}
+
+// ============================================================================
+// Attachments
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    #[serde(flatten)]
+    pub attachment: TransactionAttachment,
+    /// Signed link; anyone holding it can download the file until it expires
+    pub download_url: String,
+    pub download_url_expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadAttachmentQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentDeletedResponse {
+    pub message: String,
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::db::models::{WebhookDelivery, WebhookDeliveryStatus, WebhookEndpoint, WebhookEvent};
+
+// ============================================================================
+// Endpoints
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWebhookRequest {
+    #[validate(length(min = 1, max = 2048, message = "URL must be between 1 and 2048 characters"))]
+    pub url: String,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Vec<WebhookEvent>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookResponse {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    /// Key for verifying the signature header; shown only once
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWebhookRequest {
+    #[validate(length(min = 1, max = 2048, message = "URL must be between 1 and 2048 characters"))]
+    pub url: Option<String>,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Option<Vec<WebhookEvent>>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+    /// Paused endpoints get no new events; their pending deliveries wait
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookDeletedResponse {
+    pub message: String,
+}
+
+// ============================================================================
+// Deliveries
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDeliveriesResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::db::models::{WorkspaceMember, WorkspaceRole, WorkspaceWithRole};
+
+// ============================================================================
+// Workspaces
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWorkspaceRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWorkspaceRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceDetailResponse {
+    #[serde(flatten)]
+    pub workspace: WorkspaceWithRole,
+    pub members: Vec<WorkspaceMember>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceDeletedResponse {
+    pub message: String,
+}
+
+// ============================================================================
+// Members
+// ============================================================================
+
+/// Add an existing user to a workspace by username
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddMemberRequest {
+    #[validate(length(min = 1, message = "Username is required"))]
+    pub username: String,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberRemovedResponse {
+    pub message: String,
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -10,16 +10,26 @@ pub enum AppError {
     Database(sqlx::Error),
     NotFound,
     BadRequest(String),
+    Conflict(String),
+    /// Well-formed request that cannot be processed as sent (e.g., a reused idempotency key)
+    UnprocessableEntity(String),
     // Authentication errors
     Unauthorized(String),
     Forbidden,
     InvalidCredentials,
     TokenExpired,
     TokenInvalid,
+    // Brute-force protection errors
+    TooManyRequests { retry_after_seconds: i64 },
+    AccountLocked { retry_after_seconds: i64 },
     // Validation errors
     ValidationError(String),
     // Hashing errors
     HashError,
+    // Upstream service errors (e.g., OIDC provider)
+    ExternalService(String),
+    // Unexpected server-side failures (e.g., file I/O)
+    Internal(String),
 }
 
 #[derive(Serialize)]
@@ -31,6 +41,12 @@ struct ErrorResponse {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after_seconds }
+            | AppError::AccountLocked { retry_after_seconds } => Some(*retry_after_seconds),
+            _ => None,
+        };
+
         let (status, error, details) = match self {
             AppError::Database(e) => {
                 tracing::error!("Database error: {:?}", e);
@@ -50,6 +66,16 @@ impl IntoResponse for AppError {
                 "Bad request".to_string(),
                 Some(msg),
             ),
+            AppError::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                "Conflict".to_string(),
+                Some(msg),
+            ),
+            AppError::UnprocessableEntity(msg) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Unprocessable entity".to_string(),
+                Some(msg),
+            ),
             AppError::Unauthorized(msg) => (
                 StatusCode::UNAUTHORIZED,
                 "Unauthorized".to_string(),
@@ -75,6 +101,19 @@ impl IntoResponse for AppError {
                 "Invalid token".to_string(),
                 None,
             ),
+            AppError::TooManyRequests { retry_after_seconds } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests".to_string(),
+                Some(format!("Try again in {} seconds", retry_after_seconds)),
+            ),
+            AppError::AccountLocked { retry_after_seconds } => (
+                StatusCode::LOCKED,
+                "Account locked".to_string(),
+                Some(format!(
+                    "Too many failed login attempts. Try again in {} seconds",
+                    retry_after_seconds
+                )),
+            ),
             AppError::ValidationError(msg) => (
                 StatusCode::BAD_REQUEST,
                 "Validation error".to_string(),
@@ -88,9 +127,33 @@ impl IntoResponse for AppError {
                     None,
                 )
             }
+            AppError::ExternalService(msg) => {
+                tracing::error!("External service error: {}", msg);
+                (
+                    StatusCode::BAD_GATEWAY,
+                    "External service error".to_string(),
+                    None,
+                )
+            }
+            AppError::Internal(msg) => {
+                tracing::error!("Internal error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                    None,
+                )
+            }
         };
 
-        (status, Json(ErrorResponse { error, details })).into_response()
+        let mut response = (status, Json(ErrorResponse { error, details })).into_response();
+
+        if let Some(seconds) = retry_after
+            && let Ok(value) = HeaderValue::from_str(&seconds.to_string())
+        {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+
+        response
     }
 }
 
@@ -121,3 +184,33 @@ impl From<validator::ValidationErrors> for AppError {
         AppError::ValidationError(err.to_string())
     }
 }
+
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        AppError::ExternalService(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::Internal(err.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for AppError {
+    fn from(err: zip::result::ZipError) -> Self {
+        AppError::Internal(err.to_string())
+    }
+}
+
+impl From<csv::Error> for AppError {
+    fn from(err: csv::Error) -> Self {
+        AppError::Internal(err.to_string())
+    }
+}
+
+impl From<rust_xlsxwriter::XlsxError> for AppError {
+    fn from(err: rust_xlsxwriter::XlsxError) -> Self {
+        AppError::Internal(err.to_string())
+    }
+}
@@ -7,8 +7,15 @@ mod utils;
 mod dto;
 mod middleware;
 
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
 use app_state::AppState;
-use config::Config;
+use config::{Config, ExchangeRateProviderKind};
+use middleware::{Idempotency, IdempotencyLayer, RateLimitLayer, RateLimiter};
+use utils::{
+    account_purge, anomaly_detection, export_cleanup, jwt::JwtKeys, messaging, ocr, rate_provider, receipt_ocr,
+    token_denylist::TokenDenylist, webhooks,
+};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -47,12 +54,84 @@ async fn main() {
 
     tracing::info!("Database migrations completed successfully");
 
+    // Load access token signing keys
+    let jwt_keys = Arc::new(JwtKeys::from_config(&config.jwt).expect("Failed to load JWT signing keys"));
+
+    // Create rate limiter
+    let rate_limiter = RateLimiter::from_config(
+        config.rate_limit.clone(),
+        jwt_keys.clone(),
+        config.trusted_proxies.clone(),
+        db_pool.clone(),
+    );
+
+    // Keep the access token denylist in sync with the database
+    let token_denylist = Arc::new(TokenDenylist::new());
+    token_denylist.clone().spawn_sync(
+        db_pool.clone(),
+        Duration::from_secs(config.jwt.denylist_sync_seconds),
+    );
+
+    // Replay responses of retried requests sent with an Idempotency-Key
+    let idempotency = Idempotency::from_config(
+        config.idempotency.clone(),
+        jwt_keys.clone(),
+        token_denylist.clone(),
+        db_pool.clone(),
+    );
+
+    // Pull exchange rates from the configured provider
+    match &config.exchange_rates.provider {
+        ExchangeRateProviderKind::File { path } => rate_provider::spawn_sync(
+            db_pool.clone(),
+            rate_provider::FileRateProvider::new(path),
+            Duration::from_secs(config.exchange_rates.sync_interval_seconds),
+        ),
+        ExchangeRateProviderKind::None => {}
+    }
+
     // Create application state
-    let app_state = AppState::new(db_pool, config.clone());
+    let app_state = AppState::new(db_pool, config.clone(), jwt_keys, token_denylist);
+
+    // Hard-delete accounts once their deletion grace period ends
+    account_purge::spawn_purge(
+        app_state.db.clone(),
+        app_state.storage.clone(),
+        Duration::from_secs(config.account_deletion.purge_interval_seconds),
+    );
+
+    // Recover exports interrupted by a restart and delete expired archives
+    export_cleanup::spawn_cleanup(app_state.db.clone(), config.export.clone());
+
+    // Read uploaded receipt images into transaction drafts
+    if let Some(engine) = ocr::from_config(&config.ocr.engine) {
+        receipt_ocr::spawn_worker(
+            app_state.db.clone(),
+            app_state.storage.clone(),
+            engine,
+            config.ocr.clone(),
+        );
+    }
+
+    // Flag unusual new transactions, telling their authors when messaging is set up
+    if config.anomalies.enabled {
+        anomaly_detection::spawn_scanner(
+            app_state.db.clone(),
+            messaging::from_config(&config.messaging.gateway, app_state.http.clone()),
+            config.anomalies.clone(),
+        );
+    }
+
+    // Deliver queued webhook events
+    if config.webhooks.enabled {
+        webhooks::spawn_worker(app_state.db.clone(), webhooks::http_client(), config.webhooks.clone());
+    }
 
     // Build application with routes and middleware
     let app = routes::create_router()
         .with_state(app_state)
+        .layer(IdempotencyLayer::new(idempotency))
+        .layer(RateLimitLayer::new(rate_limiter))
         .layer(TraceLayer::new_for_http());
 
     // Create TCP listener
@@ -64,5 +143,10 @@ async fn main() {
     tracing::info!("Server listening on {}", addr);
 
     // Start server
-    axum::serve(listener, app).await.expect("Server failed");
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .expect("Server failed");
 }
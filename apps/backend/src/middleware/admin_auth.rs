@@ -0,0 +1,34 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::request::Parts,
+};
+use crate::{
+    app_state::AppState,
+    db::models::User,
+    error::AppError,
+    middleware::AuthUser,
+};
+
+/// Admin user extractor (requires a valid JWT of a user with the admin role)
+/// Use this in handlers under /admin
+pub struct AdminUser(pub User);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthUser(user) = AuthUser::from_request_parts(parts, state).await?;
+
+        // Check if user is an admin
+        if !user.is_admin() {
+            return Err(AppError::Forbidden);
+        }
+
+        Ok(AdminUser(user))
+    }
+}
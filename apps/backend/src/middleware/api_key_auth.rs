@@ -3,6 +3,7 @@ use axum::{
     extract::{FromRequestParts, State},
     http::{request::Parts, HeaderMap},
 };
+use sqlx::PgPool;
 use crate::{
     app_state::AppState,
     db::{models::User, repositories::{ApiKeyRepository, UserRepository}},
@@ -29,37 +30,11 @@ impl FromRequestParts<AppState> for ApiKeyAuth {
             .and_then(|value| value.to_str().ok())
             .ok_or_else(|| AppError::Unauthorized("Missing API key".to_string()))?;
 
-        // Validate API key format
-        if !validate_api_key_format(api_key) {
-            return Err(AppError::Unauthorized("Invalid API key format".to_string()));
-        }
-
-        // Hash the API key
-        let key_hash = hash_api_key(api_key);
-
-        // Lookup API key in database
-        let api_key_record = ApiKeyRepository::find_by_hash(&state.db, &key_hash)
-            .await?
-            .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
-
-        // Check if API key is valid (not revoked, not expired)
-        if !api_key_record.is_valid() {
-            return Err(AppError::Unauthorized("API key is revoked or expired".to_string()));
-        }
-
-        // Fetch user from database
-        let user = UserRepository::find_by_id(&state.db, api_key_record.user_id)
-            .await?
-            .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
-
-        // Check if user is active
-        if !user.is_active() {
-            return Err(AppError::Forbidden);
-        }
+        let user = authenticate_api_key(&state.db, api_key).await?;
 
         // Update last_used_at timestamp (fire-and-forget)
         let pool = state.db.clone();
-        let hash = key_hash.clone();
+        let hash = hash_api_key(api_key);
         tokio::spawn(async move {
             let _ = ApiKeyRepository::update_last_used(&pool, &hash).await;
         });
@@ -67,3 +42,36 @@ impl FromRequestParts<AppState> for ApiKeyAuth {
         Ok(ApiKeyAuth(user))
     }
 }
+
+/// Check an API key and load its active user
+pub async fn authenticate_api_key(pool: &PgPool, api_key: &str) -> Result<User, AppError> {
+    // Validate API key format
+    if !validate_api_key_format(api_key) {
+        return Err(AppError::Unauthorized("Invalid API key format".to_string()));
+    }
+
+    // Hash the API key
+    let key_hash = hash_api_key(api_key);
+
+    // Lookup API key in database
+    let api_key_record = ApiKeyRepository::find_by_hash(pool, &key_hash)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
+
+    // Check if API key is valid (not revoked, not expired)
+    if !api_key_record.is_valid() {
+        return Err(AppError::Unauthorized("API key is revoked or expired".to_string()));
+    }
+
+    // Fetch user from database
+    let user = UserRepository::find_by_id(pool, api_key_record.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
+
+    // Check if user is active
+    if !user.is_active() {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(user)
+}
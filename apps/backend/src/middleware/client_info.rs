@@ -0,0 +1,114 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, Extensions, HeaderMap},
+};
+use ipnet::IpNet;
+
+use crate::app_state::AppState;
+
+/// Client network information extractor
+/// Proxy headers (X-Forwarded-For, X-Real-IP) are only believed from trusted proxies
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(ClientInfo {
+            ip_address: client_ip(&parts.headers, &parts.extensions, &state.config.trusted_proxies),
+            user_agent: header_str(&parts.headers, header::USER_AGENT.as_str()),
+        })
+    }
+}
+
+/// Resolve the client IP from the socket address, following proxy headers only while the
+/// hop that set them is a trusted proxy
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions, trusted_proxies: &[IpNet]) -> Option<String> {
+    let peer = extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())?;
+
+    Some(resolve_client_ip(peer, headers, trusted_proxies).to_string())
+}
+
+/// Walk X-Forwarded-For from the right (the hop closest to us) and stop at the first
+/// address that is not a trusted proxy; anything left of it may be forged by the client
+fn resolve_client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    let Some(forwarded) = header_str(headers, "X-Forwarded-For") else {
+        return header_str(headers, "X-Real-IP")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(peer);
+    };
+
+    let mut client = peer;
+    for hop in forwarded.rsplit(',') {
+        let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+    client
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_proxy_headers_ignored_from_untrusted_peer() {
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let forged = headers(&[("X-Forwarded-For", "1.2.3.4"), ("X-Real-IP", "5.6.7.8")]);
+
+        assert_eq!(resolve_client_ip(peer, &forged, &[]), peer);
+        assert_eq!(resolve_client_ip(peer, &forged, &["10.0.0.0/8".parse().unwrap()]), peer);
+    }
+
+    #[test]
+    fn test_forwarded_for_takes_right_most_untrusted_hop() {
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+
+        // The client prepended a fake address; the proxy appended the real one
+        let forwarded = headers(&[("X-Forwarded-For", "1.2.3.4, 198.51.100.9, 10.0.0.5")]);
+        assert_eq!(resolve_client_ip(peer, &forwarded, &trusted), "198.51.100.9".parse::<IpAddr>().unwrap());
+
+        let real_ip = headers(&[("X-Real-IP", "198.51.100.9")]);
+        assert_eq!(resolve_client_ip(peer, &real_ip, &trusted), "198.51.100.9".parse::<IpAddr>().unwrap());
+
+        let garbage = headers(&[("X-Forwarded-For", "not-an-ip")]);
+        assert_eq!(resolve_client_ip(peer, &garbage, &trusted), peer);
+    }
+}
@@ -0,0 +1,426 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tower::{Layer, Service};
+
+use crate::{
+    config::IdempotencyConfig,
+    db::{models::IdempotencyRecord, repositories::IdempotencyRepository},
+    error::AppError,
+    middleware::{api_key_auth::authenticate_api_key, jwt_auth::authenticate_token, principal::Principal},
+    utils::{jwt::JwtKeys, token_denylist::TokenDenylist},
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Set on responses replayed from an earlier request
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// Headers of a response that must not be kept anywhere
+pub type NoStore = [(HeaderName, &'static str); 1];
+/// `Cache-Control: no-store`. Responses carrying it are never stored for replay (a retry
+/// gets a conflict instead of running again), so every handler returning a secret
+/// (tokens, API keys, signing secrets) adds it
+pub const NO_STORE: NoStore = [(header::CACHE_CONTROL, "no-store")];
+
+const MAX_KEY_LENGTH: usize = 255;
+/// A key held this long without a response (e.g. the replica died) can be claimed again
+const STALE_CLAIM_SECONDS: i64 = 300;
+/// How often expired keys are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Stores and replays responses of requests made with an `Idempotency-Key`
+pub struct Idempotency {
+    pool: PgPool,
+    config: IdempotencyConfig,
+    jwt_keys: Arc<JwtKeys>,
+    token_denylist: Arc<TokenDenylist>,
+}
+
+impl Idempotency {
+    /// Build the store and start pruning expired keys when enabled
+    pub fn from_config(
+        config: IdempotencyConfig,
+        jwt_keys: Arc<JwtKeys>,
+        token_denylist: Arc<TokenDenylist>,
+        pool: PgPool,
+    ) -> Self {
+        if config.enabled {
+            let pruner = pool.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = IdempotencyRepository::delete_expired(&pruner).await {
+                        tracing::warn!("Failed to prune idempotency keys: {:?}", e);
+                    }
+                }
+            });
+        }
+
+        Self {
+            pool,
+            config,
+            jwt_keys,
+            token_denylist,
+        }
+    }
+
+    /// Check the caller's credentials as the handler would (revoked tokens and keys,
+    /// suspended users), since a replayed response skips the handler
+    async fn authenticate(&self, headers: &HeaderMap, principal: &Principal) -> Result<(), AppError> {
+        match principal {
+            Principal::ApiKey(_) => {
+                let api_key = headers
+                    .get("X-API-Key")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+                authenticate_api_key(&self.pool, api_key).await?;
+            }
+            Principal::User(_) => {
+                let token = headers
+                    .get(header::AUTHORIZATION)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .unwrap_or_default();
+                authenticate_token(&self.pool, &self.jwt_keys, &self.token_denylist, token).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Tower layer replaying the stored response when a POST, PUT or PATCH is retried with
+/// the same `Idempotency-Key`. Keys are per caller, who must still be authorized when a
+/// response is replayed; anonymous requests are not covered
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    idempotency: Arc<Idempotency>,
+}
+
+impl IdempotencyLayer {
+    pub fn new(idempotency: Idempotency) -> Self {
+        Self {
+            idempotency: Arc::new(idempotency),
+        }
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            idempotency: self.idempotency.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    idempotency: Arc<Idempotency>,
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Error: Send,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // Take the service that was polled ready and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let idempotency = self.idempotency.clone();
+
+        Box::pin(async move {
+            let covered = idempotency.config.enabled
+                && matches!(*request.method(), Method::POST | Method::PUT | Method::PATCH);
+            let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER).filter(|_| covered).cloned() else {
+                return inner.call(request).await;
+            };
+            let Some(principal) = Principal::from_headers(request.headers(), &idempotency.jwt_keys) else {
+                return inner.call(request).await;
+            };
+            let key = match validate_key(&key) {
+                Ok(key) => key,
+                Err(e) => return Ok(e.into_response()),
+            };
+
+            let (parts, body) = request.into_parts();
+            let body = match to_bytes(body, idempotency.config.max_request_bytes).await {
+                Ok(body) => body,
+                Err(_) => {
+                    return Ok(AppError::BadRequest(format!(
+                        "Requests with an {} are limited to {} bytes",
+                        IDEMPOTENCY_KEY_HEADER, idempotency.config.max_request_bytes
+                    ))
+                    .into_response());
+                }
+            };
+            let path = parts.uri.path_and_query().map_or(parts.uri.path(), |path| path.as_str());
+            let request_hash = request_hash(&parts.method, path, &body);
+
+            let caller = principal.to_string();
+            match begin(&idempotency, &caller, &key, &request_hash).await {
+                Ok(None) => {}
+                Ok(Some(response)) => {
+                    if let Err(e) = idempotency.authenticate(&parts.headers, &principal).await {
+                        return Ok(e.into_response());
+                    }
+                    return Ok(response);
+                }
+                Err(e) => return Ok(e.into_response()),
+            }
+
+            let response = match inner.call(Request::from_parts(parts, Body::from(body))).await {
+                Ok(response) => response,
+                Err(e) => {
+                    release(&idempotency.pool, &caller, &key).await;
+                    return Err(e);
+                }
+            };
+
+            Ok(finish(&idempotency.pool, &caller, &key, response).await)
+        })
+    }
+}
+
+/// Keys are opaque strings of visible ASCII, such as UUIDs
+fn validate_key(value: &HeaderValue) -> Result<String, AppError> {
+    value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{} must be 1 to {} visible ASCII characters",
+                IDEMPOTENCY_KEY_HEADER, MAX_KEY_LENGTH
+            ))
+        })
+}
+
+/// Hex SHA-256 of the method, path with query and body
+fn request_hash(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// Claim the key for this request, or answer from the request that holds it. None when
+/// the request should go ahead
+async fn begin(
+    idempotency: &Idempotency,
+    principal: &str,
+    key: &str,
+    request_hash: &str,
+) -> Result<Option<Response>, AppError> {
+    let pool = &idempotency.pool;
+    let ttl_seconds = idempotency.config.ttl_seconds;
+    if IdempotencyRepository::claim(pool, principal, key, request_hash, ttl_seconds, STALE_CLAIM_SECONDS).await? {
+        return Ok(None);
+    }
+
+    // Expired or released since the claim failed; the client can simply retry
+    let record = IdempotencyRepository::find(pool, principal, key).await?.ok_or_else(|| {
+        AppError::Conflict(format!("{} is being reused; retry the request", IDEMPOTENCY_KEY_HEADER))
+    })?;
+
+    answer(record, request_hash).map(Some)
+}
+
+/// Answer a retry from the record of the request that holds its key
+fn answer(record: IdempotencyRecord, request_hash: &str) -> Result<Response, AppError> {
+    if record.request_hash != request_hash {
+        return Err(AppError::UnprocessableEntity(format!(
+            "{} was already used for a different request",
+            IDEMPOTENCY_KEY_HEADER
+        )));
+    }
+    if record.status_code.is_some() && record.response_body.is_none() {
+        return Err(AppError::Conflict(format!(
+            "A request with this {} already completed; its response is not stored for replay",
+            IDEMPOTENCY_KEY_HEADER
+        )));
+    }
+
+    replay(record).ok_or_else(|| {
+        AppError::Conflict(format!(
+            "A request with this {} is still being processed",
+            IDEMPOTENCY_KEY_HEADER
+        ))
+    })
+}
+
+/// Rebuild a stored response; None while it has not been stored yet
+fn replay(record: IdempotencyRecord) -> Option<Response> {
+    let status = StatusCode::from_u16(u16::try_from(record.status_code?).ok()?).ok()?;
+
+    let mut response = Response::new(Body::from(record.response_body.unwrap_or_default()));
+    *response.status_mut() = status;
+
+    let headers: Vec<(String, String)> = record
+        .response_headers
+        .and_then(|headers| serde_json::from_value(headers).ok())
+        .unwrap_or_default();
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    Some(response)
+}
+
+/// Store the response for replay. Server errors are not stored, so the request can be
+/// retried. Of `no-store` responses, which hold secrets, only the status is kept: the key
+/// stays taken without the response being replayable
+async fn finish(pool: &PgPool, principal: &str, key: &str, response: Response) -> Response {
+    if response.status().is_server_error() {
+        release(pool, principal, key).await;
+        return response;
+    }
+
+    let status = response.status().as_u16() as i16;
+    if is_no_store(response.headers()) {
+        if let Err(e) = IdempotencyRepository::complete(pool, principal, key, status, None, None).await {
+            tracing::error!("Failed to record idempotent request: {:?}", e);
+            release(pool, principal, key).await;
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(pool, principal, key).await;
+            return AppError::Internal(format!("Failed to read response body: {}", e)).into_response();
+        }
+    };
+
+    let headers = json!(stored_headers(&parts.headers));
+    if let Err(e) =
+        IdempotencyRepository::complete(pool, principal, key, status, Some(&headers), Some(&body)).await
+    {
+        tracing::error!("Failed to store idempotent response: {:?}", e);
+        release(pool, principal, key).await;
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn is_no_store(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+}
+
+fn stored_headers(headers: &HeaderMap) -> Vec<(&str, &str)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .collect()
+}
+
+async fn release(pool: &PgPool, principal: &str, key: &str) {
+    if let Err(e) = IdempotencyRepository::release(pool, principal, key).await {
+        tracing::error!("Failed to release idempotency key: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_hash_covers_method_path_and_body() {
+        let hash = request_hash(&Method::POST, "/auth/api-keys", br#"{"name":"bot"}"#);
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, request_hash(&Method::POST, "/auth/api-keys", br#"{"name":"bot"}"#));
+        assert_ne!(hash, request_hash(&Method::POST, "/auth/api-keys", br#"{"name":"app"}"#));
+        assert_ne!(hash, request_hash(&Method::PUT, "/auth/api-keys", br#"{"name":"bot"}"#));
+        assert_ne!(hash, request_hash(&Method::POST, "/agent/transactions", br#"{"name":"bot"}"#));
+    }
+
+    #[test]
+    fn test_replay_restores_status_headers_and_body() {
+        let record = IdempotencyRecord {
+            request_hash: String::new(),
+            status_code: Some(201),
+            response_headers: Some(json!([["content-type", "application/json"]])),
+            response_body: Some(b"{}".to_vec()),
+        };
+
+        let response = replay(record.clone()).unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+
+        let in_progress = IdempotencyRecord {
+            status_code: None,
+            ..record
+        };
+        assert!(replay(in_progress).is_none());
+    }
+
+    #[test]
+    fn test_completed_no_store_keys_are_not_run_again() {
+        let record = IdempotencyRecord {
+            request_hash: "hash".to_string(),
+            status_code: Some(201),
+            response_headers: None,
+            response_body: None,
+        };
+
+        assert!(matches!(answer(record.clone(), "hash"), Err(AppError::Conflict(_))));
+        assert!(matches!(answer(record, "other"), Err(AppError::UnprocessableEntity(_))));
+    }
+
+    #[test]
+    fn test_no_store_responses_are_recognized() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_no_store(&headers));
+
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, max-age=300"));
+        assert!(!is_no_store(&headers));
+
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, No-Store"));
+        assert!(is_no_store(&headers));
+    }
+}
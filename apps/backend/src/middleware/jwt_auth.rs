@@ -8,11 +8,15 @@ use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
+use sqlx::PgPool;
 use crate::{
     app_state::AppState,
     db::{models::User, repositories::UserRepository},
     error::AppError,
-    utils::jwt::{extract_user_id, validate_token, TokenType},
+    utils::{
+        jwt::{extract_jti, extract_user_id, validate_access_token, JwtKeys},
+        token_denylist::TokenDenylist,
+    },
 };
 
 /// Authenticated user extractor (required authentication)
@@ -33,25 +37,7 @@ impl FromRequestParts<AppState> for AuthUser {
             .await
             .map_err(|_| AppError::Unauthorized("Missing authorization token".to_string()))?;
 
-        // Validate JWT token
-        let claims = validate_token(
-            bearer.token(),
-            &state.config.jwt.access_secret,
-            TokenType::Access,
-        )?;
-
-        // Extract user_id from claims
-        let user_id = extract_user_id(&claims)?;
-
-        // Fetch user from database
-        let user = UserRepository::find_by_id(&state.db, user_id)
-            .await?
-            .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
-
-        // Check if user is active
-        if !user.is_active() {
-            return Err(AppError::Forbidden);
-        }
+        let user = authenticate(state, bearer.token()).await?;
 
         Ok(AuthUser(user))
     }
@@ -80,42 +66,54 @@ impl FromRequestParts<AppState> for OptionalAuthUser {
             return Ok(OptionalAuthUser(None));
         };
 
-        // Try to validate token
-        let claims = validate_token(
-            bearer.token(),
-            &state.config.jwt.access_secret,
-            TokenType::Access,
-        )
-        .ok();
-
-        // If token invalid, return None
-        let Some(claims) = claims else {
+        // Invalid, revoked or stale tokens are treated as anonymous
+        let Ok(user) = authenticate(state, bearer.token()).await else {
             return Ok(OptionalAuthUser(None));
         };
 
-        // Try to extract user_id
-        let user_id = extract_user_id(&claims).ok();
+        Ok(OptionalAuthUser(Some(user)))
+    }
+}
 
-        // If user_id invalid, return None
-        let Some(user_id) = user_id else {
-            return Ok(OptionalAuthUser(None));
-        };
+/// Validate an access token and load its active user.
+/// Rejects tokens whose jti is denylisted or whose version predates the user's current one.
+async fn authenticate(state: &AppState, token: &str) -> Result<User, AppError> {
+    authenticate_token(&state.db, &state.jwt_keys, &state.token_denylist, token).await
+}
 
-        // Try to fetch user from database
-        let user = UserRepository::find_by_id(&state.db, user_id)
-            .await
-            .ok()
-            .flatten();
+/// `authenticate` for callers without the app state (middleware)
+pub async fn authenticate_token(
+    pool: &PgPool,
+    jwt_keys: &JwtKeys,
+    token_denylist: &TokenDenylist,
+    token: &str,
+) -> Result<User, AppError> {
+    // Validate JWT token
+    let claims = validate_access_token(token, jwt_keys)?;
+
+    // Check the denylist before touching the database
+    let jti = extract_jti(&claims)?;
+    if token_denylist.is_revoked(&jti) {
+        return Err(AppError::Unauthorized("Token has been revoked".to_string()));
+    }
 
-        // If user not found or inactive, return None
-        let Some(user) = user else {
-            return Ok(OptionalAuthUser(None));
-        };
+    // Extract user_id from claims
+    let user_id = extract_user_id(&claims)?;
 
-        if !user.is_active() {
-            return Ok(OptionalAuthUser(None));
-        }
+    // Fetch user from database
+    let user = UserRepository::find_by_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
 
-        Ok(OptionalAuthUser(Some(user)))
+    // Tokens issued before suspension, password change or logout-all are stale
+    if claims.ver != user.token_version {
+        return Err(AppError::Unauthorized("Token has been revoked".to_string()));
+    }
+
+    // Check if user is active
+    if !user.is_active() {
+        return Err(AppError::Forbidden);
     }
+
+    Ok(user)
 }
@@ -0,0 +1,80 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::request::Parts,
+};
+use uuid::Uuid;
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{LedgerScope, User, WorkspaceRole},
+        repositories::WorkspaceRepository,
+    },
+    error::AppError,
+    middleware::AuthUser,
+};
+
+/// Header selecting a workspace ledger; without it requests work on the personal ledger
+pub const WORKSPACE_HEADER: &str = "X-Workspace-Id";
+
+/// Ledger extractor (requires a valid JWT). Resolves the ledger a request works on: the
+/// user's personal ledger, or the workspace named by `X-Workspace-Id` if the user is a
+/// member of it. Use `scope` for every repository query on ledger records, and `require`
+/// before changing anything.
+pub struct Ledger {
+    pub user: User,
+    pub scope: LedgerScope,
+    /// Owner on the personal ledger
+    pub role: WorkspaceRole,
+}
+
+impl Ledger {
+    /// Reject the request unless the user's role allows at least `role`
+    pub fn require(&self, role: WorkspaceRole) -> Result<(), AppError> {
+        if self.role < role {
+            return Err(AppError::Forbidden);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Ledger {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthUser(user) = AuthUser::from_request_parts(parts, state).await?;
+
+        let Some(header) = parts.headers.get(WORKSPACE_HEADER) else {
+            return Ok(Ledger {
+                scope: LedgerScope::personal(user.id),
+                role: WorkspaceRole::Owner,
+                user,
+            });
+        };
+
+        let workspace_id = header
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse::<Uuid>().ok())
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid {} header", WORKSPACE_HEADER)))?;
+
+        // Workspaces the user is not a member of do not exist as far as they can tell
+        let role = WorkspaceRepository::find_role(&state.db, workspace_id, user.id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        Ok(Ledger {
+            scope: LedgerScope {
+                user_id: user.id,
+                workspace_id: Some(workspace_id),
+            },
+            role,
+            user,
+        })
+    }
+}
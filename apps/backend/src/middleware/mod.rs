@@ -1,5 +1,16 @@
 pub mod jwt_auth;
 pub mod api_key_auth;
+pub mod admin_auth;
+pub mod client_info;
+pub mod rate_limit;
+pub mod ledger;
+pub mod principal;
+pub mod idempotency;
 
 pub use jwt_auth::{AuthUser, OptionalAuthUser};
 pub use api_key_auth::ApiKeyAuth;
+pub use admin_auth::AdminUser;
+pub use client_info::ClientInfo;
+pub use rate_limit::{RateLimitLayer, RateLimiter};
+pub use ledger::Ledger;
+pub use idempotency::{Idempotency, IdempotencyLayer, NoStore, NO_STORE};
@@ -0,0 +1,47 @@
+use std::fmt;
+use axum::http::HeaderMap;
+use axum_extra::headers::{authorization::Bearer, Authorization, HeaderMapExt};
+use uuid::Uuid;
+
+use crate::utils::{
+    api_key::{hash_api_key, validate_api_key_format},
+    jwt::{extract_user_id, validate_access_token, JwtKeys},
+};
+
+/// Who a request is made by, judged from its credentials alone (no database lookup)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    /// Hash of a well-formed API key; it identifies exactly one api_keys row
+    ApiKey(String),
+    /// User of a valid access token
+    User(Uuid),
+}
+
+impl Principal {
+    /// The most specific identity: API key, then JWT user
+    pub fn from_headers(headers: &HeaderMap, jwt_keys: &JwtKeys) -> Option<Self> {
+        if let Some(api_key) = headers.get("X-API-Key").and_then(|value| value.to_str().ok())
+            && validate_api_key_format(api_key)
+        {
+            return Some(Principal::ApiKey(hash_api_key(api_key)));
+        }
+
+        if let Some(Authorization(bearer)) = headers.typed_get::<Authorization<Bearer>>()
+            && let Ok(claims) = validate_access_token(bearer.token(), jwt_keys)
+            && let Ok(user_id) = extract_user_id(&claims)
+        {
+            return Some(Principal::User(user_id));
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Principal::ApiKey(hash) => write!(f, "api_key:{}", hash),
+            Principal::User(user_id) => write!(f, "user:{}", user_id),
+        }
+    }
+}
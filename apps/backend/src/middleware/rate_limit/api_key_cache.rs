@@ -0,0 +1,60 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use sqlx::PgPool;
+use crate::{db::repositories::ApiKeyRepository, error::AppError};
+
+/// How long a lookup is reused; a revoked key keeps its own bucket at most this long
+const CACHE_TTL: Duration = Duration::from_secs(60);
+/// Entries kept before expired ones are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Remembers whether API key hashes belong to active keys, so only real keys get a
+/// bucket of their own without a database lookup on every request
+pub struct ApiKeyCache {
+    pool: PgPool,
+    entries: Mutex<HashMap<String, (bool, Instant)>>,
+}
+
+impl ApiKeyCache {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `key_hash` is an active (not revoked, not expired) API key
+    pub async fn is_active(&self, key_hash: &str) -> Result<bool, AppError> {
+        if let Some(active) = self.cached(key_hash) {
+            return Ok(active);
+        }
+
+        let active = ApiKeyRepository::find_by_hash(&self.pool, key_hash)
+            .await?
+            .is_some_and(|api_key| api_key.is_valid());
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= PRUNE_THRESHOLD {
+            entries.retain(|_, (_, checked_at)| now.duration_since(*checked_at) < CACHE_TTL);
+            // A flood of made-up keys: start over rather than grow without bound
+            if entries.len() >= PRUNE_THRESHOLD {
+                entries.clear();
+            }
+        }
+        entries.insert(key_hash.to_string(), (active, now));
+
+        Ok(active)
+    }
+
+    fn cached(&self, key_hash: &str) -> Option<bool> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key_hash)
+            .filter(|(_, checked_at)| checked_at.elapsed() < CACHE_TTL)
+            .map(|(active, _)| *active)
+    }
+}
@@ -0,0 +1,44 @@
+use std::{collections::HashMap, sync::Mutex};
+use axum::async_trait;
+use chrono::Utc;
+use crate::{config::RateLimitPolicy, error::AppError};
+use super::{
+    token_bucket::{take_token, BucketState, RateLimitDecision},
+    RateLimitBackend,
+};
+
+/// Buckets kept before full (idle) ones are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Process-local token buckets; limits are per replica
+#[derive(Default)]
+pub struct MemoryRateLimitBackend {
+    buckets: Mutex<HashMap<String, BucketState>>,
+}
+
+impl MemoryRateLimitBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for MemoryRateLimitBackend {
+    async fn check(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, AppError> {
+        let now = Utc::now().naive_utc();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            let refill_per_second = f64::from(policy.per_minute.max(1)) / 60.0;
+            let full_after = f64::from(policy.burst) / refill_per_second;
+            buckets.retain(|_, state| {
+                ((now - state.updated_at).num_seconds() as f64) < full_after
+            });
+        }
+
+        let (state, decision) = take_token(buckets.get(key), policy, now);
+        buckets.insert(key.to_string(), state);
+
+        Ok(decision)
+    }
+}
@@ -0,0 +1,216 @@
+pub mod token_bucket;
+pub mod memory;
+pub mod postgres;
+pub mod api_key_cache;
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use axum::{
+    async_trait,
+    body::Body,
+    http::{HeaderMap, HeaderValue, Request},
+    response::{IntoResponse, Response},
+};
+use ipnet::IpNet;
+use sqlx::PgPool;
+use tower::{Layer, Service};
+
+use crate::{
+    config::{RateLimitBackendKind, RateLimitConfig, RateLimitPolicy},
+    error::AppError,
+    middleware::{client_info::client_ip, principal::Principal},
+    utils::jwt::JwtKeys,
+};
+use self::{
+    api_key_cache::ApiKeyCache,
+    memory::MemoryRateLimitBackend,
+    postgres::PostgresRateLimitBackend,
+    token_bucket::RateLimitDecision,
+};
+
+/// Paths that are never throttled
+const EXEMPT_PATHS: &[&str] = &["/health", "/.well-known/jwks.json"];
+
+/// How often idle Postgres buckets are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+const PRUNE_IDLE_SECONDS: i64 = 3600;
+
+/// Storage for token buckets
+#[async_trait]
+pub trait RateLimitBackend: Send + Sync {
+    /// Take one token from the bucket identified by `key`
+    async fn check(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, AppError>;
+}
+
+/// Resolves the rate limit key of a request and checks it against the backend
+pub struct RateLimiter {
+    backend: Arc<dyn RateLimitBackend>,
+    config: RateLimitConfig,
+    jwt_keys: Arc<JwtKeys>,
+    trusted_proxies: Vec<IpNet>,
+    api_keys: ApiKeyCache,
+}
+
+impl RateLimiter {
+    pub fn new(
+        backend: Arc<dyn RateLimitBackend>,
+        config: RateLimitConfig,
+        jwt_keys: Arc<JwtKeys>,
+        trusted_proxies: Vec<IpNet>,
+        pool: PgPool,
+    ) -> Self {
+        Self {
+            backend,
+            config,
+            jwt_keys,
+            trusted_proxies,
+            api_keys: ApiKeyCache::new(pool),
+        }
+    }
+
+    /// Build the limiter with the backend selected in `config`
+    pub fn from_config(
+        config: RateLimitConfig,
+        jwt_keys: Arc<JwtKeys>,
+        trusted_proxies: Vec<IpNet>,
+        pool: PgPool,
+    ) -> Self {
+        let backend: Arc<dyn RateLimitBackend> = match config.backend {
+            RateLimitBackendKind::Memory => Arc::new(MemoryRateLimitBackend::new()),
+            RateLimitBackendKind::Postgres => {
+                let backend = Arc::new(PostgresRateLimitBackend::new(pool.clone()));
+                let pruner = backend.clone();
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        if let Err(e) = pruner.prune_idle(PRUNE_IDLE_SECONDS).await {
+                            tracing::warn!("Failed to prune rate limit buckets: {:?}", e);
+                        }
+                    }
+                });
+                backend
+            }
+        };
+
+        Self::new(backend, config, jwt_keys, trusted_proxies, pool)
+    }
+
+    /// Pick the most specific identity: API key, then JWT user, then client IP. An API key
+    /// only gets its own bucket once it is known to be active, so made-up keys share the
+    /// caller's IP bucket
+    async fn resolve_key(&self, headers: &HeaderMap, ip_address: Option<String>) -> (String, &RateLimitPolicy) {
+        match Principal::from_headers(headers, &self.jwt_keys) {
+            Some(Principal::ApiKey(hash)) if self.api_key_is_active(&hash).await => {
+                (Principal::ApiKey(hash).to_string(), &self.config.api_key)
+            }
+            Some(principal @ Principal::User(_)) => (principal.to_string(), &self.config.user),
+            _ => {
+                let ip_address = ip_address.unwrap_or_else(|| "unknown".to_string());
+                (format!("ip:{}", ip_address), &self.config.ip)
+            }
+        }
+    }
+
+    async fn api_key_is_active(&self, key_hash: &str) -> bool {
+        match self.api_keys.is_active(key_hash).await {
+            Ok(active) => active,
+            Err(e) => {
+                tracing::error!("Failed to look up API key for rate limiting: {:?}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Tower layer applying token-bucket rate limits to every request
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self {
+            limiter: Arc::new(limiter),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // Take the service that was polled ready and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            if !limiter.config.enabled || EXEMPT_PATHS.contains(&request.uri().path()) {
+                return inner.call(request).await;
+            }
+
+            let ip_address = client_ip(request.headers(), request.extensions(), &limiter.trusted_proxies);
+            let (key, policy) = limiter.resolve_key(request.headers(), ip_address).await;
+
+            // Fail open: a broken backend must not take the API down
+            let decision = match limiter.backend.check(&key, policy).await {
+                Ok(decision) => decision,
+                Err(e) => {
+                    tracing::error!("Rate limit backend error: {:?}", e);
+                    return inner.call(request).await;
+                }
+            };
+
+            let mut response = match decision.retry_after_seconds {
+                Some(retry_after) if !decision.allowed => AppError::TooManyRequests {
+                    retry_after_seconds: retry_after as i64,
+                }
+                .into_response(),
+                _ => inner.call(request).await?,
+            };
+
+            insert_rate_limit_headers(response.headers_mut(), &decision);
+            Ok(response)
+        })
+    }
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(decision.limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    headers.insert("X-RateLimit-Reset", HeaderValue::from(decision.reset_after_seconds));
+}
@@ -0,0 +1,75 @@
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sqlx::PgPool;
+use crate::{config::RateLimitPolicy, error::AppError};
+use super::{
+    token_bucket::{take_token, BucketState, RateLimitDecision},
+    RateLimitBackend,
+};
+
+/// Token buckets stored in `rate_limit_buckets`, shared by every replica
+pub struct PostgresRateLimitBackend {
+    pool: PgPool,
+}
+
+impl PostgresRateLimitBackend {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Delete buckets that have not been touched for `idle_seconds`
+    pub async fn prune_idle(&self, idle_seconds: i64) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM rate_limit_buckets
+            WHERE updated_at < now() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(idle_seconds as f64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for PostgresRateLimitBackend {
+    async fn check(&self, key: &str, policy: &RateLimitPolicy) -> Result<RateLimitDecision, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Lock the bucket row so concurrent replicas serialize on the same key
+        let existing: Option<(f64, NaiveDateTime)> = sqlx::query_as(
+            r#"
+            SELECT tokens, updated_at
+            FROM rate_limit_buckets
+            WHERE key = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let existing = existing.map(|(tokens, updated_at)| BucketState { tokens, updated_at });
+        let (state, decision) = take_token(existing.as_ref(), policy, Utc::now().naive_utc());
+
+        sqlx::query(
+            r#"
+            INSERT INTO rate_limit_buckets (key, tokens, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (key) DO UPDATE
+            SET tokens = EXCLUDED.tokens, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(state.tokens)
+        .bind(state.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(decision)
+    }
+}
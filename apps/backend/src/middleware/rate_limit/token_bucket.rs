@@ -0,0 +1,100 @@
+use chrono::NaiveDateTime;
+use crate::config::RateLimitPolicy;
+
+/// Persisted state of a single token bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketState {
+    pub tokens: f64,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Outcome of taking a token, used to build the X-RateLimit-* headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the bucket is full again
+    pub reset_after_seconds: u64,
+    /// Seconds until the next token is available (only set when denied)
+    pub retry_after_seconds: Option<u64>,
+}
+
+/// Refill the bucket for the time elapsed since its last update and try to take one token.
+/// A missing bucket starts full.
+pub fn take_token(
+    state: Option<&BucketState>,
+    policy: &RateLimitPolicy,
+    now: NaiveDateTime,
+) -> (BucketState, RateLimitDecision) {
+    let capacity = f64::from(policy.burst);
+    let refill_per_second = f64::from(policy.per_minute.max(1)) / 60.0;
+
+    let refilled = match state {
+        Some(state) => {
+            let elapsed = (now - state.updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+            (state.tokens + elapsed * refill_per_second).min(capacity)
+        }
+        None => capacity,
+    };
+
+    let allowed = refilled >= 1.0;
+    let tokens = if allowed { refilled - 1.0 } else { refilled };
+
+    let decision = RateLimitDecision {
+        allowed,
+        limit: policy.burst,
+        remaining: tokens.floor() as u32,
+        reset_after_seconds: ((capacity - tokens) / refill_per_second).ceil() as u64,
+        retry_after_seconds: (!allowed)
+            .then(|| ((1.0 - tokens) / refill_per_second).ceil().max(1.0) as u64),
+    };
+
+    (BucketState { tokens, updated_at: now }, decision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn policy() -> RateLimitPolicy {
+        RateLimitPolicy {
+            burst: 2,
+            per_minute: 60,
+        }
+    }
+
+    #[test]
+    fn test_bucket_drains_and_denies() {
+        let policy = policy();
+        let now = Utc::now().naive_utc();
+
+        let (state, first) = take_token(None, &policy, now);
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+
+        let (state, second) = take_token(Some(&state), &policy, now);
+        assert!(second.allowed);
+        assert_eq!(second.remaining, 0);
+        assert_eq!(second.reset_after_seconds, 2);
+
+        let (_, third) = take_token(Some(&state), &policy, now);
+        assert!(!third.allowed);
+        assert_eq!(third.retry_after_seconds, Some(1));
+    }
+
+    #[test]
+    fn test_bucket_refills_up_to_burst() {
+        let policy = policy();
+        let now = Utc::now().naive_utc();
+        let empty = BucketState {
+            tokens: 0.0,
+            updated_at: now - Duration::seconds(3600),
+        };
+
+        let (state, decision) = take_token(Some(&empty), &policy, now);
+        assert!(decision.allowed);
+        assert_eq!(state.tokens, 1.0);
+    }
+}
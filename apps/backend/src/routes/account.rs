@@ -0,0 +1,112 @@
+use axum::{
+    extract::State,
+    routing::{patch, post},
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::repositories::{ApiKeyRepository, AuditLogRepository, RefreshTokenRepository, UserRepository},
+    dto::{account::*, auth::UserResponse},
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+    routes::exports,
+    utils::password::verify_password,
+};
+
+// ============================================================================
+// POST /account/delete - Delete own account (restorable during the grace period)
+// ============================================================================
+pub async fn delete_account(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<DeleteAccountRequest>,
+) -> Result<Json<DeleteAccountResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    // Confirm with the password when the account has one
+    if let Some(password_hash) = user.password_hash.as_deref() {
+        let password = payload
+            .password
+            .as_deref()
+            .ok_or_else(|| AppError::BadRequest("Password is required".to_string()))?;
+
+        if !verify_password(password, password_hash)? {
+            return Err(AppError::InvalidCredentials);
+        }
+    }
+
+    let purge_after =
+        Utc::now().naive_utc() + Duration::days(state.config.account_deletion.grace_period_days);
+
+    // Soft-delete (also bumps the token version) and cut off every credential
+    UserRepository::schedule_deletion(&state.db, user.id, purge_after).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+    ApiKeyRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_account",
+        "user",
+        user.id,
+        Some(json!({ "status": user.status })),
+        Some(json!({ "status": "deleted", "purge_after": purge_after })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(DeleteAccountResponse {
+        message: "Account deleted. Log in before the purge date to restore it".to_string(),
+        purge_after,
+    }))
+}
+
+// ============================================================================
+// PATCH /account/settings - Update account preferences (requires JWT auth)
+// ============================================================================
+pub async fn update_settings(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<UpdateSettingsRequest>,
+) -> Result<Json<UserResponse>, AppError> {
+    let Some(base_currency) = payload.base_currency else {
+        return Ok(Json(UserResponse::from(user.to_safe_user())));
+    };
+
+    let updated = UserRepository::update_base_currency(&state.db, user.id, base_currency).await?;
+
+    if updated.base_currency != user.base_currency {
+        AuditLogRepository::create(
+            &state.db,
+            Some(user.id),
+            "update_settings",
+            "user",
+            user.id,
+            Some(json!({ "base_currency": user.base_currency })),
+            Some(json!({ "base_currency": updated.base_currency })),
+            client.ip_address.as_deref(),
+            client.user_agent.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(UserResponse::from(updated.to_safe_user())))
+}
+
+// ============================================================================
+// Account Router
+// ============================================================================
+pub fn account_routes() -> Router<AppState> {
+    Router::new()
+        .route("/delete", post(delete_account))
+        .route("/settings", patch(update_settings))
+        .nest("/exports", exports::export_routes())
+}
@@ -0,0 +1,244 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, patch},
+    Json, Router,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{Account, AccountWithBalance, User},
+        repositories::{AccountRepository, AuditLogRepository, PaymentMethodRepository},
+    },
+    dto::account::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+};
+
+// ============================================================================
+// GET /accounts - List accounts with their balances (requires JWT auth)
+// ============================================================================
+pub async fn list_accounts(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<ListAccountsQuery>,
+) -> Result<Json<Vec<AccountWithBalance>>, AppError> {
+    let accounts = AccountRepository::list_for_user(&state.db, user.id, query.include_archived).await?;
+
+    Ok(Json(accounts))
+}
+
+// ============================================================================
+// POST /accounts - Create an account (requires JWT auth)
+// ============================================================================
+pub async fn create_account(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateAccountRequest>,
+) -> Result<Json<Account>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let name = payload.name.trim();
+    ensure_unique_name(&state, &user, name, None).await?;
+    if let Some(payment_method_id) = payload.payment_method_id {
+        ensure_linkable(&state, &user, payment_method_id, None).await?;
+    }
+
+    let account = AccountRepository::create(
+        &state.db,
+        user.id,
+        name,
+        &payload.r#type,
+        payload.payment_method_id,
+        payload.currency.unwrap_or(user.base_currency),
+        payload.opening_balance,
+    )
+    .await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "create_account",
+        "account",
+        account.id,
+        None,
+        Some(json!(account)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(account))
+}
+
+// ============================================================================
+// PATCH /accounts/:account_id - Rename, relink, archive or correct the opening balance
+// (requires JWT auth)
+// ============================================================================
+pub async fn update_account(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(account_id): Path<Uuid>,
+    Json(payload): Json<UpdateAccountRequest>,
+) -> Result<Json<Account>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let existing = find_account(&state, &user, account_id).await?;
+    let mut account = existing.clone();
+
+    if let Some(name) = payload.name.as_deref().map(str::trim) {
+        ensure_unique_name(&state, &user, name, Some(account.id)).await?;
+        account.name = name.to_string();
+    }
+    if let Some(r#type) = payload.r#type {
+        account.r#type = r#type;
+    }
+    if payload.unlink_payment_method {
+        account.payment_method_id = None;
+    } else if let Some(payment_method_id) = payload.payment_method_id {
+        ensure_linkable(&state, &user, payment_method_id, Some(account.id)).await?;
+        account.payment_method_id = Some(payment_method_id);
+    }
+    if let Some(opening_balance) = payload.opening_balance {
+        account.opening_balance = opening_balance;
+    }
+    if let Some(is_archived) = payload.is_archived {
+        account.is_archived = is_archived;
+    }
+
+    let account = AccountRepository::update(&state.db, &account).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "update_account",
+        "account",
+        account.id,
+        Some(json!(existing)),
+        Some(json!(account)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(account))
+}
+
+// ============================================================================
+// DELETE /accounts/:account_id - Delete an unused account (requires JWT auth)
+// Accounts with transactions keep their history; archive those instead
+// ============================================================================
+pub async fn delete_account(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<AccountDeletedResponse>, AppError> {
+    let account = find_account(&state, &user, account_id).await?;
+
+    if AccountRepository::has_transactions(&state.db, account.id).await? {
+        return Err(AppError::Conflict(
+            "Account has transactions; archive it instead".to_string(),
+        ));
+    }
+
+    AccountRepository::delete(&state.db, user.id, account.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_account",
+        "account",
+        account.id,
+        Some(json!(account)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(AccountDeletedResponse {
+        message: "Account deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// GET /accounts/:account_id/entries - Transactions of an account with the running
+// balance after each (requires JWT auth)
+// ============================================================================
+pub async fn list_entries(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<AccountEntriesQuery>,
+) -> Result<Json<AccountEntriesResponse>, AppError> {
+    // Validate input
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+
+    let account = find_account(&state, &user, account_id).await?;
+    let entries = AccountRepository::list_entries(&state.db, &account, query.from, query.to).await?;
+
+    Ok(Json(AccountEntriesResponse { account, entries }))
+}
+
+async fn find_account(state: &AppState, user: &User, account_id: Uuid) -> Result<Account, AppError> {
+    AccountRepository::find_for_user(&state.db, user.id, account_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn ensure_unique_name(
+    state: &AppState,
+    user: &User,
+    name: &str,
+    except_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    if AccountRepository::name_exists(&state.db, user.id, name, except_id).await? {
+        return Err(AppError::Conflict(format!("An account named '{}' already exists", name)));
+    }
+
+    Ok(())
+}
+
+/// The payment method must be visible to the user and not linked to another account
+async fn ensure_linkable(
+    state: &AppState,
+    user: &User,
+    payment_method_id: Uuid,
+    except_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    if PaymentMethodRepository::find_available_for_user(&state.db, user.id, payment_method_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::ValidationError("Unknown payment method".to_string()));
+    }
+    if AccountRepository::payment_method_linked(&state.db, user.id, payment_method_id, except_id).await? {
+        return Err(AppError::Conflict(
+            "Payment method is already linked to another account".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Accounts Router
+// ============================================================================
+pub fn account_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_accounts).post(create_account))
+        .route("/:account_id", patch(update_account).delete(delete_account))
+        .route("/:account_id/entries", get(list_entries))
+}
@@ -0,0 +1,441 @@
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{User, UserStatus},
+        repositories::{
+            ApiKeyRepository, AuditLogRepository, ExchangeRateRepository, RefreshTokenRepository,
+            UserIdentityRepository, UserRepository,
+        },
+    },
+    dto::{admin::*, auth::UserResponse},
+    error::AppError,
+    middleware::{AdminUser, ClientInfo},
+    utils::exchange_rates::parse_rates_csv,
+};
+
+const DEFAULT_PER_PAGE: i64 = 20;
+const MAX_PER_PAGE: i64 = 100;
+/// Largest exchange-rate CSV accepted
+const MAX_RATES_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+// ============================================================================
+// GET /admin/users - Search users (requires admin)
+// ============================================================================
+pub async fn list_users(
+    State(state): State<AppState>,
+    AdminUser(_admin): AdminUser,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<Json<ListUsersResponse>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    let (users, total) = UserRepository::search(
+        &state.db,
+        search,
+        query.status.as_ref(),
+        query.role.as_ref(),
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ListUsersResponse {
+        users: users
+            .iter()
+            .map(|user| UserResponse::from(user.to_safe_user()))
+            .collect(),
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// GET /admin/users/:user_id - View a user (requires admin)
+// ============================================================================
+pub async fn get_user(
+    State(state): State<AppState>,
+    AdminUser(_admin): AdminUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminUserResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+    let identities = UserIdentityRepository::list_for_user(&state.db, user.id).await?;
+
+    Ok(Json(AdminUserResponse::new(&user, identities)))
+}
+
+// ============================================================================
+// POST /admin/users/:user_id/suspend - Suspend a user (requires admin)
+// ============================================================================
+pub async fn suspend_user(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+    ensure_not_self(&admin, &user)?;
+
+    if user.status != UserStatus::Active {
+        return Err(AppError::BadRequest("Only active users can be suspended".to_string()));
+    }
+
+    UserRepository::update_status(&state.db, user.id, &UserStatus::Suspended).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    audit(
+        &state,
+        &admin,
+        &client,
+        "suspend_user",
+        &user,
+        Some(json!({ "status": user.status })),
+        Some(json!({ "status": UserStatus::Suspended })),
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "User suspended successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /admin/users/:user_id/reactivate - Reactivate a suspended or deleted user
+// ============================================================================
+pub async fn reactivate_user(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+
+    if user.status == UserStatus::Active && user.deleted_at.is_none() {
+        return Err(AppError::BadRequest("User is already active".to_string()));
+    }
+
+    UserRepository::update_status(&state.db, user.id, &UserStatus::Active).await?;
+
+    audit(
+        &state,
+        &admin,
+        &client,
+        "reactivate_user",
+        &user,
+        Some(json!({ "status": user.status, "deleted_at": user.deleted_at })),
+        Some(json!({ "status": UserStatus::Active })),
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "User reactivated successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// DELETE /admin/users/:user_id - Soft-delete a user (requires admin)
+// ============================================================================
+pub async fn delete_user(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+    ensure_not_self(&admin, &user)?;
+
+    if user.status == UserStatus::Deleted {
+        return Err(AppError::BadRequest("User is already deleted".to_string()));
+    }
+
+    UserRepository::update_status(&state.db, user.id, &UserStatus::Deleted).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+    ApiKeyRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    audit(
+        &state,
+        &admin,
+        &client,
+        "delete_user",
+        &user,
+        Some(json!({ "status": user.status })),
+        Some(json!({ "status": UserStatus::Deleted })),
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "User deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /admin/users/:user_id/sessions/revoke - Log a user out everywhere
+// ============================================================================
+pub async fn revoke_user_sessions(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+
+    UserRepository::bump_token_version(&state.db, user.id).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    audit(&state, &admin, &client, "revoke_user_sessions", &user, None, None).await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "User sessions revoked successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /admin/users/:user_id/api-keys/revoke - Revoke all of a user's API keys
+// ============================================================================
+pub async fn revoke_user_api_keys(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+
+    let revoked = ApiKeyRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    audit(
+        &state,
+        &admin,
+        &client,
+        "revoke_user_api_keys",
+        &user,
+        None,
+        Some(json!({ "revoked_api_keys": revoked })),
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: format!("{} API key(s) revoked successfully", revoked),
+    }))
+}
+
+// ============================================================================
+// POST /admin/users/:user_id/unlock - Clear a login lockout (requires admin)
+// ============================================================================
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let user = find_user(&state, user_id).await?;
+
+    UserRepository::reset_failed_logins(&state.db, user.id).await?;
+
+    audit(
+        &state,
+        &admin,
+        &client,
+        "unlock_account",
+        &user,
+        Some(json!({
+            "failed_login_attempts": user.failed_login_attempts,
+            "locked_until": user.locked_until,
+        })),
+        None,
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "User unlocked successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /admin/exchange-rates - Upload exchange rates as CSV (requires admin)
+// Multipart field `file` with the columns date,base,quote,rate; existing rates for the
+// same pair and date are replaced
+// ============================================================================
+pub async fn upload_exchange_rates(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    mut multipart: Multipart,
+) -> Result<Json<UploadExchangeRatesResponse>, AppError> {
+    let mut file = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+            file = Some(bytes.to_vec());
+        }
+    }
+
+    let file = file.ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+
+    // Validate input
+    let quotes = parse_rates_csv(&file)?;
+    let from = quotes.iter().map(|q| q.rate_date).min().unwrap_or_default();
+    let to = quotes.iter().map(|q| q.rate_date).max().unwrap_or_default();
+
+    let stored_rates = ExchangeRateRepository::upsert_many(&state.db, &quotes, "admin_upload").await?;
+
+    let upload_id = Uuid::new_v4();
+    AuditLogRepository::create(
+        &state.db,
+        Some(admin.id),
+        "upload_exchange_rates",
+        "exchange_rate_upload",
+        upload_id,
+        None,
+        Some(json!({ "stored_rates": stored_rates, "from": from, "to": to })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(UploadExchangeRatesResponse {
+        upload_id,
+        stored_rates,
+        from,
+        to,
+    }))
+}
+
+// ============================================================================
+// GET /admin/exchange-rates - List stored exchange rates (requires admin)
+// ============================================================================
+pub async fn list_exchange_rates(
+    State(state): State<AppState>,
+    AdminUser(_admin): AdminUser,
+    Query(query): Query<ListExchangeRatesQuery>,
+) -> Result<Json<ListExchangeRatesResponse>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+
+    let (rates, total) = ExchangeRateRepository::search(
+        &state.db,
+        query.base,
+        query.quote,
+        query.from,
+        query.to,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ListExchangeRatesResponse {
+        rates,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// DELETE /admin/exchange-rates/:rate_id - Delete an exchange rate (requires admin)
+// ============================================================================
+pub async fn delete_exchange_rate(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    client: ClientInfo,
+    Path(rate_id): Path<Uuid>,
+) -> Result<Json<AdminActionResponse>, AppError> {
+    let rate = ExchangeRateRepository::delete(&state.db, rate_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(admin.id),
+        "delete_exchange_rate",
+        "exchange_rate",
+        rate.id,
+        Some(json!(rate)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(AdminActionResponse {
+        message: "Exchange rate deleted successfully".to_string(),
+    }))
+}
+
+async fn find_user(state: &AppState, user_id: Uuid) -> Result<User, AppError> {
+    UserRepository::find_by_id_including_deleted(&state.db, user_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Admins cannot suspend or delete their own account
+fn ensure_not_self(admin: &User, user: &User) -> Result<(), AppError> {
+    if admin.id == user.id {
+        return Err(AppError::BadRequest(
+            "Admins cannot perform this action on their own account".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Record an admin action against a user
+async fn audit(
+    state: &AppState,
+    admin: &User,
+    client: &ClientInfo,
+    action: &str,
+    user: &User,
+    old_values: Option<JsonValue>,
+    new_values: Option<JsonValue>,
+) -> Result<(), AppError> {
+    AuditLogRepository::create(
+        &state.db,
+        Some(admin.id),
+        action,
+        "user",
+        user.id,
+        old_values,
+        new_values,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await
+}
+
+// ============================================================================
+// Admin Router
+// ============================================================================
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/users/:user_id", get(get_user).delete(delete_user))
+        .route("/users/:user_id/suspend", post(suspend_user))
+        .route("/users/:user_id/reactivate", post(reactivate_user))
+        .route("/users/:user_id/sessions/revoke", post(revoke_user_sessions))
+        .route("/users/:user_id/api-keys/revoke", post(revoke_user_api_keys))
+        .route("/users/:user_id/unlock", post(unlock_user))
+        .route(
+            "/exchange-rates",
+            get(list_exchange_rates)
+                .post(upload_exchange_rates)
+                .layer(DefaultBodyLimit::max(MAX_RATES_UPLOAD_BYTES)),
+        )
+        .route("/exchange-rates/:rate_id", delete(delete_exchange_rate))
+}
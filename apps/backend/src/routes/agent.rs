@@ -0,0 +1,179 @@
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{CategoryType, LedgerScope, NewTransaction, Transaction, TransactionSource, TransactionType},
+        repositories::{
+            CategoryRepository, ClientRepository, GoalRepository, PaymentMethodRepository,
+            TransactionRepository,
+        },
+    },
+    dto::{
+        goal::GoalResponse,
+        transaction::{PossibleDuplicate, RecordMessageTransactionRequest, RecordMessageTransactionResponse},
+    },
+    error::AppError,
+    middleware::ApiKeyAuth,
+    routes::goals::goal_response,
+    utils::transaction_duplicates::{duplicate_score, DUPLICATE_THRESHOLD},
+};
+
+// ============================================================================
+// GET /agent/goals - Progress of the user's active savings goals, for the chat agent
+// (requires API key)
+// ============================================================================
+pub async fn list_goals(
+    State(state): State<AppState>,
+    ApiKeyAuth(user): ApiKeyAuth,
+) -> Result<Json<Vec<GoalResponse>>, AppError> {
+    let goals = GoalRepository::list_for_user(&state.db, user.id, false).await?;
+
+    Ok(Json(goals.into_iter().map(goal_response).collect()))
+}
+
+// ============================================================================
+// POST /agent/transactions - Record a transaction from a chat message (requires API key)
+// A message is recorded once per client: retries and double-sends return the first
+// transaction. Similar transactions a day either side are returned as possible duplicates
+// ============================================================================
+pub async fn record_transaction(
+    State(state): State<AppState>,
+    ApiKeyAuth(user): ApiKeyAuth,
+    Json(payload): Json<RecordMessageTransactionRequest>,
+) -> Result<Json<RecordMessageTransactionResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+    let client = ClientRepository::list_for_user(&state.db, user.id)
+        .await?
+        .into_iter()
+        .find(|client| client.id == payload.client_id)
+        .ok_or_else(|| AppError::ValidationError("Unknown client".to_string()))?;
+    if !client.can_interact() {
+        return Err(AppError::Forbidden);
+    }
+
+    if let Some(transaction) =
+        TransactionRepository::find_by_client_message(&state.db, client.id, &payload.source_message_id).await?
+    {
+        return duplicates_response(&state, transaction, false).await;
+    }
+
+    if payload.r#type == TransactionType::Transfer {
+        return Err(AppError::ValidationError("Transfers cannot be recorded from chat".to_string()));
+    }
+    if payload.amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError("Amount must be positive".to_string()));
+    }
+
+    let scope = LedgerScope::personal(user.id);
+    if let Some(category_id) = payload.category_id {
+        let categories = CategoryRepository::list_for_ledger(&state.db, scope).await?;
+        let category = categories
+            .iter()
+            .find(|category| category.id == category_id && category.is_active)
+            .ok_or_else(|| AppError::ValidationError("Unknown category".to_string()))?;
+        let fits = match payload.r#type {
+            TransactionType::Income => category.r#type != CategoryType::Expense,
+            _ => category.r#type != CategoryType::Income,
+        };
+        if !fits {
+            return Err(AppError::ValidationError("Category does not match the transaction type".to_string()));
+        }
+    }
+    if let Some(payment_method_id) = payload.payment_method_id {
+        PaymentMethodRepository::find_available_for_user(&state.db, user.id, payment_method_id)
+            .await?
+            .filter(|payment_method| payment_method.is_active)
+            .ok_or_else(|| AppError::ValidationError("Unknown payment method".to_string()))?;
+    }
+
+    let currency = payload.currency.unwrap_or(user.base_currency);
+    let transaction = NewTransaction {
+        r#type: payload.r#type,
+        amount: payload.amount.round_dp(currency.minor_units()),
+        currency,
+        category_id: payload.category_id,
+        payment_method_id: payload.payment_method_id,
+        account_id: None,
+        transfer_account_id: None,
+        merchant_name: non_empty(payload.merchant_name),
+        location: non_empty(payload.location),
+        description: non_empty(payload.description),
+        transaction_date: payload.transaction_date.unwrap_or_else(|| Utc::now().date_naive()),
+        source: TransactionSource::Whatsapp,
+        client_id: Some(client.id),
+        source_message_id: Some(payload.source_message_id.clone()),
+        metadata: None,
+    };
+
+    match TransactionRepository::create(&state.db, scope, &transaction).await {
+        Ok(transaction) => duplicates_response(&state, transaction, true).await,
+        // Another delivery of the same message won the race
+        Err(AppError::Conflict(_)) => {
+            let transaction =
+                TransactionRepository::find_by_client_message(&state.db, client.id, &payload.source_message_id)
+                    .await?
+                    .ok_or_else(|| AppError::Internal("Recorded message transaction not found".to_string()))?;
+            duplicates_response(&state, transaction, false).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn duplicates_response(
+    state: &AppState,
+    transaction: Transaction,
+    created: bool,
+) -> Result<Json<RecordMessageTransactionResponse>, AppError> {
+    let scope = LedgerScope {
+        user_id: transaction.user_id,
+        workspace_id: transaction.workspace_id,
+    };
+    let nearby = TransactionRepository::list_in_range(
+        &state.db,
+        scope,
+        transaction.transaction_date - Duration::days(1),
+        transaction.transaction_date + Duration::days(1),
+    )
+    .await?;
+
+    let mut possible_duplicates: Vec<PossibleDuplicate> = nearby
+        .into_iter()
+        .filter_map(|other| {
+            let score = duplicate_score(&transaction, &other)?;
+            (score >= DUPLICATE_THRESHOLD).then_some(PossibleDuplicate { transaction: other, score })
+        })
+        .collect();
+    possible_duplicates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    Ok(Json(RecordMessageTransactionResponse {
+        transaction,
+        created,
+        possible_duplicates,
+    }))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+// ============================================================================
+// Agent Router
+// ============================================================================
+pub fn agent_routes() -> Router<AppState> {
+    Router::new()
+        .route("/goals", get(list_goals))
+        .route("/transactions", post(record_transaction))
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{AnomalyStatus, AnomalyWithTransaction, WorkspaceRole},
+        repositories::{AnomalyRepository, AuditLogRepository},
+    },
+    dto::anomaly::*,
+    error::AppError,
+    middleware::{ClientInfo, Ledger},
+};
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
+
+// ============================================================================
+// GET /anomalies - Transactions flagged as unusual, newest first (requires JWT auth)
+// Covers the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn list_anomalies(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<ListAnomaliesQuery>,
+) -> Result<Json<ListAnomaliesResponse>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+
+    let (anomalies, total) = AnomalyRepository::list_for_ledger(
+        &state.db,
+        ledger.scope,
+        Some(query.status.unwrap_or(AnomalyStatus::Open)),
+        query.kind,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ListAnomaliesResponse {
+        anomalies,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// GET /anomalies/:anomaly_id - View a flagged transaction (requires JWT auth)
+// ============================================================================
+pub async fn get_anomaly(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Path(anomaly_id): Path<Uuid>,
+) -> Result<Json<AnomalyWithTransaction>, AppError> {
+    let anomaly = AnomalyRepository::find_for_ledger(&state.db, ledger.scope, anomaly_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(anomaly))
+}
+
+// ============================================================================
+// POST /anomalies/:anomaly_id/dismiss - Mark a flagged transaction as fine
+// (requires JWT auth)
+// ============================================================================
+pub async fn dismiss_anomaly(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path(anomaly_id): Path<Uuid>,
+) -> Result<Json<AnomalyDismissedResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let anomaly = AnomalyRepository::find_for_ledger(&state.db, ledger.scope, anomaly_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if anomaly.anomaly.status != AnomalyStatus::Open {
+        return Err(AppError::Conflict("Anomaly has already been dismissed".to_string()));
+    }
+
+    AnomalyRepository::dismiss(&state.db, anomaly_id, ledger.user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "dismiss_anomaly",
+        "transaction",
+        anomaly.anomaly.transaction_id,
+        Some(json!(anomaly.anomaly)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(AnomalyDismissedResponse {
+        message: "Anomaly dismissed".to_string(),
+    }))
+}
+
+// ============================================================================
+// Anomalies Router
+// ============================================================================
+pub fn anomaly_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_anomalies))
+        .route("/:anomaly_id", get(get_anomaly))
+        .route("/:anomaly_id/dismiss", post(dismiss_anomaly))
+}
@@ -0,0 +1,291 @@
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    config::OcrEngineKind,
+    db::{
+        models::{LedgerScope, NewTransactionAttachment, Transaction, TransactionAttachment, WorkspaceRole},
+        repositories::{
+            AttachmentRepository, AuditLogRepository, ReceiptDraftRepository, TransactionRepository,
+        },
+    },
+    dto::transaction::*,
+    error::AppError,
+    middleware::{ClientInfo, Ledger},
+    utils::{receipts, signed_url},
+};
+
+// ============================================================================
+// POST /transactions/:transaction_id/attachments - Upload a receipt (requires JWT auth)
+// Multipart field: `file` (JPEG, PNG, WebP or PDF); the type is detected from the contents
+// When OCR is enabled, images are queued to be read into a receipt draft
+// ============================================================================
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path(transaction_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<Json<AttachmentResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let transaction = find_transaction(&state, ledger.scope, transaction_id).await?;
+
+    // Validate input
+    let max_per_transaction = state.config.attachments.max_per_transaction;
+    if AttachmentRepository::count_for_transaction(&state.db, transaction.id).await? >= max_per_transaction {
+        return Err(AppError::Conflict(format!(
+            "A transaction can have at most {} attachments",
+            max_per_transaction
+        )));
+    }
+
+    let (uploaded_name, bytes) = read_upload(multipart, state.config.attachments.max_bytes).await?;
+    let content_type = receipts::detect_content_type(&bytes).ok_or_else(|| {
+        AppError::ValidationError("Receipts must be JPEG, PNG, WebP or PDF files".to_string())
+    })?;
+
+    let attachment_id = Uuid::new_v4();
+    let attachment = NewTransactionAttachment {
+        id: attachment_id,
+        transaction_id: transaction.id,
+        uploaded_by: ledger.user.id,
+        storage_key: receipts::storage_key(transaction.id, attachment_id, content_type),
+        file_name: receipts::file_name(uploaded_name.as_deref(), content_type),
+        content_type: content_type.to_string(),
+        size_bytes: bytes.len() as i64,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+    };
+
+    state.storage.put(&attachment.storage_key, bytes, content_type).await?;
+    let attachment = match AttachmentRepository::create(&state.db, &attachment).await {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            // Do not leave an unreferenced file behind
+            if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+                tracing::warn!("Failed to remove attachment {}: {:?}", attachment.storage_key, e);
+            }
+            return Err(e);
+        }
+    };
+
+    if state.config.ocr.engine != OcrEngineKind::None && attachment.content_type.starts_with("image/") {
+        ReceiptDraftRepository::create(&state.db, attachment.id, transaction.id).await?;
+    }
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "upload_attachment",
+        "transaction",
+        transaction.id,
+        None,
+        Some(json!(attachment)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(attachment_response(&state, attachment)?))
+}
+
+// ============================================================================
+// GET /transactions/:transaction_id/attachments - List receipts with signed download
+// links (requires JWT auth)
+// ============================================================================
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<Json<Vec<AttachmentResponse>>, AppError> {
+    let transaction = find_transaction(&state, ledger.scope, transaction_id).await?;
+    let attachments = AttachmentRepository::list_for_transaction(&state.db, transaction.id).await?;
+
+    let attachments = attachments
+        .into_iter()
+        .map(|attachment| attachment_response(&state, attachment))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(attachments))
+}
+
+// ============================================================================
+// DELETE /transactions/:transaction_id/attachments/:attachment_id - Remove a receipt
+// (requires JWT auth)
+// ============================================================================
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path((transaction_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<AttachmentDeletedResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let transaction = find_transaction(&state, ledger.scope, transaction_id).await?;
+
+    let attachment = AttachmentRepository::delete(&state.db, transaction.id, attachment_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // The record is gone either way; a leftover file is only logged
+    if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+        tracing::warn!("Failed to remove attachment {}: {:?}", attachment.storage_key, e);
+    }
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "delete_attachment",
+        "transaction",
+        transaction.id,
+        Some(json!(attachment)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(AttachmentDeletedResponse {
+        message: "Attachment deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// GET /attachments/:attachment_id/download - Download a receipt via its signed link
+// ============================================================================
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+    Query(query): Query<DownloadAttachmentQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    // Links are only handed out to users who may see the transaction; the signature is the
+    // only credential here, so browsers and image tags can follow them without a token
+    if !signed_url::verify(
+        &state.config.attachments.signing_secret,
+        &download_path(attachment_id),
+        query.expires,
+        &query.signature,
+        Utc::now().timestamp(),
+    ) {
+        return Err(AppError::Forbidden);
+    }
+
+    let attachment = AttachmentRepository::find_by_id(&state.db, attachment_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let bytes = state
+        .storage
+        .get(&attachment.storage_key)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", attachment.file_name),
+            ),
+            (header::CACHE_CONTROL, "private, max-age=300".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        bytes,
+    ))
+}
+
+/// Read the `file` field of an upload, giving up as soon as it exceeds `max_bytes`
+async fn read_upload(mut multipart: Multipart, max_bytes: usize) -> Result<(Option<String>, Vec<u8>), AppError> {
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().map(str::to_string);
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?
+        {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(AppError::ValidationError(format!(
+                    "File is larger than {} bytes",
+                    max_bytes
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if bytes.is_empty() {
+            return Err(AppError::ValidationError("File is empty".to_string()));
+        }
+
+        return Ok((file_name, bytes));
+    }
+
+    Err(AppError::BadRequest("Missing 'file' field".to_string()))
+}
+
+async fn find_transaction(
+    state: &AppState,
+    scope: LedgerScope,
+    transaction_id: Uuid,
+) -> Result<Transaction, AppError> {
+    TransactionRepository::find_by_id(&state.db, scope, transaction_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+fn download_path(attachment_id: Uuid) -> String {
+    format!("/attachments/{}/download", attachment_id)
+}
+
+/// An attachment with a freshly signed download link
+fn attachment_response(state: &AppState, attachment: TransactionAttachment) -> Result<AttachmentResponse, AppError> {
+    let expires = Utc::now().timestamp() + state.config.attachments.link_expiry_seconds;
+    let download_url = format!(
+        "{}{}",
+        state.config.public_url.trim_end_matches('/'),
+        signed_url::signed_path(
+            &state.config.attachments.signing_secret,
+            &download_path(attachment.id),
+            expires,
+        )
+    );
+    let download_url_expires_at = DateTime::from_timestamp(expires, 0)
+        .ok_or_else(|| AppError::Internal("Invalid link expiry".to_string()))?
+        .naive_utc();
+
+    Ok(AttachmentResponse {
+        attachment,
+        download_url,
+        download_url_expires_at,
+    })
+}
+
+// ============================================================================
+// Attachment Router (nested under /transactions)
+// Uploads are size-checked while they are read, so the default body limit is lifted
+// ============================================================================
+pub fn attachment_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:transaction_id/attachments",
+            get(list_attachments)
+                .post(upload_attachment)
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route("/:transaction_id/attachments/:attachment_id", delete(delete_attachment))
+}
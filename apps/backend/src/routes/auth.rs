@@ -4,18 +4,32 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
 use validator::Validate;
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
-    db::repositories::{ApiKeyRepository, RefreshTokenRepository, UserRepository},
+    db::models::{User, UserStatus},
+    db::repositories::{
+        ApiKeyRepository, AuditLogRepository, LoginAttemptRepository, RefreshTokenRepository,
+        UserRepository,
+    },
     dto::auth::*,
     error::AppError,
-    middleware::AuthUser,
+    middleware::{AuthUser, ClientInfo, NoStore, NO_STORE},
     utils::{
         api_key::generate_api_key,
-        jwt::{generate_access_token, generate_refresh_token, validate_token, extract_user_id, TokenType},
+        jwt::{
+            generate_access_token, generate_refresh_token, validate_access_token, validate_token,
+            extract_jti, extract_user_id, TokenType,
+        },
+        login_protection::{ip_retry_after, lockout_duration, lockout_remaining},
         password::{hash_password, verify_password},
     },
 };
@@ -26,7 +40,7 @@ use crate::{
 pub async fn register(
     State(state): State<AppState>,
     Json(payload): Json<RegisterRequest>,
-) -> Result<Json<RegisterResponse>, AppError> {
+) -> Result<(NoStore, Json<RegisterResponse>), AppError> {
     // Validate input
     payload.validate()?;
 
@@ -53,52 +67,19 @@ pub async fn register(
     )
     .await?;
 
-    // Generate tokens
-    let access_token = generate_access_token(
-        user.id,
-        user.email.as_ref().unwrap(),
-        &user.role,
-        &state.config.jwt.access_secret,
-        state.config.jwt.access_expiry_seconds,
-    )?;
+    // Generate tokens and store the refresh token
+    let (access_token, refresh_token_str) = issue_session(&state, &user, None, None).await?;
 
-    let refresh_token_str = generate_refresh_token(
-        user.id,
-        user.email.as_ref().unwrap(),
-        &user.role,
-        &state.config.jwt.refresh_secret,
-        state.config.jwt.refresh_expiry_seconds,
-    )?;
+    let user_response = UserResponse::from(user.to_safe_user());
 
-    // Store refresh token in database
-    let token_hash = crate::utils::api_key::hash_api_key(&refresh_token_str);
-    RefreshTokenRepository::create(
-        &state.db,
-        user.id,
-        &token_hash,
-        state.config.jwt.refresh_expiry_seconds,
-        None, // device_info
-        None, // ip_address
-        None, // user_agent
-    )
-    .await?;
-
-    let safe_user = user.to_safe_user();
-    let user_response = UserResponse {
-        id: safe_user.id,
-        email: safe_user.email,
-        username: safe_user.username,
-        full_name: safe_user.full_name,
-        role: format!("{:?}", safe_user.role).to_lowercase(),
-        status: format!("{:?}", safe_user.status).to_lowercase(),
-        created_at: safe_user.created_at,
-    };
-
-    Ok(Json(RegisterResponse {
-        user: user_response,
-        access_token,
-        refresh_token: refresh_token_str,
-    }))
+    Ok((
+        NO_STORE,
+        Json(RegisterResponse {
+            user: user_response,
+            access_token,
+            refresh_token: refresh_token_str,
+        }),
+    ))
 }
 
 // ============================================================================
@@ -106,78 +87,137 @@ pub async fn register(
 // ============================================================================
 pub async fn login(
     State(state): State<AppState>,
+    client: ClientInfo,
     Json(payload): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, AppError> {
+) -> Result<(NoStore, Json<LoginResponse>), AppError> {
     // Validate input
     payload.validate()?;
 
-    // Find user by email
-    let user = UserRepository::find_by_email(&state.db, &payload.email)
-        .await?
-        .ok_or(AppError::InvalidCredentials)?;
+    let protection = &state.config.login_protection;
+    let ip_address = client.ip_address.as_deref();
+    let user_agent = client.user_agent.as_deref();
+
+    // Throttle IPs with too many recent failures
+    if let Some(ip) = ip_address {
+        let (recent_failures, oldest_failure) = LoginAttemptRepository::recent_failures_for_ip(
+            &state.db,
+            ip,
+            protection.ip_window_seconds,
+        )
+        .await?;
+
+        if let Some(retry_after_seconds) = ip_retry_after(
+            recent_failures,
+            oldest_failure,
+            Utc::now().naive_utc(),
+            protection,
+        ) {
+            return Err(AppError::TooManyRequests { retry_after_seconds });
+        }
+    }
+
+    // Find user by email (accounts pending deletion can still log in to restore them)
+    let Some(user) = UserRepository::find_by_email_for_login(&state.db, &payload.email).await? else {
+        return refuse_unknown_login(&state, None, &payload.email, &client).await;
+    };
+
+    // Refuse locked accounts before checking the password
+    if let Some(retry_after_seconds) = user.lockout_remaining_seconds() {
+        return Err(AppError::AccountLocked { retry_after_seconds });
+    }
 
     // Check if user has dashboard access (email + password)
     if !user.has_dashboard_access() {
-        return Err(AppError::InvalidCredentials);
+        return refuse_unknown_login(&state, Some(user.id), &payload.email, &client).await;
     }
 
     // Verify password
     let password_hash = user.password_hash.as_ref().unwrap();
     if !verify_password(&payload.password, password_hash)? {
+        LoginAttemptRepository::create(
+            &state.db,
+            Some(user.id),
+            &payload.email,
+            ip_address,
+            user_agent,
+            false,
+        )
+        .await?;
+
+        let failed_attempts = UserRepository::increment_failed_logins(&state.db, user.id).await?;
+
+        if let Some(duration) = lockout_duration(failed_attempts, protection) {
+            let locked_until = Utc::now().naive_utc() + duration;
+            UserRepository::lock_until(&state.db, user.id, locked_until).await?;
+
+            AuditLogRepository::create(
+                &state.db,
+                Some(user.id),
+                "lock_account",
+                "user",
+                user.id,
+                None,
+                Some(json!({
+                    "failed_login_attempts": failed_attempts,
+                    "locked_until": locked_until,
+                })),
+                ip_address,
+                user_agent,
+            )
+            .await?;
+
+            tracing::warn!("Account {} locked after {} failed logins", user.id, failed_attempts);
+
+            return Err(AppError::AccountLocked {
+                retry_after_seconds: duration.num_seconds(),
+            });
+        }
+
         return Err(AppError::InvalidCredentials);
     }
 
+    // Logging in during the deletion grace period cancels the deletion
+    let user = if user.is_pending_deletion() {
+        restore_account(&state, user, &client).await?
+    } else {
+        user
+    };
+
     // Check if user is active
     if !user.is_active() {
         return Err(AppError::Forbidden);
     }
 
-    // Generate tokens
-    let access_token = generate_access_token(
-        user.id,
-        user.email.as_ref().unwrap(),
-        &user.role,
-        &state.config.jwt.access_secret,
-        state.config.jwt.access_expiry_seconds,
-    )?;
-
-    let refresh_token_str = generate_refresh_token(
-        user.id,
-        user.email.as_ref().unwrap(),
-        &user.role,
-        &state.config.jwt.refresh_secret,
-        state.config.jwt.refresh_expiry_seconds,
-    )?;
-
-    // Store refresh token in database
-    let token_hash = crate::utils::api_key::hash_api_key(&refresh_token_str);
-    RefreshTokenRepository::create(
+    LoginAttemptRepository::create(
         &state.db,
-        user.id,
-        &token_hash,
-        state.config.jwt.refresh_expiry_seconds,
-        None, // device_info
-        None, // ip_address
-        None, // user_agent
+        Some(user.id),
+        &payload.email,
+        ip_address,
+        user_agent,
+        true,
+    )
+    .await?;
+    UserRepository::reset_failed_logins(&state.db, user.id).await?;
+
+    // Generate tokens and store the refresh token
+    let (access_token, refresh_token_str) = issue_session(
+        &state,
+        &user,
+        client.ip_address.clone(),
+        client.user_agent.clone(),
     )
     .await?;
 
-    let safe_user = user.to_safe_user();
-    let user_response = UserResponse {
-        id: safe_user.id,
-        email: safe_user.email,
-        username: safe_user.username,
-        full_name: safe_user.full_name,
-        role: format!("{:?}", safe_user.role).to_lowercase(),
-        status: format!("{:?}", safe_user.status).to_lowercase(),
-        created_at: safe_user.created_at,
-    };
+    let user_response = UserResponse::from(user.to_safe_user());
 
-    Ok(Json(LoginResponse {
-        user: user_response,
-        access_token,
-        refresh_token: refresh_token_str,
-    }))
+    Ok((
+        NO_STORE,
+        Json(LoginResponse {
+            user: user_response,
+            access_token,
+            refresh_token: refresh_token_str,
+        }),
+    ))
 }
 
 // ============================================================================
@@ -186,7 +226,7 @@ pub async fn login(
 pub async fn refresh(
     State(state): State<AppState>,
     Json(payload): Json<RefreshTokenRequest>,
-) -> Result<Json<RefreshTokenResponse>, AppError> {
+) -> Result<(NoStore, Json<RefreshTokenResponse>), AppError> {
     // Validate input
     payload.validate()?;
 
@@ -221,12 +261,18 @@ pub async fn refresh(
         return Err(AppError::Forbidden);
     }
 
+    // Refresh tokens issued before a token version bump are stale
+    if claims.ver != user.token_version {
+        return Err(AppError::TokenInvalid);
+    }
+
     // Generate new tokens
     let new_access_token = generate_access_token(
         user.id,
         user.email.as_ref().unwrap(),
         &user.role,
-        &state.config.jwt.access_secret,
+        user.token_version,
+        &state.jwt_keys,
         state.config.jwt.access_expiry_seconds,
     )?;
 
@@ -234,6 +280,7 @@ pub async fn refresh(
         user.id,
         user.email.as_ref().unwrap(),
         &user.role,
+        user.token_version,
         &state.config.jwt.refresh_secret,
         state.config.jwt.refresh_expiry_seconds,
     )?;
@@ -254,17 +301,21 @@ pub async fn refresh(
     )
     .await?;
 
-    Ok(Json(RefreshTokenResponse {
-        access_token: new_access_token,
-        refresh_token: new_refresh_token_str,
-    }))
+    Ok((
+        NO_STORE,
+        Json(RefreshTokenResponse {
+            access_token: new_access_token,
+            refresh_token: new_refresh_token_str,
+        }),
+    ))
 }
 
 // ============================================================================
-// POST /auth/logout - Revoke refresh token
+// POST /auth/logout - Revoke refresh token (and the presented access token)
 // ============================================================================
 pub async fn logout(
     State(state): State<AppState>,
+    bearer: Option<TypedHeader<Authorization<Bearer>>>,
     Json(payload): Json<LogoutRequest>,
 ) -> Result<Json<LogoutResponse>, AppError> {
     // Validate input
@@ -276,25 +327,90 @@ pub async fn logout(
     // Revoke the refresh token
     RefreshTokenRepository::revoke(&state.db, &token_hash).await?;
 
+    // Denylist the access token until it expires; an already invalid one needs nothing
+    if let Some(TypedHeader(Authorization(bearer))) = bearer
+        && let Ok(claims) = validate_access_token(bearer.token(), &state.jwt_keys)
+    {
+        let jti = extract_jti(&claims)?;
+        let user_id = extract_user_id(&claims)?;
+        let expires_at = DateTime::from_timestamp(claims.exp, 0)
+            .ok_or(AppError::TokenInvalid)?
+            .naive_utc();
+
+        state.token_denylist.revoke(&state.db, jti, user_id, expires_at).await?;
+    }
+
     Ok(Json(LogoutResponse {
         message: "Logged out successfully".to_string(),
     }))
 }
 
+// ============================================================================
+// POST /auth/logout-all - Invalidate every session of the current user
+// ============================================================================
+pub async fn logout_all(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<Json<LogoutResponse>, AppError> {
+    // Bumping the version invalidates all outstanding access tokens
+    UserRepository::bump_token_version(&state.db, user.id).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    Ok(Json(LogoutResponse {
+        message: "Logged out of all sessions successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /auth/change-password - Change password and end other sessions
+// ============================================================================
+pub async fn change_password(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<Json<ChangePasswordResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    // Accounts created through social login have no password to change
+    let password_hash = user
+        .password_hash
+        .as_deref()
+        .ok_or_else(|| AppError::BadRequest("Account has no password set".to_string()))?;
+
+    if !verify_password(&payload.current_password, password_hash)? {
+        return Err(AppError::InvalidCredentials);
+    }
+
+    // Store the new hash; this also bumps the token version
+    let new_hash = hash_password(&payload.new_password)?;
+    UserRepository::update_password(&state.db, user.id, &new_hash).await?;
+    RefreshTokenRepository::revoke_all_for_user(&state.db, user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "change_password",
+        "user",
+        user.id,
+        None,
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ChangePasswordResponse {
+        message: "Password changed successfully, please log in again".to_string(),
+    }))
+}
+
 // ============================================================================
 // GET /auth/me - Get current user info (requires JWT auth)
 // ============================================================================
 pub async fn me(AuthUser(user): AuthUser) -> Result<Json<MeResponse>, AppError> {
-    let safe_user = user.to_safe_user();
-    let user_response = UserResponse {
-        id: safe_user.id,
-        email: safe_user.email,
-        username: safe_user.username,
-        full_name: safe_user.full_name,
-        role: format!("{:?}", safe_user.role).to_lowercase(),
-        status: format!("{:?}", safe_user.status).to_lowercase(),
-        created_at: safe_user.created_at,
-    };
+    let user_response = UserResponse::from(user.to_safe_user());
 
     Ok(Json(MeResponse {
         user: user_response,
@@ -308,7 +424,7 @@ pub async fn create_api_key(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Json(payload): Json<CreateApiKeyRequest>,
-) -> Result<(StatusCode, Json<CreateApiKeyResponse>), AppError> {
+) -> Result<(StatusCode, NoStore, Json<CreateApiKeyResponse>), AppError> {
     // Validate input
     payload.validate()?;
 
@@ -328,6 +444,7 @@ pub async fn create_api_key(
 
     Ok((
         StatusCode::CREATED,
+        NO_STORE,
         Json(CreateApiKeyResponse {
             id: api_key.id,
             name: api_key.name,
@@ -381,6 +498,121 @@ pub async fn revoke_api_key(
     }))
 }
 
+// ============================================================================
+// Shared session issuance
+// ============================================================================
+
+/// Generate an access/refresh token pair and store the refresh token
+pub(crate) async fn issue_session(
+    state: &AppState,
+    user: &User,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(String, String), AppError> {
+    let email = user
+        .email
+        .as_deref()
+        .ok_or_else(|| AppError::Unauthorized("User has no dashboard access".to_string()))?;
+
+    let access_token = generate_access_token(
+        user.id,
+        email,
+        &user.role,
+        user.token_version,
+        &state.jwt_keys,
+        state.config.jwt.access_expiry_seconds,
+    )?;
+
+    let refresh_token = generate_refresh_token(
+        user.id,
+        email,
+        &user.role,
+        user.token_version,
+        &state.config.jwt.refresh_secret,
+        state.config.jwt.refresh_expiry_seconds,
+    )?;
+
+    let token_hash = crate::utils::api_key::hash_api_key(&refresh_token);
+    RefreshTokenRepository::create(
+        &state.db,
+        user.id,
+        &token_hash,
+        state.config.jwt.refresh_expiry_seconds,
+        None, // device_info
+        ip_address,
+        user_agent,
+    )
+    .await?;
+
+    Ok((access_token, refresh_token))
+}
+
+/// Refuse a login to an email without a password account the way a wrong password is
+/// refused, lockout included, so the response does not tell whether the email is
+/// registered. Failures count since the email's last successful login
+async fn refuse_unknown_login(
+    state: &AppState,
+    user_id: Option<Uuid>,
+    email: &str,
+    client: &ClientInfo,
+) -> Result<(NoStore, Json<LoginResponse>), AppError> {
+    let protection = &state.config.login_protection;
+    let (failures, last_failure) =
+        LoginAttemptRepository::failures_since_success_for_email(&state.db, email).await?;
+    let failures = i32::try_from(failures).unwrap_or(i32::MAX);
+
+    if let Some(retry_after_seconds) =
+        lockout_remaining(failures, last_failure, Utc::now().naive_utc(), protection)
+    {
+        return Err(AppError::AccountLocked { retry_after_seconds });
+    }
+
+    LoginAttemptRepository::create(
+        &state.db,
+        user_id,
+        email,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+        false,
+    )
+    .await?;
+
+    match lockout_duration(failures.saturating_add(1), protection) {
+        Some(duration) => Err(AppError::AccountLocked {
+            retry_after_seconds: duration.num_seconds(),
+        }),
+        None => Err(AppError::InvalidCredentials),
+    }
+}
+
+/// Restore an account deleted by its owner that is still within the grace period
+pub(crate) async fn restore_account(
+    state: &AppState,
+    user: User,
+    client: &ClientInfo,
+) -> Result<User, AppError> {
+    UserRepository::restore(&state.db, user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "restore_account",
+        "user",
+        user.id,
+        Some(json!({ "status": user.status, "purge_after": user.purge_after })),
+        Some(json!({ "status": UserStatus::Active })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("Account {} restored during its deletion grace period", user.id);
+
+    UserRepository::find_by_id(&state.db, user.id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
 // ============================================================================
 // Auth Router
 // ============================================================================
@@ -390,6 +622,8 @@ pub fn auth_routes() -> Router<AppState> {
         .route("/login", post(login))
         .route("/refresh", post(refresh))
         .route("/logout", post(logout))
+        .route("/logout-all", post(logout_all))
+        .route("/change-password", post(change_password))
         .route("/me", get(me))
         .route("/api-keys", post(create_api_key))
         .route("/api-keys", get(list_api_keys))
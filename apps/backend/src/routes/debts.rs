@@ -0,0 +1,419 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{Currency, DebtDirection, DebtKind, DebtWithParties, LedgerScope, NewDebt, User},
+        repositories::{AuditLogRepository, DebtRepository, TransactionRepository, UserRepository},
+    },
+    dto::debt::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+};
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
+
+// ============================================================================
+// GET /debts - Debts and settle-ups the user is a party to (requires JWT auth)
+// ============================================================================
+pub async fn list_debts(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<ListDebtsQuery>,
+) -> Result<Json<ListDebtsResponse>, AppError> {
+    // Validate input
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let contact_name = query.contact_name.as_deref().map(str::trim).filter(|name| !name.is_empty());
+
+    let (entries, total) = DebtRepository::search(
+        &state.db,
+        user.id,
+        query.counterparty_user_id,
+        contact_name,
+        query.kind.as_ref(),
+        query.from,
+        query.to,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ListDebtsResponse {
+        entries: entries
+            .into_iter()
+            .map(|entry| DebtResponse::for_user(entry, user.id))
+            .collect(),
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// POST /debts - Record who owes whom, with another user or a contact (requires JWT auth)
+// ============================================================================
+pub async fn create_debt(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateDebtRequest>,
+) -> Result<Json<DebtResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+    let currency = payload.currency.unwrap_or(user.base_currency);
+    let amount = positive_amount(payload.amount, currency.minor_units())?;
+
+    let counterparty = match (payload.counterparty_username.as_deref(), payload.contact_name.as_deref()) {
+        (Some(username), None) => {
+            let other = UserRepository::find_active_by_username(&state.db, username.trim())
+                .await?
+                .ok_or_else(|| AppError::ValidationError("Unknown user".to_string()))?;
+            if other.id == user.id {
+                return Err(AppError::ValidationError("Cannot record a debt with yourself".to_string()));
+            }
+            Counterparty::User(other.id)
+        }
+        (None, Some(name)) => Counterparty::Contact(contact_name(name)?),
+        _ => {
+            return Err(AppError::ValidationError(
+                "Give either counterparty_username or contact_name".to_string(),
+            ));
+        }
+    };
+    ensure_own_transaction(&state, &user, payload.transaction_id).await?;
+
+    let debt = new_debt(
+        &user,
+        DebtKind::Debt,
+        payload.direction,
+        counterparty,
+        amount,
+        currency,
+        payload.description,
+        payload.entry_date,
+        payload.transaction_id,
+    );
+    let entry = record(&state, &user, &client, "create_debt", &debt).await?;
+
+    Ok(Json(DebtResponse::for_user(entry, user.id)))
+}
+
+// ============================================================================
+// POST /debts/settle-up - Record a repayment of the balance with a counterparty
+// (requires JWT auth)
+// The direction follows the balance: whoever owes pays. The amount defaults to the
+// whole balance and may not exceed it.
+// ============================================================================
+pub async fn settle_up(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<SettleUpRequest>,
+) -> Result<Json<DebtResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+    let currency = payload.currency.unwrap_or(user.base_currency);
+
+    let counterparty = match (payload.counterparty_user_id, payload.contact_name.as_deref()) {
+        (Some(user_id), None) => Counterparty::User(user_id),
+        (None, Some(name)) => Counterparty::Contact(contact_name(name)?),
+        _ => {
+            return Err(AppError::ValidationError(
+                "Give either counterparty_user_id or contact_name".to_string(),
+            ));
+        }
+    };
+
+    let outstanding = DebtRepository::balances(&state.db, user.id, false)
+        .await?
+        .into_iter()
+        .find(|balance| {
+            balance.currency == currency
+                && match &counterparty {
+                    Counterparty::User(user_id) => balance.counterparty_user_id == Some(*user_id),
+                    Counterparty::Contact(name) => {
+                        balance.counterparty_user_id.is_none()
+                            && balance.counterparty_name.to_lowercase() == name.to_lowercase()
+                    }
+                }
+        })
+        .map(|balance| balance.balance)
+        .unwrap_or_default();
+
+    if outstanding.is_zero() {
+        return Err(AppError::BadRequest(format!("Nothing is owed in {} with this counterparty", currency)));
+    }
+
+    let amount = match payload.amount {
+        Some(amount) => positive_amount(amount, currency.minor_units())?,
+        None => outstanding.abs(),
+    };
+    if amount > outstanding.abs() {
+        return Err(AppError::ValidationError(format!(
+            "Amount is more than the outstanding {} {}",
+            outstanding.abs(),
+            currency
+        )));
+    }
+    ensure_own_transaction(&state, &user, payload.transaction_id).await?;
+
+    // A positive balance means the counterparty owes the user, so they pay the user
+    let direction = if outstanding > Decimal::ZERO { DebtDirection::ToMe } else { DebtDirection::FromMe };
+    let debt = new_debt(
+        &user,
+        DebtKind::Settlement,
+        direction,
+        counterparty,
+        amount,
+        currency,
+        payload.description,
+        payload.entry_date,
+        payload.transaction_id,
+    );
+    let entry = record(&state, &user, &client, "settle_debt", &debt).await?;
+
+    Ok(Json(DebtResponse::for_user(entry, user.id)))
+}
+
+// ============================================================================
+// GET /debts/balances - Who owes whom, per counterparty and currency (requires JWT auth)
+// ============================================================================
+pub async fn list_balances(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<DebtBalancesQuery>,
+) -> Result<Json<DebtBalancesResponse>, AppError> {
+    let balances = DebtRepository::balances(&state.db, user.id, query.include_settled).await?;
+
+    Ok(Json(DebtBalancesResponse { balances }))
+}
+
+// ============================================================================
+// GET /debts/:debt_id - View an entry (requires JWT auth)
+// ============================================================================
+pub async fn get_debt(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(debt_id): Path<Uuid>,
+) -> Result<Json<DebtResponse>, AppError> {
+    let entry = find_debt(&state, &user, debt_id).await?;
+
+    Ok(Json(DebtResponse::for_user(entry, user.id)))
+}
+
+// ============================================================================
+// POST /debts/:debt_id/dispute - Dispute an entry the other party recorded
+// (requires JWT auth)
+// A disputed entry stays visible to both parties but no longer counts towards their
+// balance; the party who recorded it can delete it.
+// ============================================================================
+pub async fn dispute_debt(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(debt_id): Path<Uuid>,
+) -> Result<Json<DebtResponse>, AppError> {
+    let entry = find_debt(&state, &user, debt_id).await?;
+
+    if entry.debt.created_by == Some(user.id) {
+        return Err(AppError::BadRequest(
+            "You recorded this entry; delete it instead".to_string(),
+        ));
+    }
+
+    if !DebtRepository::dispute(&state.db, user.id, entry.debt.id).await? {
+        return Err(AppError::Conflict("Debt entry is already disputed".to_string()));
+    }
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "dispute_debt",
+        "debt",
+        entry.debt.id,
+        Some(json!(entry.debt)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    let entry = find_debt(&state, &user, debt_id).await?;
+
+    Ok(Json(DebtResponse::for_user(entry, user.id)))
+}
+
+// ============================================================================
+// DELETE /debts/:debt_id - Delete an entry the user recorded, or one whose recorder
+// deleted their account (requires JWT auth)
+// ============================================================================
+pub async fn delete_debt(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(debt_id): Path<Uuid>,
+) -> Result<Json<DebtDeletedResponse>, AppError> {
+    let entry = find_debt(&state, &user, debt_id).await?;
+
+    if entry.debt.created_by.is_some_and(|created_by| created_by != user.id) {
+        // Only the user who recorded the entry can delete it; the other party disputes it
+        return Err(AppError::Forbidden);
+    }
+
+    DebtRepository::delete(&state.db, user.id, entry.debt.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_debt",
+        "debt",
+        entry.debt.id,
+        Some(json!(entry.debt)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(DebtDeletedResponse {
+        message: "Debt entry deleted successfully".to_string(),
+    }))
+}
+
+/// The other party of a new entry
+enum Counterparty {
+    User(Uuid),
+    Contact(String),
+}
+
+#[allow(clippy::too_many_arguments)]
+fn new_debt(
+    user: &User,
+    kind: DebtKind,
+    direction: DebtDirection,
+    counterparty: Counterparty,
+    amount: Decimal,
+    currency: Currency,
+    description: Option<String>,
+    entry_date: Option<NaiveDate>,
+    transaction_id: Option<Uuid>,
+) -> NewDebt {
+    let (other_user_id, contact_name) = match counterparty {
+        Counterparty::User(user_id) => (Some(user_id), None),
+        Counterparty::Contact(name) => (None, Some(name)),
+    };
+    let (creditor_user_id, debtor_user_id) = match direction {
+        DebtDirection::ToMe => (Some(user.id), other_user_id),
+        DebtDirection::FromMe => (other_user_id, Some(user.id)),
+    };
+
+    NewDebt {
+        created_by: user.id,
+        kind,
+        creditor_user_id,
+        debtor_user_id,
+        contact_name,
+        amount,
+        currency,
+        description: description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+        entry_date: entry_date.unwrap_or_else(|| Utc::now().date_naive()),
+        transaction_id,
+    }
+}
+
+async fn record(
+    state: &AppState,
+    user: &User,
+    client: &ClientInfo,
+    action: &str,
+    debt: &NewDebt,
+) -> Result<DebtWithParties, AppError> {
+    let debt = DebtRepository::create(&state.db, debt).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        action,
+        "debt",
+        debt.id,
+        None,
+        Some(json!(debt)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    find_debt(state, user, debt.id).await
+}
+
+async fn find_debt(state: &AppState, user: &User, debt_id: Uuid) -> Result<DebtWithParties, AppError> {
+    DebtRepository::find_for_user(&state.db, user.id, debt_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+fn positive_amount(amount: Decimal, precision: u32) -> Result<Decimal, AppError> {
+    if amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError("Amount must be positive".to_string()));
+    }
+
+    Ok(amount.round_dp(precision))
+}
+
+fn contact_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError("Contact name must not be blank".to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+/// A linked transaction must be one of the user's own
+async fn ensure_own_transaction(
+    state: &AppState,
+    user: &User,
+    transaction_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    let scope = LedgerScope::personal(user.id);
+    if let Some(transaction_id) = transaction_id
+        && TransactionRepository::find_by_id(&state.db, scope, transaction_id).await?.is_none()
+    {
+        return Err(AppError::ValidationError("Unknown transaction".to_string()));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Debts Router
+// ============================================================================
+pub fn debt_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_debts).post(create_debt))
+        .route("/settle-up", post(settle_up))
+        .route("/balances", get(list_balances))
+        .route("/:debt_id", get(get_debt).delete(delete_debt))
+        .route("/:debt_id/dispute", post(dispute_debt))
+}
@@ -0,0 +1,232 @@
+use std::path::PathBuf;
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{DataExport, User},
+        repositories::{AuditLogRepository, DataExportRepository},
+    },
+    dto::export::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+    utils::{
+        data_export::{build_archive, AccountData},
+        signed_url,
+    },
+};
+
+// ============================================================================
+// POST /account/exports - Request a full data export (requires JWT auth)
+// ============================================================================
+pub async fn request_export(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+) -> Result<(StatusCode, Json<ExportResponse>), AppError> {
+    // One export at a time per user
+    if DataExportRepository::has_active_for_user(&state.db, user.id).await? {
+        return Err(AppError::Conflict("An export is already in progress".to_string()));
+    }
+
+    let export = DataExportRepository::create(&state.db, user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "request_data_export",
+        "data_export",
+        export.id,
+        None,
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    // Assemble the archive in the background; clients poll the export for its status
+    tokio::spawn(process_export(state.clone(), export.id, user));
+
+    Ok((StatusCode::ACCEPTED, Json(ExportResponse::new(export, None))))
+}
+
+// ============================================================================
+// GET /account/exports - List the current user's exports (requires JWT auth)
+// ============================================================================
+pub async fn list_exports(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<Json<ListExportsResponse>, AppError> {
+    let exports = DataExportRepository::list_for_user(&state.db, user.id).await?;
+
+    Ok(Json(ListExportsResponse {
+        exports: exports
+            .into_iter()
+            .map(|export| {
+                let download = download_link(&state, &export);
+                ExportResponse::new(export, download)
+            })
+            .collect(),
+    }))
+}
+
+// ============================================================================
+// GET /account/exports/:export_id - Export status with a fresh download link
+// ============================================================================
+pub async fn get_export(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(export_id): Path<Uuid>,
+) -> Result<Json<ExportResponse>, AppError> {
+    let export = DataExportRepository::find_for_user(&state.db, export_id, user.id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let download = download_link(&state, &export);
+
+    Ok(Json(ExportResponse::new(export, download)))
+}
+
+// ============================================================================
+// GET /exports/:export_id/download - Download an archive via its signed link
+// ============================================================================
+pub async fn download_export(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Path(export_id): Path<Uuid>,
+    Query(query): Query<DownloadExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    // The signature is the only credential; browsers follow the link without a token
+    if !signed_url::verify(
+        &state.config.export.signing_secret,
+        &download_path(export_id),
+        query.expires,
+        &query.signature,
+        Utc::now().timestamp(),
+    ) {
+        return Err(AppError::Forbidden);
+    }
+
+    let export = DataExportRepository::find_by_id(&state.db, export_id)
+        .await?
+        .filter(DataExport::is_downloadable)
+        .ok_or(AppError::NotFound)?;
+
+    let file_path = export.file_path.ok_or(AppError::NotFound)?;
+    let file = tokio::fs::File::open(&file_path).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(export.user_id),
+        "download_data_export",
+        "data_export",
+        export.id,
+        None,
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_LENGTH, file.metadata().await?.len().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"mintora-export-{}.zip\"",
+                    export.created_at.format("%Y%m%d")
+                ),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    ))
+}
+
+/// Background job: collect the user's data, write the archive and record the result
+async fn process_export(state: AppState, export_id: Uuid, user: User) {
+    if let Err(e) = generate_archive(&state, export_id, &user).await {
+        tracing::error!("Data export {} failed: {:?}", export_id, e);
+
+        if let Err(e) =
+            DataExportRepository::mark_failed(&state.db, export_id, "Export could not be generated").await
+        {
+            tracing::error!("Failed to mark data export {} as failed: {:?}", export_id, e);
+        }
+    }
+}
+
+async fn generate_archive(state: &AppState, export_id: Uuid, user: &User) -> Result<(), AppError> {
+    DataExportRepository::mark_processing(&state.db, export_id).await?;
+
+    let data = AccountData::collect(&state.db, user).await?;
+    let bytes = tokio::task::spawn_blocking(move || build_archive(&data))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+
+    let dir = PathBuf::from(&state.config.export.dir);
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let file_path = dir.join(format!("{}.zip", export_id));
+    tokio::fs::write(&file_path, &bytes).await?;
+
+    let expires_at =
+        Utc::now().naive_utc() + Duration::seconds(state.config.export.retention_seconds);
+
+    DataExportRepository::mark_completed(
+        &state.db,
+        export_id,
+        &file_path.to_string_lossy(),
+        bytes.len() as i64,
+        expires_at,
+    )
+    .await
+}
+
+fn download_path(export_id: Uuid) -> String {
+    format!("/exports/{}/download", export_id)
+}
+
+/// Signed download URL, valid for the link lifetime but never past the archive's expiry
+fn download_link(state: &AppState, export: &DataExport) -> Option<(String, NaiveDateTime)> {
+    if !export.is_downloadable() {
+        return None;
+    }
+
+    let link_expiry = Utc::now().timestamp() + state.config.export.link_expiry_seconds;
+    let expires = export
+        .expires_at
+        .map(|at| at.and_utc().timestamp().min(link_expiry))?;
+
+    let url = format!(
+        "{}{}",
+        state.config.public_url.trim_end_matches('/'),
+        signed_url::signed_path(
+            &state.config.export.signing_secret,
+            &download_path(export.id),
+            expires,
+        )
+    );
+
+    Some((url, DateTime::from_timestamp(expires, 0)?.naive_utc()))
+}
+
+// ============================================================================
+// Export Router
+// ============================================================================
+pub fn export_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_exports).post(request_export))
+        .route("/:export_id", get(get_export))
+}
@@ -0,0 +1,413 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{Currency, GoalContribution, GoalWithSaved, LedgerScope, User},
+        repositories::{
+            AccountRepository, AuditLogRepository, GoalRepository, TagRepository, TransactionRepository,
+        },
+    },
+    dto::goal::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+    utils::goals::goal_progress,
+};
+
+// ============================================================================
+// GET /goals - List savings goals with their progress (requires JWT auth)
+// ============================================================================
+pub async fn list_goals(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<ListGoalsQuery>,
+) -> Result<Json<Vec<GoalResponse>>, AppError> {
+    let goals = GoalRepository::list_for_user(&state.db, user.id, query.include_archived).await?;
+
+    Ok(Json(goals.into_iter().map(goal_response).collect()))
+}
+
+// ============================================================================
+// POST /goals - Create a savings goal (requires JWT auth)
+// ============================================================================
+pub async fn create_goal(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateGoalRequest>,
+) -> Result<Json<GoalResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+    if payload.account_id.is_some() && payload.tag_id.is_some() {
+        return Err(AppError::ValidationError(
+            "A goal can be linked to an account or a tag, not both".to_string(),
+        ));
+    }
+
+    let account_currency = match payload.account_id {
+        Some(account_id) => Some(linked_account_currency(&state, &user, account_id).await?),
+        None => None,
+    };
+    let currency = payload.currency.or(account_currency).unwrap_or(user.base_currency);
+    if account_currency.is_some_and(|account_currency| account_currency != currency) {
+        return Err(AppError::ValidationError(
+            "The linked account must be in the goal currency".to_string(),
+        ));
+    }
+    if let Some(tag_id) = payload.tag_id {
+        ensure_own_tag(&state, &user, tag_id).await?;
+    }
+
+    let name = payload.name.trim();
+    ensure_unique_name(&state, &user, name, None).await?;
+    let target_amount = target_amount(payload.target_amount, currency)?;
+    let start_date = payload.start_date.unwrap_or_else(|| Utc::now().date_naive());
+    ensure_dates(start_date, payload.deadline)?;
+
+    let goal = GoalRepository::create(
+        &state.db,
+        user.id,
+        name,
+        target_amount,
+        currency,
+        start_date,
+        payload.deadline,
+        payload.account_id,
+        payload.tag_id,
+    )
+    .await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "create_goal",
+        "goal",
+        goal.id,
+        None,
+        Some(json!(goal)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    let goal = find_goal(&state, &user, goal.id).await?;
+
+    Ok(Json(goal_response(goal)))
+}
+
+// ============================================================================
+// GET /goals/:goal_id - View a goal with its progress and contributions
+// (requires JWT auth)
+// ============================================================================
+pub async fn get_goal(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<GoalDetailResponse>, AppError> {
+    let goal = find_goal(&state, &user, goal_id).await?;
+    let contributions = GoalRepository::list_contributions(&state.db, goal.goal.id).await?;
+
+    Ok(Json(GoalDetailResponse {
+        goal: goal_response(goal),
+        contributions,
+    }))
+}
+
+// ============================================================================
+// PATCH /goals/:goal_id - Change the target, dates or links, or archive a goal
+// (requires JWT auth)
+// ============================================================================
+pub async fn update_goal(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(goal_id): Path<Uuid>,
+    Json(payload): Json<UpdateGoalRequest>,
+) -> Result<Json<GoalResponse>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let existing = find_goal(&state, &user, goal_id).await?.goal;
+    let mut goal = existing.clone();
+
+    if let Some(name) = payload.name.as_deref().map(str::trim) {
+        ensure_unique_name(&state, &user, name, Some(goal.id)).await?;
+        goal.name = name.to_string();
+    }
+    if let Some(amount) = payload.target_amount {
+        goal.target_amount = target_amount(amount, goal.currency)?;
+    }
+    if let Some(start_date) = payload.start_date {
+        goal.start_date = start_date;
+    }
+    if let Some(deadline) = payload.deadline {
+        goal.deadline = deadline;
+    }
+    ensure_dates(goal.start_date, goal.deadline)?;
+
+    if payload.unlink_account {
+        goal.account_id = None;
+    } else if let Some(account_id) = payload.account_id {
+        if linked_account_currency(&state, &user, account_id).await? != goal.currency {
+            return Err(AppError::ValidationError(
+                "The linked account must be in the goal currency".to_string(),
+            ));
+        }
+        goal.account_id = Some(account_id);
+    }
+    if payload.unlink_tag {
+        goal.tag_id = None;
+    } else if let Some(tag_id) = payload.tag_id {
+        ensure_own_tag(&state, &user, tag_id).await?;
+        goal.tag_id = Some(tag_id);
+    }
+    if goal.account_id.is_some() && goal.tag_id.is_some() {
+        return Err(AppError::ValidationError(
+            "A goal can be linked to an account or a tag, not both".to_string(),
+        ));
+    }
+    if let Some(is_archived) = payload.is_archived {
+        goal.is_archived = is_archived;
+    }
+
+    let goal = GoalRepository::update(&state.db, &goal).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "update_goal",
+        "goal",
+        goal.id,
+        Some(json!(existing)),
+        Some(json!(goal)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    let goal = find_goal(&state, &user, goal.id).await?;
+
+    Ok(Json(goal_response(goal)))
+}
+
+// ============================================================================
+// DELETE /goals/:goal_id - Delete a goal and its contributions (requires JWT auth)
+// Linked transactions are kept
+// ============================================================================
+pub async fn delete_goal(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<GoalDeletedResponse>, AppError> {
+    let goal = find_goal(&state, &user, goal_id).await?.goal;
+
+    GoalRepository::delete(&state.db, user.id, goal.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_goal",
+        "goal",
+        goal.id,
+        Some(json!(goal)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(GoalDeletedResponse {
+        message: "Goal deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// POST /goals/:goal_id/contributions - Put money towards a goal, by hand or from a
+// transaction (requires JWT auth)
+// ============================================================================
+pub async fn add_contribution(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(goal_id): Path<Uuid>,
+    Json(payload): Json<CreateContributionRequest>,
+) -> Result<Json<GoalContribution>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let goal = find_goal(&state, &user, goal_id).await?.goal;
+
+    let (amount, date) = match payload.transaction_id {
+        Some(transaction_id) => {
+            let transaction =
+                TransactionRepository::find_by_id(&state.db, LedgerScope::personal(user.id), transaction_id)
+                    .await?
+                    .ok_or_else(|| AppError::ValidationError("Unknown transaction".to_string()))?;
+            if transaction.currency != goal.currency {
+                return Err(AppError::ValidationError(format!(
+                    "The transaction is in {}, the goal in {}",
+                    transaction.currency, goal.currency
+                )));
+            }
+            if GoalRepository::transaction_contributed(&state.db, goal.id, transaction.id).await? {
+                return Err(AppError::Conflict(
+                    "The transaction is already recorded for this goal".to_string(),
+                ));
+            }
+            (
+                payload.amount.unwrap_or(transaction.amount),
+                payload.contribution_date.unwrap_or(transaction.transaction_date),
+            )
+        }
+        None => {
+            let amount = payload.amount.ok_or_else(|| {
+                AppError::ValidationError("Give an amount or a transaction_id".to_string())
+            })?;
+            (amount, payload.contribution_date.unwrap_or_else(|| Utc::now().date_naive()))
+        }
+    };
+
+    if amount.is_zero() {
+        return Err(AppError::ValidationError("Amount must not be zero".to_string()));
+    }
+    let amount = amount.round_dp(goal.currency.minor_units());
+    let note = payload.note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+
+    let contribution =
+        GoalRepository::add_contribution(&state.db, goal.id, amount, date, payload.transaction_id, note).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "add_goal_contribution",
+        "goal",
+        goal.id,
+        None,
+        Some(json!(contribution)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(contribution))
+}
+
+// ============================================================================
+// DELETE /goals/:goal_id/contributions/:contribution_id - Remove a contribution
+// (requires JWT auth)
+// ============================================================================
+pub async fn delete_contribution(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path((goal_id, contribution_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ContributionDeletedResponse>, AppError> {
+    let goal = find_goal(&state, &user, goal_id).await?.goal;
+
+    let contribution = GoalRepository::delete_contribution(&state.db, goal.id, contribution_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_goal_contribution",
+        "goal",
+        goal.id,
+        Some(json!(contribution)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ContributionDeletedResponse {
+        message: "Contribution deleted successfully".to_string(),
+    }))
+}
+
+/// A goal with its progress as of today
+pub fn goal_response(goal: GoalWithSaved) -> GoalResponse {
+    let progress = goal_progress(&goal.goal, goal.saved, Utc::now().date_naive());
+
+    GoalResponse {
+        goal: goal.goal,
+        progress,
+    }
+}
+
+async fn find_goal(state: &AppState, user: &User, goal_id: Uuid) -> Result<GoalWithSaved, AppError> {
+    GoalRepository::find_for_user(&state.db, user.id, goal_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn ensure_unique_name(
+    state: &AppState,
+    user: &User,
+    name: &str,
+    except_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    if GoalRepository::name_exists(&state.db, user.id, name, except_id).await? {
+        return Err(AppError::Conflict(format!("A goal named '{}' already exists", name)));
+    }
+
+    Ok(())
+}
+
+fn target_amount(amount: Decimal, currency: Currency) -> Result<Decimal, AppError> {
+    if amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError("Target amount must be positive".to_string()));
+    }
+
+    Ok(amount.round_dp(currency.minor_units()))
+}
+
+fn ensure_dates(start_date: NaiveDate, deadline: NaiveDate) -> Result<(), AppError> {
+    if deadline <= start_date {
+        return Err(AppError::ValidationError(
+            "Deadline must be after the start date".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Currency of one of the user's accounts that a goal is to be linked to
+async fn linked_account_currency(state: &AppState, user: &User, account_id: Uuid) -> Result<Currency, AppError> {
+    let account = AccountRepository::find_for_user(&state.db, user.id, account_id)
+        .await?
+        .ok_or_else(|| AppError::ValidationError("Unknown account".to_string()))?;
+
+    Ok(account.currency)
+}
+
+async fn ensure_own_tag(state: &AppState, user: &User, tag_id: Uuid) -> Result<(), AppError> {
+    let tag = TagRepository::find_for_ledger(&state.db, LedgerScope::personal(user.id), tag_id).await?;
+    if tag.is_none() {
+        return Err(AppError::ValidationError("Unknown tag".to_string()));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Goals Router
+// ============================================================================
+pub fn goal_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_goals).post(create_goal))
+        .route("/:goal_id", get(get_goal).patch(update_goal).delete(delete_goal))
+        .route("/:goal_id/contributions", post(add_contribution))
+        .route("/:goal_id/contributions/:contribution_id", delete(delete_contribution))
+}
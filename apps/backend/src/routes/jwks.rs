@@ -0,0 +1,15 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+use crate::{app_state::AppState, utils::jwt::PublicJwk};
+
+#[derive(Serialize)]
+pub struct JwksResponse {
+    keys: Vec<PublicJwk>,
+}
+
+/// Public keys for verifying Mintora access tokens (empty when signing with HS256)
+pub async fn jwks(State(state): State<AppState>) -> Json<JwksResponse> {
+    Json(JwksResponse {
+        keys: state.jwt_keys.jwks(),
+    })
+}
@@ -1,5 +1,21 @@
 pub mod health;
 pub mod auth;
+pub mod admin;
+pub mod oidc;
+pub mod jwks;
+pub mod exports;
+pub mod account;
+pub mod transactions;
+pub mod reports;
+pub mod accounts;
+pub mod debts;
+pub mod goals;
+pub mod agent;
+pub mod workspaces;
+pub mod attachments;
+pub mod receipt_drafts;
+pub mod anomalies;
+pub mod webhooks;
 
 use axum::{Router, routing::get};
 use crate::app_state::AppState;
@@ -7,5 +23,24 @@ use crate::app_state::AppState;
 pub fn create_router() -> Router<AppState> {
     Router::new()
         .route("/health", get(health::health_check))
+        .route("/.well-known/jwks.json", get(jwks::jwks))
         .nest("/auth", auth::auth_routes())
+        .nest("/auth/oidc", oidc::oidc_routes())
+        .nest("/admin", admin::admin_routes())
+        .nest("/account", account::account_routes())
+        .nest("/accounts", accounts::account_routes())
+        .nest(
+            "/transactions",
+            transactions::transaction_routes().merge(attachments::attachment_routes()),
+        )
+        .nest("/reports", reports::report_routes())
+        .nest("/debts", debts::debt_routes())
+        .nest("/goals", goals::goal_routes())
+        .nest("/agent", agent::agent_routes())
+        .nest("/workspaces", workspaces::workspace_routes())
+        .nest("/receipt-drafts", receipt_drafts::receipt_draft_routes())
+        .nest("/anomalies", anomalies::anomaly_routes())
+        .nest("/webhooks", webhooks::webhook_routes())
+        .route("/exports/:export_id/download", get(exports::download_export))
+        .route("/attachments/:attachment_id/download", get(attachments::download_attachment))
 }
@@ -0,0 +1,194 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::Rng;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    config::OidcProviderConfig,
+    db::{
+        models::User,
+        repositories::{UserIdentityRepository, UserRepository},
+    },
+    dto::{auth::{LoginResponse, UserResponse}, oidc::*},
+    error::AppError,
+    middleware::{ClientInfo, NoStore, NO_STORE},
+    routes::auth::{issue_session, restore_account},
+    utils::oidc::{authorization_url, discover, exchange_code, generate_pkce, random_token, IdTokenClaims},
+};
+
+/// How long a pending login (state + PKCE verifier) stays valid
+const LOGIN_STATE_EXPIRY_SECONDS: i64 = 600;
+
+// ============================================================================
+// GET /auth/oidc/:provider/authorize - Start authorization code + PKCE login
+// ============================================================================
+pub async fn authorize(
+    State(state): State<AppState>,
+    Path(provider_name): Path<String>,
+) -> Result<Json<OidcAuthorizeResponse>, AppError> {
+    let provider = find_provider(&state, &provider_name)?;
+    let metadata = discover(&state.http, provider).await?;
+
+    let login_state = random_token();
+    let nonce = random_token();
+    let pkce = generate_pkce();
+
+    UserIdentityRepository::create_login_state(
+        &state.db,
+        &login_state,
+        &provider.name,
+        &pkce.code_verifier,
+        &nonce,
+        LOGIN_STATE_EXPIRY_SECONDS,
+    )
+    .await?;
+
+    let authorization_url =
+        authorization_url(&metadata, provider, &login_state, &nonce, &pkce.code_challenge)?;
+
+    Ok(Json(OidcAuthorizeResponse {
+        authorization_url,
+        state: login_state,
+    }))
+}
+
+// ============================================================================
+// POST /auth/oidc/:provider/callback - Exchange code and issue Mintora tokens
+// ============================================================================
+pub async fn callback(
+    State(state): State<AppState>,
+    Path(provider_name): Path<String>,
+    client: ClientInfo,
+    Json(payload): Json<OidcCallbackRequest>,
+) -> Result<(NoStore, Json<LoginResponse>), AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let provider = find_provider(&state, &provider_name)?;
+
+    // State is single use and bound to the provider it was issued for
+    let login_state = UserIdentityRepository::take_login_state(&state.db, &payload.state)
+        .await?
+        .filter(|login_state| login_state.provider == provider.name)
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired login state".to_string()))?;
+
+    let metadata = discover(&state.http, provider).await?;
+    let claims = exchange_code(
+        &state.http,
+        &metadata,
+        provider,
+        &payload.code,
+        &login_state.code_verifier,
+        &login_state.nonce,
+    )
+    .await?;
+
+    let user = resolve_user(&state, provider, &claims).await?;
+
+    // Logging in during the deletion grace period cancels the deletion
+    let user = if user.is_pending_deletion() {
+        restore_account(&state, user, &client).await?
+    } else {
+        user
+    };
+
+    // Check if user is active
+    if !user.is_active() {
+        return Err(AppError::Forbidden);
+    }
+
+    let (access_token, refresh_token) = issue_session(
+        &state,
+        &user,
+        client.ip_address.clone(),
+        client.user_agent.clone(),
+    )
+    .await?;
+
+    Ok((
+        NO_STORE,
+        Json(LoginResponse {
+            user: UserResponse::from(user.to_safe_user()),
+            access_token,
+            refresh_token,
+        }),
+    ))
+}
+
+fn find_provider<'a>(state: &'a AppState, name: &str) -> Result<&'a OidcProviderConfig, AppError> {
+    state.config.oidc_provider(name).ok_or(AppError::NotFound)
+}
+
+/// Find the user linked to the identity, link by verified email, or create a new user
+async fn resolve_user(
+    state: &AppState,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+) -> Result<User, AppError> {
+    let email = claims.email.as_deref();
+
+    if let Some(identity) =
+        UserIdentityRepository::find_by_provider_subject(&state.db, &provider.name, &claims.sub).await?
+    {
+        UserIdentityRepository::update_last_login(&state.db, identity.id, email).await?;
+
+        return UserRepository::find_by_id_including_deleted(&state.db, identity.user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("User not found".to_string()));
+    }
+
+    // Only trust the provider's email for linking or sign-up once it has verified it
+    let email = email
+        .filter(|_| claims.email_verified.unwrap_or(false))
+        .ok_or_else(|| AppError::BadRequest("Provider did not return a verified email".to_string()))?;
+
+    let user = match UserRepository::find_by_email_for_login(&state.db, email).await? {
+        Some(user) => user,
+        None => {
+            let full_name = claims.name.clone().unwrap_or_else(|| email.to_string());
+            let username = available_username(state, email).await?;
+            UserRepository::create_without_password(&state.db, email, &username, &full_name).await?
+        }
+    };
+
+    UserIdentityRepository::create(&state.db, user.id, &provider.name, &claims.sub, Some(email)).await?;
+
+    Ok(user)
+}
+
+/// Derive a unique username from the email's local part
+async fn available_username(state: &AppState, email: &str) -> Result<String, AppError> {
+    let base: String = email
+        .split('@')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '.')
+        .take(40)
+        .collect();
+    let base = if base.len() < 3 { format!("user_{}", base) } else { base };
+
+    if !UserRepository::username_exists(&state.db, &base).await? {
+        return Ok(base);
+    }
+
+    loop {
+        let candidate = format!("{}_{:04x}", base, rand::thread_rng().r#gen::<u16>());
+        if !UserRepository::username_exists(&state.db, &candidate).await? {
+            return Ok(candidate);
+        }
+    }
+}
+
+// ============================================================================
+// OIDC Router
+// ============================================================================
+pub fn oidc_routes() -> Router<AppState> {
+    Router::new()
+        .route("/:provider/authorize", get(authorize))
+        .route("/:provider/callback", post(callback))
+}
@@ -0,0 +1,204 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{LedgerScope, ReceiptDraft, ReceiptDraftStatus, WorkspaceRole},
+        repositories::{AuditLogRepository, ReceiptDraftRepository, TransactionRepository},
+    },
+    dto::receipt_draft::*,
+    error::AppError,
+    middleware::{ClientInfo, Ledger},
+};
+
+// ============================================================================
+// GET /receipt-drafts - List receipt drafts (requires JWT auth)
+// Without filters this is the review queue: drafts OCR was not confident about
+// ============================================================================
+pub async fn list_drafts(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<ListReceiptDraftsQuery>,
+) -> Result<Json<Vec<ReceiptDraft>>, AppError> {
+    let status = match (query.status, query.transaction_id) {
+        (None, None) => Some(ReceiptDraftStatus::NeedsReview),
+        (status, _) => status,
+    };
+
+    let drafts = ReceiptDraftRepository::list_for_ledger(&state.db, ledger.scope, status, query.transaction_id).await?;
+
+    Ok(Json(drafts))
+}
+
+// ============================================================================
+// GET /receipt-drafts/:draft_id - View a receipt draft with its OCR text (requires JWT auth)
+// ============================================================================
+pub async fn get_draft(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Path(draft_id): Path<Uuid>,
+) -> Result<Json<ReceiptDraft>, AppError> {
+    let draft = find_draft(&state, ledger.scope, draft_id).await?;
+
+    Ok(Json(draft))
+}
+
+// ============================================================================
+// POST /receipt-drafts/:draft_id/confirm - Apply a draft to its transaction
+// (requires JWT auth)
+// Values given in the request replace what OCR read
+// ============================================================================
+pub async fn confirm_draft(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path(draft_id): Path<Uuid>,
+    Json(payload): Json<ConfirmReceiptDraftRequest>,
+) -> Result<Json<ConfirmReceiptDraftResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let draft = find_reviewable_draft(&state, ledger.scope, draft_id).await?;
+    let transaction = TransactionRepository::find_by_id(&state.db, ledger.scope, draft.transaction_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // Validate input
+    payload.validate()?;
+
+    let amount = payload
+        .amount
+        .or(draft.amount)
+        .ok_or_else(|| AppError::ValidationError("The receipt total could not be read; amount is required".to_string()))?
+        .round_dp(transaction.currency.minor_units());
+    if amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError("Amount must be positive".to_string()));
+    }
+    let transaction_date = payload
+        .transaction_date
+        .or(draft.transaction_date)
+        .ok_or_else(|| {
+            AppError::ValidationError("The receipt date could not be read; transaction_date is required".to_string())
+        })?;
+    let merchant_name = payload
+        .merchant_name
+        .as_deref()
+        .or(draft.merchant_name.as_deref())
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    if let Some(currency) = draft.currency
+        && payload.amount.is_none()
+        && currency != transaction.currency
+    {
+        return Err(AppError::ValidationError(format!(
+            "The receipt is in {} but the transaction is in {}; give the amount explicitly",
+            currency, transaction.currency
+        )));
+    }
+
+    // Split lines must keep adding up to the transaction amount
+    if amount != transaction.amount
+        && !TransactionRepository::list_splits(&state.db, transaction.id).await?.is_empty()
+    {
+        return Err(AppError::Conflict(
+            "The transaction is split; update or remove its splits before changing the amount".to_string(),
+        ));
+    }
+
+    let updated = ReceiptDraftRepository::confirm(
+        &state.db,
+        &draft,
+        ledger.user.id,
+        amount,
+        transaction_date,
+        merchant_name,
+    )
+    .await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "confirm_receipt_draft",
+        "transaction",
+        transaction.id,
+        Some(json!(transaction)),
+        Some(json!({ "transaction": updated, "receipt_draft_id": draft.id })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ConfirmReceiptDraftResponse {
+        message: "Receipt draft confirmed".to_string(),
+        transaction: updated,
+    }))
+}
+
+// ============================================================================
+// POST /receipt-drafts/:draft_id/dismiss - Discard a draft without changing its
+// transaction (requires JWT auth)
+// ============================================================================
+pub async fn dismiss_draft(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path(draft_id): Path<Uuid>,
+) -> Result<Json<ReceiptDraftDismissedResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let draft = find_reviewable_draft(&state, ledger.scope, draft_id).await?;
+
+    ReceiptDraftRepository::dismiss(&state.db, draft.id, ledger.user.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "dismiss_receipt_draft",
+        "transaction",
+        draft.transaction_id,
+        Some(json!(draft)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ReceiptDraftDismissedResponse {
+        message: "Receipt draft dismissed".to_string(),
+    }))
+}
+
+async fn find_draft(state: &AppState, scope: LedgerScope, draft_id: Uuid) -> Result<ReceiptDraft, AppError> {
+    ReceiptDraftRepository::find_for_ledger(&state.db, scope, draft_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// A draft OCR has finished with and nobody has confirmed or dismissed yet
+async fn find_reviewable_draft(state: &AppState, scope: LedgerScope, draft_id: Uuid) -> Result<ReceiptDraft, AppError> {
+    let draft = find_draft(state, scope, draft_id).await?;
+    if !draft.status.is_reviewable() {
+        return Err(AppError::Conflict(
+            "Receipt draft is still being read or has already been reviewed".to_string(),
+        ));
+    }
+
+    Ok(draft)
+}
+
+// ============================================================================
+// Receipt Drafts Router
+// ============================================================================
+pub fn receipt_draft_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_drafts))
+        .route("/:draft_id", get(get_draft))
+        .route("/:draft_id/confirm", post(confirm_draft))
+        .route("/:draft_id/dismiss", post(dismiss_draft))
+}
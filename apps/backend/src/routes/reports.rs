@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{Currency, TransactionType},
+        repositories::{ExchangeRateRepository, TransactionRepository},
+    },
+    dto::report::*,
+    error::AppError,
+    middleware::Ledger,
+    utils::exchange_rates::RateTable,
+};
+
+/// Buckets shown when no `from` is given
+const DEFAULT_TIMESERIES_BUCKETS: u32 = 12;
+const MAX_TIMESERIES_BUCKETS: i64 = 400;
+const DEFAULT_MOVING_AVERAGE_WINDOW: u32 = 3;
+const MAX_MOVING_AVERAGE_WINDOW: u32 = 24;
+
+// ============================================================================
+// GET /reports/summary - Income, expense and category totals in the base currency
+// (requires JWT auth)
+// Covers the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn summary(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<ReportSummaryQuery>,
+) -> Result<Json<ReportSummaryResponse>, AppError> {
+    let today = Utc::now().date_naive();
+    let from = query.from.unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+    let to = query.to.unwrap_or(today);
+
+    // Validate input
+    if from > to {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+
+    let base = ledger.user.base_currency;
+    let rows = TransactionRepository::summarize(&state.db, ledger.scope, from, to).await?;
+
+    let currencies: BTreeSet<Currency> = rows.iter().map(|row| row.currency).collect();
+    let rates = load_rates(&state, base, &currencies, from, to).await?;
+
+    let mut income = Decimal::ZERO;
+    let mut expense = Decimal::ZERO;
+    let mut transaction_count = 0;
+    let mut by_category: HashMap<(TransactionType, Option<Uuid>), CategorySummary> = HashMap::new();
+    // Ordered by currency, income before expense
+    let mut by_currency: BTreeMap<(Currency, bool), CurrencySummary> = BTreeMap::new();
+    let mut unconverted_currencies = BTreeSet::new();
+
+    for row in rows {
+        let converted = rates.convert(row.total, row.currency, base, row.transaction_date);
+
+        let currency = by_currency
+            .entry((row.currency, row.r#type == TransactionType::Expense))
+            .or_insert_with(|| CurrencySummary {
+                currency: row.currency,
+                r#type: row.r#type.clone(),
+                amount: Decimal::ZERO,
+                converted_amount: Some(Decimal::ZERO),
+                count: 0,
+            });
+        currency.amount += row.total;
+        currency.count += row.transaction_count;
+        currency.converted_amount = currency.converted_amount.zip(converted).map(|(sum, amount)| sum + amount);
+
+        let Some(converted) = converted else {
+            unconverted_currencies.insert(row.currency);
+            continue;
+        };
+
+        match row.r#type {
+            TransactionType::Income => income += converted,
+            TransactionType::Expense => expense += converted,
+            TransactionType::Transfer => continue,
+        }
+        transaction_count += row.transaction_count;
+
+        let category = by_category
+            .entry((row.r#type.clone(), row.category_id))
+            .or_insert_with(|| CategorySummary {
+                category_id: row.category_id,
+                category_name: row.category_name.clone(),
+                r#type: row.r#type.clone(),
+                amount: Decimal::ZERO,
+                count: 0,
+            });
+        category.amount += converted;
+        category.count += row.count;
+    }
+
+    let mut by_category: Vec<CategorySummary> = by_category.into_values().collect();
+    by_category.sort_by_key(|category| std::cmp::Reverse(category.amount));
+
+    Ok(Json(ReportSummaryResponse {
+        from,
+        to,
+        base_currency: base,
+        income,
+        expense,
+        net: income - expense,
+        transaction_count,
+        by_category,
+        by_currency: by_currency.into_values().collect(),
+        unconverted_currencies: unconverted_currencies.into_iter().collect(),
+    }))
+}
+
+// ============================================================================
+// GET /reports/timeseries - Income and expense over time in the base currency, with
+// moving averages and period-over-period change (requires JWT auth)
+// Covers the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn timeseries(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<ReportTimeseriesQuery>,
+) -> Result<Json<ReportTimeseriesResponse>, AppError> {
+    let interval = query.interval;
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = interval.bucket_start(
+        query
+            .from
+            .unwrap_or_else(|| interval.buckets_before(interval.bucket_start(to), DEFAULT_TIMESERIES_BUCKETS - 1)),
+    );
+    let window = query.window.unwrap_or(DEFAULT_MOVING_AVERAGE_WINDOW);
+
+    // Validate input
+    if from > to {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+    if interval.bucket_count(from, to) > MAX_TIMESERIES_BUCKETS {
+        return Err(AppError::ValidationError(format!(
+            "A time series can have at most {} buckets; use a longer interval or a shorter range",
+            MAX_TIMESERIES_BUCKETS
+        )));
+    }
+    if !(1..=MAX_MOVING_AVERAGE_WINDOW).contains(&window) {
+        return Err(AppError::ValidationError(format!(
+            "'window' must be between 1 and {}",
+            MAX_MOVING_AVERAGE_WINDOW
+        )));
+    }
+
+    // Earlier buckets give the first reported ones a full moving average and a change
+    let history_from = interval.buckets_before(from, window.max(2) - 1);
+    let base = ledger.user.base_currency;
+
+    let used = TransactionRepository::list_foreign_currency_dates(&state.db, ledger.scope, base, history_from, to)
+        .await?;
+    let currencies: BTreeSet<Currency> = used.iter().map(|(currency, _)| *currency).collect();
+    let rates = load_rates(&state, base, &currencies, history_from, to).await?;
+
+    let mut known_rates = Vec::with_capacity(used.len());
+    let mut unconverted_currencies = BTreeSet::new();
+    for (currency, date) in used {
+        match rates.rate(currency, base, date) {
+            Some(rate) => known_rates.push((currency, date, rate)),
+            None if date >= from => {
+                unconverted_currencies.insert(currency);
+            }
+            None => {}
+        }
+    }
+
+    let rows = TransactionRepository::timeseries(
+        &state.db,
+        ledger.scope,
+        interval,
+        query.group_by,
+        history_from,
+        from,
+        to,
+        window,
+        base,
+        &known_rates,
+    )
+    .await?;
+
+    let mut series: Vec<TimeseriesSeries> = Vec::new();
+    for row in rows {
+        let point = TimeseriesPoint {
+            bucket_start: row.bucket_start,
+            income: row.income,
+            expense: row.expense,
+            net: row.net,
+            transaction_count: row.transaction_count,
+            income_moving_average: row.income_moving_average,
+            expense_moving_average: row.expense_moving_average,
+            income_change_pct: row.income_change_pct,
+            expense_change_pct: row.expense_change_pct,
+        };
+
+        // Rows arrive ordered by series, then bucket
+        match series.last_mut() {
+            Some(last) if last.id == row.series_id && last.name == row.series_name => last.points.push(point),
+            _ => series.push(TimeseriesSeries {
+                id: row.series_id,
+                name: row.series_name,
+                points: vec![point],
+            }),
+        }
+    }
+
+    Ok(Json(ReportTimeseriesResponse {
+        from,
+        to,
+        interval,
+        group_by: query.group_by,
+        window,
+        base_currency: base,
+        series,
+        unconverted_currencies: unconverted_currencies.into_iter().collect(),
+    }))
+}
+
+/// Rates needed to convert `currencies` into `base` for transactions between `from` and
+/// `to`, reaching back far enough for the first day's latest rate
+async fn load_rates(
+    state: &AppState,
+    base: Currency,
+    currencies: &BTreeSet<Currency>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<RateTable, AppError> {
+    let max_age_days = state.config.exchange_rates.max_age_days;
+
+    if currencies.iter().all(|currency| *currency == base) {
+        return Ok(RateTable::new(max_age_days));
+    }
+
+    let wanted: Vec<Currency> = currencies.iter().copied().chain([base]).collect();
+    let rates = ExchangeRateRepository::list_for_currencies(
+        &state.db,
+        &wanted,
+        from - Duration::days(max_age_days),
+        to,
+    )
+    .await?;
+
+    Ok(RateTable::from_rates(&rates, max_age_days))
+}
+
+// ============================================================================
+// Reports Router
+// ============================================================================
+pub fn report_routes() -> Router<AppState> {
+    Router::new()
+        .route("/summary", get(summary))
+        .route("/timeseries", get(timeseries))
+}
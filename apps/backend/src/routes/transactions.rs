@@ -0,0 +1,901 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use futures_util::{stream, TryStreamExt};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{
+            LedgerScope, NewTransaction, NewTransactionSplit, Transaction, TransactionFilter,
+            TransactionSource, TransactionSplit, TransactionType, WorkspaceRole,
+        },
+        repositories::{
+            AccountRepository, AuditLogRepository, CategoryRepository, PaymentMethodRepository,
+            TransactionRepository,
+        },
+    },
+    dto::transaction::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo, Ledger},
+    utils::{
+        csv_import::{duplicate_key, parse_csv, DuplicateKey, ImportOptions, NameResolver},
+        statement_import::{parse_statement, StatementFormat, RECONCILE_WINDOW_DAYS},
+        transaction_duplicates::find_duplicate_pairs,
+        transaction_search::search_terms,
+        transaction_splits::validate_splits,
+        transaction_export::ExportEncoder,
+    },
+};
+
+/// Largest accepted CSV or statement upload
+const MAX_IMPORT_BYTES: usize = 10 * 1024 * 1024;
+/// Most data rows (or statement entries) accepted in one import
+const MAX_IMPORT_ROWS: usize = 10_000;
+/// Encoded export output is sent to the client in chunks of roughly this size
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+/// Chunks buffered between the database reader and a slow client
+const EXPORT_CHANNEL_CAPACITY: usize = 4;
+const DEFAULT_SEARCH_PER_PAGE: i64 = 50;
+const MAX_SEARCH_PER_PAGE: i64 = 200;
+/// Default and longest date range scanned for duplicates
+const DEFAULT_DUPLICATE_DAYS: i64 = 90;
+const MAX_DUPLICATE_DAYS: i64 = 366;
+
+// ============================================================================
+// POST /transactions/import - Import transactions from CSV (requires JWT auth)
+// Multipart fields: `file` (the CSV) and `options` (JSON column mapping and formats)
+// Imports into the ledger selected by `X-Workspace-Id` (editor role required)
+// ============================================================================
+pub async fn import_transactions(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Query(query): Query<ImportQuery>,
+    multipart: Multipart,
+) -> Result<Json<ImportReport>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let user = &ledger.user;
+    let (file, options) = read_import_upload(multipart).await?;
+
+    let rows = parse_csv(&file, &options)?;
+    if rows.is_empty() {
+        return Err(AppError::BadRequest("CSV contains no data rows".to_string()));
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::BadRequest(format!(
+            "CSV has {} rows; at most {} can be imported at once",
+            rows.len(),
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    // Resolve categories by name or alias, payment methods by name (own entries win)
+    let categories = CategoryRepository::list_for_ledger(&state.db, ledger.scope).await?;
+    let aliases = CategoryRepository::list_aliases_for_ledger(&state.db, ledger.scope).await?;
+    let payment_methods = PaymentMethodRepository::list_available_for_user(&state.db, user.id).await?;
+
+    let category_resolver = NameResolver::new(
+        categories.iter().map(|c| (c.id, c.name.as_str())),
+        aliases.iter().map(|a| (a.category_id, a.alias.as_str())),
+    );
+    let payment_method_resolver = NameResolver::new(
+        payment_methods.iter().map(|p| (p.id, p.name.as_str())),
+        [],
+    );
+
+    // Existing transactions in the file's date range, for duplicate detection. Each one
+    // accounts for a single matching row, so rows repeated within the file are all kept
+    // unless the ledger already holds as many
+    let dates = rows
+        .iter()
+        .filter_map(|row| row.result.as_ref().ok())
+        .map(|parsed| parsed.transaction_date);
+    let mut existing: HashMap<DuplicateKey, usize> = HashMap::new();
+    if let (Some(from), Some(to)) = (dates.clone().min(), dates.max()) {
+        let keys = TransactionRepository::list_duplicate_keys(&state.db, ledger.scope, from, to).await?;
+        for (date, r#type, currency, amount, merchant) in keys {
+            *existing
+                .entry(duplicate_key(date, r#type, currency, amount, merchant.as_deref()))
+                .or_default() += 1;
+        }
+    }
+
+    let batch_id = Uuid::new_v4();
+    let mut reports = Vec::with_capacity(rows.len());
+    let mut to_insert = Vec::new();
+
+    for row in rows {
+        let parsed = match row.result {
+            Ok(parsed) => parsed,
+            Err(errors) => {
+                reports.push(ImportRowReport {
+                    line: row.line,
+                    status: ImportRowStatus::Invalid,
+                    errors,
+                    warnings: vec![],
+                    transaction: None,
+                });
+                continue;
+            }
+        };
+
+        let mut warnings = Vec::new();
+        let mut resolve = |resolver: &NameResolver, name: &Option<String>, kind: &str| {
+            let name = name.as_deref()?;
+            let id = resolver.resolve(name);
+            if id.is_none() {
+                warnings.push(format!("Unknown {} '{}'; left empty", kind, name));
+            }
+            id
+        };
+        let category_id = resolve(&category_resolver, &parsed.category, "category");
+        let payment_method_id =
+            resolve(&payment_method_resolver, &parsed.payment_method, "payment method");
+
+        let key = duplicate_key(
+            parsed.transaction_date,
+            parsed.r#type.clone(),
+            parsed.currency,
+            parsed.amount,
+            parsed.merchant_name.as_deref(),
+        );
+        let is_duplicate = match existing.get_mut(&key) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        };
+        if is_duplicate && !query.skip_duplicates {
+            warnings.push("Matches an existing transaction; imported anyway".to_string());
+        }
+        let is_new = !is_duplicate || !query.skip_duplicates;
+
+        let transaction = ImportedTransaction {
+            id: None,
+            r#type: parsed.r#type,
+            amount: parsed.amount,
+            currency: parsed.currency,
+            transaction_date: parsed.transaction_date,
+            category_id,
+            payment_method_id,
+            merchant_name: parsed.merchant_name,
+            description: parsed.description,
+            location: parsed.location,
+        };
+
+        if is_new {
+            to_insert.push((reports.len(), new_transaction(&transaction, batch_id, row.line)));
+        }
+
+        reports.push(ImportRowReport {
+            line: row.line,
+            status: if is_new { ImportRowStatus::Valid } else { ImportRowStatus::Duplicate },
+            errors: vec![],
+            warnings,
+            transaction: Some(transaction),
+        });
+    }
+
+    let mut imported_rows = 0;
+
+    if !query.dry_run && !to_insert.is_empty() {
+        let transactions: Vec<NewTransaction> =
+            to_insert.iter().map(|(_, transaction)| transaction.clone()).collect();
+        let ids = TransactionRepository::create_batch(&state.db, ledger.scope, &transactions).await?;
+
+        for ((index, _), id) in to_insert.iter().zip(ids) {
+            let report = &mut reports[*index];
+            report.status = ImportRowStatus::Imported;
+            if let Some(transaction) = report.transaction.as_mut() {
+                transaction.id = Some(id);
+            }
+        }
+        imported_rows = to_insert.len();
+
+        AuditLogRepository::create(
+            &state.db,
+            Some(user.id),
+            "import_transactions",
+            "transaction_import",
+            batch_id,
+            None,
+            Some(json!({ "source": "csv", "imported_rows": imported_rows })),
+            client.ip_address.as_deref(),
+            client.user_agent.as_deref(),
+        )
+        .await?;
+    }
+
+    let count = |status| reports.iter().filter(|r| r.status == status).count();
+
+    Ok(Json(ImportReport {
+        dry_run: query.dry_run,
+        batch_id: (imported_rows > 0).then_some(batch_id),
+        total_rows: reports.len(),
+        valid_rows: count(ImportRowStatus::Valid) + count(ImportRowStatus::Imported),
+        invalid_rows: count(ImportRowStatus::Invalid),
+        duplicate_rows: count(ImportRowStatus::Duplicate),
+        imported_rows,
+        rows: reports,
+    }))
+}
+
+/// Read the `file` and `options` fields of an import upload
+async fn read_import_upload(mut multipart: Multipart) -> Result<(Vec<u8>, ImportOptions), AppError> {
+    let mut file = None;
+    let mut options = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name() {
+            Some("file") => {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+                file = Some(bytes.to_vec());
+            }
+            Some("options") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read options: {}", e)))?;
+                let parsed = serde_json::from_str::<ImportOptions>(&text)
+                    .map_err(|e| AppError::ValidationError(format!("Invalid import options: {}", e)))?;
+                options = Some(parsed);
+            }
+            _ => {}
+        }
+    }
+
+    let file = file.ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+    let options = options.ok_or_else(|| AppError::BadRequest("Missing 'options' field".to_string()))?;
+
+    Ok((file, options))
+}
+
+fn new_transaction(transaction: &ImportedTransaction, batch_id: Uuid, line: usize) -> NewTransaction {
+    NewTransaction {
+        r#type: transaction.r#type.clone(),
+        amount: transaction.amount,
+        currency: transaction.currency,
+        category_id: transaction.category_id,
+        payment_method_id: transaction.payment_method_id,
+        account_id: None,
+        transfer_account_id: None,
+        merchant_name: transaction.merchant_name.clone(),
+        location: transaction.location.clone(),
+        description: transaction.description.clone(),
+        transaction_date: transaction.transaction_date,
+        source: TransactionSource::Web,
+        client_id: None,
+        source_message_id: None,
+        metadata: Some(json!({ "import": { "format": "csv", "batch_id": batch_id, "line": line } })),
+    }
+}
+
+// ============================================================================
+// POST /transactions/import/statement - Import a bank statement (requires JWT auth)
+// Multipart fields: `file` (OFX/QFX, CAMT.053 or MT940) and optional `format`
+// Entries already imported are skipped; entries matching a WhatsApp-entered
+// transaction are linked to it rather than imported again
+// Imports into the ledger selected by `X-Workspace-Id` (editor role required)
+// ============================================================================
+pub async fn import_statement(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Query(query): Query<ImportQuery>,
+    multipart: Multipart,
+) -> Result<Json<StatementImportReport>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let user = &ledger.user;
+    let (file, format) = read_statement_upload(multipart).await?;
+
+    let (format, entries) = parse_statement(&file, format)?;
+    if entries.is_empty() {
+        return Err(AppError::BadRequest("Statement contains no booked entries".to_string()));
+    }
+    if entries.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::BadRequest(format!(
+            "Statement has {} entries; at most {} can be imported at once",
+            entries.len(),
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    // Entries imported before, by their account and statement transaction id
+    let statement_keys: Vec<(String, String)> = entries.iter().map(|e| e.dedup_key()).collect();
+    let mut seen: HashSet<(String, String)> =
+        TransactionRepository::list_imported_statement_entries(&state.db, ledger.scope, &statement_keys)
+            .await?
+            .into_iter()
+            .collect();
+
+    // WhatsApp-entered transactions the entries may correspond to
+    let window = Duration::days(RECONCILE_WINDOW_DAYS);
+    let dates = entries.iter().map(|e| e.booking_date);
+    let candidates = match (dates.clone().min(), dates.max()) {
+        (Some(from), Some(to)) => {
+            TransactionRepository::list_reconciliation_candidates(
+                &state.db,
+                ledger.scope,
+                from - window,
+                to + window,
+            )
+            .await?
+        }
+        _ => vec![],
+    };
+
+    let batch_id = Uuid::new_v4();
+    let mut claimed = HashSet::new();
+    let mut reports = Vec::with_capacity(entries.len());
+    let mut to_insert = Vec::new();
+    let mut links = Vec::new();
+
+    for entry in &entries {
+        let (status, transaction_id) = if !seen.insert(entry.dedup_key()) {
+            (StatementRowStatus::Duplicate, None)
+        } else if let Some(matched) = entry.find_match(&candidates, &claimed) {
+            claimed.insert(matched);
+            links.push((matched, entry.metadata(format, batch_id)));
+            (StatementRowStatus::Matched, Some(matched))
+        } else {
+            to_insert.push((reports.len(), entry.to_new_transaction(format, batch_id)));
+            (StatementRowStatus::New, None)
+        };
+
+        reports.push(StatementRowReport {
+            statement_transaction_id: entry.transaction_id.clone(),
+            account: entry.account.clone(),
+            status,
+            transaction_id,
+            r#type: entry.r#type.clone(),
+            amount: entry.amount,
+            currency: entry.currency,
+            transaction_date: entry.booking_date,
+            merchant_name: entry.counterparty.clone(),
+            description: entry.description.clone(),
+        });
+    }
+
+    let duplicate_rows = reports
+        .iter()
+        .filter(|r| r.status == StatementRowStatus::Duplicate)
+        .count();
+    let new_rows = to_insert.len();
+    let matched_rows = links.len();
+    let mut imported_rows = 0;
+    let mut reconciled_rows = 0;
+
+    if !query.dry_run && (new_rows > 0 || matched_rows > 0) {
+        let transactions: Vec<NewTransaction> =
+            to_insert.iter().map(|(_, transaction)| transaction.clone()).collect();
+        let ids =
+            TransactionRepository::import_statement(&state.db, ledger.scope, &transactions, &links).await?;
+
+        for ((index, _), id) in to_insert.iter().zip(ids) {
+            reports[*index].status = StatementRowStatus::Imported;
+            reports[*index].transaction_id = Some(id);
+        }
+        for report in reports.iter_mut().filter(|r| r.status == StatementRowStatus::Matched) {
+            report.status = StatementRowStatus::Reconciled;
+        }
+        imported_rows = new_rows;
+        reconciled_rows = matched_rows;
+
+        AuditLogRepository::create(
+            &state.db,
+            Some(user.id),
+            "import_transactions",
+            "transaction_import",
+            batch_id,
+            None,
+            Some(json!({
+                "source": format.as_str(),
+                "imported_rows": imported_rows,
+                "reconciled_rows": reconciled_rows,
+            })),
+            client.ip_address.as_deref(),
+            client.user_agent.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(StatementImportReport {
+        dry_run: query.dry_run,
+        format,
+        batch_id: (imported_rows + reconciled_rows > 0).then_some(batch_id),
+        total_rows: reports.len(),
+        new_rows,
+        matched_rows,
+        duplicate_rows,
+        imported_rows,
+        reconciled_rows,
+        rows: reports,
+    }))
+}
+
+/// Read the `file` and optional `format` fields of a statement upload
+async fn read_statement_upload(
+    mut multipart: Multipart,
+) -> Result<(Vec<u8>, Option<StatementFormat>), AppError> {
+    let mut file = None;
+    let mut format = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name() {
+            Some("file") => {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+                file = Some(bytes.to_vec());
+            }
+            Some("format") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read format: {}", e)))?;
+                if !text.trim().is_empty() {
+                    format = Some(text.parse::<StatementFormat>()?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let file = file.ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+
+    Ok((file, format))
+}
+
+// ============================================================================
+// GET /transactions/search - Full-text search over transactions (requires JWT auth)
+// Matches description, merchant, location and tag names, best matches first; takes the
+// same filters as the export. Searches the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn search_transactions(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<SearchTransactionsQuery>,
+) -> Result<Json<SearchTransactionsResponse>, AppError> {
+    // Validate input
+    let terms = search_terms(&query.q)?;
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_SEARCH_PER_PAGE).clamp(1, MAX_SEARCH_PER_PAGE);
+
+    let (results, total) = TransactionRepository::search(
+        &state.db,
+        ledger.scope,
+        &terms,
+        &query.filter(),
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(SearchTransactionsResponse {
+        results,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// GET /transactions/export - Download transactions as CSV, XLSX or OFX (requires JWT auth)
+// Rows are streamed from the database; category, payment method and tags are names
+// Exports the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn export_transactions(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<TransactionExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    // Validate input
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+
+    let format = query.format;
+    let filter = query.filter();
+    let generated_at = Utc::now().naive_utc();
+    let encoder = ExportEncoder::new(format, ledger.user.id, &filter, generated_at)?;
+
+    let (sender, mut receiver) = mpsc::channel::<Result<Vec<u8>, io::Error>>(EXPORT_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        if let Err(e) = write_export(&state.db, ledger.scope, &filter, encoder, &sender).await {
+            tracing::error!("Transaction export for user {} failed: {:?}", ledger.user.id, e);
+            // Abort the body so the client sees a failed download, not a truncated file
+            let _ = sender.send(Err(io::Error::other("transaction export failed"))).await;
+        }
+    });
+
+    let body = Body::from_stream(stream::poll_fn(move |cx| receiver.poll_recv(cx)));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"mintora-transactions-{}.{}\"",
+                    generated_at.format("%Y%m%d"),
+                    format.extension()
+                ),
+            ),
+        ],
+        body,
+    ))
+}
+
+/// Encode matching rows as they are fetched and pass them to the response body in chunks
+async fn write_export(
+    pool: &PgPool,
+    scope: LedgerScope,
+    filter: &TransactionFilter,
+    mut encoder: ExportEncoder,
+    sender: &mpsc::Sender<Result<Vec<u8>, io::Error>>,
+) -> Result<(), AppError> {
+    let mut rows = TransactionRepository::stream_export_rows(pool, scope, filter);
+    let mut chunk = Vec::with_capacity(EXPORT_CHUNK_BYTES);
+
+    while let Some(row) = rows.try_next().await? {
+        encoder.write_row(&row, &mut chunk)?;
+
+        if chunk.len() >= EXPORT_CHUNK_BYTES {
+            let full = std::mem::replace(&mut chunk, Vec::with_capacity(EXPORT_CHUNK_BYTES));
+            if sender.send(Ok(full)).await.is_err() {
+                // The client disconnected
+                return Ok(());
+            }
+        }
+    }
+
+    // Assembling an XLSX workbook is CPU-bound
+    let chunk = tokio::task::spawn_blocking(move || {
+        encoder.finish(&mut chunk)?;
+        Ok::<_, AppError>(chunk)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))??;
+
+    let _ = sender.send(Ok(chunk)).await;
+
+    Ok(())
+}
+
+// ============================================================================
+// POST /transactions/transfers - Move money between two accounts (requires JWT auth)
+// Recorded as one `transfer` transaction, so both sides change together
+// ============================================================================
+pub async fn create_transfer(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateTransferRequest>,
+) -> Result<Json<Transaction>, AppError> {
+    // Validate input
+    payload.validate()?;
+    if payload.amount <= Decimal::ZERO {
+        return Err(AppError::ValidationError("Amount must be positive".to_string()));
+    }
+    if payload.from_account_id == payload.to_account_id {
+        return Err(AppError::ValidationError("Cannot transfer to the same account".to_string()));
+    }
+
+    let from = AccountRepository::find_for_user(&state.db, user.id, payload.from_account_id)
+        .await?
+        .ok_or_else(|| AppError::ValidationError("Unknown source account".to_string()))?;
+    let to = AccountRepository::find_for_user(&state.db, user.id, payload.to_account_id)
+        .await?
+        .ok_or_else(|| AppError::ValidationError("Unknown destination account".to_string()))?;
+
+    if from.is_archived || to.is_archived {
+        return Err(AppError::BadRequest("Archived accounts cannot be used in transfers".to_string()));
+    }
+    if from.currency != to.currency {
+        return Err(AppError::BadRequest(format!(
+            "Accounts have different currencies ({} and {})",
+            from.currency, to.currency
+        )));
+    }
+
+    let transfer = NewTransaction {
+        r#type: TransactionType::Transfer,
+        amount: payload.amount.round_dp(from.currency.minor_units()),
+        currency: from.currency,
+        category_id: None,
+        payment_method_id: None,
+        account_id: Some(from.id),
+        transfer_account_id: Some(to.id),
+        merchant_name: None,
+        location: None,
+        description: payload
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+        transaction_date: payload.transaction_date.unwrap_or_else(|| Utc::now().date_naive()),
+        source: TransactionSource::Web,
+        client_id: None,
+        source_message_id: None,
+        metadata: None,
+    };
+    let transaction =
+        TransactionRepository::create(&state.db, LedgerScope::personal(user.id), &transfer).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "create_transfer",
+        "transaction",
+        transaction.id,
+        None,
+        Some(json!({
+            "from_account_id": from.id,
+            "to_account_id": to.id,
+            "amount": transaction.amount,
+            "currency": transaction.currency,
+        })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(transaction))
+}
+
+// ============================================================================
+// GET /transactions/duplicates - Suspected duplicate pairs (requires JWT auth)
+// Same type and currency, amounts within 1%, at most a day apart and similar merchants,
+// or recorded from the same chat message. Scans the ledger selected by `X-Workspace-Id`
+// ============================================================================
+pub async fn list_duplicates(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Query(query): Query<ListDuplicatesQuery>,
+) -> Result<Json<ListDuplicatesResponse>, AppError> {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_DUPLICATE_DAYS - 1));
+
+    // Validate input
+    if from > to {
+        return Err(AppError::ValidationError("'from' must not be after 'to'".to_string()));
+    }
+    if (to - from).num_days() >= MAX_DUPLICATE_DAYS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} days can be scanned at once",
+            MAX_DUPLICATE_DAYS
+        )));
+    }
+
+    let transactions = TransactionRepository::list_in_range(&state.db, ledger.scope, from, to).await?;
+    let pairs = find_duplicate_pairs(&transactions)
+        .into_iter()
+        .map(|pair| DuplicatePairResponse {
+            transaction: pair.keep.clone(),
+            duplicate: pair.duplicate.clone(),
+            score: pair.score,
+            same_message: pair.same_message,
+        })
+        .collect();
+
+    Ok(Json(ListDuplicatesResponse { from, to, pairs }))
+}
+
+// ============================================================================
+// POST /transactions/duplicates/merge - Merge a duplicate into another transaction
+// (requires JWT auth)
+// Tags, conversation links, attachments, debt entries and goal contributions move to the
+// one kept; the duplicate is soft-deleted. A split duplicate can only be merged into an
+// unsplit transaction of the same amount. Both must be in the ledger selected by
+// `X-Workspace-Id` (editor role)
+// ============================================================================
+pub async fn merge_duplicates(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Json(payload): Json<MergeDuplicateRequest>,
+) -> Result<Json<MergeDuplicateResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+
+    // Validate input
+    if payload.keep_id == payload.duplicate_id {
+        return Err(AppError::ValidationError("Cannot merge a transaction into itself".to_string()));
+    }
+
+    let keep = find_transaction(&state, ledger.scope, payload.keep_id).await?;
+    let duplicate = find_transaction(&state, ledger.scope, payload.duplicate_id).await?;
+    if keep.r#type != duplicate.r#type {
+        return Err(AppError::BadRequest("Only transactions of the same type can be merged".to_string()));
+    }
+    if keep.currency != duplicate.currency {
+        return Err(AppError::BadRequest(format!(
+            "Transactions have different currencies ({} and {})",
+            keep.currency, duplicate.currency
+        )));
+    }
+
+    // The duplicate's splits add up to its own amount, so they only fit an unsplit
+    // transaction of the same amount
+    if !TransactionRepository::list_splits(&state.db, duplicate.id).await?.is_empty() {
+        if !TransactionRepository::list_splits(&state.db, keep.id).await?.is_empty() {
+            return Err(AppError::BadRequest(
+                "Both transactions are split; remove the split of one of them first".to_string(),
+            ));
+        }
+        if keep.amount != duplicate.amount {
+            return Err(AppError::BadRequest(
+                "The duplicate is split and its amount differs; remove its split first".to_string(),
+            ));
+        }
+    }
+
+    let transaction = TransactionRepository::merge(&state.db, ledger.user.id, keep.id, &duplicate).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "merge_transactions",
+        "transaction",
+        transaction.id,
+        Some(json!({ "duplicate": duplicate })),
+        Some(json!({ "transaction": transaction })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(MergeDuplicateResponse {
+        message: "Transactions merged".to_string(),
+        transaction,
+    }))
+}
+
+// ============================================================================
+// GET /transactions/:transaction_id/splits - Category lines of a transaction
+// (requires JWT auth)
+// ============================================================================
+pub async fn get_splits(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<Json<TransactionSplitsResponse>, AppError> {
+    let transaction = find_transaction(&state, ledger.scope, transaction_id).await?;
+    let splits = TransactionRepository::list_splits(&state.db, transaction.id).await?;
+
+    Ok(Json(splits_response(&transaction, splits)))
+}
+
+// ============================================================================
+// PUT /transactions/:transaction_id/splits - Split a transaction across categories
+// (requires JWT auth)
+// Replaces any previous lines, which must add up to the transaction amount; an empty
+// list removes the split
+// ============================================================================
+pub async fn update_splits(
+    State(state): State<AppState>,
+    ledger: Ledger,
+    client: ClientInfo,
+    Path(transaction_id): Path<Uuid>,
+    Json(payload): Json<UpdateSplitsRequest>,
+) -> Result<Json<TransactionSplitsResponse>, AppError> {
+    ledger.require(WorkspaceRole::Editor)?;
+    let transaction = find_transaction(&state, ledger.scope, transaction_id).await?;
+
+    // Validate input
+    if !payload.lines.is_empty() {
+        let categories = CategoryRepository::list_for_ledger(&state.db, ledger.scope).await?;
+        validate_splits(&transaction, &payload.lines, &categories)?;
+    }
+
+    let lines: Vec<NewTransactionSplit> = payload
+        .lines
+        .into_iter()
+        .map(|line| NewTransactionSplit {
+            description: line
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(str::to_string),
+            ..line
+        })
+        .collect();
+
+    let previous = TransactionRepository::list_splits(&state.db, transaction.id).await?;
+    let splits = TransactionRepository::replace_splits(&state.db, transaction.id, &lines).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(ledger.user.id),
+        "update_transaction_splits",
+        "transaction",
+        transaction.id,
+        Some(json!({ "splits": previous })),
+        Some(json!({ "splits": splits })),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(splits_response(&transaction, splits)))
+}
+
+async fn find_transaction(
+    state: &AppState,
+    scope: LedgerScope,
+    transaction_id: Uuid,
+) -> Result<Transaction, AppError> {
+    TransactionRepository::find_by_id(&state.db, scope, transaction_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+fn splits_response(transaction: &Transaction, splits: Vec<TransactionSplit>) -> TransactionSplitsResponse {
+    TransactionSplitsResponse {
+        transaction_id: transaction.id,
+        amount: transaction.amount,
+        currency: transaction.currency,
+        splits,
+    }
+}
+
+// ============================================================================
+// Transaction Router
+// ============================================================================
+pub fn transaction_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/import",
+            post(import_transactions).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .route(
+            "/import/statement",
+            post(import_statement).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .route("/export", get(export_transactions))
+        .route("/search", get(search_transactions))
+        .route("/transfers", post(create_transfer))
+        .route("/duplicates", get(list_duplicates))
+        .route("/duplicates/merge", post(merge_duplicates))
+        .route("/:transaction_id/splits", get(get_splits).put(update_splits))
+}
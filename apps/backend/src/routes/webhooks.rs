@@ -0,0 +1,252 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{User, WebhookDelivery, WebhookEndpoint, WebhookEvent},
+        repositories::{AuditLogRepository, WebhookRepository},
+    },
+    dto::webhook::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo, NoStore, NO_STORE},
+    utils::webhooks::{generate_secret, validate_url},
+};
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
+
+// ============================================================================
+// GET /webhooks - List webhook endpoints (requires JWT auth)
+// ============================================================================
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<Json<Vec<WebhookEndpoint>>, AppError> {
+    let endpoints = WebhookRepository::list_endpoints(&state.db, user.id).await?;
+
+    Ok(Json(endpoints))
+}
+
+// ============================================================================
+// POST /webhooks - Register an endpoint for events (requires JWT auth)
+// Deliveries are signed with the returned secret, which is not shown again
+// ============================================================================
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(NoStore, Json<CreateWebhookResponse>), AppError> {
+    // Validate input
+    payload.validate()?;
+    let url = payload.url.trim();
+    validate_url(url, state.config.webhooks.allow_http).await?;
+
+    let secret = generate_secret();
+    let endpoint = WebhookRepository::create_endpoint(
+        &state.db,
+        user.id,
+        url,
+        non_empty(payload.description.as_deref()),
+        &secret,
+        &event_names(&payload.events),
+    )
+    .await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "create_webhook",
+        "webhook",
+        endpoint.id,
+        None,
+        Some(json!(endpoint)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok((NO_STORE, Json(CreateWebhookResponse { endpoint, secret })))
+}
+
+// ============================================================================
+// GET /webhooks/:webhook_id - View an endpoint (requires JWT auth)
+// ============================================================================
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<WebhookEndpoint>, AppError> {
+    let endpoint = find_endpoint(&state, &user, webhook_id).await?;
+
+    Ok(Json(endpoint))
+}
+
+// ============================================================================
+// PATCH /webhooks/:webhook_id - Change the URL, events or description, or pause an
+// endpoint (requires JWT auth)
+// ============================================================================
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(webhook_id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookEndpoint>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    let existing = find_endpoint(&state, &user, webhook_id).await?;
+    let mut endpoint = existing.clone();
+
+    if let Some(url) = payload.url.as_deref().map(str::trim) {
+        validate_url(url, state.config.webhooks.allow_http).await?;
+        endpoint.url = url.to_string();
+    }
+    if let Some(events) = &payload.events {
+        endpoint.events = event_names(events);
+    }
+    if let Some(description) = payload.description.as_deref() {
+        endpoint.description = non_empty(Some(description)).map(str::to_string);
+    }
+    if let Some(is_active) = payload.is_active {
+        endpoint.is_active = is_active;
+    }
+
+    let endpoint = WebhookRepository::update_endpoint(&state.db, &endpoint).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "update_webhook",
+        "webhook",
+        endpoint.id,
+        Some(json!(existing)),
+        Some(json!(endpoint)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(endpoint))
+}
+
+// ============================================================================
+// DELETE /webhooks/:webhook_id - Remove an endpoint and its delivery log
+// (requires JWT auth)
+// ============================================================================
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<WebhookDeletedResponse>, AppError> {
+    let endpoint = find_endpoint(&state, &user, webhook_id).await?;
+
+    WebhookRepository::delete_endpoint(&state.db, user.id, endpoint.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_webhook",
+        "webhook",
+        endpoint.id,
+        Some(json!(endpoint)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(WebhookDeletedResponse {
+        message: "Webhook deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// GET /webhooks/:webhook_id/deliveries - Delivery log of an endpoint, newest first
+// (requires JWT auth)
+// ============================================================================
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<ListDeliveriesResponse>, AppError> {
+    let endpoint = find_endpoint(&state, &user, webhook_id).await?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+
+    let (deliveries, total) = WebhookRepository::list_deliveries(
+        &state.db,
+        endpoint.id,
+        query.status,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+
+    Ok(Json(ListDeliveriesResponse {
+        deliveries,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+// ============================================================================
+// POST /webhooks/:webhook_id/deliveries/:delivery_id/redeliver - Send an event again
+// (requires JWT auth)
+// Queued as a new delivery with the same event id and payload, sent on the next round
+// ============================================================================
+pub async fn redeliver(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((webhook_id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<WebhookDelivery>, AppError> {
+    let endpoint = find_endpoint(&state, &user, webhook_id).await?;
+    let delivery = WebhookRepository::find_delivery(&state.db, endpoint.id, delivery_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let redelivery = WebhookRepository::redeliver(&state.db, &delivery).await?;
+
+    Ok(Json(redelivery))
+}
+
+async fn find_endpoint(state: &AppState, user: &User, webhook_id: Uuid) -> Result<WebhookEndpoint, AppError> {
+    WebhookRepository::find_endpoint(&state.db, user.id, webhook_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Subscribed event names, without repeats
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+    let mut names: Vec<String> = events.iter().map(|event| event.as_str().to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+// ============================================================================
+// Webhooks Router
+// ============================================================================
+pub fn webhook_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_webhooks).post(create_webhook))
+        .route("/:webhook_id", get(get_webhook).patch(update_webhook).delete(delete_webhook))
+        .route("/:webhook_id/deliveries", get(list_deliveries))
+        .route("/:webhook_id/deliveries/:delivery_id/redeliver", post(redeliver))
+}
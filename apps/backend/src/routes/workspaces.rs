@@ -0,0 +1,356 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, patch},
+    Json, Router,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app_state::AppState,
+    db::{
+        models::{User, WorkspaceMember, WorkspaceRole, WorkspaceWithRole},
+        repositories::{AttachmentRepository, AuditLogRepository, UserRepository, WorkspaceRepository},
+    },
+    dto::workspace::*,
+    error::AppError,
+    middleware::{AuthUser, ClientInfo},
+};
+
+// ============================================================================
+// GET /workspaces - List the workspaces the user is a member of (requires JWT auth)
+// ============================================================================
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<Json<Vec<WorkspaceWithRole>>, AppError> {
+    let workspaces = WorkspaceRepository::list_for_user(&state.db, user.id).await?;
+
+    Ok(Json(workspaces))
+}
+
+// ============================================================================
+// POST /workspaces - Create a workspace owned by the user (requires JWT auth)
+// ============================================================================
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Json(payload): Json<CreateWorkspaceRequest>,
+) -> Result<Json<WorkspaceWithRole>, AppError> {
+    // Validate input
+    payload.validate()?;
+    let name = workspace_name(&payload.name)?;
+
+    let workspace = WorkspaceRepository::create(&state.db, user.id, name).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "create_workspace",
+        "workspace",
+        workspace.id,
+        None,
+        Some(json!(workspace)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    let workspace = find_workspace(&state, &user, workspace.id).await?;
+
+    Ok(Json(workspace))
+}
+
+// ============================================================================
+// GET /workspaces/:workspace_id - View a workspace with its members (requires JWT auth)
+// ============================================================================
+pub async fn get_workspace(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<WorkspaceDetailResponse>, AppError> {
+    let workspace = find_workspace(&state, &user, workspace_id).await?;
+    let members = WorkspaceRepository::list_members(&state.db, workspace_id).await?;
+
+    Ok(Json(WorkspaceDetailResponse { workspace, members }))
+}
+
+// ============================================================================
+// PATCH /workspaces/:workspace_id - Rename a workspace (requires JWT auth, owner)
+// ============================================================================
+pub async fn update_workspace(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkspaceRequest>,
+) -> Result<Json<WorkspaceWithRole>, AppError> {
+    // Validate input
+    payload.validate()?;
+    let name = workspace_name(&payload.name)?;
+
+    let existing = find_owned_workspace(&state, &user, workspace_id).await?;
+    let workspace = WorkspaceRepository::rename(&state.db, workspace_id, name).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "update_workspace",
+        "workspace",
+        workspace.id,
+        Some(json!(existing.workspace)),
+        Some(json!(workspace)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    let workspace = find_workspace(&state, &user, workspace.id).await?;
+
+    Ok(Json(workspace))
+}
+
+// ============================================================================
+// DELETE /workspaces/:workspace_id - Delete a workspace and everything recorded in it
+// (requires JWT auth, owner)
+// ============================================================================
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<WorkspaceDeletedResponse>, AppError> {
+    let workspace = find_owned_workspace(&state, &user, workspace_id).await?;
+
+    // Receipts live in the storage backend, outside the cascade
+    let attachments = AttachmentRepository::list_for_workspace(&state.db, workspace_id).await?;
+
+    WorkspaceRepository::delete(&state.db, workspace_id).await?;
+
+    for attachment in attachments {
+        if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+            tracing::warn!("Failed to remove attachment {}: {:?}", attachment.storage_key, e);
+        }
+    }
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "delete_workspace",
+        "workspace",
+        workspace_id,
+        Some(json!(workspace.workspace)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(WorkspaceDeletedResponse {
+        message: "Workspace deleted successfully".to_string(),
+    }))
+}
+
+// ============================================================================
+// GET /workspaces/:workspace_id/members - List the members of a workspace
+// (requires JWT auth)
+// ============================================================================
+pub async fn list_members(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<Vec<WorkspaceMember>>, AppError> {
+    find_workspace(&state, &user, workspace_id).await?;
+    let members = WorkspaceRepository::list_members(&state.db, workspace_id).await?;
+
+    Ok(Json(members))
+}
+
+// ============================================================================
+// POST /workspaces/:workspace_id/members - Add a user to a workspace (requires JWT auth, owner)
+// ============================================================================
+pub async fn add_member(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<AddMemberRequest>,
+) -> Result<Json<WorkspaceMember>, AppError> {
+    // Validate input
+    payload.validate()?;
+
+    find_owned_workspace(&state, &user, workspace_id).await?;
+
+    let other = UserRepository::find_active_by_username(&state.db, payload.username.trim())
+        .await?
+        .ok_or_else(|| AppError::ValidationError("Unknown user".to_string()))?;
+    if WorkspaceRepository::find_role(&state.db, workspace_id, other.id).await?.is_some() {
+        return Err(AppError::Conflict(format!(
+            "'{}' is already a member of this workspace",
+            other.username
+        )));
+    }
+
+    WorkspaceRepository::add_member(&state.db, workspace_id, other.id, payload.role).await?;
+    let member = find_member(&state, workspace_id, other.id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "add_workspace_member",
+        "workspace",
+        workspace_id,
+        None,
+        Some(json!(member)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(member))
+}
+
+// ============================================================================
+// PATCH /workspaces/:workspace_id/members/:user_id - Change a member's role
+// (requires JWT auth, owner)
+// A workspace always keeps at least one owner
+// ============================================================================
+pub async fn update_member(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path((workspace_id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateMemberRequest>,
+) -> Result<Json<WorkspaceMember>, AppError> {
+    find_owned_workspace(&state, &user, workspace_id).await?;
+    let existing = find_member(&state, workspace_id, member_id).await?;
+
+    if existing.role == WorkspaceRole::Owner && payload.role != WorkspaceRole::Owner {
+        ensure_another_owner(&state, workspace_id).await?;
+    }
+
+    WorkspaceRepository::update_member_role(&state.db, workspace_id, member_id, payload.role).await?;
+    let member = find_member(&state, workspace_id, member_id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "update_workspace_member",
+        "workspace",
+        workspace_id,
+        Some(json!(existing)),
+        Some(json!(member)),
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(member))
+}
+
+// ============================================================================
+// DELETE /workspaces/:workspace_id/members/:user_id - Remove a member, or leave a
+// workspace (requires JWT auth, owner unless removing yourself)
+// Records the member entered stay in the workspace
+// ============================================================================
+pub async fn remove_member(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    client: ClientInfo,
+    Path((workspace_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MemberRemovedResponse>, AppError> {
+    if member_id == user.id {
+        find_workspace(&state, &user, workspace_id).await?;
+    } else {
+        find_owned_workspace(&state, &user, workspace_id).await?;
+    }
+    let member = find_member(&state, workspace_id, member_id).await?;
+
+    if member.role == WorkspaceRole::Owner {
+        ensure_another_owner(&state, workspace_id).await?;
+    }
+
+    WorkspaceRepository::remove_member(&state.db, workspace_id, member_id).await?;
+
+    AuditLogRepository::create(
+        &state.db,
+        Some(user.id),
+        "remove_workspace_member",
+        "workspace",
+        workspace_id,
+        Some(json!(member)),
+        None,
+        client.ip_address.as_deref(),
+        client.user_agent.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(MemberRemovedResponse {
+        message: "Member removed successfully".to_string(),
+    }))
+}
+
+fn workspace_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError("Name must not be blank".to_string()));
+    }
+
+    Ok(name)
+}
+
+/// A workspace the user is a member of; others are reported as not found
+async fn find_workspace(state: &AppState, user: &User, workspace_id: Uuid) -> Result<WorkspaceWithRole, AppError> {
+    WorkspaceRepository::find_for_user(&state.db, user.id, workspace_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// A workspace the user owns
+async fn find_owned_workspace(
+    state: &AppState,
+    user: &User,
+    workspace_id: Uuid,
+) -> Result<WorkspaceWithRole, AppError> {
+    let workspace = find_workspace(state, user, workspace_id).await?;
+    if workspace.role != WorkspaceRole::Owner {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(workspace)
+}
+
+async fn find_member(state: &AppState, workspace_id: Uuid, user_id: Uuid) -> Result<WorkspaceMember, AppError> {
+    WorkspaceRepository::find_member(&state.db, workspace_id, user_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn ensure_another_owner(state: &AppState, workspace_id: Uuid) -> Result<(), AppError> {
+    if WorkspaceRepository::count_owners(&state.db, workspace_id).await? <= 1 {
+        return Err(AppError::Conflict(
+            "A workspace must keep at least one owner".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Workspaces Router
+// ============================================================================
+pub fn workspace_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_workspaces).post(create_workspace))
+        .route(
+            "/:workspace_id",
+            get(get_workspace).patch(update_workspace).delete(delete_workspace),
+        )
+        .route("/:workspace_id/members", get(list_members).post(add_member))
+        .route(
+            "/:workspace_id/members/:user_id",
+            patch(update_member).delete(remove_member),
+        )
+}
@@ -0,0 +1,55 @@
+use std::{sync::Arc, time::Duration};
+use sqlx::PgPool;
+use crate::{
+    db::repositories::{AttachmentRepository, DataExportRepository, UserRepository},
+    error::AppError,
+    utils::storage::StorageBackend,
+};
+
+/// Accounts purged per run; the rest wait for the next tick
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// Hard-delete accounts whose deletion grace period has ended.
+/// Returns the number of accounts purged.
+pub async fn purge_due_accounts(pool: &PgPool, storage: &dyn StorageBackend) -> Result<usize, AppError> {
+    let mut purged = 0;
+
+    for user_id in UserRepository::list_due_for_purge(pool, PURGE_BATCH_SIZE).await? {
+        // Export archives live on disk and receipts in the storage backend, outside the cascade
+        let exports = DataExportRepository::list_for_user(pool, user_id).await?;
+        let attachments = AttachmentRepository::list_all_for_user(pool, user_id).await?;
+
+        if !UserRepository::purge(pool, user_id).await? {
+            continue;
+        }
+
+        for file_path in exports.into_iter().filter_map(|export| export.file_path) {
+            if let Err(e) = tokio::fs::remove_file(&file_path).await {
+                tracing::warn!("Failed to remove export archive {}: {}", file_path, e);
+            }
+        }
+        for attachment in attachments {
+            if let Err(e) = storage.delete(&attachment.storage_key).await {
+                tracing::warn!("Failed to remove attachment {}: {:?}", attachment.storage_key, e);
+            }
+        }
+
+        tracing::info!("Purged deleted account {}", user_id);
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+/// Run the purge every `interval`
+pub fn spawn_purge(pool: PgPool, storage: Arc<dyn StorageBackend>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = purge_due_accounts(&pool, storage.as_ref()).await {
+                tracing::warn!("Failed to purge deleted accounts: {:?}", e);
+            }
+        }
+    });
+}
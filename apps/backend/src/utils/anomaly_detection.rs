@@ -0,0 +1,347 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+use chrono::Duration;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    config::AnomalyConfig,
+    db::{
+        models::{AnomalyKind, NewTransactionAnomaly, Transaction, TransactionAnomaly, TransactionType},
+        repositories::{AnomalyRepository, ClientRepository},
+    },
+    error::AppError,
+    utils::messaging::{preferred_client, MessagingGateway},
+};
+
+/// Transactions taken per round of the scan
+const SCAN_BATCH_SIZE: i64 = 200;
+/// Transactions claimed longer ago than this without being checked are scanned again
+const CLAIM_LEASE_SECONDS: i64 = 600;
+
+/// Median, mean and standard deviation of earlier amounts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmountStats {
+    pub count: usize,
+    pub median: Decimal,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl AmountStats {
+    /// None without any amounts
+    pub fn of(amounts: &[Decimal]) -> Option<Self> {
+        if amounts.is_empty() {
+            return None;
+        }
+
+        let mut sorted = amounts.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / Decimal::TWO
+        } else {
+            sorted[middle]
+        };
+
+        let values: Vec<f64> = sorted.iter().filter_map(Decimal::to_f64).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+
+        Some(Self {
+            count: sorted.len(),
+            median,
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// Standard deviations `amount` lies above the mean; None when the amounts do not vary
+    pub fn z_score(&self, amount: Decimal) -> Option<f64> {
+        (self.std_dev > 0.0).then(|| (amount.to_f64().unwrap_or_default() - self.mean) / self.std_dev)
+    }
+
+    /// How many times the median `amount` is
+    pub fn median_multiple(&self, amount: Decimal) -> Option<f64> {
+        (self.median > Decimal::ZERO).then(|| (amount / self.median).to_f64().unwrap_or_default())
+    }
+}
+
+/// An amount is unusual for its category when it is both far above the median and far
+/// above the spread of earlier amounts (or they never varied)
+pub fn unusual_amount(amount: Decimal, history: &[Decimal], config: &AnomalyConfig) -> Option<serde_json::Value> {
+    if history.len() < config.min_history {
+        return None;
+    }
+    let stats = AmountStats::of(history)?;
+    let multiple = stats.median_multiple(amount)?;
+    let z_score = stats.z_score(amount);
+
+    let unusual = multiple >= config.large_amount_multiple && z_score.is_none_or(|z| z >= config.z_score);
+    unusual.then(|| {
+        json!({
+            "amount": amount,
+            "compared_count": stats.count,
+            "median": stats.median,
+            "mean": round2(stats.mean),
+            "std_dev": round2(stats.std_dev),
+            "z_score": z_score.map(round2),
+            "median_multiple": round2(multiple),
+        })
+    })
+}
+
+/// A first transaction with a merchant stands out when it is far above the ledger's
+/// median amount
+pub fn large_for_new_merchant(
+    amount: Decimal,
+    ledger_history: &[Decimal],
+    config: &AnomalyConfig,
+) -> Option<serde_json::Value> {
+    if ledger_history.len() < config.min_history {
+        return None;
+    }
+    let stats = AmountStats::of(ledger_history)?;
+    let multiple = stats.median_multiple(amount)?;
+
+    (multiple >= config.large_amount_multiple).then(|| {
+        json!({
+            "amount": amount,
+            "compared_count": stats.count,
+            "median": stats.median,
+            "median_multiple": round2(multiple),
+        })
+    })
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Look at every transaction created since the last scan and record what looks unusual.
+/// A transaction that cannot be checked is left for a later scan. Imported transactions
+/// are flagged but their authors are not messaged, since an import makes a whole history
+/// new at once. Returns the number of anomalies recorded.
+pub async fn scan_new_transactions(
+    pool: &PgPool,
+    gateway: Option<&dyn MessagingGateway>,
+    config: &AnomalyConfig,
+) -> Result<usize, AppError> {
+    let mut recorded = 0;
+
+    loop {
+        let transactions = AnomalyRepository::claim_unchecked(pool, SCAN_BATCH_SIZE, CLAIM_LEASE_SECONDS).await?;
+        if transactions.is_empty() {
+            break;
+        }
+
+        for transaction in transactions {
+            let anomalies = match check_transaction(pool, &transaction, config).await {
+                Ok(anomalies) => anomalies,
+                Err(e) => {
+                    tracing::warn!("Anomaly check of transaction {} failed: {:?}", transaction.id, e);
+                    continue;
+                }
+            };
+            AnomalyRepository::mark_checked(pool, transaction.id).await?;
+            recorded += anomalies.len();
+
+            if config.notify
+                && let Some(gateway) = gateway
+                && !anomalies.is_empty()
+                && !is_imported(&transaction)
+                && let Err(e) = notify(pool, gateway, &transaction, &anomalies).await
+            {
+                tracing::warn!("Failed to notify user {} of anomalies: {:?}", transaction.user_id, e);
+            }
+        }
+    }
+
+    Ok(recorded)
+}
+
+/// Whether the transaction came from a CSV or bank statement import
+fn is_imported(transaction: &Transaction) -> bool {
+    transaction
+        .metadata
+        .as_ref()
+        .is_some_and(|metadata| metadata.get("import").is_some() || metadata.get("statement").is_some())
+}
+
+async fn check_transaction(
+    pool: &PgPool,
+    transaction: &Transaction,
+    config: &AnomalyConfig,
+) -> Result<Vec<TransactionAnomaly>, AppError> {
+    if transaction.deleted_at.is_some() || transaction.r#type == TransactionType::Transfer {
+        return Ok(Vec::new());
+    }
+
+    let since = transaction.transaction_date - Duration::days(config.history_days);
+    let mut found = Vec::new();
+
+    if let Some(duplicate) =
+        AnomalyRepository::find_duplicate_of(pool, transaction, config.duplicate_window_seconds).await?
+    {
+        found.push(NewTransactionAnomaly {
+            transaction_id: transaction.id,
+            kind: AnomalyKind::PossibleDuplicate,
+            details: json!({
+                "seconds_apart": (transaction.created_at - duplicate.created_at).num_seconds(),
+            }),
+            related_transaction_id: Some(duplicate.id),
+        });
+    }
+
+    if transaction.r#type == TransactionType::Expense {
+        if transaction.category_id.is_some() {
+            let history = AnomalyRepository::list_comparable_amounts(pool, transaction, since, true).await?;
+            if let Some(details) = unusual_amount(transaction.amount, &history, config) {
+                found.push(NewTransactionAnomaly {
+                    transaction_id: transaction.id,
+                    kind: AnomalyKind::UnusualAmount,
+                    details,
+                    related_transaction_id: None,
+                });
+            }
+        }
+
+        if transaction.merchant_name.as_deref().is_some_and(|name| !name.trim().is_empty())
+            && !AnomalyRepository::merchant_seen_before(pool, transaction).await?
+        {
+            let history = AnomalyRepository::list_comparable_amounts(pool, transaction, since, false).await?;
+            if let Some(details) = large_for_new_merchant(transaction.amount, &history, config) {
+                found.push(NewTransactionAnomaly {
+                    transaction_id: transaction.id,
+                    kind: AnomalyKind::NewMerchantLargeAmount,
+                    details,
+                    related_transaction_id: None,
+                });
+            }
+        }
+    }
+
+    let mut anomalies = Vec::with_capacity(found.len());
+    for anomaly in &found {
+        if let Some(anomaly) = AnomalyRepository::create(pool, anomaly).await? {
+            anomalies.push(anomaly);
+        }
+    }
+
+    Ok(anomalies)
+}
+
+/// Tell the author on their chat client, when they have one
+async fn notify(
+    pool: &PgPool,
+    gateway: &dyn MessagingGateway,
+    transaction: &Transaction,
+    anomalies: &[TransactionAnomaly],
+) -> Result<(), AppError> {
+    let clients = ClientRepository::list_for_user(pool, transaction.user_id).await?;
+    let Some(client) = preferred_client(&clients) else {
+        return Ok(());
+    };
+
+    gateway.send(client, &notification_text(transaction, anomalies)).await?;
+
+    let ids: Vec<_> = anomalies.iter().map(|anomaly| anomaly.id).collect();
+    AnomalyRepository::mark_notified(pool, &ids).await
+}
+
+fn notification_text(transaction: &Transaction, anomalies: &[TransactionAnomaly]) -> String {
+    let what = match &transaction.merchant_name {
+        Some(merchant) => format!("{} {} at {}", transaction.currency, transaction.amount, merchant),
+        None => format!("{} {}", transaction.currency, transaction.amount),
+    };
+    let reasons: Vec<&str> = anomalies
+        .iter()
+        .map(|anomaly| match anomaly.kind {
+            AnomalyKind::UnusualAmount => "much more than you usually spend in this category",
+            AnomalyKind::NewMerchantLargeAmount => "a large amount at a merchant you have not used before",
+            AnomalyKind::PossibleDuplicate => "entered again just minutes after an identical transaction",
+        })
+        .collect();
+
+    format!(
+        "Please check your transaction of {} on {}: it is {}. If it is correct, you can dismiss this in the app.",
+        what,
+        transaction.transaction_date.format("%d/%m/%Y"),
+        reasons.join("; and ")
+    )
+}
+
+/// Scan new transactions every `scan_interval_seconds`
+pub fn spawn_scanner(pool: PgPool, gateway: Option<Arc<dyn MessagingGateway>>, config: AnomalyConfig) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(StdDuration::from_secs(config.scan_interval_seconds));
+        loop {
+            ticker.tick().await;
+            match scan_new_transactions(&pool, gateway.as_deref(), &config).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Flagged {} unusual transactions", count),
+                Err(e) => tracing::warn!("Anomaly scan failed: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn config() -> AnomalyConfig {
+        AnomalyConfig {
+            enabled: true,
+            scan_interval_seconds: 60,
+            history_days: 180,
+            min_history: 5,
+            z_score: 3.0,
+            large_amount_multiple: 3.0,
+            duplicate_window_seconds: 600,
+            notify: false,
+        }
+    }
+
+    fn amounts(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|value| Decimal::from_str(value).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_amount_stats() {
+        let stats = AmountStats::of(&amounts(&["30000", "10000", "20000", "40000"])).unwrap();
+        assert_eq!(stats.median, Decimal::from(25000));
+        assert_eq!(stats.mean, 25000.0);
+        assert!((stats.std_dev - 11180.34).abs() < 0.01);
+        assert!(AmountStats::of(&[]).is_none());
+    }
+
+    #[test]
+    fn test_unusual_amount_needs_history_and_both_thresholds() {
+        let coffees = amounts(&["35000", "38000", "32000", "40000", "36000", "34000"]);
+
+        let flagged = unusual_amount(Decimal::from(250000), &coffees, &config()).unwrap();
+        assert_eq!(flagged["median"], json!(Decimal::from(35500)));
+        assert!(flagged["z_score"].as_f64().unwrap() > 3.0);
+
+        // Above the spread but not a large multiple of the median
+        assert!(unusual_amount(Decimal::from(60000), &coffees, &config()).is_none());
+        // Too little history to judge
+        assert!(unusual_amount(Decimal::from(250000), &coffees[..4], &config()).is_none());
+        // Amounts that never varied only need the multiple
+        let same = amounts(&["15000"; 5]);
+        assert!(unusual_amount(Decimal::from(45000), &same, &config()).unwrap()["z_score"].is_null());
+    }
+
+    #[test]
+    fn test_large_for_new_merchant() {
+        let ledger = amounts(&["20000", "50000", "75000", "120000", "30000"]);
+
+        assert!(large_for_new_merchant(Decimal::from(100000), &ledger, &config()).is_none());
+        assert_eq!(
+            large_for_new_merchant(Decimal::from(1500000), &ledger, &config()).unwrap()["median_multiple"],
+            json!(30.0)
+        );
+    }
+}
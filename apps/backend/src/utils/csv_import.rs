@@ -0,0 +1,451 @@
+use std::{collections::HashMap, str::FromStr};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::{
+    db::models::{Currency, TransactionType},
+    error::AppError,
+};
+
+/// CSV header names holding each transaction field; only date and amount are required
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    pub date: String,
+    pub amount: String,
+    /// Values such as income/expense, pemasukan/pengeluaran or credit/debit
+    pub r#type: Option<String>,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub merchant: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub currency: Option<String>,
+}
+
+/// Which character separates decimals; the other one is treated as a thousands separator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecimalSeparator {
+    /// 1,234,567.89
+    #[default]
+    Dot,
+    /// 1.234.567,89 (Indonesian)
+    Comma,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportOptions {
+    pub columns: ColumnMapping,
+    /// chrono format string, e.g. `%d/%m/%Y` for Indonesian dates
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default)]
+    pub decimal_separator: DecimalSeparator,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Currency for rows without a currency column
+    #[serde(default)]
+    pub default_currency: Currency,
+    /// Type of positive amounts when there is no type column; negative amounts are expenses
+    #[serde(default = "default_type")]
+    pub default_type: TransactionType,
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_type() -> TransactionType {
+    TransactionType::Expense
+}
+
+/// A row that parsed cleanly; names are resolved to ids afterwards
+#[derive(Debug, Clone)]
+pub struct ParsedTransaction {
+    pub r#type: TransactionType,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub transaction_date: NaiveDate,
+    pub category: Option<String>,
+    pub payment_method: Option<String>,
+    pub merchant_name: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ParsedRow {
+    /// Line number in the file (the header is line 1)
+    pub line: usize,
+    pub result: Result<ParsedTransaction, Vec<String>>,
+}
+
+/// Parse every data row of the CSV. Fails as a whole only when the file itself is
+/// unreadable or a mapped column is missing from the header.
+pub fn parse_csv(data: &[u8], options: &ImportOptions) -> Result<Vec<ParsedRow>, AppError> {
+    if !options.delimiter.is_ascii() {
+        return Err(AppError::BadRequest("Delimiter must be an ASCII character".to_string()));
+    }
+    // Transfers need a source and destination account, which imports don't carry
+    if options.default_type == TransactionType::Transfer {
+        return Err(AppError::BadRequest("Default type must be income or expense".to_string()));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::BadRequest(format!("Invalid CSV header: {}", e)))?
+        .clone();
+
+    let index_of = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+            .ok_or_else(|| AppError::BadRequest(format!("Column '{}' not found in CSV header", name)))
+    };
+    let optional_index = |name: &Option<String>| name.as_deref().map(index_of).transpose();
+
+    let columns = &options.columns;
+    let date_index = index_of(&columns.date)?;
+    let amount_index = index_of(&columns.amount)?;
+    let type_index = optional_index(&columns.r#type)?;
+    let category_index = optional_index(&columns.category)?;
+    let payment_method_index = optional_index(&columns.payment_method)?;
+    let merchant_index = optional_index(&columns.merchant)?;
+    let description_index = optional_index(&columns.description)?;
+    let location_index = optional_index(&columns.location)?;
+    let currency_index = optional_index(&columns.currency)?;
+
+    let mut rows = Vec::new();
+
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                rows.push(ParsedRow { line, result: Err(vec![format!("Unreadable row: {}", e)]) });
+                continue;
+            }
+        };
+
+        // Skip blank lines that spreadsheets like to leave at the end
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let mut errors = Vec::new();
+
+        let raw_date = field(Some(date_index)).unwrap_or_default();
+        let transaction_date = parse_date(&raw_date, &options.date_format);
+        if transaction_date.is_none() {
+            errors.push(format!(
+                "Invalid date '{}' (expected format {})",
+                raw_date, options.date_format
+            ));
+        }
+
+        let raw_amount = field(Some(amount_index)).unwrap_or_default();
+        let amount = parse_amount(&raw_amount, options.decimal_separator);
+        match amount {
+            None => errors.push(format!("Invalid amount '{}'", raw_amount)),
+            Some(amount) if amount.is_zero() => errors.push("Amount must not be zero".to_string()),
+            Some(_) => {}
+        }
+
+        let r#type = match field(type_index) {
+            Some(raw_type) => {
+                let parsed = parse_type(&raw_type);
+                if parsed.is_none() {
+                    errors.push(format!("Unknown transaction type '{}'", raw_type));
+                }
+                parsed
+            }
+            None if amount.is_some_and(|amount| amount.is_sign_negative()) => {
+                Some(TransactionType::Expense)
+            }
+            None => Some(options.default_type.clone()),
+        };
+
+        let currency = match field(currency_index) {
+            Some(raw_currency) => {
+                let parsed = raw_currency.parse::<Currency>();
+                if let Err(e) = &parsed {
+                    errors.push(format!("Invalid currency: {}", e));
+                }
+                parsed.ok()
+            }
+            None => Some(options.default_currency),
+        };
+
+        let result = match (transaction_date, amount, r#type, currency) {
+            (Some(transaction_date), Some(amount), Some(r#type), Some(currency))
+                if errors.is_empty() =>
+            {
+                Ok(ParsedTransaction {
+                    r#type,
+                    amount: amount.abs(),
+                    currency,
+                    transaction_date,
+                    category: field(category_index),
+                    payment_method: field(payment_method_index),
+                    merchant_name: field(merchant_index),
+                    description: field(description_index),
+                    location: field(location_index),
+                })
+            }
+            _ => Err(errors),
+        };
+
+        rows.push(ParsedRow { line, result });
+    }
+
+    Ok(rows)
+}
+
+/// Parse an amount such as `Rp 1.234.567,89`, `-50,000.00` or `(1.000)`
+pub fn parse_amount(raw: &str, decimal_separator: DecimalSeparator) -> Option<Decimal> {
+    let raw = raw.trim();
+    let negative = raw.contains('-') || (raw.starts_with('(') && raw.ends_with(')'));
+
+    let (decimal, thousands) = match decimal_separator {
+        DecimalSeparator::Dot => ('.', ','),
+        DecimalSeparator::Comma => (',', '.'),
+    };
+
+    // Only look between the first and last digit, dropping currency symbols like `Rp.`
+    let start = raw.find(|c: char| c.is_ascii_digit())?;
+    let end = raw.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let number = &raw[start..end];
+
+    let is_group_separator = |c: char| c == thousands || c == ' ' || c == '\u{a0}';
+    if !number.chars().all(|c| c.is_ascii_digit() || c == decimal || is_group_separator(c)) {
+        return None;
+    }
+
+    // A thousands separator must not appear after the decimal separator
+    if let Some(decimal_at) = number.find(decimal)
+        && number[decimal_at..].contains(is_group_separator)
+    {
+        return None;
+    }
+
+    let normalized: String = number
+        .chars()
+        .filter(|c| !is_group_separator(*c))
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+
+    let amount = Decimal::from_str(&normalized).ok()?.round_dp(2);
+
+    Some(if negative { -amount } else { amount })
+}
+
+/// Parse a date with the given chrono format
+pub fn parse_date(raw: &str, format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(raw.trim(), format).ok()
+}
+
+/// Map common English and Indonesian labels to a transaction type
+pub fn parse_type(raw: &str) -> Option<TransactionType> {
+    match raw.trim().to_lowercase().as_str() {
+        "income" | "pemasukan" | "masuk" | "credit" | "kredit" | "cr" | "in" => {
+            Some(TransactionType::Income)
+        }
+        "expense" | "pengeluaran" | "keluar" | "debit" | "db" | "out" => {
+            Some(TransactionType::Expense)
+        }
+        _ => None,
+    }
+}
+
+/// Date, type, currency, amount and merchant of a transaction, compared case-insensitively
+pub type DuplicateKey = (NaiveDate, TransactionType, Currency, Decimal, String);
+
+/// Key used to spot transactions that were already recorded
+pub fn duplicate_key(
+    transaction_date: NaiveDate,
+    r#type: TransactionType,
+    currency: Currency,
+    amount: Decimal,
+    merchant_name: Option<&str>,
+) -> DuplicateKey {
+    (
+        transaction_date,
+        r#type,
+        currency,
+        amount.normalize(),
+        merchant_name.unwrap_or_default().trim().to_lowercase(),
+    )
+}
+
+/// Case-insensitive lookup of ids by name or alias; names win over aliases
+#[derive(Debug, Default)]
+pub struct NameResolver {
+    by_name: HashMap<String, Uuid>,
+    by_alias: HashMap<String, Uuid>,
+}
+
+impl NameResolver {
+    /// Later entries override earlier ones, so pass system entries before the user's own
+    pub fn new<'a>(
+        names: impl IntoIterator<Item = (Uuid, &'a str)>,
+        aliases: impl IntoIterator<Item = (Uuid, &'a str)>,
+    ) -> Self {
+        Self {
+            by_name: names
+                .into_iter()
+                .map(|(id, name)| (name.trim().to_lowercase(), id))
+                .collect(),
+            by_alias: aliases
+                .into_iter()
+                .map(|(id, alias)| (alias.trim().to_lowercase(), id))
+                .collect(),
+        }
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<Uuid> {
+        let key = name.trim().to_lowercase();
+        self.by_name
+            .get(&key)
+            .or_else(|| self.by_alias.get(&key))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(json: serde_json::Value) -> ImportOptions {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_amount_formats() {
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+
+        assert_eq!(parse_amount("1.234.567,89", DecimalSeparator::Comma), Some(dec("1234567.89")));
+        assert_eq!(parse_amount("Rp 25.000", DecimalSeparator::Comma), Some(dec("25000")));
+        assert_eq!(parse_amount("-50,000.00", DecimalSeparator::Dot), Some(dec("-50000.00")));
+        assert_eq!(parse_amount("(1.000)", DecimalSeparator::Comma), Some(dec("-1000")));
+        assert_eq!(parse_amount("12.5", DecimalSeparator::Dot), Some(dec("12.5")));
+        assert_eq!(parse_amount("Rp. 25,000", DecimalSeparator::Dot), Some(dec("25000")));
+        assert_eq!(parse_amount("1 234 567,5", DecimalSeparator::Comma), Some(dec("1234567.5")));
+        assert_eq!(parse_amount("1,5.000", DecimalSeparator::Comma), None);
+        assert_eq!(parse_amount("abc", DecimalSeparator::Dot), None);
+    }
+
+    #[test]
+    fn test_parse_csv_with_indonesian_formats() {
+        let csv = "Tanggal;Jumlah;Tipe;Kategori;Toko\n\
+                   01/12/2025;1.234.567,89;pengeluaran;Makanan;Warung Bu Sri\n\
+                   31/02/2025;10.000;pemasukan;;\n\
+                   02/12/2025;abc;lainnya;;\n\
+                   ;;;;\n";
+        let options = options(serde_json::json!({
+            "columns": { "date": "tanggal", "amount": "Jumlah", "type": "Tipe",
+                         "category": "Kategori", "merchant": "Toko" },
+            "date_format": "%d/%m/%Y",
+            "decimal_separator": "comma",
+            "delimiter": ";",
+        }));
+
+        let rows = parse_csv(csv.as_bytes(), &options).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        let first = rows[0].result.as_ref().unwrap();
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(first.r#type, TransactionType::Expense);
+        assert_eq!(first.amount, Decimal::from_str("1234567.89").unwrap());
+        assert_eq!(first.transaction_date, NaiveDate::from_ymd_opt(2025, 12, 1).unwrap());
+        assert_eq!(first.category.as_deref(), Some("Makanan"));
+        assert_eq!(first.merchant_name.as_deref(), Some("Warung Bu Sri"));
+        assert_eq!(first.currency, Currency::IDR);
+
+        assert_eq!(rows[1].result.as_ref().unwrap_err().len(), 1);
+        assert_eq!(rows[2].result.as_ref().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_signed_amounts_without_type_column() {
+        let csv = "date,amount\n2025-12-01,-15000\n2025-12-02,20000\n";
+        let options = options(serde_json::json!({
+            "columns": { "date": "date", "amount": "amount" },
+            "default_type": "income",
+        }));
+
+        let rows = parse_csv(csv.as_bytes(), &options).unwrap();
+        let first = rows[0].result.as_ref().unwrap();
+        let second = rows[1].result.as_ref().unwrap();
+
+        assert_eq!(first.r#type, TransactionType::Expense);
+        assert_eq!(first.amount, Decimal::from(15000));
+        assert_eq!(second.r#type, TransactionType::Income);
+    }
+
+    #[test]
+    fn test_missing_mapped_column_is_rejected() {
+        let options = options(serde_json::json!({
+            "columns": { "date": "date", "amount": "total" },
+        }));
+
+        assert!(matches!(
+            parse_csv(b"date,amount\n2025-12-01,1000\n", &options),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_name_resolver_prefers_names_over_aliases() {
+        let groceries = Uuid::new_v4();
+        let food = Uuid::new_v4();
+        let resolver = NameResolver::new(
+            [(groceries, "Groceries"), (food, "Food")],
+            [(groceries, "supermarket"), (groceries, "food")],
+        );
+
+        assert_eq!(resolver.resolve(" SUPERMARKET "), Some(groceries));
+        assert_eq!(resolver.resolve("food"), Some(food));
+        assert_eq!(resolver.resolve("rent"), None);
+    }
+
+    #[test]
+    fn test_duplicate_key_ignores_scale_and_case() {
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let key = |r#type, currency, amount: &str, merchant| {
+            duplicate_key(date, r#type, currency, Decimal::from_str(amount).unwrap(), Some(merchant))
+        };
+        let usd: Currency = "USD".parse().unwrap();
+
+        assert_eq!(
+            key(TransactionType::Expense, Currency::IDR, "25000.00", "Warung "),
+            key(TransactionType::Expense, Currency::IDR, "25000", "warung")
+        );
+        // A refund, or the same amount in another currency, is a different transaction
+        assert_ne!(
+            key(TransactionType::Expense, Currency::IDR, "15", "warung"),
+            key(TransactionType::Income, Currency::IDR, "15", "warung")
+        );
+        assert_ne!(
+            key(TransactionType::Expense, Currency::IDR, "15", "warung"),
+            key(TransactionType::Expense, usd, "15", "warung")
+        );
+    }
+}
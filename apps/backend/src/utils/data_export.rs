@@ -0,0 +1,337 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Write},
+};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+use crate::{
+    db::{
+        models::{
+            Account, ApiKey, AuditLog, Category, Client, Conversation, Debt, Goal, GoalContribution, Merchant, PaymentMethod, SafeUser,
+            Tag, Transaction, TransactionAttachment, TransactionSplit, TransactionTag, User,
+        },
+        repositories::{
+            AccountRepository, ApiKeyRepository, AttachmentRepository, AuditLogRepository, CategoryRepository, ClientRepository,
+            ConversationRepository, DebtRepository, GoalRepository, MerchantRepository, PaymentMethodRepository, TagRepository,
+            TransactionRepository,
+        },
+    },
+    error::AppError,
+};
+
+/// Everything we hold on a user, as included in their data export
+pub struct AccountData {
+    pub profile: SafeUser,
+    pub clients: Vec<Client>,
+    /// Categories visible to the user; only their own are exported as records
+    pub categories: Vec<Category>,
+    /// Payment methods visible to the user; only their own are exported as records
+    pub payment_methods: Vec<PaymentMethod>,
+    pub accounts: Vec<Account>,
+    pub transactions: Vec<Transaction>,
+    pub transaction_splits: Vec<TransactionSplit>,
+    /// Receipt records; the files themselves are not included
+    pub transaction_attachments: Vec<TransactionAttachment>,
+    pub tags: Vec<Tag>,
+    pub transaction_tags: Vec<TransactionTag>,
+    /// Debts and settle-ups the user is a party to, including ones the other user recorded
+    pub debts: Vec<Debt>,
+    pub goals: Vec<Goal>,
+    pub goal_contributions: Vec<GoalContribution>,
+    pub merchants: Vec<Merchant>,
+    pub conversations: Vec<Conversation>,
+    pub api_keys: Vec<ApiKey>,
+    pub audit_logs: Vec<AuditLog>,
+}
+
+impl AccountData {
+    /// Load all of a user's data
+    pub async fn collect(pool: &PgPool, user: &User) -> Result<Self, AppError> {
+        Ok(AccountData {
+            profile: user.to_safe_user(),
+            clients: ClientRepository::list_for_user(pool, user.id).await?,
+            categories: CategoryRepository::list_available_for_user(pool, user.id).await?,
+            payment_methods: PaymentMethodRepository::list_available_for_user(pool, user.id).await?,
+            accounts: AccountRepository::list_all_for_user(pool, user.id).await?,
+            transactions: TransactionRepository::list_all_for_user(pool, user.id).await?,
+            transaction_splits: TransactionRepository::list_splits_for_user(pool, user.id).await?,
+            transaction_attachments: AttachmentRepository::list_all_for_user(pool, user.id).await?,
+            tags: TagRepository::list_for_user(pool, user.id).await?,
+            transaction_tags: TagRepository::list_transaction_tags_for_user(pool, user.id).await?,
+            debts: DebtRepository::list_all_for_user(pool, user.id).await?,
+            goals: GoalRepository::list_all_for_user(pool, user.id).await?,
+            goal_contributions: GoalRepository::list_contributions_for_user(pool, user.id).await?,
+            merchants: MerchantRepository::list_for_user(pool, user.id).await?,
+            conversations: ConversationRepository::list_for_user(pool, user.id).await?,
+            api_keys: ApiKeyRepository::list_all_for_user(pool, user.id).await?,
+            audit_logs: AuditLogRepository::list_for_user(pool, user.id).await?,
+        })
+    }
+}
+
+/// Build the export ZIP: one JSON file per record type, plus a spreadsheet-friendly
+/// `transactions.csv` with category, payment method, account and tag names resolved
+pub fn build_archive(data: &AccountData) -> Result<Vec<u8>, AppError> {
+    let user_id = Some(data.profile.id);
+    let own_categories: Vec<&Category> =
+        data.categories.iter().filter(|c| c.user_id == user_id).collect();
+    let own_payment_methods: Vec<&PaymentMethod> =
+        data.payment_methods.iter().filter(|p| p.user_id == user_id).collect();
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    write_json(&mut zip, "profile.json", &data.profile)?;
+    write_json(&mut zip, "clients.json", &data.clients)?;
+    write_json(&mut zip, "categories.json", &own_categories)?;
+    write_json(&mut zip, "payment_methods.json", &own_payment_methods)?;
+    write_json(&mut zip, "accounts.json", &data.accounts)?;
+    write_json(&mut zip, "transactions.json", &data.transactions)?;
+    write_json(&mut zip, "transaction_splits.json", &data.transaction_splits)?;
+    write_json(&mut zip, "transaction_attachments.json", &data.transaction_attachments)?;
+    write_json(&mut zip, "tags.json", &data.tags)?;
+    write_json(&mut zip, "transaction_tags.json", &data.transaction_tags)?;
+    write_json(&mut zip, "debts.json", &data.debts)?;
+    write_json(&mut zip, "goals.json", &data.goals)?;
+    write_json(&mut zip, "goal_contributions.json", &data.goal_contributions)?;
+    write_json(&mut zip, "merchants.json", &data.merchants)?;
+    write_json(&mut zip, "conversations.json", &data.conversations)?;
+    write_json(&mut zip, "api_keys.json", &data.api_keys)?;
+    write_json(&mut zip, "audit_logs.json", &data.audit_logs)?;
+
+    zip.start_file("transactions.csv", file_options())?;
+    zip.write_all(&transactions_csv(data)?)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn file_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn write_json<T: Serialize + ?Sized>(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    value: &T,
+) -> Result<(), AppError> {
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize {}: {}", name, e)))?;
+
+    zip.start_file(name, file_options())?;
+    zip.write_all(&json)?;
+
+    Ok(())
+}
+
+fn transactions_csv(data: &AccountData) -> Result<Vec<u8>, AppError> {
+    let category_names: HashMap<Uuid, &str> =
+        data.categories.iter().map(|c| (c.id, c.name.as_str())).collect();
+    let payment_method_names: HashMap<Uuid, &str> =
+        data.payment_methods.iter().map(|p| (p.id, p.name.as_str())).collect();
+    let account_names: HashMap<Uuid, &str> =
+        data.accounts.iter().map(|a| (a.id, a.name.as_str())).collect();
+    let tag_names: HashMap<Uuid, &str> = data.tags.iter().map(|t| (t.id, t.name.as_str())).collect();
+
+    let mut tags_by_transaction: HashMap<Uuid, Vec<&str>> = HashMap::new();
+    for link in &data.transaction_tags {
+        if let Some(name) = tag_names.get(&link.tag_id) {
+            tags_by_transaction.entry(link.transaction_id).or_default().push(name);
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "id", "date", "type", "amount", "currency", "category", "payment_method", "account",
+        "to_account", "merchant", "location", "description", "tags", "source", "created_at",
+        "deleted_at",
+    ])?;
+
+    for tx in &data.transactions {
+        let lookup = |names: &HashMap<Uuid, &str>, id: Option<Uuid>| {
+            id.and_then(|id| names.get(&id).map(|name| name.to_string()))
+                .unwrap_or_default()
+        };
+
+        writer.write_record([
+            tx.id.to_string(),
+            tx.transaction_date.to_string(),
+            enum_label(&tx.r#type),
+            tx.amount.to_string(),
+            tx.currency.to_string(),
+            lookup(&category_names, tx.category_id),
+            lookup(&payment_method_names, tx.payment_method_id),
+            lookup(&account_names, tx.account_id),
+            lookup(&account_names, tx.transfer_account_id),
+            tx.merchant_name.clone().unwrap_or_default(),
+            tx.location.clone().unwrap_or_default(),
+            tx.description.clone().unwrap_or_default(),
+            tags_by_transaction
+                .get(&tx.id)
+                .map(|names| names.join(";"))
+                .unwrap_or_default(),
+            enum_label(&tx.source),
+            tx.created_at.to_string(),
+            tx.deleted_at.map(|at| at.to_string()).unwrap_or_default(),
+        ])?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to write transactions.csv: {}", e)))
+}
+
+/// Serialized name of a unit enum variant (e.g., `expense`)
+fn enum_label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(label)) => label,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use chrono::{NaiveDate, Utc};
+    use rust_decimal::Decimal;
+    use zip::ZipArchive;
+    use crate::db::models::{
+        CategoryType, Currency, TransactionSource, TransactionType, UserRole, UserStatus,
+    };
+
+    fn sample_data() -> AccountData {
+        let now = Utc::now().naive_utc();
+        let user_id = Uuid::new_v4();
+        let food = Uuid::new_v4();
+        let tag = Uuid::new_v4();
+        let tx = Uuid::new_v4();
+
+        let category = |id, user_id, name: &str| Category {
+            id,
+            user_id,
+            workspace_id: None,
+            name: name.to_string(),
+            r#type: CategoryType::Expense,
+            icon: None,
+            color: None,
+            parent_category_id: None,
+            is_system: user_id.is_none(),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        AccountData {
+            profile: SafeUser {
+                id: user_id,
+                email: Some("budi@example.com".to_string()),
+                username: "budi".to_string(),
+                full_name: "Budi Santoso".to_string(),
+                role: UserRole::User,
+                status: UserStatus::Active,
+                base_currency: Currency::IDR,
+                created_at: now,
+            },
+            clients: vec![],
+            categories: vec![
+                category(food, None, "Food & Drinks"),
+                category(Uuid::new_v4(), Some(user_id), "Side Project"),
+            ],
+            payment_methods: vec![],
+            accounts: vec![],
+            transactions: vec![Transaction {
+                id: tx,
+                user_id,
+                workspace_id: None,
+                r#type: TransactionType::Expense,
+                amount: Decimal::new(2500000, 2),
+                currency: Currency::IDR,
+                category_id: Some(food),
+                payment_method_id: None,
+                account_id: None,
+                transfer_account_id: None,
+                merchant_name: Some("Warung, \"Bu Sri\"".to_string()),
+                location: None,
+                description: Some("Lunch".to_string()),
+                transaction_date: NaiveDate::from_ymd_opt(2025, 12, 1).unwrap(),
+                source: TransactionSource::Whatsapp,
+                client_id: None,
+                source_message_id: None,
+                attachment_urls: None,
+                metadata: None,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            }],
+            transaction_splits: vec![],
+            tags: vec![Tag {
+                id: tag,
+                user_id,
+                workspace_id: None,
+                name: "work".to_string(),
+                color: None,
+                created_at: now,
+            }],
+            transaction_tags: vec![TransactionTag {
+                transaction_id: tx,
+                tag_id: tag,
+                created_at: now,
+            }],
+            transaction_attachments: vec![],
+            debts: vec![],
+            goals: vec![],
+            goal_contributions: vec![],
+            merchants: vec![],
+            conversations: vec![],
+            api_keys: vec![],
+            audit_logs: vec![],
+        }
+    }
+
+    fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_archive_contains_json_and_csv_files() {
+        let bytes = build_archive(&sample_data()).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "accounts.json", "api_keys.json", "audit_logs.json", "categories.json",
+                "clients.json", "conversations.json", "debts.json", "goal_contributions.json",
+                "goals.json", "merchants.json", "payment_methods.json", "profile.json", "tags.json",
+                "transaction_attachments.json", "transaction_splits.json", "transaction_tags.json",
+                "transactions.csv", "transactions.json",
+            ]
+        );
+
+        // System categories are not the user's data
+        let categories: serde_json::Value =
+            serde_json::from_str(&read_entry(&mut archive, "categories.json")).unwrap();
+        assert_eq!(categories.as_array().unwrap().len(), 1);
+        assert_eq!(categories[0]["name"], "Side Project");
+    }
+
+    #[test]
+    fn test_transactions_csv_resolves_names() {
+        let bytes = build_archive(&sample_data()).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let csv = read_entry(&mut archive, "transactions.csv");
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("id,date,type,amount,currency,category"));
+        assert!(rows[1].contains(
+            ",2025-12-01,expense,25000.00,IDR,Food & Drinks,,,,\"Warung, \"\"Bu Sri\"\"\",,Lunch,work,whatsapp,"
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, RoundingStrategy};
+use crate::{
+    db::models::{Currency, ExchangeRate, RateQuote},
+    error::AppError,
+};
+
+/// In-memory lookup of stored exchange rates, used to convert report amounts into a
+/// user's base currency with the rate in effect on each transaction date.
+#[derive(Debug, Clone)]
+pub struct RateTable {
+    rates: HashMap<(Currency, Currency), BTreeMap<NaiveDate, Decimal>>,
+    currencies: BTreeSet<Currency>,
+    /// How old the latest rate before a date may be and still apply (weekends, holidays)
+    max_age_days: i64,
+}
+
+impl RateTable {
+    pub fn new(max_age_days: i64) -> Self {
+        Self {
+            rates: HashMap::new(),
+            currencies: BTreeSet::new(),
+            max_age_days,
+        }
+    }
+
+    pub fn from_rates(rates: &[ExchangeRate], max_age_days: i64) -> Self {
+        let mut table = Self::new(max_age_days);
+        for rate in rates {
+            table.insert(rate.base_currency, rate.quote_currency, rate.rate_date, rate.rate);
+        }
+        table
+    }
+
+    pub fn insert(&mut self, base: Currency, quote: Currency, date: NaiveDate, rate: Decimal) {
+        if base == quote || rate <= Decimal::ZERO {
+            return;
+        }
+        self.rates.entry((base, quote)).or_default().insert(date, rate);
+        self.currencies.insert(base);
+        self.currencies.insert(quote);
+    }
+
+    /// Units of `to` per unit of `from` on `date`: a stored rate, its inverse, or a
+    /// cross rate through one intermediate currency
+    pub fn rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        if let Some(rate) = self.pair_rate(from, to, date) {
+            return Some(rate);
+        }
+
+        self.currencies
+            .iter()
+            .filter(|pivot| **pivot != from && **pivot != to)
+            .find_map(|pivot| {
+                let first = self.pair_rate(from, *pivot, date)?;
+                let second = self.pair_rate(*pivot, to, date)?;
+                first.checked_mul(second)
+            })
+    }
+
+    /// `amount` in `from` expressed in `to`, rounded to the minor unit of `to`
+    pub fn convert(&self, amount: Decimal, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        if from == to {
+            return Some(amount);
+        }
+
+        let converted = amount.checked_mul(self.rate(from, to, date)?)?;
+        Some(converted.round_dp_with_strategy(to.minor_units(), RoundingStrategy::MidpointAwayFromZero))
+    }
+
+    fn pair_rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        if let Some(rate) = self.latest(from, to, date) {
+            return Some(rate);
+        }
+        self.latest(to, from, date)
+            .and_then(|inverse| Decimal::ONE.checked_div(inverse))
+    }
+
+    fn latest(&self, base: Currency, quote: Currency, date: NaiveDate) -> Option<Decimal> {
+        let (rate_date, rate) = self.rates.get(&(base, quote))?.range(..=date).next_back()?;
+        ((date - *rate_date).num_days() <= self.max_age_days).then_some(*rate)
+    }
+}
+
+/// Parse an exchange-rate CSV with the columns `date,base,quote,rate` (any order,
+/// dates as YYYY-MM-DD). Every invalid row is reported, with its line number.
+pub fn parse_rates_csv(data: &[u8]) -> Result<Vec<RateQuote>, AppError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::BadRequest(format!("Invalid CSV header: {}", e)))?
+        .clone();
+
+    let index_of = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+            .ok_or_else(|| AppError::BadRequest(format!("Column '{}' not found in CSV header", name)))
+    };
+    let date_index = index_of("date")?;
+    let base_index = index_of("base")?;
+    let quote_index = index_of("quote")?;
+    let rate_index = index_of("rate")?;
+
+    let mut quotes = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("Line {}: unreadable row: {}", line, e));
+                continue;
+            }
+        };
+
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+
+        let field = |index: usize| record.get(index).unwrap_or_default();
+
+        let rate_date = NaiveDate::parse_from_str(field(date_index), "%Y-%m-%d");
+        let base_currency = field(base_index).parse::<Currency>();
+        let quote_currency = field(quote_index).parse::<Currency>();
+        let rate = field(rate_index).parse::<Decimal>();
+
+        match (rate_date, base_currency, quote_currency, rate) {
+            (Ok(rate_date), Ok(base_currency), Ok(quote_currency), Ok(rate)) => {
+                if base_currency == quote_currency {
+                    errors.push(format!("Line {}: base and quote currency are both {}", line, base_currency));
+                } else if rate <= Decimal::ZERO {
+                    errors.push(format!("Line {}: rate must be positive", line));
+                } else {
+                    quotes.push(RateQuote { base_currency, quote_currency, rate, rate_date });
+                }
+            }
+            (rate_date, base_currency, quote_currency, rate) => {
+                let mut problems = Vec::new();
+                if rate_date.is_err() {
+                    problems.push(format!("invalid date '{}'", field(date_index)));
+                }
+                if let Err(e) = base_currency {
+                    problems.push(e);
+                }
+                if let Err(e) = quote_currency {
+                    problems.push(e);
+                }
+                if rate.is_err() {
+                    problems.push(format!("invalid rate '{}'", field(rate_index)));
+                }
+                errors.push(format!("Line {}: {}", line, problems.join(", ")));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::ValidationError(errors.join("; ")));
+    }
+    if quotes.is_empty() {
+        return Err(AppError::BadRequest("The file contains no exchange rates".to_string()));
+    }
+
+    Ok(quotes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn currency(code: &str) -> Currency {
+        code.parse().unwrap()
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_rate_uses_latest_rate_within_max_age() {
+        let (usd, idr) = (currency("USD"), Currency::IDR);
+        let mut table = RateTable::new(3);
+        table.insert(usd, idr, date("2025-12-01"), dec("15500"));
+        table.insert(usd, idr, date("2025-12-05"), dec("15700"));
+
+        assert_eq!(table.rate(usd, idr, date("2025-12-01")), Some(dec("15500")));
+        assert_eq!(table.rate(usd, idr, date("2025-12-04")), Some(dec("15500")));
+        assert_eq!(table.rate(usd, idr, date("2025-12-06")), Some(dec("15700")));
+        // Before the first rate, and too long after the last one
+        assert_eq!(table.rate(usd, idr, date("2025-11-30")), None);
+        assert_eq!(table.rate(usd, idr, date("2025-12-09")), None);
+        assert_eq!(table.rate(idr, idr, date("2020-01-01")), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_convert_inverse_and_cross_rates() {
+        let (usd, eur, jpy, idr) = (currency("USD"), currency("EUR"), currency("JPY"), Currency::IDR);
+        let day = date("2025-12-10");
+        let mut table = RateTable::new(7);
+        table.insert(usd, idr, day, dec("16000"));
+        table.insert(eur, usd, day, dec("1.1"));
+        table.insert(usd, jpy, day, dec("150"));
+
+        assert_eq!(table.convert(dec("12.50"), usd, idr, day), Some(dec("200000.00")));
+        assert_eq!(table.convert(dec("32000"), idr, usd, day), Some(dec("2.00")));
+        // EUR -> USD -> IDR
+        assert_eq!(table.convert(dec("10"), eur, idr, day), Some(dec("176000.0")));
+        // JPY has no minor unit
+        assert_eq!(table.convert(dec("3.33"), usd, jpy, day), Some(dec("500")));
+        assert_eq!(table.convert(dec("1"), currency("GBP"), idr, day), None);
+    }
+
+    #[test]
+    fn test_parse_rates_csv_reports_every_invalid_line() {
+        let valid = b"date,base,quote,rate\n2025-12-10,usd,IDR,16000.5\n\n2025-12-10,EUR,USD,1.1\n";
+        let quotes = parse_rates_csv(valid).unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].base_currency, currency("USD"));
+        assert_eq!(quotes[0].rate, dec("16000.5"));
+
+        let invalid = b"rate,quote,base,date\n1.1,USD,EUR,2025-12-10\n0,USD,EUR,2025-12-10\n1,XYZ,USD,10/12/2025\n";
+        let error = parse_rates_csv(invalid).unwrap_err();
+        let AppError::ValidationError(message) = error else { panic!("unexpected error") };
+        assert!(message.contains("Line 3: rate must be positive"));
+        assert!(message.contains("Line 4: invalid date '10/12/2025', 'XYZ' is not an ISO 4217 currency code"));
+        assert!(!message.contains("Line 2"));
+
+        assert!(matches!(parse_rates_csv(b"date,base,rate\n"), Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,52 @@
+use std::{io::ErrorKind, time::Duration};
+use sqlx::PgPool;
+use crate::{config::ExportConfig, db::repositories::DataExportRepository, error::AppError};
+
+/// Expired archives removed per run; the rest wait for the next tick
+const CLEANUP_BATCH_SIZE: i64 = 100;
+
+/// Fail exports whose background job was lost and delete archives past their expiry.
+/// Returns the number of archives deleted.
+pub async fn cleanup_exports(pool: &PgPool, config: &ExportConfig) -> Result<usize, AppError> {
+    let failed = DataExportRepository::fail_stale(pool, config.stale_seconds).await?;
+    if failed > 0 {
+        tracing::warn!("Marked {} interrupted data exports as failed", failed);
+    }
+
+    let mut deleted = 0;
+    for export in DataExportRepository::list_expired_files(pool, CLEANUP_BATCH_SIZE).await? {
+        let Some(file_path) = export.file_path else {
+            continue;
+        };
+
+        match tokio::fs::remove_file(&file_path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                tracing::warn!("Failed to remove export archive {}: {}", file_path, e);
+                continue;
+            }
+        }
+
+        DataExportRepository::clear_file(pool, export.id).await?;
+        deleted += 1;
+    }
+
+    Ok(deleted)
+}
+
+/// Run the cleanup now (recovering exports interrupted by a restart) and then every
+/// `cleanup_interval_seconds`
+pub fn spawn_cleanup(pool: PgPool, config: ExportConfig) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.cleanup_interval_seconds));
+        loop {
+            ticker.tick().await;
+            match cleanup_exports(&pool, &config).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Deleted {} expired export archives", count),
+                Err(e) => tracing::warn!("Failed to clean up data exports: {:?}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,157 @@
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Serialize;
+use crate::db::models::Goal;
+
+/// How a goal stands on a given day
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    pub saved: Decimal,
+    /// Zero once the target is reached
+    pub remaining: Decimal,
+    /// Share of the target saved, in percent with one decimal
+    pub percent: Decimal,
+    /// What saving evenly from the start date to the deadline would have put aside by now
+    pub expected_by_now: Decimal,
+    /// Calendar months until the deadline, counting the current one
+    pub months_left: u32,
+    /// Needed each month from now on to reach the target by the deadline
+    pub required_monthly: Decimal,
+    pub status: GoalStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Achieved,
+    /// Saved at least as much as expected by now
+    OnTrack,
+    Behind,
+    /// The deadline passed before the target was reached
+    Missed,
+}
+
+/// Progress of `goal` on `today`, given the amount `saved` towards it
+pub fn goal_progress(goal: &Goal, saved: Decimal, today: NaiveDate) -> GoalProgress {
+    let precision = goal.currency.minor_units();
+    let target = goal.target_amount;
+    let remaining = (target - saved).max(Decimal::ZERO);
+
+    let percent = (saved.max(Decimal::ZERO) * Decimal::ONE_HUNDRED / target)
+        .round_dp_with_strategy(1, RoundingStrategy::ToZero);
+
+    let total_days = (goal.deadline - goal.start_date).num_days().max(1);
+    let elapsed_days = (today - goal.start_date).num_days().clamp(0, total_days);
+    let expected_by_now = (target * Decimal::from(elapsed_days) / Decimal::from(total_days))
+        .round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero);
+
+    let months_left = if today > goal.deadline {
+        0
+    } else {
+        let months = (goal.deadline.year() - today.year()) * 12 + goal.deadline.month() as i32
+            - today.month() as i32
+            + 1;
+        months.max(1) as u32
+    };
+
+    // Whatever is left is due at once when the deadline has passed
+    let required_monthly = (remaining / Decimal::from(months_left.max(1)))
+        .round_dp_with_strategy(precision, RoundingStrategy::AwayFromZero);
+
+    let status = if remaining.is_zero() {
+        GoalStatus::Achieved
+    } else if today > goal.deadline {
+        GoalStatus::Missed
+    } else if saved >= expected_by_now {
+        GoalStatus::OnTrack
+    } else {
+        GoalStatus::Behind
+    };
+
+    GoalProgress {
+        saved,
+        remaining,
+        percent,
+        expected_by_now,
+        months_left,
+        required_monthly,
+        status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use uuid::Uuid;
+    use crate::db::models::Currency;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn goal(target: &str, start: &str, deadline: &str) -> Goal {
+        let now = date(start).and_hms_opt(0, 0, 0).unwrap();
+        Goal {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Trip to Japan".to_string(),
+            target_amount: dec(target),
+            currency: Currency::IDR,
+            start_date: date(start),
+            deadline: date(deadline),
+            account_id: None,
+            tag_id: None,
+            is_archived: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_progress_on_track_and_behind() {
+        // 100 days from start to deadline, 40 days in
+        let trip = goal("10000000", "2025-12-01", "2026-03-11");
+        let today = date("2026-01-10");
+
+        let progress = goal_progress(&trip, dec("4500000"), today);
+        assert_eq!(progress.expected_by_now, dec("4000000.00"));
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+        assert_eq!(progress.remaining, dec("5500000"));
+        assert_eq!(progress.percent, dec("45.0"));
+        // January, February and March
+        assert_eq!(progress.months_left, 3);
+        assert_eq!(progress.required_monthly, dec("1833333.34"));
+
+        let progress = goal_progress(&trip, dec("1000000"), today);
+        assert_eq!(progress.status, GoalStatus::Behind);
+        assert_eq!(progress.required_monthly, dec("3000000"));
+    }
+
+    #[test]
+    fn test_progress_achieved_and_missed() {
+        let trip = goal("10000000", "2025-12-01", "2026-03-11");
+
+        let progress = goal_progress(&trip, dec("10500000"), date("2026-02-01"));
+        assert_eq!(progress.status, GoalStatus::Achieved);
+        assert_eq!(progress.remaining, Decimal::ZERO);
+        assert_eq!(progress.required_monthly, Decimal::ZERO);
+        assert_eq!(progress.percent, dec("105.0"));
+
+        let progress = goal_progress(&trip, dec("7000000"), date("2026-04-01"));
+        assert_eq!(progress.status, GoalStatus::Missed);
+        assert_eq!(progress.months_left, 0);
+        assert_eq!(progress.expected_by_now, dec("10000000.00"));
+        assert_eq!(progress.required_monthly, dec("3000000"));
+
+        // Before the start nothing is expected yet
+        let progress = goal_progress(&trip, Decimal::ZERO, date("2025-11-20"));
+        assert_eq!(progress.expected_by_now, Decimal::ZERO);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+        assert_eq!(progress.months_left, 5);
+    }
+}
@@ -1,7 +1,11 @@
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use std::{fs, path::Path};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::{pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePrivateKey, traits::PublicKeyParts, RsaPrivateKey};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use crate::config::JwtConfig;
 use crate::db::models::UserRole;
 use crate::error::AppError;
 
@@ -13,6 +17,10 @@ pub struct Claims {
     pub exp: i64,           // expiration timestamp
     pub iat: i64,           // issued at timestamp
     pub token_type: TokenType,
+    #[serde(default)]
+    pub jti: String,        // unique token id (denylist key)
+    #[serde(default)]
+    pub ver: i32,           // users.token_version at issue time
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -22,12 +30,234 @@ pub enum TokenType {
     Refresh,
 }
 
-/// Generate access token (short-lived)
+/// Public key in JWK form, published at /.well-known/jwks.json
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicJwk {
+    pub kty: String,
+    pub kid: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+/// A single access token signing key
+struct SigningKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// None for the HS256 shared secret, which is never published
+    public_jwk: Option<PublicJwk>,
+    retired_at: Option<DateTime<Utc>>,
+}
+
+/// Access token keyring: one active signing key plus retired keys kept for verification
+pub struct JwtKeys {
+    active: usize,
+    keys: Vec<SigningKey>,
+    access_expiry_seconds: i64,
+}
+
+impl JwtKeys {
+    /// Load the keyring from `JwtConfig`: `<kid>.pem` files in `keys_dir`, or the HS256 secret
+    pub fn from_config(config: &JwtConfig) -> Result<Self, String> {
+        let Some(keys_dir) = &config.keys_dir else {
+            return Ok(Self::from_secret(&config.access_secret, config.access_expiry_seconds));
+        };
+
+        let active_kid = config
+            .active_kid
+            .as_deref()
+            .ok_or("JWT_ACTIVE_KID must be set when JWT_KEYS_DIR is set")?;
+
+        let mut pems = Vec::new();
+        let entries = fs::read_dir(keys_dir).map_err(|e| format!("Cannot read {}: {}", keys_dir, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+                continue;
+            }
+
+            let kid = key_id_from_path(&path)?;
+            let pem = fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            let retired_at = config
+                .retired_keys
+                .iter()
+                .find(|(retired_kid, _)| *retired_kid == kid)
+                .map(|(_, retired_at)| *retired_at);
+            pems.push((kid, pem, retired_at));
+        }
+
+        Self::from_pems(pems, active_kid, config.access_expiry_seconds)
+    }
+
+    /// Single HS256 key from a shared secret (no kid, empty JWKS)
+    pub fn from_secret(secret: &str, access_expiry_seconds: i64) -> Self {
+        Self {
+            active: 0,
+            keys: vec![SigningKey {
+                kid: None,
+                algorithm: Algorithm::HS256,
+                encoding: EncodingKey::from_secret(secret.as_bytes()),
+                decoding: DecodingKey::from_secret(secret.as_bytes()),
+                public_jwk: None,
+                retired_at: None,
+            }],
+            access_expiry_seconds,
+        }
+    }
+
+    /// Build the keyring from PEM-encoded private keys (RSA → RS256, Ed25519 → EdDSA)
+    pub fn from_pems(
+        pems: Vec<(String, Vec<u8>, Option<DateTime<Utc>>)>,
+        active_kid: &str,
+        access_expiry_seconds: i64,
+    ) -> Result<Self, String> {
+        let keys = pems
+            .into_iter()
+            .map(|(kid, pem, retired_at)| load_signing_key(kid, &pem, retired_at))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let active = keys
+            .iter()
+            .position(|key| key.kid.as_deref() == Some(active_kid))
+            .ok_or_else(|| format!("No key file for JWT_ACTIVE_KID {}", active_kid))?;
+
+        if keys[active].retired_at.is_some() {
+            return Err(format!("Active key {} is listed as retired", active_kid));
+        }
+
+        Ok(Self {
+            active,
+            keys,
+            access_expiry_seconds,
+        })
+    }
+
+    /// Keys still usable for verification: active, or retired recently enough that
+    /// tokens they signed may not have expired yet
+    fn verifying_keys(&self) -> impl Iterator<Item = &SigningKey> {
+        let cutoff = Utc::now() - Duration::seconds(self.access_expiry_seconds);
+        self.keys
+            .iter()
+            .filter(move |key| key.retired_at.map(|retired_at| retired_at > cutoff).unwrap_or(true))
+    }
+
+    /// Public keys for the JWKS endpoint
+    pub fn jwks(&self) -> Vec<PublicJwk> {
+        self.verifying_keys()
+            .filter_map(|key| key.public_jwk.clone())
+            .collect()
+    }
+
+    fn sign(&self, claims: &Claims) -> Result<String, AppError> {
+        let key = &self.keys[self.active];
+        let mut header = Header::new(key.algorithm);
+        header.kid = key.kid.clone();
+
+        Ok(encode(&header, claims, &key.encoding)?)
+    }
+
+    fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        let header = decode_header(token)?;
+
+        let key = self
+            .verifying_keys()
+            .find(|key| key.kid == header.kid && key.algorithm == header.alg)
+            .ok_or(AppError::TokenInvalid)?;
+
+        let claims = decode::<Claims>(token, &key.decoding, &Validation::new(key.algorithm))?.claims;
+
+        // A retired key only vouches for tokens it signed before it was rotated out
+        if let Some(retired_at) = key.retired_at
+            && claims.iat > retired_at.timestamp()
+        {
+            return Err(AppError::TokenInvalid);
+        }
+
+        Ok(claims)
+    }
+}
+
+fn key_id_from_path(path: &Path) -> Result<String, String> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid key file name {}", path.display()))
+}
+
+fn load_signing_key(
+    kid: String,
+    pem: &[u8],
+    retired_at: Option<DateTime<Utc>>,
+) -> Result<SigningKey, String> {
+    let pem_str = std::str::from_utf8(pem).map_err(|_| format!("Key {} is not valid PEM", kid))?;
+
+    let rsa_key = RsaPrivateKey::from_pkcs8_pem(pem_str)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem_str));
+    if let Ok(rsa_key) = rsa_key {
+        let n = URL_SAFE_NO_PAD.encode(rsa_key.n().to_bytes_be());
+        let e = URL_SAFE_NO_PAD.encode(rsa_key.e().to_bytes_be());
+
+        return Ok(SigningKey {
+            encoding: EncodingKey::from_rsa_pem(pem).map_err(|e| format!("Key {}: {}", kid, e))?,
+            decoding: DecodingKey::from_rsa_components(&n, &e).map_err(|e| format!("Key {}: {}", kid, e))?,
+            algorithm: Algorithm::RS256,
+            public_jwk: Some(PublicJwk {
+                kty: "RSA".to_string(),
+                kid: kid.clone(),
+                key_use: "sig".to_string(),
+                alg: "RS256".to_string(),
+                n: Some(n),
+                e: Some(e),
+                crv: None,
+                x: None,
+            }),
+            kid: Some(kid),
+            retired_at,
+        });
+    }
+
+    if let Ok(ed_key) = ed25519_dalek::SigningKey::from_pkcs8_pem(pem_str) {
+        let x = URL_SAFE_NO_PAD.encode(ed_key.verifying_key().to_bytes());
+
+        return Ok(SigningKey {
+            encoding: EncodingKey::from_ed_pem(pem).map_err(|e| format!("Key {}: {}", kid, e))?,
+            decoding: DecodingKey::from_ed_components(&x).map_err(|e| format!("Key {}: {}", kid, e))?,
+            algorithm: Algorithm::EdDSA,
+            public_jwk: Some(PublicJwk {
+                kty: "OKP".to_string(),
+                kid: kid.clone(),
+                key_use: "sig".to_string(),
+                alg: "EdDSA".to_string(),
+                n: None,
+                e: None,
+                crv: Some("Ed25519".to_string()),
+                x: Some(x),
+            }),
+            kid: Some(kid),
+            retired_at,
+        });
+    }
+
+    Err(format!("Key {} is neither an RSA nor an Ed25519 private key", kid))
+}
+
+/// Generate access token (short-lived), signed with the active key
 pub fn generate_access_token(
     user_id: Uuid,
     email: &str,
     role: &UserRole,
-    secret: &str,
+    token_version: i32,
+    keys: &JwtKeys,
     expiry_seconds: i64,
 ) -> Result<String, AppError> {
     let now = Utc::now();
@@ -40,15 +270,23 @@ pub fn generate_access_token(
         exp: exp.timestamp(),
         iat: now.timestamp(),
         token_type: TokenType::Access,
+        jti: Uuid::new_v4().to_string(),
+        ver: token_version,
     };
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )?;
+    keys.sign(&claims)
+}
 
-    Ok(token)
+/// Validate and decode an access token against the keyring
+pub fn validate_access_token(token: &str, keys: &JwtKeys) -> Result<Claims, AppError> {
+    let claims = keys.verify(token)?;
+
+    // Verify token type
+    if claims.token_type != TokenType::Access {
+        return Err(AppError::TokenInvalid);
+    }
+
+    Ok(claims)
 }
 
 /// Generate refresh token (long-lived)
@@ -56,6 +294,7 @@ pub fn generate_refresh_token(
     user_id: Uuid,
     email: &str,
     role: &UserRole,
+    token_version: i32,
     secret: &str,
     expiry_seconds: i64,
 ) -> Result<String, AppError> {
@@ -69,6 +308,8 @@ pub fn generate_refresh_token(
         exp: exp.timestamp(),
         iat: now.timestamp(),
         token_type: TokenType::Refresh,
+        jti: Uuid::new_v4().to_string(),
+        ver: token_version,
     };
 
     let token = encode(
@@ -104,3 +345,109 @@ pub fn validate_token(
 pub fn extract_user_id(claims: &Claims) -> Result<Uuid, AppError> {
     Uuid::parse_str(&claims.sub).map_err(|_| AppError::TokenInvalid)
 }
+
+/// Extract the token id (jti) from token
+pub fn extract_jti(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.jti).map_err(|_| AppError::TokenInvalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSA_KEY: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/keys/rsa_private.pem"));
+    const RSA_KEY_2: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/keys/rsa_private_2.pem"));
+    const ED25519_KEY: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/keys/ed25519_private.pem"));
+
+    fn token(keys: &JwtKeys) -> String {
+        generate_access_token(Uuid::new_v4(), "budi@example.com", &UserRole::User, 0, keys, 900).unwrap()
+    }
+
+    #[test]
+    fn test_hs256_secret_round_trip() {
+        let keys = JwtKeys::from_secret("test-secret", 900);
+
+        let claims = validate_access_token(&token(&keys), &keys).unwrap();
+        assert_eq!(claims.email, "budi@example.com");
+        assert!(keys.jwks().is_empty());
+    }
+
+    #[test]
+    fn test_tokens_carry_unique_jti_and_version() {
+        let keys = JwtKeys::from_secret("test-secret", 900);
+        let user_id = Uuid::new_v4();
+
+        let first = generate_access_token(user_id, "budi@example.com", &UserRole::User, 3, &keys, 900).unwrap();
+        let second = generate_access_token(user_id, "budi@example.com", &UserRole::User, 3, &keys, 900).unwrap();
+        assert_ne!(first, second);
+
+        let claims = validate_access_token(&first, &keys).unwrap();
+        assert_eq!(claims.ver, 3);
+        assert!(extract_jti(&claims).is_ok());
+    }
+
+    #[test]
+    fn test_asymmetric_keys_sign_and_publish_jwks() {
+        for (kid, pem, kty) in [("rsa", RSA_KEY, "RSA"), ("ed", ED25519_KEY, "OKP")] {
+            let keys = JwtKeys::from_pems(vec![(kid.to_string(), pem.to_vec(), None)], kid, 900).unwrap();
+
+            let token = token(&keys);
+            assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some(kid));
+            assert!(validate_access_token(&token, &keys).is_ok());
+
+            let jwks = keys.jwks();
+            assert_eq!(jwks.len(), 1);
+            assert_eq!(jwks[0].kty, kty);
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_tokens_valid() {
+        let old = JwtKeys::from_pems(vec![("2025-11".to_string(), RSA_KEY.to_vec(), None)], "2025-11", 900)
+            .unwrap();
+        let old_token = token(&old);
+
+        // Rotate: new active key, old key retired just now
+        let rotated = JwtKeys::from_pems(
+            vec![
+                ("2025-11".to_string(), RSA_KEY.to_vec(), Some(Utc::now() + Duration::seconds(1))),
+                ("2025-12".to_string(), RSA_KEY_2.to_vec(), None),
+            ],
+            "2025-12",
+            900,
+        )
+        .unwrap();
+
+        assert!(validate_access_token(&old_token, &rotated).is_ok());
+        assert!(validate_access_token(&token(&rotated), &rotated).is_ok());
+        assert_eq!(rotated.jwks().len(), 2);
+
+        // Once every token the old key signed has expired, it is dropped entirely
+        let expired = JwtKeys::from_pems(
+            vec![
+                ("2025-11".to_string(), RSA_KEY.to_vec(), Some(Utc::now() - Duration::seconds(901))),
+                ("2025-12".to_string(), RSA_KEY_2.to_vec(), None),
+            ],
+            "2025-12",
+            900,
+        )
+        .unwrap();
+
+        assert!(validate_access_token(&old_token, &expired).is_err());
+        assert_eq!(expired.jwks().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_kid_is_rejected() {
+        let keys = JwtKeys::from_pems(vec![("a".to_string(), RSA_KEY.to_vec(), None)], "a", 900).unwrap();
+        let other = JwtKeys::from_pems(vec![("b".to_string(), RSA_KEY_2.to_vec(), None)], "b", 900).unwrap();
+
+        assert!(matches!(
+            validate_access_token(&token(&other), &keys),
+            Err(AppError::TokenInvalid)
+        ));
+    }
+}
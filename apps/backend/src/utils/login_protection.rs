@@ -0,0 +1,95 @@
+use chrono::{Duration, NaiveDateTime};
+use crate::config::LoginProtectionConfig;
+
+/// Lockout duration after `failed_attempts` consecutive failures.
+/// Returns None while the account is still below the threshold; beyond it the
+/// duration doubles with every further failure, capped at `lockout_max_seconds`.
+pub fn lockout_duration(failed_attempts: i32, config: &LoginProtectionConfig) -> Option<Duration> {
+    if failed_attempts < config.max_failed_attempts {
+        return None;
+    }
+
+    let exponent = (failed_attempts - config.max_failed_attempts).min(30) as u32;
+    let seconds = config
+        .lockout_base_seconds
+        .saturating_mul(1i64 << exponent)
+        .min(config.lockout_max_seconds);
+
+    Some(Duration::seconds(seconds))
+}
+
+/// Seconds left of the lockout put in place by `failed_attempts` consecutive failures, the
+/// last one at `last_failure`; None when it is over or there is none
+pub fn lockout_remaining(
+    failed_attempts: i32,
+    last_failure: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+    config: &LoginProtectionConfig,
+) -> Option<i64> {
+    let locked_until = last_failure? + lockout_duration(failed_attempts, config)?;
+    (locked_until > now).then(|| (locked_until - now).num_seconds().max(1))
+}
+
+/// Seconds until the oldest failure in the IP window expires, if the IP is over its limit
+pub fn ip_retry_after(
+    recent_failures: i64,
+    oldest_failure: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+    config: &LoginProtectionConfig,
+) -> Option<i64> {
+    if recent_failures < config.ip_max_failed_attempts {
+        return None;
+    }
+
+    let window_end = oldest_failure? + Duration::seconds(config.ip_window_seconds);
+    Some((window_end - now).num_seconds().max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn config() -> LoginProtectionConfig {
+        LoginProtectionConfig {
+            max_failed_attempts: 5,
+            lockout_base_seconds: 60,
+            lockout_max_seconds: 3600,
+            ip_max_failed_attempts: 20,
+            ip_window_seconds: 900,
+        }
+    }
+
+    #[test]
+    fn test_lockout_duration_backoff() {
+        let config = config();
+
+        assert_eq!(lockout_duration(4, &config), None);
+        assert_eq!(lockout_duration(5, &config), Some(Duration::seconds(60)));
+        assert_eq!(lockout_duration(6, &config), Some(Duration::seconds(120)));
+        assert_eq!(lockout_duration(8, &config), Some(Duration::seconds(480)));
+        assert_eq!(lockout_duration(50, &config), Some(Duration::seconds(3600)));
+    }
+
+    #[test]
+    fn test_lockout_remaining() {
+        let config = config();
+        let now = Utc::now().naive_utc();
+        let last = now - Duration::seconds(20);
+
+        assert_eq!(lockout_remaining(4, Some(last), now, &config), None);
+        assert_eq!(lockout_remaining(5, Some(last), now, &config), Some(40));
+        assert_eq!(lockout_remaining(5, Some(now - Duration::seconds(90)), now, &config), None);
+        assert_eq!(lockout_remaining(5, None, now, &config), None);
+    }
+
+    #[test]
+    fn test_ip_retry_after() {
+        let config = config();
+        let now = Utc::now().naive_utc();
+        let oldest = now - Duration::seconds(300);
+
+        assert_eq!(ip_retry_after(19, Some(oldest), now, &config), None);
+        assert_eq!(ip_retry_after(20, Some(oldest), now, &config), Some(600));
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use axum::async_trait;
+use serde_json::json;
+
+use crate::{config::MessagingGatewayKind, db::models::Client, error::AppError};
+
+/// Sends a message to a user's chat client
+#[async_trait]
+pub trait MessagingGateway: Send + Sync {
+    async fn send(&self, client: &Client, text: &str) -> Result<(), AppError>;
+}
+
+/// Build the gateway selected in the configuration; None when messaging is disabled
+pub fn from_config(kind: &MessagingGatewayKind, http: reqwest::Client) -> Option<Arc<dyn MessagingGateway>> {
+    match kind {
+        MessagingGatewayKind::None => None,
+        MessagingGatewayKind::Http { url, token } => Some(Arc::new(HttpGateway {
+            http,
+            url: url.clone(),
+            token: token.clone(),
+        })),
+    }
+}
+
+/// The client a user is best reached on: an active, verified one, most recently used first
+pub fn preferred_client(clients: &[Client]) -> Option<&Client> {
+    clients
+        .iter()
+        .filter(|client| client.can_interact())
+        .max_by_key(|client| client.last_interaction_at)
+}
+
+/// Posts `{channel, phone_number, country_code, text}` to the chatbot service, which
+/// delivers it on the client's channel
+pub struct HttpGateway {
+    http: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+#[async_trait]
+impl MessagingGateway for HttpGateway {
+    async fn send(&self, client: &Client, text: &str) -> Result<(), AppError> {
+        let mut request = self.http.post(&self.url).json(&json!({
+            "channel": client.channel,
+            "phone_number": client.phone_number,
+            "country_code": client.country_code,
+            "text": text,
+        }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalService(format!(
+                "Messaging gateway responded with {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+    use chrono::NaiveDate;
+    use serde_json::Value;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+    use crate::db::models::Channel;
+
+    fn client(verified: bool, last_interaction_day: Option<u32>) -> Client {
+        let now = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        Client {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            phone_number: "81234567890".to_string(),
+            country_code: "62".to_string(),
+            channel: Channel::Whatsapp,
+            is_verified: verified,
+            is_active: true,
+            last_interaction_at: last_interaction_day
+                .map(|day| NaiveDate::from_ymd_opt(2025, 12, day).unwrap().and_hms_opt(9, 0, 0).unwrap()),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_prefers_most_recently_used_verified_client() {
+        let clients = vec![client(true, Some(3)), client(false, Some(20)), client(true, Some(10)), client(true, None)];
+
+        assert_eq!(preferred_client(&clients).unwrap().id, clients[2].id);
+        assert!(preferred_client(&[client(false, Some(1))]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_gateway_posts_message() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/messages", listener.local_addr().unwrap());
+        let (sender, mut received) = mpsc::unbounded_channel::<(Option<String>, Value)>();
+
+        let app = Router::new()
+            .route(
+                "/messages",
+                post(
+                    |State(sender): State<mpsc::UnboundedSender<(Option<String>, Value)>>,
+                     headers: HeaderMap,
+                     Json(body): Json<Value>| async move {
+                        let auth = headers
+                            .get("authorization")
+                            .and_then(|value| value.to_str().ok())
+                            .map(str::to_string);
+                        sender.send((auth, body)).unwrap();
+                    },
+                ),
+            )
+            .with_state(sender);
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let gateway = from_config(
+            &MessagingGatewayKind::Http { url, token: Some("chatbot-token".to_string()) },
+            reqwest::Client::new(),
+        )
+        .unwrap();
+        gateway.send(&client(true, None), "Halo").await.unwrap();
+
+        let (auth, body) = received.recv().await.unwrap();
+        assert_eq!(auth.as_deref(), Some("Bearer chatbot-token"));
+        assert_eq!(body["channel"], "whatsapp");
+        assert_eq!(body["phone_number"], "81234567890");
+        assert_eq!(body["text"], "Halo");
+    }
+}
@@ -1,3 +1,28 @@
 pub mod password;
 pub mod jwt;
 pub mod api_key;
+pub mod login_protection;
+pub mod oidc;
+pub mod token_denylist;
+pub mod signed_url;
+pub mod data_export;
+pub mod account_purge;
+pub mod export_cleanup;
+pub mod csv_import;
+pub mod transaction_export;
+pub mod statement_import;
+pub mod exchange_rates;
+pub mod rate_provider;
+pub mod transaction_splits;
+pub mod goals;
+pub mod storage;
+pub mod receipts;
+pub mod receipt_parser;
+pub mod ocr;
+pub mod receipt_ocr;
+pub mod transaction_search;
+pub mod timeseries;
+pub mod messaging;
+pub mod anomaly_detection;
+pub mod transaction_duplicates;
+pub mod webhooks;
@@ -0,0 +1,27 @@
+pub mod tesseract;
+
+use std::sync::Arc;
+use axum::async_trait;
+
+use crate::{config::OcrEngineKind, error::AppError};
+use self::tesseract::TesseractEngine;
+
+/// Reads the text of a receipt image
+#[async_trait]
+pub trait OcrEngine: Send + Sync {
+    /// Stored with every draft this engine produces
+    fn name(&self) -> &str;
+
+    /// Plain text of the image, one printed line per line
+    async fn recognize(&self, image: &[u8]) -> Result<String, AppError>;
+}
+
+/// Build the engine selected in the configuration; None when OCR is disabled
+pub fn from_config(kind: &OcrEngineKind) -> Option<Arc<dyn OcrEngine>> {
+    match kind {
+        OcrEngineKind::None => None,
+        OcrEngineKind::Tesseract { command, languages } => {
+            Some(Arc::new(TesseractEngine::new(command, languages)))
+        }
+    }
+}
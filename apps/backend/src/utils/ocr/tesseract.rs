@@ -0,0 +1,107 @@
+use std::{process::Stdio, time::Duration};
+use axum::async_trait;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::error::AppError;
+use super::OcrEngine;
+
+/// Longest a single recognition may take
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Runs the `tesseract` command line tool, passing the image on stdin
+pub struct TesseractEngine {
+    command: String,
+    languages: String,
+}
+
+impl TesseractEngine {
+    pub fn new(command: impl Into<String>, languages: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            languages: languages.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl OcrEngine for TesseractEngine {
+    fn name(&self) -> &str {
+        "tesseract"
+    }
+
+    async fn recognize(&self, image: &[u8]) -> Result<String, AppError> {
+        // Page segmentation mode 4: a single column of text of variable sizes, as on a receipt
+        let mut child = Command::new(&self.command)
+            .args(["stdin", "stdout", "-l", &self.languages, "--psm", "4"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AppError::Internal(format!("Failed to start {}: {}", self.command, e)))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| AppError::Internal("tesseract stdin is not available".to_string()))?;
+        let image = image.to_vec();
+        let writer = tokio::spawn(async move {
+            stdin.write_all(&image).await?;
+            stdin.shutdown().await
+        });
+
+        let output = tokio::time::timeout(TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| AppError::ExternalService("tesseract timed out".to_string()))??;
+        // The tool may exit before reading everything, e.g. on an unreadable image
+        let _ = writer.await;
+
+        if !output.status.success() {
+            return Err(AppError::ExternalService(format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use uuid::Uuid;
+    use super::*;
+
+    /// A stand-in for the tool that checks its arguments and prints fixed text
+    async fn fake_tesseract(script: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("fake-tesseract-{}", Uuid::new_v4()));
+        tokio::fs::write(&path, script).await.unwrap();
+        tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_recognize_reads_stdout() {
+        let path = fake_tesseract(
+            "#!/bin/sh\n[ \"$1 $2 $3 $4\" = \"stdin stdout -l ind+eng\" ] || exit 2\ncat > /dev/null\necho 'TOTAL 22.500'\n",
+        )
+        .await;
+        let engine = TesseractEngine::new(path.to_string_lossy(), "ind+eng");
+
+        assert_eq!(engine.recognize(b"image").await.unwrap(), "TOTAL 22.500\n");
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recognize_reports_failures() {
+        let path = fake_tesseract("#!/bin/sh\necho 'Error in pixReadStream' >&2\nexit 1\n").await;
+        let engine = TesseractEngine::new(path.to_string_lossy(), "ind+eng");
+
+        let result = engine.recognize(b"not an image").await;
+        assert!(matches!(result, Err(AppError::ExternalService(message)) if message.contains("pixReadStream")));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
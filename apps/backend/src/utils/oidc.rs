@@ -0,0 +1,384 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{AlgorithmParameters, Jwk, JwkSet, PublicKeyUse},
+    Algorithm, DecodingKey, Validation,
+};
+use rand::Rng;
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use crate::config::OidcProviderConfig;
+use crate::error::AppError;
+
+/// Provider metadata from `/.well-known/openid-configuration`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Claims read from a verified ID token
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// PKCE verifier and its S256 challenge
+pub struct Pkce {
+    pub code_verifier: String,
+    pub code_challenge: String,
+}
+
+/// Generate a URL-safe random token (used for state and nonce)
+pub fn random_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().r#gen();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Generate a PKCE code verifier with its S256 code challenge
+pub fn generate_pkce() -> Pkce {
+    let code_verifier = random_token();
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+    Pkce {
+        code_verifier,
+        code_challenge,
+    }
+}
+
+/// Fetch provider metadata via OIDC discovery
+pub async fn discover(
+    http: &reqwest::Client,
+    provider: &OidcProviderConfig,
+) -> Result<ProviderMetadata, AppError> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        provider.issuer_url.trim_end_matches('/')
+    );
+
+    let metadata = http
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<ProviderMetadata>()
+        .await?;
+
+    Ok(metadata)
+}
+
+/// Build the authorization URL the dashboard redirects the browser to
+pub fn authorization_url(
+    metadata: &ProviderMetadata,
+    provider: &OidcProviderConfig,
+    state: &str,
+    nonce: &str,
+    code_challenge: &str,
+) -> Result<String, AppError> {
+    let mut url = Url::parse(&metadata.authorization_endpoint)
+        .map_err(|e| AppError::ExternalService(format!("Invalid authorization endpoint: {}", e)))?;
+
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &provider.redirect_uri)
+        .append_pair("scope", &provider.scopes)
+        .append_pair("state", state)
+        .append_pair("nonce", nonce)
+        .append_pair("code_challenge", code_challenge)
+        .append_pair("code_challenge_method", "S256");
+
+    Ok(url.into())
+}
+
+/// Exchange an authorization code for tokens and return the verified ID token claims
+pub async fn exchange_code(
+    http: &reqwest::Client,
+    metadata: &ProviderMetadata,
+    provider: &OidcProviderConfig,
+    code: &str,
+    code_verifier: &str,
+    expected_nonce: &str,
+) -> Result<IdTokenClaims, AppError> {
+    let response = http
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", provider.redirect_uri.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await?;
+
+    // A rejected code is the client's problem, not an upstream outage
+    if response.status().is_client_error() {
+        return Err(AppError::Unauthorized("Authorization code was rejected".to_string()));
+    }
+
+    let tokens = response.error_for_status()?.json::<TokenResponse>().await?;
+    let jwks = http
+        .get(&metadata.jwks_uri)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<JwkSet>()
+        .await?;
+
+    verify_id_token(&tokens.id_token, &jwks, metadata, provider, expected_nonce)
+}
+
+/// Verify an ID token's signature, issuer, audience, expiry and nonce
+pub fn verify_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    metadata: &ProviderMetadata,
+    provider: &OidcProviderConfig,
+    expected_nonce: &str,
+) -> Result<IdTokenClaims, AppError> {
+    let header = decode_header(id_token)?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[&provider.client_id]);
+    validation.set_issuer(&[&metadata.issuer]);
+
+    // Without a kid, try every signing key that fits the token's algorithm rather than
+    // depending on the order of the key set
+    let candidates: Vec<&Jwk> = match header.kid.as_deref() {
+        Some(kid) => jwks.find(kid).into_iter().collect(),
+        None => jwks.keys.iter().filter(|jwk| verifies(jwk, header.alg)).collect(),
+    };
+
+    let mut result = Err(AppError::TokenInvalid);
+    for jwk in candidates {
+        result = decode::<IdTokenClaims>(id_token, &DecodingKey::from_jwk(jwk)?, &validation)
+            .map(|data| data.claims)
+            .map_err(AppError::from);
+        if result.is_ok() {
+            break;
+        }
+    }
+    let claims = result?;
+
+    if claims.nonce.as_deref() != Some(expected_nonce) {
+        return Err(AppError::TokenInvalid);
+    }
+
+    Ok(claims)
+}
+
+/// Whether a key from the provider's JWKS can verify signatures made with `alg`
+fn verifies(jwk: &Jwk, alg: Algorithm) -> bool {
+    if jwk.common.public_key_use.as_ref().is_some_and(|key_use| *key_use != PublicKeyUse::Signature) {
+        return false;
+    }
+    if let Some(key_algorithm) = jwk.common.key_algorithm
+        && key_algorithm.to_string() != format!("{:?}", alg)
+    {
+        return false;
+    }
+
+    match jwk.algorithm {
+        AlgorithmParameters::RSA(_) => matches!(
+            alg,
+            Algorithm::RS256
+                | Algorithm::RS384
+                | Algorithm::RS512
+                | Algorithm::PS256
+                | Algorithm::PS384
+                | Algorithm::PS512
+        ),
+        AlgorithmParameters::EllipticCurve(_) => matches!(alg, Algorithm::ES256 | Algorithm::ES384),
+        AlgorithmParameters::OctetKeyPair(_) => alg == Algorithm::EdDSA,
+        // Providers never publish shared secrets
+        AlgorithmParameters::OctetKey(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::{get, post}, Form, Json, Router};
+    use chrono::Utc;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    const PRIVATE_KEY: &str =
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/keys/rsa_private.pem"));
+    const MODULUS: &str = "uKDtNldhN9R3ebgoQYMSn5dwpSGd_8Cju71Dt7Thj9WW46yXVcqDz2OoEKkJuYNmX3MRLoDPC7qSEwONyFLJo84bOQZZfH0YAlqOITzDDzcWYRnRkPqF2-sVAaDNquqH-xiWY7Wa2wygar9L4rEkaVr2nrFfstOWz31RSrXyiP0mSQXAbgXqL09H8LgVCHVVjyWOvThcY6ZDBsB7WuAL2Vp7Erzy5d4abu2u-QyTDArmdLtwVqWbX5_rehhSaow34OsFvvSobaRptaIfu6yjkMJthiK8XFeasY_w8y9qqs5FmorpWRfqdqcYZ1fmYt6UEESYH5wSSb3_Wl7uT_yafQ";
+
+    /// Minimal OIDC provider: discovery, JWKS and a token endpoint that signs an ID token
+    async fn spawn_mock_provider() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let app = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(|State(issuer): State<String>| async move {
+                    Json(json!({
+                        "issuer": issuer,
+                        "authorization_endpoint": format!("{}/authorize", issuer),
+                        "token_endpoint": format!("{}/token", issuer),
+                        "jwks_uri": format!("{}/jwks", issuer),
+                    }))
+                }),
+            )
+            .route(
+                "/jwks",
+                get(|| async {
+                    Json(json!({
+                        "keys": [{ "kty": "RSA", "kid": "test-key", "alg": "RS256", "use": "sig", "n": MODULUS, "e": "AQAB" }]
+                    }))
+                }),
+            )
+            .route(
+                "/token",
+                post(|State(issuer): State<String>, Form(form): Form<HashMap<String, String>>| async move {
+                    // The code doubles as the nonce so the test controls it
+                    assert_eq!(form["grant_type"], "authorization_code");
+                    assert_eq!(form["code_verifier"].len(), 43);
+
+                    let mut header = Header::new(Algorithm::RS256);
+                    header.kid = Some("test-key".to_string());
+                    let claims = json!({
+                        "iss": issuer,
+                        "aud": "mintora-dashboard",
+                        "sub": "provider-user-1",
+                        "email": "budi@example.com",
+                        "email_verified": true,
+                        "name": "Budi Santoso",
+                        "nonce": form["code"],
+                        "iat": Utc::now().timestamp(),
+                        "exp": Utc::now().timestamp() + 300,
+                    });
+                    let id_token = encode(
+                        &header,
+                        &claims,
+                        &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap(),
+                    )
+                    .unwrap();
+
+                    Json::<Value>(json!({ "access_token": "opaque", "token_type": "Bearer", "id_token": id_token }))
+                }),
+            )
+            .with_state(issuer.clone());
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        issuer
+    }
+
+    fn provider(issuer_url: &str) -> OidcProviderConfig {
+        OidcProviderConfig {
+            name: "mock".to_string(),
+            issuer_url: issuer_url.to_string(),
+            client_id: "mintora-dashboard".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "http://localhost:4200/auth/callback/mock".to_string(),
+            scopes: "openid email profile".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pkce_challenge_is_s256_of_verifier() {
+        let pkce = generate_pkce();
+
+        assert_eq!(pkce.code_verifier.len(), 43);
+        assert_eq!(
+            pkce.code_challenge,
+            URL_SAFE_NO_PAD.encode(Sha256::digest(pkce.code_verifier.as_bytes()))
+        );
+    }
+
+    #[test]
+    fn test_id_token_without_kid_tries_every_matching_key() {
+        let provider = provider("https://issuer.example.com");
+        let metadata = ProviderMetadata {
+            issuer: provider.issuer_url.clone(),
+            authorization_endpoint: format!("{}/authorize", provider.issuer_url),
+            token_endpoint: format!("{}/token", provider.issuer_url),
+            jwks_uri: format!("{}/jwks", provider.issuer_url),
+        };
+        let claims = json!({
+            "iss": metadata.issuer,
+            "aud": "mintora-dashboard",
+            "sub": "provider-user-1",
+            "nonce": "nonce-1",
+            "exp": Utc::now().timestamp() + 300,
+        });
+        let id_token = encode(
+            &Header::new(Algorithm::RS256),
+            &claims,
+            &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap(),
+        )
+        .unwrap();
+
+        // The signing key comes last, behind an encryption key and another signing key
+        let other_modulus = MODULUS.replacen('u', "v", 1);
+        let jwks: JwkSet = serde_json::from_value(json!({
+            "keys": [
+                { "kty": "RSA", "kid": "enc", "use": "enc", "n": MODULUS, "e": "AQAB" },
+                { "kty": "RSA", "kid": "old", "alg": "RS256", "n": other_modulus, "e": "AQAB" },
+                { "kty": "RSA", "kid": "new", "alg": "RS256", "n": MODULUS, "e": "AQAB" },
+            ]
+        }))
+        .unwrap();
+
+        let verified = verify_id_token(&id_token, &jwks, &metadata, &provider, "nonce-1").unwrap();
+        assert_eq!(verified.sub, "provider-user-1");
+
+        let without_signing_key = JwkSet {
+            keys: jwks.keys[..2].to_vec(),
+        };
+        assert!(matches!(
+            verify_id_token(&id_token, &without_signing_key, &metadata, &provider, "nonce-1"),
+            Err(AppError::TokenInvalid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_login_flow_against_mock_provider() {
+        let issuer = spawn_mock_provider().await;
+        let provider = provider(&issuer);
+        let http = reqwest::Client::new();
+
+        let metadata = discover(&http, &provider).await.unwrap();
+        let pkce = generate_pkce();
+        let url = authorization_url(&metadata, &provider, "state-1", "nonce-1", &pkce.code_challenge)
+            .unwrap();
+        assert!(url.starts_with(&format!("{}/authorize?response_type=code", issuer)));
+        assert!(url.contains("code_challenge_method=S256"));
+
+        let claims = exchange_code(&http, &metadata, &provider, "nonce-1", &pkce.code_verifier, "nonce-1")
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "provider-user-1");
+        assert_eq!(claims.email.as_deref(), Some("budi@example.com"));
+
+        let replayed =
+            exchange_code(&http, &metadata, &provider, "nonce-1", &pkce.code_verifier, "other-nonce").await;
+        assert!(matches!(replayed, Err(AppError::TokenInvalid)));
+    }
+}
@@ -0,0 +1,60 @@
+use std::{future::Future, path::PathBuf, time::Duration};
+use sqlx::PgPool;
+use crate::{
+    db::{models::RateQuote, repositories::ExchangeRateRepository},
+    error::AppError,
+    utils::exchange_rates::parse_rates_csv,
+};
+
+/// A source of exchange rates that is synced into the `exchange_rates` table periodically.
+/// Implementations return whatever rates they currently know; existing rows for the same
+/// pair and date are overwritten.
+pub trait ExchangeRateProvider: Send + Sync {
+    /// Stored as the `source` of every rate this provider supplies
+    fn name(&self) -> &str;
+
+    fn fetch(&self) -> impl Future<Output = Result<Vec<RateQuote>, AppError>> + Send;
+}
+
+/// Reads rates from a local CSV file (`date,base,quote,rate`), e.g. one dropped in place
+/// by a cron job or kept by hand for development
+pub struct FileRateProvider {
+    path: PathBuf,
+}
+
+impl FileRateProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ExchangeRateProvider for FileRateProvider {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn fetch(&self) -> Result<Vec<RateQuote>, AppError> {
+        let data = tokio::fs::read(&self.path).await?;
+        parse_rates_csv(&data)
+    }
+}
+
+/// Fetch rates from `provider` and store them. Returns the number of rates written.
+pub async fn sync_rates<P: ExchangeRateProvider>(pool: &PgPool, provider: &P) -> Result<u64, AppError> {
+    let quotes = provider.fetch().await?;
+    ExchangeRateRepository::upsert_many(pool, &quotes, provider.name()).await
+}
+
+/// Run the sync every `interval`
+pub fn spawn_sync<P: ExchangeRateProvider + 'static>(pool: PgPool, provider: P, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match sync_rates(&pool, &provider).await {
+                Ok(count) => tracing::info!("Synced {} exchange rates from {}", count, provider.name()),
+                Err(e) => tracing::warn!("Failed to sync exchange rates from {}: {:?}", provider.name(), e),
+            }
+        }
+    });
+}
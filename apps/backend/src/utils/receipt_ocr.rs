@@ -0,0 +1,141 @@
+use std::{sync::Arc, time::Duration};
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{
+    config::OcrConfig,
+    db::{
+        models::{Currency, ReceiptDraftResult, ReceiptDraftStatus, ReceiptOcrJob},
+        repositories::ReceiptDraftRepository,
+    },
+    error::AppError,
+    utils::{ocr::OcrEngine, receipt_parser::parse_receipt, storage::StorageBackend, webhooks::retry_delay},
+};
+
+/// Drafts claimed longer ago than this are taken to be abandoned by a stopped worker
+const STALE_CLAIM_SECONDS: i64 = 600;
+
+/// Read every due receipt image into its draft; failed drafts wait out a growing delay
+/// before their next attempt. Returns the number of drafts handled.
+pub async fn process_queue(
+    pool: &PgPool,
+    storage: &dyn StorageBackend,
+    engine: &dyn OcrEngine,
+    config: &OcrConfig,
+) -> Result<usize, AppError> {
+    let mut processed = 0;
+
+    while let Some(job) = ReceiptDraftRepository::claim_next(pool, STALE_CLAIM_SECONDS).await? {
+        match read_receipt(storage, engine, &job, config.review_threshold).await {
+            Ok(result) => ReceiptDraftRepository::complete(pool, job.draft_id, &result).await?,
+            Err(e) => {
+                let retry_at = (job.attempts < config.max_attempts).then(|| {
+                    let delay = retry_delay(job.attempts, config.backoff_base_seconds, config.backoff_max_seconds);
+                    Utc::now().naive_utc() + chrono::Duration::seconds(delay)
+                });
+                tracing::warn!(
+                    "OCR of receipt draft {} failed (attempt {}): {:?}",
+                    job.draft_id,
+                    job.attempts,
+                    e
+                );
+                ReceiptDraftRepository::record_error(pool, job.draft_id, &format!("{:?}", e), retry_at).await?;
+            }
+        }
+        processed += 1;
+    }
+
+    Ok(processed)
+}
+
+async fn read_receipt(
+    storage: &dyn StorageBackend,
+    engine: &dyn OcrEngine,
+    job: &ReceiptOcrJob,
+    review_threshold: f64,
+) -> Result<ReceiptDraftResult, AppError> {
+    let image = storage
+        .get(&job.storage_key)
+        .await?
+        .ok_or_else(|| AppError::Internal(format!("Receipt file {} is missing", job.storage_key)))?;
+    let text = engine.recognize(&image).await?;
+
+    Ok(draft_result(engine.name(), text, job.transaction_currency, review_threshold))
+}
+
+/// Turn OCR text into draft values; anything below `review_threshold` needs a person
+pub fn draft_result(
+    engine: &str,
+    text: String,
+    transaction_currency: Currency,
+    review_threshold: f64,
+) -> ReceiptDraftResult {
+    let receipt = parse_receipt(&text);
+    let confidence = receipt.confidence();
+    let field_confidence = serde_json::to_value(receipt.field_confidence()).unwrap_or_default();
+
+    ReceiptDraftResult {
+        status: if confidence >= review_threshold {
+            ReceiptDraftStatus::Ready
+        } else {
+            ReceiptDraftStatus::NeedsReview
+        },
+        engine: engine.to_string(),
+        ocr_text: text,
+        amount: receipt.amount,
+        currency: receipt.currency.unwrap_or(transaction_currency),
+        transaction_date: receipt.transaction_date,
+        merchant_name: receipt.merchant_name,
+        confidence,
+        field_confidence,
+    }
+}
+
+/// Work through the queue every `interval`
+pub fn spawn_worker(
+    pool: PgPool,
+    storage: Arc<dyn StorageBackend>,
+    engine: Arc<dyn OcrEngine>,
+    config: OcrConfig,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.poll_interval_seconds));
+        loop {
+            ticker.tick().await;
+            match process_queue(&pool, storage.as_ref(), engine.as_ref(), &config).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Read {} receipts with {}", count, engine.name()),
+                Err(e) => tracing::warn!("Failed to process the receipt OCR queue: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal::Decimal;
+    use super::*;
+
+    #[test]
+    fn test_low_confidence_drafts_need_review() {
+        let usd = Currency::from_str("USD").unwrap();
+
+        let clear = draft_result(
+            "tesseract",
+            "Kopi Kenangan\n05/12/2025 08:10\nTOTAL Rp 38.000\nTUNAI 50.000\nKEMBALI 12.000\n".to_string(),
+            usd,
+            0.8,
+        );
+        assert_eq!(clear.status, ReceiptDraftStatus::Ready);
+        assert_eq!(clear.amount, Some(Decimal::from_str("38000").unwrap()));
+        assert_eq!(clear.currency, Currency::IDR);
+        assert_eq!(clear.field_confidence["amount"], 0.99);
+
+        let blurred = draft_result("tesseract", "K0p1 ~~\n38 000\n".to_string(), usd, 0.8);
+        assert_eq!(blurred.status, ReceiptDraftStatus::NeedsReview);
+        assert_eq!(blurred.amount, None);
+        assert_eq!(blurred.currency, usd);
+        assert_eq!(blurred.confidence, 0.0);
+    }
+}
@@ -0,0 +1,415 @@
+use std::str::FromStr;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::db::models::Currency;
+
+/// Keywords introducing the amount paid, best first, with the confidence they give it.
+/// Matched against upper-cased lines; `EXCLUDED_KEYWORDS` win over these.
+const TOTAL_KEYWORDS: &[(&str, f64)] = &[
+    ("GRAND TOTAL", 0.95),
+    ("TOTAL BAYAR", 0.95),
+    ("TOTAL BELANJA", 0.9),
+    ("TOTAL HARGA", 0.9),
+    ("TOTAL TAGIHAN", 0.9),
+    ("JUMLAH BAYAR", 0.85),
+    ("TOTAL", 0.85),
+    ("TAGIHAN", 0.7),
+    ("JUMLAH", 0.65),
+];
+
+/// Lines that mention a total but are not the amount paid
+const EXCLUDED_KEYWORDS: &[&str] = &[
+    "SUBTOTAL", "SUB TOTAL", "TOTAL ITEM", "TOTAL QTY", "TOTAL DISKON", "TOTAL DISC", "TOTAL HEMAT",
+    "TOTAL POIN", "JUMLAH ITEM", "JUMLAH QTY",
+];
+
+/// Payment lines; cash paid minus change confirms the total
+const CASH_KEYWORDS: &[&str] = &["TUNAI", "CASH", "BAYAR"];
+const CHANGE_KEYWORDS: &[&str] = &["KEMBALI", "KEMBALIAN", "CHANGE"];
+
+/// Header lines that are not the merchant name
+const NOT_MERCHANT_PREFIXES: &[&str] = &[
+    "JL", "JALAN", "TELP", "TEL", "HP", "NPWP", "NO", "KASIR", "TANGGAL", "TGL", "WWW", "HTTP",
+    "STRUK", "NOTA", "INVOICE", "RT", "KEC", "KOTA",
+];
+
+/// Confidence for the largest amount on a receipt without a recognizable total line
+const FALLBACK_AMOUNT_CONFIDENCE: f64 = 0.35;
+
+/// Weights of the fields in the overall confidence
+const AMOUNT_WEIGHT: f64 = 0.6;
+const DATE_WEIGHT: f64 = 0.25;
+const MERCHANT_WEIGHT: f64 = 0.15;
+
+const MONTHS: &[(&str, u32)] = &[
+    ("JAN", 1), ("FEB", 2), ("PEB", 2), ("MAR", 3), ("APR", 4), ("MEI", 5), ("MAY", 5), ("JUN", 6),
+    ("JUL", 7), ("AGU", 8), ("AGT", 8), ("AGS", 8), ("AUG", 8), ("SEP", 9), ("OKT", 10), ("OCT", 10),
+    ("NOV", 11), ("NOP", 11), ("DES", 12), ("DEC", 12),
+];
+
+/// What could be read from a receipt, each field with a confidence between 0 and 1
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedReceipt {
+    pub amount: Option<Decimal>,
+    pub amount_confidence: f64,
+    /// Set when the receipt names its currency (Rp, IDR)
+    pub currency: Option<Currency>,
+    pub transaction_date: Option<NaiveDate>,
+    pub date_confidence: f64,
+    pub merchant_name: Option<String>,
+    pub merchant_confidence: f64,
+}
+
+/// Per-field confidence, as stored with a draft
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldConfidence {
+    pub amount: f64,
+    pub transaction_date: f64,
+    pub merchant_name: f64,
+}
+
+impl ParsedReceipt {
+    /// Overall confidence; zero without an amount, since a draft is useless without one
+    pub fn confidence(&self) -> f64 {
+        if self.amount.is_none() {
+            return 0.0;
+        }
+
+        let confidence = self.amount_confidence * AMOUNT_WEIGHT
+            + self.date_confidence * DATE_WEIGHT
+            + self.merchant_confidence * MERCHANT_WEIGHT;
+
+        (confidence * 100.0).round() / 100.0
+    }
+
+    pub fn field_confidence(&self) -> FieldConfidence {
+        FieldConfidence {
+            amount: self.amount_confidence,
+            transaction_date: self.date_confidence,
+            merchant_name: self.merchant_confidence,
+        }
+    }
+}
+
+/// Read the total, date and merchant from OCR text of a receipt laid out the way
+/// Indonesian shops print them (`TOTAL  Rp 125.000`, `12/12/2025`, `12 Des 2025`)
+pub fn parse_receipt(text: &str) -> ParsedReceipt {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    let upper: Vec<String> = lines.iter().map(|line| line.to_uppercase()).collect();
+
+    let mut receipt = ParsedReceipt::default();
+
+    if let Some((amount, confidence)) = find_total(&upper) {
+        receipt.amount = Some(amount);
+        receipt.amount_confidence = confidence;
+    }
+
+    if upper.iter().any(|line| line.contains("RP") || line.contains("IDR")) {
+        receipt.currency = Some(Currency::IDR);
+    }
+
+    if let Some((date, confidence)) = upper.iter().find_map(|line| find_date(line)) {
+        receipt.transaction_date = Some(date);
+        receipt.date_confidence = confidence;
+    }
+
+    if let Some((merchant, confidence)) = find_merchant(&lines, &upper) {
+        receipt.merchant_name = Some(merchant);
+        receipt.merchant_confidence = confidence;
+    }
+
+    receipt
+}
+
+/// The amount on the best total line, confirmed by cash minus change when both are printed
+fn find_total(upper: &[String]) -> Option<(Decimal, f64)> {
+    let mut best: Option<(Decimal, f64)> = None;
+
+    for (index, line) in upper.iter().enumerate() {
+        if EXCLUDED_KEYWORDS.iter().any(|keyword| line.contains(keyword)) {
+            continue;
+        }
+        let Some(&(_, confidence)) = TOTAL_KEYWORDS.iter().find(|(keyword, _)| line.contains(keyword)) else {
+            continue;
+        };
+
+        // The amount is on the same line, or alone on the next one
+        let amount = last_amount(line).or_else(|| {
+            upper
+                .get(index + 1)
+                .filter(|next| !next.chars().any(char::is_alphabetic) || next.starts_with("RP"))
+                .and_then(|next| last_amount(next))
+        });
+        let Some(amount) = amount else {
+            continue;
+        };
+
+        // A later line with an equally good keyword (e.g. a second TOTAL after tax) wins
+        if best.is_none_or(|(_, best_confidence)| confidence >= best_confidence) {
+            best = Some((amount, confidence));
+        }
+    }
+
+    let Some((amount, confidence)) = best else {
+        let largest = upper.iter().filter_map(|line| last_amount(line)).max()?;
+        return Some((largest, FALLBACK_AMOUNT_CONFIDENCE));
+    };
+
+    let cash = keyword_amount(upper, CASH_KEYWORDS);
+    let change = keyword_amount(upper, CHANGE_KEYWORDS);
+    if let (Some(cash), Some(change)) = (cash, change)
+        && cash - change == amount
+    {
+        return Some((amount, 0.99));
+    }
+
+    Some((amount, confidence))
+}
+
+/// Amount on the first line starting with one of `keywords` that is not a total line
+fn keyword_amount(upper: &[String], keywords: &[&str]) -> Option<Decimal> {
+    upper
+        .iter()
+        .filter(|line| keywords.iter().any(|keyword| line.starts_with(keyword)))
+        .filter(|line| !line.contains("TOTAL"))
+        .find_map(|line| last_amount(line))
+}
+
+/// The last amount on a line, e.g. `125.000` in `TOTAL  3  Rp 125.000`
+fn last_amount(line: &str) -> Option<Decimal> {
+    line.split_whitespace()
+        .rev()
+        .map(|token| {
+            token
+                .trim_start_matches("RP")
+                .trim_start_matches("IDR")
+                .trim_start_matches(['.', ':'])
+                .trim_end_matches(['-', ','])
+        })
+        .find(|token| token.chars().any(|c| c.is_ascii_digit()))
+        .and_then(parse_amount)
+}
+
+/// Parse `1.250.000`, `1,250,000`, `1.250.000,00`, `1,250,000.00` or `12500` into an amount
+pub fn parse_amount(token: &str) -> Option<Decimal> {
+    if token.is_empty() || !token.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',') {
+        return None;
+    }
+
+    let last_separator = token.rfind(['.', ',']);
+    let normalized = match last_separator {
+        None => token.to_string(),
+        Some(position) => {
+            let separator = token[position..].chars().next()?;
+            let decimals = token.len() - position - 1;
+            let separators = token.matches(['.', ',']).count();
+            let mixed = token.contains('.') && token.contains(',');
+
+            // Rupiah have no minor units in practice, so one separator followed by three
+            // digits groups thousands; two digits after the last separator are cents
+            let is_decimal = decimals == 2 && (mixed || separators == 1);
+            if is_decimal {
+                let integer: String = token[..position].chars().filter(char::is_ascii_digit).collect();
+                format!("{}.{}", integer, &token[position + 1..])
+            } else if decimals == 3 || (separators > 1 && token.matches(separator).count() > 1) {
+                token.chars().filter(char::is_ascii_digit).collect()
+            } else {
+                return None;
+            }
+        }
+    };
+
+    let amount = Decimal::from_str(&normalized).ok()?;
+    (amount > Decimal::ZERO).then_some(amount)
+}
+
+/// The first date on a line, numeric (`12/12/2025`, `12-12-25`, `2025-12-12`) or with an
+/// Indonesian or English month name (`12 Des 2025`, `12-DES-2025`)
+fn find_date(line: &str) -> Option<(NaiveDate, f64)> {
+    let tokens: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    for (index, token) in tokens.iter().enumerate() {
+        if let Some(found) = numeric_date(token) {
+            return Some(found);
+        }
+
+        let parts: Vec<&str> = token.split(['-', '/', '.']).collect();
+        if let [day, month, year] = parts.as_slice()
+            && let Some(found) = named_month_date(day, month, year)
+        {
+            return Some(found);
+        }
+
+        if let (Some(month), Some(year)) = (tokens.get(index + 1), tokens.get(index + 2))
+            && let Some(found) = named_month_date(token, month, year)
+        {
+            return Some(found);
+        }
+    }
+
+    None
+}
+
+fn numeric_date(token: &str) -> Option<(NaiveDate, f64)> {
+    let separator = token.chars().find(|c| matches!(c, '/' | '-' | '.'))?;
+    // Drop what follows the date, e.g. the time in `12.12.2025-19:04`
+    let date: String = token
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == separator)
+        .collect();
+    let parts: Vec<&str> = date.split(separator).collect();
+    let [first, second, third] = parts.as_slice() else {
+        return None;
+    };
+    let numbers: Vec<u32> = [first, second, third]
+        .iter()
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+
+    // Year first (ISO), otherwise day first as printed in Indonesia
+    if first.len() == 4 {
+        build_date(numbers[0], numbers[1], numbers[2], 4)
+    } else {
+        build_date(numbers[2], numbers[1], numbers[0], third.len())
+    }
+}
+
+fn named_month_date(day: &str, month: &str, year: &str) -> Option<(NaiveDate, f64)> {
+    let day: u32 = day.parse().ok()?;
+    let month = month.get(..3).and_then(|prefix| {
+        MONTHS.iter().find(|(name, _)| *name == prefix).map(|(_, number)| *number)
+    })?;
+    let year_digits = year.trim_end_matches(|c: char| !c.is_ascii_digit());
+    let year_number: u32 = year_digits.parse().ok()?;
+
+    build_date(year_number, month, day, year_digits.len())
+}
+
+fn build_date(year: u32, month: u32, day: u32, year_digits: usize) -> Option<(NaiveDate, f64)> {
+    let (year, confidence) = match year_digits {
+        4 => (year, 0.9),
+        2 => (2000 + year, 0.75),
+        _ => return None,
+    };
+    if !(2000..2100).contains(&year) {
+        return None;
+    }
+
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    Some((date, confidence))
+}
+
+/// The shop name: the first header line that reads like a name rather than an address,
+/// phone number or receipt number
+fn find_merchant(lines: &[&str], upper: &[String]) -> Option<(String, f64)> {
+    lines
+        .iter()
+        .zip(upper)
+        .take(5)
+        .enumerate()
+        .find(|(_, (_, line))| {
+            let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+            let digits = line.chars().filter(|c| c.is_ascii_digit()).count();
+            let first_word: String = line.chars().take_while(|c| c.is_alphabetic()).collect();
+
+            letters >= 3
+                && digits <= letters / 2
+                && !NOT_MERCHANT_PREFIXES.contains(&first_word.as_str())
+                && !TOTAL_KEYWORDS.iter().any(|(keyword, _)| line.contains(keyword))
+                && find_date(line).is_none()
+        })
+        .map(|(index, (line, _))| {
+            let name: String = line
+                .chars()
+                .filter(|c| c.is_alphanumeric() || c.is_whitespace() || matches!(c, '&' | '.' | '-' | '\''))
+                .collect();
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            // OCR reads the first line worst, but it is usually the name
+            (name, if index == 0 { 0.6 } else { 0.45 })
+        })
+        .filter(|(name, _)| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_amount_formats() {
+        assert_eq!(parse_amount("125.000"), Some(decimal("125000")));
+        assert_eq!(parse_amount("1.250.000"), Some(decimal("1250000")));
+        assert_eq!(parse_amount("1,250,000"), Some(decimal("1250000")));
+        assert_eq!(parse_amount("1.250.000,00"), Some(decimal("1250000.00")));
+        assert_eq!(parse_amount("1,250.50"), Some(decimal("1250.50")));
+        assert_eq!(parse_amount("12500"), Some(decimal("12500")));
+        assert_eq!(parse_amount("12.5"), None);
+        assert_eq!(parse_amount("0"), None);
+        assert_eq!(parse_amount("12/12"), None);
+    }
+
+    #[test]
+    fn test_parses_minimarket_receipt() {
+        let text = "\
+            INDOMARET CEMPAKA PUTIH\n\
+            JL. CEMPAKA PUTIH RAYA NO. 12\n\
+            NPWP 01.234.567.8-901.000\n\
+            12.12.2025-19:04 / 2.0.31 / KSR01\n\
+            AQUA 600ML        2    3.500     7.000\n\
+            INDOMIE GORENG    5    3.100    15.500\n\
+            TOTAL ITEM 7\n\
+            HARGA JUAL :                    22.500\n\
+            TOTAL :                         22.500\n\
+            TUNAI :                         50.000\n\
+            KEMBALI :                       27.500\n";
+
+        let receipt = parse_receipt(text);
+
+        assert_eq!(receipt.amount, Some(decimal("22500")));
+        assert_eq!(receipt.amount_confidence, 0.99);
+        assert_eq!(receipt.transaction_date, NaiveDate::from_ymd_opt(2025, 12, 12));
+        assert_eq!(receipt.merchant_name.as_deref(), Some("INDOMARET CEMPAKA PUTIH"));
+        assert!(receipt.confidence() > 0.8);
+    }
+
+    #[test]
+    fn test_parses_restaurant_receipt_with_month_name() {
+        let text = "\
+            Warung Makan Sederhana\n\
+            Telp 021-555-0192\n\
+            Tanggal: 3 Des 2025 12:41\n\
+            Nasi Padang 2x        Rp 50.000\n\
+            Subtotal              Rp 50.000\n\
+            PPN 11%               Rp 5.500\n\
+            Grand Total\n\
+            Rp 55.500\n";
+
+        let receipt = parse_receipt(text);
+
+        assert_eq!(receipt.amount, Some(decimal("55500")));
+        assert_eq!(receipt.amount_confidence, 0.95);
+        assert_eq!(receipt.currency, Some(Currency::IDR));
+        assert_eq!(receipt.transaction_date, NaiveDate::from_ymd_opt(2025, 12, 3));
+        assert_eq!(receipt.merchant_name.as_deref(), Some("Warung Makan Sederhana"));
+    }
+
+    #[test]
+    fn test_unreadable_receipt_has_low_confidence() {
+        let receipt = parse_receipt("~~ ::: 45.000 \n blurred 120.000 text\n");
+
+        assert_eq!(receipt.amount, Some(decimal("120000")));
+        assert_eq!(receipt.amount_confidence, FALLBACK_AMOUNT_CONFIDENCE);
+        assert_eq!(receipt.transaction_date, None);
+        assert!(receipt.confidence() < 0.5);
+
+        assert_eq!(parse_receipt("").confidence(), 0.0);
+    }
+}
@@ -0,0 +1,90 @@
+use uuid::Uuid;
+
+/// Receipt file types accepted for upload, as (content type, file extension)
+const RECEIPT_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/webp", "webp"),
+    ("application/pdf", "pdf"),
+];
+
+/// Longest file name kept for a receipt
+const MAX_FILE_NAME_CHARS: usize = 200;
+
+/// Content type of a receipt, detected from its leading bytes rather than trusted from the
+/// upload. None for anything but JPEG, PNG, WebP and PDF.
+pub fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    let content_type = if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        "image/webp"
+    } else if bytes.starts_with(b"%PDF-") {
+        "application/pdf"
+    } else {
+        return None;
+    };
+
+    Some(content_type)
+}
+
+/// Extension used for a receipt type
+pub fn extension(content_type: &str) -> &'static str {
+    RECEIPT_TYPES
+        .iter()
+        .find(|(known, _)| *known == content_type)
+        .map(|(_, extension)| *extension)
+        .unwrap_or("bin")
+}
+
+/// The uploaded file name, stripped of any directory and of characters that would break a
+/// `Content-Disposition` header; falls back to `receipt.<extension>`
+pub fn file_name(uploaded: Option<&str>, content_type: &str) -> String {
+    let name: String = uploaded
+        .unwrap_or_default()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        return format!("receipt.{}", extension(content_type));
+    }
+
+    name.to_string()
+}
+
+/// Storage key of an attachment
+pub fn storage_key(transaction_id: Uuid, attachment_id: Uuid, content_type: &str) -> String {
+    format!("receipts/{}/{}.{}", transaction_id, attachment_id, extension(content_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_content_type() {
+        assert_eq!(detect_content_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0x10]), Some("image/jpeg"));
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        assert_eq!(detect_content_type(b"RIFF\x24\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(detect_content_type(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(detect_content_type(b"GIF89a"), None);
+        assert_eq!(detect_content_type(b"<html>"), None);
+        assert_eq!(detect_content_type(b""), None);
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name(Some("C:\\Users\\budi\\struk.jpg"), "image/jpeg"), "struk.jpg");
+        assert_eq!(file_name(Some("../../etc/passwd"), "application/pdf"), "passwd");
+        assert_eq!(file_name(Some("a\"b\r\n.pdf"), "application/pdf"), "ab.pdf");
+        assert_eq!(file_name(Some(".."), "image/png"), "receipt.png");
+        assert_eq!(file_name(None, "image/webp"), "receipt.webp");
+    }
+}
@@ -0,0 +1,64 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign `path` until the unix timestamp `expires`; returns a hex HMAC-SHA256
+pub fn sign(secret: &str, path: &str, expires: i64) -> String {
+    hex::encode(mac(secret, path, expires).finalize().into_bytes())
+}
+
+/// Append `expires` and `signature` query parameters to `path`
+pub fn signed_path(secret: &str, path: &str, expires: i64) -> String {
+    format!("{}?expires={}&signature={}", path, expires, sign(secret, path, expires))
+}
+
+/// Check a signature (constant time) and that the link has not expired at `now`
+pub fn verify(secret: &str, path: &str, expires: i64, signature: &str, now: i64) -> bool {
+    if expires <= now {
+        return false;
+    }
+
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+
+    mac(secret, path, expires).verify_slice(&signature).is_ok()
+}
+
+fn mac(secret: &str, path: &str, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}", path, expires).as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-signing-secret";
+    const PATH: &str = "/exports/4f9c1e2a-0000-0000-0000-000000000000/download";
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign(SECRET, PATH, 2_000);
+
+        assert!(verify(SECRET, PATH, 2_000, &signature, 1_000));
+        assert_eq!(
+            signed_path(SECRET, PATH, 2_000),
+            format!("{}?expires=2000&signature={}", PATH, signature)
+        );
+    }
+
+    #[test]
+    fn test_rejects_tampered_or_expired_links() {
+        let signature = sign(SECRET, PATH, 2_000);
+
+        assert!(!verify(SECRET, PATH, 2_000, &signature, 2_000));
+        assert!(!verify(SECRET, PATH, 3_000, &signature, 1_000));
+        assert!(!verify(SECRET, "/exports/other/download", 2_000, &signature, 1_000));
+        assert!(!verify("other-secret", PATH, 2_000, &signature, 1_000));
+        assert!(!verify(SECRET, PATH, 2_000, "not-hex", 1_000));
+    }
+}
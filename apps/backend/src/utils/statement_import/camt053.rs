@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use crate::db::models::TransactionType;
+use super::{
+    clean, content_id,
+    markup::{self, Element},
+    parse_currency, parse_decimal, StatementEntry,
+};
+
+/// Parse the booked entries (`Ntry`) of every `Stmt` in a CAMT.053 document. Batch
+/// bookings whose transaction details carry their own amounts become one entry each.
+pub fn parse(content: &str) -> Result<Vec<StatementEntry>, String> {
+    let document = markup::parse(content)?;
+    let report = document.find("BkToCstmrStmt").ok_or("Missing <BkToCstmrStmt> element")?;
+
+    let mut entries = Vec::new();
+
+    for statement in report.children_named("Stmt") {
+        let account = statement
+            .text_at(&["Acct", "Id", "IBAN"])
+            .or_else(|| statement.text_at(&["Acct", "Id", "Othr", "Id"]))
+            .map(str::to_string);
+        let account_currency = statement.text_at(&["Acct", "Ccy"]);
+
+        for (index, entry) in statement.children_named("Ntry").enumerate() {
+            // Only booked entries; pending and informational ones may still change
+            let status = entry.text_at(&["Sts", "Cd"]).or_else(|| entry.text_at(&["Sts"]));
+            if status.is_some_and(|status| status != "BOOK") {
+                continue;
+            }
+
+            let r#type = match entry.text_at(&["CdtDbtInd"]) {
+                Some("CRDT") => TransactionType::Income,
+                Some("DBIT") => TransactionType::Expense,
+                other => return Err(format!("Invalid CdtDbtInd {:?}", other)),
+            };
+
+            let date = entry
+                .text_at(&["BookgDt", "Dt"])
+                .or_else(|| entry.text_at(&["BookgDt", "DtTm"]))
+                .or_else(|| entry.text_at(&["ValDt", "Dt"]))
+                .ok_or("Entry without a booking date")?;
+            let booking_date = date
+                .get(..10)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .ok_or_else(|| format!("Invalid booking date '{}'", date))?;
+
+            let entry_amount = entry.child("Amt").ok_or("Entry without an amount")?;
+            let entry_reference = entry
+                .text_at(&["AcctSvcrRef"])
+                .or_else(|| entry.text_at(&["NtryRef"]));
+
+            let details: Vec<&Element> = entry
+                .children_named("NtryDtls")
+                .flat_map(|d| d.children_named("TxDtls"))
+                .collect();
+            let split = details.len() > 1 && details.iter().all(|d| transaction_amount(d).is_some());
+
+            let parts: Vec<(Option<&Element>, &Element)> = if split {
+                details.iter().map(|d| (Some(*d), transaction_amount(d).unwrap_or(entry_amount))).collect()
+            } else {
+                vec![(details.first().copied(), entry_amount)]
+            };
+
+            for (part, (details, amount_element)) in parts.iter().enumerate() {
+                let amount = parse_decimal(&amount_element.text)
+                    .ok_or_else(|| format!("Invalid amount '{}'", amount_element.text.trim()))?;
+                let currency = amount_element.attribute("Ccy").or(account_currency);
+
+                let transaction_reference = details.and_then(|d| {
+                    d.text_at(&["Refs", "AcctSvcrRef"]).or_else(|| {
+                        d.text_at(&["Refs", "EndToEndId"])
+                            .filter(|id| *id != "NOTPROVIDED")
+                    })
+                });
+
+                let transaction_id = match (transaction_reference, entry_reference) {
+                    (Some(reference), _) => reference.to_string(),
+                    (None, Some(reference)) if split => format!("{}#{}", reference, part + 1),
+                    (None, Some(reference)) => reference.to_string(),
+                    (None, None) => content_id(&[
+                        account.as_deref().unwrap_or_default(),
+                        date,
+                        &index.to_string(),
+                        &part.to_string(),
+                        amount_element.text.trim(),
+                    ]),
+                };
+
+                // The other party: the creditor of a debit, the debtor of a credit
+                let party = match r#type {
+                    TransactionType::Expense => "Cdtr",
+                    TransactionType::Income | TransactionType::Transfer => "Dbtr",
+                };
+                let counterparty = details
+                    .and_then(|d| d.child("RltdPties"))
+                    .and_then(|p| p.child(party))
+                    .and_then(|p| p.find("Nm"))
+                    .and_then(|n| clean(&n.text));
+
+                let remittance = details
+                    .and_then(|d| d.child("RmtInf"))
+                    .map(|r| {
+                        r.children_named("Ustrd")
+                            .map(|u| u.text.trim())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .and_then(|text| clean(&text));
+                let description = remittance
+                    .or_else(|| details.and_then(|d| d.text_at(&["AddtlTxInf"])).and_then(clean))
+                    .or_else(|| entry.text_at(&["AddtlNtryInf"]).and_then(clean));
+
+                entries.push(StatementEntry {
+                    transaction_id,
+                    account: account.clone(),
+                    booking_date,
+                    r#type: r#type.clone(),
+                    amount,
+                    currency: parse_currency(currency)?,
+                    counterparty,
+                    description,
+                });
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Amount of a single transaction within a batch booking
+fn transaction_amount(details: &Element) -> Option<&Element> {
+    details
+        .path(&["AmtDtls", "TxAmt", "Amt"])
+        .or_else(|| details.child("Amt"))
+}
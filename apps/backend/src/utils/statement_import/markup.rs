@@ -0,0 +1,247 @@
+//! A small, lenient markup reader for bank statements. It accepts XML (OFX 2.x,
+//! CAMT.053) as well as OFX 1.x SGML, where leaf elements have no closing tag.
+//! Namespace prefixes are dropped from element names.
+
+/// Deepest element nesting accepted. Statements need a dozen levels; anything far deeper
+/// is rejected before the tree gets deep enough to overflow the stack when dropped
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Default)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Follow a path of child names, e.g. `["BookgDt", "Dt"]`
+    pub fn path(&self, path: &[&str]) -> Option<&Element> {
+        path.iter().try_fold(self, |element, name| element.child(name))
+    }
+
+    /// Trimmed text at a path; `None` when missing or empty
+    pub fn text_at(&self, path: &[&str]) -> Option<&str> {
+        self.path(path).map(|e| e.text.trim()).filter(|t| !t.is_empty())
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First element with this name, depth-first, including `self`
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        let mut pending = vec![self];
+        while let Some(element) = pending.pop() {
+            if element.name == name {
+                return Some(element);
+            }
+            pending.extend(element.children.iter().rev());
+        }
+        None
+    }
+
+    /// All elements with this name, depth-first, not descending into matches
+    pub fn find_all<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        let mut pending: Vec<&Element> = self.children.iter().rev().collect();
+        while let Some(element) = pending.pop() {
+            if element.name == name {
+                found.push(element);
+            } else {
+                pending.extend(element.children.iter().rev());
+            }
+        }
+    }
+}
+
+/// Parse a document into a tree under an unnamed root element
+pub fn parse(input: &str) -> Result<Element, String> {
+    let mut stack = vec![Element::default()];
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        append_text(&mut stack, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("Unterminated CDATA section")?;
+            if let Some(top) = stack.last_mut() {
+                top.text.push_str(&after[..end]);
+            }
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or("Unterminated comment")?;
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") {
+            let end = rest.find("?>").ok_or("Unterminated processing instruction")?;
+            rest = &rest[end + 2..];
+        } else if rest.starts_with("<!") {
+            let end = rest.find('>').ok_or("Unterminated declaration")?;
+            rest = &rest[end + 1..];
+        } else {
+            let end = rest.find('>').ok_or("Unterminated tag")?;
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                close(&mut stack, local_name(name.trim()));
+            } else {
+                open(&mut stack, tag)?;
+            }
+        }
+    }
+    append_text(&mut stack, rest);
+
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+
+    stack.pop().ok_or_else(|| "Empty document".to_string())
+}
+
+fn append_text(stack: &mut [Element], raw: &str) {
+    if raw.trim().is_empty() {
+        return;
+    }
+    if let Some(top) = stack.last_mut() {
+        top.text.push_str(&decode_entities(raw));
+    }
+}
+
+/// An SGML leaf: an element holding text that was never explicitly closed
+fn is_open_leaf(stack: &[Element]) -> bool {
+    stack.len() > 1
+        && stack
+            .last()
+            .is_some_and(|top| top.children.is_empty() && !top.text.trim().is_empty())
+}
+
+fn pop(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop()
+        && let Some(parent) = stack.last_mut()
+    {
+        parent.children.push(element);
+    }
+}
+
+fn open(stack: &mut Vec<Element>, tag: &str) -> Result<(), String> {
+    let (tag, self_closing) = match tag.strip_suffix('/') {
+        Some(tag) => (tag, true),
+        None => (tag, false),
+    };
+
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = local_name(&tag[..name_end]);
+    if name.is_empty() {
+        return Err("Tag without a name".to_string());
+    }
+
+    if is_open_leaf(stack) {
+        pop(stack);
+    }
+    // The stack holds the unnamed root below the open elements
+    if stack.len() > MAX_DEPTH {
+        return Err(format!("Elements are nested more than {} levels deep", MAX_DEPTH));
+    }
+
+    let element = Element {
+        name: name.to_string(),
+        attributes: parse_attributes(&tag[name_end..]),
+        ..Element::default()
+    };
+
+    if self_closing {
+        if let Some(top) = stack.last_mut() {
+            top.children.push(element);
+        }
+    } else {
+        stack.push(element);
+    }
+
+    Ok(())
+}
+
+fn close(stack: &mut Vec<Element>, name: &str) {
+    // Closing tags without a matching open element are ignored
+    let Some(index) = stack.iter().skip(1).rposition(|e| e.name == name) else {
+        return;
+    };
+    while stack.len() > index + 1 {
+        pop(stack);
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn parse_attributes(mut input: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+
+    loop {
+        input = input.trim_start();
+        let Some(eq) = input.find('=') else { break };
+        let key = local_name(input[..eq].trim()).to_string();
+        let value_part = input[eq + 1..].trim_start();
+
+        let Some(quote) = value_part.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value_part[1..].find(quote) else { break };
+
+        attributes.push((key, decode_entities(&value_part[1..end + 1])));
+        input = &value_part[end + 2..];
+    }
+
+    attributes
+}
+
+fn decode_entities(raw: &str) -> String {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let replacement = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        });
+
+        match (entity, replacement) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
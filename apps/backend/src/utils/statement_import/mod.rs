@@ -0,0 +1,411 @@
+//! Bank and e-wallet statement import: parsers for OFX/QFX, ISO 20022 CAMT.053 and
+//! SWIFT MT940 that turn statement entries into transaction drafts.
+
+mod camt053;
+mod markup;
+mod mt940;
+mod ofx;
+
+use std::{collections::HashSet, str::FromStr};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::{
+    db::models::{
+        Currency, NewTransaction, ReconciliationCandidate, TransactionSource, TransactionType,
+    },
+    error::AppError,
+};
+
+/// How far apart, in days, a statement entry and a WhatsApp-entered transaction may be
+/// and still be considered the same payment
+pub const RECONCILE_WINDOW_DAYS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    /// OFX 1.x (SGML) and 2.x (XML); QFX is OFX with Quicken extensions
+    Ofx,
+    Camt053,
+    Mt940,
+}
+
+impl FromStr for StatementFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ofx" | "qfx" => Ok(StatementFormat::Ofx),
+            "camt053" | "camt.053" => Ok(StatementFormat::Camt053),
+            "mt940" => Ok(StatementFormat::Mt940),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown statement format '{}'; expected ofx, qfx, camt053 or mt940",
+                other
+            ))),
+        }
+    }
+}
+
+impl StatementFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementFormat::Ofx => "ofx",
+            StatementFormat::Camt053 => "camt053",
+            StatementFormat::Mt940 => "mt940",
+        }
+    }
+
+    /// Guess the format from the file content
+    pub fn detect(content: &str) -> Option<Self> {
+        let head: String = content.chars().take(4096).collect();
+
+        if head.contains("OFXHEADER") || head.contains("<OFX>") {
+            Some(StatementFormat::Ofx)
+        } else if head.contains("camt.053") || head.contains("BkToCstmrStmt") {
+            Some(StatementFormat::Camt053)
+        } else if head.contains(":20:") && content.contains(":61:") {
+            Some(StatementFormat::Mt940)
+        } else {
+            None
+        }
+    }
+}
+
+/// One booked entry of a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementEntry {
+    /// The bank's id for the entry (OFX FITID, CAMT reference, MT940 bank reference),
+    /// or a content hash when the statement has none
+    pub transaction_id: String,
+    pub account: Option<String>,
+    pub booking_date: NaiveDate,
+    pub r#type: TransactionType,
+    /// Always positive; the direction is in `type`
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub counterparty: Option<String>,
+    pub description: Option<String>,
+}
+
+impl StatementEntry {
+    /// What makes an entry unique: bank ids only identify an entry within its account
+    pub fn dedup_key(&self) -> (String, String) {
+        (self.account.clone().unwrap_or_default(), self.transaction_id.clone())
+    }
+
+    /// JSON stored under `metadata.statement` of imported and reconciled transactions
+    pub fn metadata(&self, format: StatementFormat, batch_id: Uuid) -> serde_json::Value {
+        json!({
+            "format": format.as_str(),
+            "transaction_id": self.transaction_id,
+            "account": self.account,
+            "batch_id": batch_id,
+        })
+    }
+
+    pub fn to_new_transaction(&self, format: StatementFormat, batch_id: Uuid) -> NewTransaction {
+        NewTransaction {
+            r#type: self.r#type.clone(),
+            amount: self.amount,
+            currency: self.currency,
+            category_id: None,
+            payment_method_id: None,
+            account_id: None,
+            transfer_account_id: None,
+            merchant_name: self.counterparty.clone(),
+            location: None,
+            description: self.description.clone(),
+            transaction_date: self.booking_date,
+            source: TransactionSource::Web,
+            client_id: None,
+            source_message_id: None,
+            metadata: Some(json!({ "statement": self.metadata(format, batch_id) })),
+        }
+    }
+
+    /// The closest-dated unclaimed WhatsApp transaction with the same direction, amount
+    /// and currency within the reconciliation window
+    pub fn find_match(
+        &self,
+        candidates: &[ReconciliationCandidate],
+        claimed: &HashSet<Uuid>,
+    ) -> Option<Uuid> {
+        candidates
+            .iter()
+            .filter(|c| !claimed.contains(&c.id))
+            .filter(|c| c.r#type == self.r#type && c.amount == self.amount && c.currency == self.currency)
+            .map(|c| (c, (c.transaction_date - self.booking_date).num_days().abs()))
+            .filter(|(_, distance)| *distance <= RECONCILE_WINDOW_DAYS)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(c, _)| c.id)
+    }
+}
+
+/// Parse a statement file; `format` is detected from the content when not given
+pub fn parse_statement(
+    data: &[u8],
+    format: Option<StatementFormat>,
+) -> Result<(StatementFormat, Vec<StatementEntry>), AppError> {
+    let content = decode(data);
+
+    let format = match format {
+        Some(format) => format,
+        None => StatementFormat::detect(&content).ok_or_else(|| {
+            AppError::BadRequest("Could not recognize the statement format".to_string())
+        })?,
+    };
+
+    let entries = match format {
+        StatementFormat::Ofx => ofx::parse(&content),
+        StatementFormat::Camt053 => camt053::parse(&content),
+        StatementFormat::Mt940 => mt940::parse(&content),
+    }
+    .map_err(|e| AppError::BadRequest(format!("Invalid {} statement: {}", format.as_str(), e)))?;
+
+    Ok((format, entries))
+}
+
+/// Statements are UTF-8 or, for older OFX and MT940 exports, a Latin-1 code page
+fn decode(data: &[u8]) -> String {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => data.iter().map(|&b| b as char).collect(),
+    };
+    text.trim_start_matches('\u{feff}').to_string()
+}
+
+/// Statement currency code; IDR when the statement does not state one
+fn parse_currency(code: Option<&str>) -> Result<Currency, String> {
+    code.map_or(Ok(Currency::IDR), str::parse)
+}
+
+/// Parse a decimal that may use a comma as the decimal separator
+fn parse_decimal(value: &str) -> Option<Decimal> {
+    let value = value.trim().replace(' ', "");
+    let normalized = if value.contains('.') { value } else { value.replace(',', ".") };
+    let normalized = normalized.trim_end_matches('.');
+    Decimal::from_str(normalized).ok()
+}
+
+/// Stable id for entries whose statement carries no reference
+fn content_id(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0x1f]);
+    }
+    format!("sha256:{}", &hex::encode(hasher.finalize())[..32])
+}
+
+/// Collapse whitespace and drop empty values
+fn clean(value: &str) -> Option<String> {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!value.is_empty()).then_some(value)
+}
+
+/// Positive amount and direction from a signed amount
+fn direction(signed: Decimal) -> (TransactionType, Decimal) {
+    if signed.is_sign_negative() && !signed.is_zero() {
+        (TransactionType::Expense, -signed)
+    } else {
+        (TransactionType::Income, signed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/statements/", $name))
+        };
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_detects_formats() {
+        let detect = |data: &[u8]| StatementFormat::detect(&decode(data));
+
+        assert_eq!(detect(fixture!("ofx1.qfx")), Some(StatementFormat::Ofx));
+        assert_eq!(detect(fixture!("ofx2.ofx")), Some(StatementFormat::Ofx));
+        assert_eq!(detect(fixture!("camt053.xml")), Some(StatementFormat::Camt053));
+        assert_eq!(detect(fixture!("mt940.sta")), Some(StatementFormat::Mt940));
+        assert_eq!(detect(b"date,amount\n2025-12-01,10"), None);
+    }
+
+    #[test]
+    fn test_parses_ofx_sgml() {
+        let (format, entries) = parse_statement(fixture!("ofx1.qfx"), None).unwrap();
+
+        assert_eq!(format, StatementFormat::Ofx);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[0],
+            StatementEntry {
+                transaction_id: "20251201-0001".to_string(),
+                account: Some("1234567890".to_string()),
+                booking_date: date(2025, 12, 1),
+                r#type: TransactionType::Expense,
+                amount: Decimal::new(4550000, 2),
+                currency: Currency::IDR,
+                counterparty: Some("KOPI KENANGAN & CO".to_string()),
+                description: Some("QRIS PAYMENT".to_string()),
+            }
+        );
+        assert_eq!(entries[1].r#type, TransactionType::Income);
+        assert_eq!(entries[1].amount, Decimal::new(1500000000, 2));
+        // Credit card statements are read as well
+        assert_eq!(entries[2].account.as_deref(), Some("4111XXXXXXXX1111"));
+        assert_eq!(entries[2].currency.as_str(), "USD");
+    }
+
+    #[test]
+    fn test_parses_ofx_xml() {
+        let (_, entries) = parse_statement(fixture!("ofx2.ofx"), None).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].transaction_id, "TX-9001");
+        assert_eq!(entries[0].booking_date, date(2025, 12, 3));
+        assert_eq!(entries[0].counterparty.as_deref(), Some("Tokopedia"));
+        assert_eq!(entries[1].r#type, TransactionType::Income);
+        assert_eq!(entries[1].description, None);
+    }
+
+    #[test]
+    fn test_parses_camt053() {
+        let (format, entries) = parse_statement(fixture!("camt053.xml"), None).unwrap();
+
+        assert_eq!(format, StatementFormat::Camt053);
+        // The pending entry is skipped; the batch booking is split into its two payments
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].transaction_id, "BANKREF-0001");
+        assert_eq!(entries[0].account.as_deref(), Some("ID12BANK0000123456789"));
+        assert_eq!(entries[0].booking_date, date(2025, 12, 2));
+        assert_eq!(entries[0].r#type, TransactionType::Expense);
+        assert_eq!(entries[0].amount, Decimal::new(12500000, 2));
+        assert_eq!(entries[0].counterparty.as_deref(), Some("PLN Persero"));
+        assert_eq!(entries[0].description.as_deref(), Some("Listrik Desember 2025"));
+
+        assert_eq!(entries[1].r#type, TransactionType::Income);
+        assert_eq!(entries[1].counterparty.as_deref(), Some("PT Maju Jaya"));
+        assert_eq!(entries[1].transaction_id, "E2E-SALARY-12");
+
+        assert_eq!(entries[2].transaction_id, "BATCH-77#1");
+        assert_eq!(entries[2].amount, Decimal::new(5000000, 2));
+        assert_eq!(entries[3].transaction_id, "BATCH-77#2");
+        assert_eq!(entries[3].counterparty.as_deref(), Some("Budi"));
+    }
+
+    #[test]
+    fn test_parses_mt940() {
+        let (format, entries) = parse_statement(fixture!("mt940.sta"), None).unwrap();
+
+        assert_eq!(format, StatementFormat::Mt940);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].transaction_id, "BNK20251201001");
+        assert_eq!(entries[0].account.as_deref(), Some("0123456789"));
+        assert_eq!(entries[0].booking_date, date(2025, 12, 1));
+        assert_eq!(entries[0].r#type, TransactionType::Expense);
+        assert_eq!(entries[0].amount, Decimal::new(7500000, 2));
+        assert_eq!(entries[0].currency, Currency::IDR);
+        assert_eq!(entries[0].description.as_deref(), Some("TRSF E-BANKING DB GOJEK TOPUP"));
+
+        // Structured :86: details
+        assert_eq!(entries[1].r#type, TransactionType::Income);
+        assert_eq!(entries[1].counterparty.as_deref(), Some("PT MAJU JAYA"));
+        assert_eq!(entries[1].description.as_deref(), Some("GAJI DESEMBER 2025"));
+        // Booked on the entry date, across the year boundary from the value date
+        assert_eq!(entries[1].booking_date, date(2025, 12, 31));
+
+        // No bank reference: a stable content hash instead
+        assert!(entries[2].transaction_id.starts_with("sha256:"));
+        let (_, again) = parse_statement(fixture!("mt940.sta"), None).unwrap();
+        assert_eq!(entries[2].transaction_id, again[2].transaction_id);
+    }
+
+    #[test]
+    fn test_mt940_customer_reference_does_not_identify_lines() {
+        let content = ":20:STMT1\n:25:0123456789\n:28C:1/1\n:60F:C251130IDR0,00\n\
+                       :61:2512011201D10000,00NTRFINV-42\n:61:2512011201D10000,00NTRFINV-42\n-";
+
+        let (_, entries) = parse_statement(content.as_bytes(), None).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_ne!(entries[0].transaction_id, entries[1].transaction_id);
+        assert_ne!(entries[0].dedup_key(), entries[1].dedup_key());
+    }
+
+    #[test]
+    fn test_mt940_non_ascii_tag_text_is_not_a_field() {
+        let content = ":20:STMT1\n:25:0123456789\n:aé:x\n:1é:x\n\
+                       :61:2512011201D10000,00NTRFNONREF\n:86:Pembayaran é\n-";
+
+        let (_, entries) = parse_statement(content.as_bytes(), None).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].description.as_deref(), Some("Pembayaran é"));
+    }
+
+    #[test]
+    fn test_rejects_deeply_nested_markup() {
+        let content = format!("<OFX>{}", "<a>".repeat(200_000));
+        let result = parse_statement(content.as_bytes(), Some(StatementFormat::Ofx));
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        // Nesting up to the limit is still read, and searched without recursion
+        let content = format!("{}<b>x</b>", "<a>".repeat(markup::MAX_DEPTH - 1));
+        let document = markup::parse(&content).unwrap();
+        assert_eq!(document.find("b").map(|b| b.text.as_str()), Some("x"));
+        let mut found = Vec::new();
+        document.find_all("b", &mut found);
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn test_find_match_prefers_closest_date() {
+        let entry = StatementEntry {
+            transaction_id: "X".to_string(),
+            account: None,
+            booking_date: date(2025, 12, 10),
+            r#type: TransactionType::Expense,
+            amount: Decimal::new(2500000, 2),
+            currency: Currency::IDR,
+            counterparty: None,
+            description: None,
+        };
+        let candidate = |day, amount| ReconciliationCandidate {
+            id: Uuid::new_v4(),
+            r#type: TransactionType::Expense,
+            amount: Decimal::new(amount, 2),
+            currency: Currency::IDR,
+            transaction_date: date(2025, 12, day),
+        };
+
+        let far = candidate(7, 2500000);
+        let near = candidate(9, 2500000);
+        let other_amount = candidate(10, 2600000);
+        let outside = candidate(14, 2500000);
+        let candidates = [far.clone(), near.clone(), other_amount, outside];
+
+        assert_eq!(entry.find_match(&candidates, &HashSet::new()), Some(near.id));
+        assert_eq!(entry.find_match(&candidates, &HashSet::from([near.id])), Some(far.id));
+        assert_eq!(entry.find_match(&candidates, &HashSet::from([near.id, far.id])), None);
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("-45500.00"), Some(Decimal::new(-4550000, 2)));
+        assert_eq!(parse_decimal("1250000,50"), Some(Decimal::new(125000050, 2)));
+        assert_eq!(parse_decimal("100,"), Some(Decimal::new(100, 0)));
+        assert_eq!(parse_decimal("abc"), None);
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{Datelike, NaiveDate};
+use crate::db::models::{Currency, TransactionType};
+use super::{clean, content_id, parse_currency, parse_decimal, StatementEntry};
+
+/// Parse the `:61:` statement lines of an MT940 file, with the `:86:` information that
+/// follows each of them. A file may hold several statements.
+pub fn parse(content: &str) -> Result<Vec<StatementEntry>, String> {
+    let mut entries = Vec::new();
+    let mut account: Option<String> = None;
+    let mut statement_number = String::new();
+    let mut currency = Currency::IDR;
+    let mut line_index = 0usize;
+    let mut previous_tag = String::new();
+
+    for (tag, value) in fields(content) {
+        match tag.as_str() {
+            "20" => line_index = 0,
+            "25" => account = clean(&value),
+            "28C" | "28" => statement_number = value.trim().to_string(),
+            "60F" | "60M" => {
+                // D/C mark, YYMMDD, currency, amount
+                if let Some(code) = value.trim().get(7..10) {
+                    currency = parse_currency(Some(code))?;
+                }
+            }
+            "61" => {
+                line_index += 1;
+                let id_parts = [
+                    account.as_deref().unwrap_or_default(),
+                    statement_number.as_str(),
+                    &line_index.to_string(),
+                    value.as_str(),
+                ];
+                let entry = parse_statement_line(&value, account.clone(), currency, content_id(&id_parts))?;
+                entries.push(entry);
+            }
+            // Information for the preceding statement line; it supersedes the :61: details
+            "86" if previous_tag == "61" => {
+                if let Some(entry) = entries.last_mut() {
+                    let (counterparty, description) = parse_information(&value);
+                    entry.counterparty = counterparty.or(entry.counterparty.take());
+                    entry.description = description.or(entry.description.take());
+                }
+            }
+            _ => {}
+        }
+        previous_tag = tag;
+    }
+
+    if entries.is_empty() && account.is_none() {
+        return Err("No :25: account or :61: statement lines found".to_string());
+    }
+
+    Ok(entries)
+}
+
+/// Split the text block into `(tag, value)` fields; continuation lines stay in the value
+fn fields(content: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim_end();
+
+        if let Some(rest) = line.strip_prefix(':')
+            && let Some(end) = rest.find(':')
+            && (2..=3).contains(&end)
+            && rest.get(..2).is_some_and(|p| p.bytes().all(|b| b.is_ascii_digit()))
+        {
+            fields.push((rest[..end].to_string(), rest[end + 1..].to_string()));
+        } else if line.starts_with('-') || line.starts_with('{') {
+            // End of a message block or SWIFT envelope
+            continue;
+        } else if let Some((_, value)) = fields.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+
+    fields
+}
+
+/// `YYMMDD[MMDD](C|D|RC|RD)[funds code]amount N type reference[//bank reference]`
+fn parse_statement_line(
+    value: &str,
+    account: Option<String>,
+    currency: Currency,
+    fallback_id: String,
+) -> Result<StatementEntry, String> {
+    let (line, supplementary) = value.split_once('\n').unwrap_or((value, ""));
+    let invalid = || format!("Invalid :61: line '{}'", line);
+
+    let value_date = line
+        .get(..6)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%y%m%d").ok())
+        .ok_or_else(invalid)?;
+    let mut rest = &line[6..];
+
+    // Optional entry (booking) date, MMDD in the value date's year
+    let mut booking_date = value_date;
+    if let Some(entry) = rest.get(..4).filter(|d| d.chars().all(|c| c.is_ascii_digit())) {
+        let month: u32 = entry[..2].parse().map_err(|_| invalid())?;
+        let day: u32 = entry[2..].parse().map_err(|_| invalid())?;
+        let year = match (value_date.month(), month) {
+            (1, 12) => value_date.year() - 1,
+            (12, 1) => value_date.year() + 1,
+            _ => value_date.year(),
+        };
+        booking_date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)?;
+        rest = &rest[4..];
+    }
+
+    // Reversals (RC/RD) undo the opposite movement
+    let (r#type, mark_len) = if rest.starts_with("RC") {
+        (TransactionType::Expense, 2)
+    } else if rest.starts_with("RD") {
+        (TransactionType::Income, 2)
+    } else if rest.starts_with('C') {
+        (TransactionType::Income, 1)
+    } else if rest.starts_with('D') {
+        (TransactionType::Expense, 1)
+    } else {
+        return Err(invalid());
+    };
+    rest = &rest[mark_len..];
+
+    // Optional funds code: a letter before the amount
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let amount_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ','))
+        .unwrap_or(rest.len());
+    let amount = parse_decimal(&rest[..amount_len]).ok_or_else(invalid)?;
+    rest = &rest[amount_len..];
+
+    // Transaction type identification: N or F plus a three-character code. Only the bank
+    // reference identifies the line; a customer reference (e.g. an invoice number) can
+    // repeat across lines
+    let reference = rest.get(4..).unwrap_or_default();
+    let bank_reference = reference.split_once("//").map_or("", |(_, bank)| bank.trim());
+
+    let transaction_id = if !bank_reference.is_empty() && !bank_reference.eq_ignore_ascii_case("NONREF") {
+        bank_reference.to_string()
+    } else {
+        fallback_id
+    };
+
+    Ok(StatementEntry {
+        transaction_id,
+        account,
+        booking_date,
+        r#type,
+        amount,
+        currency,
+        counterparty: None,
+        description: clean(supplementary),
+    })
+}
+
+/// Counterparty and description from `:86:`. Structured details (`?20`..`?29` remittance,
+/// `?32`/`?33` name) are split up; free text becomes the description.
+fn parse_information(value: &str) -> (Option<String>, Option<String>) {
+    let text = value.replace('\n', "");
+
+    if !text.contains("?20") {
+        return (None, clean(&value.replace('\n', " ")));
+    }
+
+    let mut remittance = Vec::new();
+    let mut name = Vec::new();
+
+    for part in text.split('?').skip(1) {
+        let (code, content) = part.split_at(part.len().min(2));
+        match code {
+            "20" | "21" | "22" | "23" | "24" | "25" | "26" | "27" | "28" | "29" => remittance.push(content),
+            "32" | "33" => name.push(content),
+            _ => {}
+        }
+    }
+
+    (clean(&name.concat()), clean(&remittance.join(" ")))
+}
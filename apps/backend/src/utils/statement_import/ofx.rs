@@ -0,0 +1,80 @@
+use chrono::NaiveDate;
+use super::{clean, content_id, direction, markup, parse_currency, parse_decimal, StatementEntry};
+
+/// Parse bank (`STMTRS`) and credit card (`CCSTMTRS`) statements of an OFX/QFX file
+pub fn parse(content: &str) -> Result<Vec<StatementEntry>, String> {
+    let document = markup::parse(content)?;
+    let ofx = document.find("OFX").ok_or("Missing <OFX> element")?;
+
+    let mut statements = Vec::new();
+    ofx.find_all("STMTRS", &mut statements);
+    ofx.find_all("CCSTMTRS", &mut statements);
+    if statements.is_empty() {
+        return Err("No statement found".to_string());
+    }
+
+    let mut entries = Vec::new();
+
+    for statement in statements {
+        let currency = statement.text_at(&["CURDEF"]);
+        let account = statement
+            .text_at(&["BANKACCTFROM", "ACCTID"])
+            .or_else(|| statement.text_at(&["CCACCTFROM", "ACCTID"]))
+            .map(str::to_string);
+
+        let Some(list) = statement.child("BANKTRANLIST") else {
+            continue;
+        };
+
+        for transaction in list.children_named("STMTTRN") {
+            let posted = transaction
+                .text_at(&["DTPOSTED"])
+                .ok_or("Transaction without DTPOSTED")?;
+            let booking_date = parse_date(posted).ok_or_else(|| format!("Invalid DTPOSTED '{}'", posted))?;
+
+            let raw_amount = transaction.text_at(&["TRNAMT"]).ok_or("Transaction without TRNAMT")?;
+            let signed = parse_decimal(raw_amount).ok_or_else(|| format!("Invalid TRNAMT '{}'", raw_amount))?;
+            let (r#type, amount) = direction(signed);
+
+            let counterparty = transaction
+                .text_at(&["NAME"])
+                .or_else(|| transaction.text_at(&["PAYEE", "NAME"]))
+                .and_then(clean);
+            let description = transaction.text_at(&["MEMO"]).and_then(clean);
+
+            let currency = transaction
+                .text_at(&["CURRENCY", "CURSYM"])
+                .or_else(|| transaction.text_at(&["ORIGCURRENCY", "CURSYM"]))
+                .or(currency);
+
+            let transaction_id = match transaction.text_at(&["FITID"]) {
+                Some(fitid) => fitid.to_string(),
+                None => content_id(&[
+                    account.as_deref().unwrap_or_default(),
+                    posted,
+                    raw_amount,
+                    counterparty.as_deref().unwrap_or_default(),
+                    description.as_deref().unwrap_or_default(),
+                ]),
+            };
+
+            entries.push(StatementEntry {
+                transaction_id,
+                account: account.clone(),
+                booking_date,
+                r#type,
+                amount,
+                currency: parse_currency(currency)?,
+                counterparty,
+                description,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// OFX dates are `YYYYMMDD`, optionally followed by a time and time zone
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
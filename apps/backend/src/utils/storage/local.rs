@@ -0,0 +1,80 @@
+use std::{io::ErrorKind, path::PathBuf};
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use super::{validate_key, StorageBackend};
+
+/// Keeps objects as files below a directory, one file per key
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write next to the target and rename, so readers never see a partial file
+        let partial = path.with_extension(format!("{}.partial", Uuid::new_v4()));
+        tokio::fs::write(&partial, &bytes).await?;
+        if let Err(e) = tokio::fs::rename(&partial, &path).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_delete() {
+        let root = std::env::temp_dir().join(format!("mintora-storage-{}", Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+
+        storage.put("receipts/t1/a1", b"receipt".to_vec(), "image/png").await.unwrap();
+        assert_eq!(storage.get("receipts/t1/a1").await.unwrap(), Some(b"receipt".to_vec()));
+
+        storage.delete("receipts/t1/a1").await.unwrap();
+        assert_eq!(storage.get("receipts/t1/a1").await.unwrap(), None);
+        storage.delete("receipts/t1/a1").await.unwrap();
+
+        assert!(storage.get("../outside").await.is_err());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+pub mod local;
+pub mod s3;
+
+use std::sync::Arc;
+use axum::async_trait;
+
+use crate::{config::StorageBackendKind, error::AppError};
+use self::{local::LocalStorage, s3::S3Storage};
+
+/// Object storage for uploaded files. Keys are relative paths such as
+/// `receipts/<transaction_id>/<attachment_id>`; see `validate_key`.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Store `bytes` under `key`, replacing any object already there
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), AppError>;
+
+    /// The object under `key`, or None when there is none
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError>;
+
+    /// Remove the object under `key`; removing a missing object is not an error
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Build the backend selected in the configuration
+pub fn from_config(kind: &StorageBackendKind, http: reqwest::Client) -> Arc<dyn StorageBackend> {
+    match kind {
+        StorageBackendKind::Local { dir } => Arc::new(LocalStorage::new(dir)),
+        StorageBackendKind::S3(config) => Arc::new(S3Storage::new(config.clone(), http)),
+    }
+}
+
+/// Keys are `/`-separated segments of ASCII letters, digits, `-`, `_` and `.`, so they map
+/// to a path inside the storage directory and need no escaping in an S3 URL
+pub fn validate_key(key: &str) -> Result<(), AppError> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+
+    if !valid {
+        return Err(AppError::Internal(format!("Invalid storage key '{}'", key)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("receipts/2b1f/9c0d.pdf").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("/receipts/a").is_err());
+        assert!(validate_key("receipts//a").is_err());
+        assert!(validate_key("receipts/../secrets").is_err());
+        assert!(validate_key("receipts/a b").is_err());
+    }
+}
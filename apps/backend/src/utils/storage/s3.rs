@@ -0,0 +1,279 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{header, Method, RequestBuilder, StatusCode, Url};
+use sha2::{Digest, Sha256};
+
+use crate::{config::S3Config, error::AppError};
+use super::{validate_key, StorageBackend};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Headers covered by the request signature
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// Keeps objects in an S3-compatible bucket. Requests are signed with AWS Signature
+/// Version 4, which AWS S3, MinIO and most other stores accept.
+pub struct S3Storage {
+    config: S3Config,
+    http: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config, http: reqwest::Client) -> Self {
+        Self { config, http }
+    }
+
+    fn object_url(&self, key: &str) -> Result<Url, AppError> {
+        validate_key(key)?;
+
+        let endpoint = Url::parse(&self.config.endpoint)
+            .map_err(|e| AppError::Internal(format!("Invalid S3 endpoint: {}", e)))?;
+        let url = if self.config.path_style {
+            format!("{}/{}/{}", endpoint.as_str().trim_end_matches('/'), self.config.bucket, key)
+        } else {
+            let host = endpoint
+                .host_str()
+                .ok_or_else(|| AppError::Internal("S3 endpoint has no host".to_string()))?;
+            let port = endpoint.port().map(|port| format!(":{}", port)).unwrap_or_default();
+            format!("{}://{}.{}{}/{}", endpoint.scheme(), self.config.bucket, host, port, key)
+        };
+
+        Url::parse(&url).map_err(|e| AppError::Internal(format!("Invalid S3 object URL: {}", e)))
+    }
+
+    /// A request for `key` carrying a Signature Version 4 `Authorization` header
+    fn signed_request(&self, method: Method, key: &str, body: Vec<u8>) -> Result<RequestBuilder, AppError> {
+        let url = self.object_url(key)?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let now = Utc::now();
+
+        let authorization = authorization(
+            &self.config,
+            method.as_str(),
+            url.path(),
+            &host,
+            &payload_hash,
+            now,
+        );
+
+        Ok(self
+            .http
+            .request(method, url)
+            .header(header::AUTHORIZATION, authorization)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date(now))
+            .body(body))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), AppError> {
+        let response = self
+            .signed_request(Method::PUT, key, bytes)?
+            .header(header::CONTENT_TYPE, content_type)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(s3_error("PUT", key, response.status()));
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let response = self.signed_request(Method::GET, key, Vec::new())?.send().await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+            status => Err(s3_error("GET", key, status)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let response = self.signed_request(Method::DELETE, key, Vec::new())?.send().await?;
+
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(s3_error("DELETE", key, response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+fn s3_error(method: &str, key: &str, status: StatusCode) -> AppError {
+    AppError::ExternalService(format!("S3 {} {} failed with status {}", method, key, status))
+}
+
+fn amz_date(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// `Authorization` header value for a request without query parameters
+fn authorization(
+    config: &S3Config,
+    method: &str,
+    path: &str,
+    host: &str,
+    payload_hash: &str,
+    at: DateTime<Utc>,
+) -> String {
+    let date = at.format("%Y%m%d").to_string();
+    let amz_date = amz_date(at);
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = signing_key(&config.secret_access_key, &date, &config.region, "s3");
+    let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key_id, scope, SIGNED_HEADERS, signature
+    )
+}
+
+/// Key derived from the secret for one day, region and service
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, service.as_bytes());
+    hmac(&key, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use axum::{
+        body::Bytes,
+        extract::{Path, State},
+        http::{HeaderMap, StatusCode},
+        routing::put,
+        Router,
+    };
+    use super::*;
+
+    type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// A MinIO-style stand-in: path-style objects kept in memory; unsigned requests are
+    /// rejected
+    async fn spawn_mock_store() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        fn signed(headers: &HeaderMap) -> bool {
+            headers
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 Credential=minio/"))
+        }
+
+        let app = Router::new()
+            .route(
+                "/receipts/*key",
+                put(|State(objects): State<Objects>, Path(key): Path<String>, headers: HeaderMap, body: Bytes| async move {
+                    if !signed(&headers) {
+                        return StatusCode::FORBIDDEN;
+                    }
+                    objects.lock().unwrap().insert(key, body.to_vec());
+                    StatusCode::OK
+                })
+                .get(|State(objects): State<Objects>, Path(key): Path<String>, headers: HeaderMap| async move {
+                    if !signed(&headers) {
+                        return Err(StatusCode::FORBIDDEN);
+                    }
+                    objects.lock().unwrap().get(&key).cloned().ok_or(StatusCode::NOT_FOUND)
+                })
+                .delete(|State(objects): State<Objects>, Path(key): Path<String>, headers: HeaderMap| async move {
+                    if !signed(&headers) {
+                        return StatusCode::FORBIDDEN;
+                    }
+                    objects.lock().unwrap().remove(&key);
+                    StatusCode::NO_CONTENT
+                }),
+            )
+            .with_state(Objects::default());
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        endpoint
+    }
+
+    fn config(endpoint: &str) -> S3Config {
+        S3Config {
+            endpoint: endpoint.to_string(),
+            bucket: "receipts".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "minio".to_string(),
+            secret_access_key: "minio-secret".to_string(),
+            path_style: true,
+        }
+    }
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        // From the AWS Signature Version 4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_object_url_styles() {
+        let mut config = config("https://s3.eu-central-1.amazonaws.com");
+        let storage = S3Storage::new(config.clone(), reqwest::Client::new());
+        assert_eq!(
+            storage.object_url("r/1").unwrap().as_str(),
+            "https://s3.eu-central-1.amazonaws.com/receipts/r/1"
+        );
+
+        config.path_style = false;
+        let storage = S3Storage::new(config, reqwest::Client::new());
+        assert_eq!(
+            storage.object_url("r/1").unwrap().as_str(),
+            "https://receipts.s3.eu-central-1.amazonaws.com/r/1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_get_delete_against_mock_store() {
+        let endpoint = spawn_mock_store().await;
+        let storage = S3Storage::new(config(&endpoint), reqwest::Client::new());
+
+        storage.put("receipts/t1/a1", b"receipt".to_vec(), "application/pdf").await.unwrap();
+        assert_eq!(storage.get("receipts/t1/a1").await.unwrap(), Some(b"receipt".to_vec()));
+
+        storage.delete("receipts/t1/a1").await.unwrap();
+        assert_eq!(storage.get("receipts/t1/a1").await.unwrap(), None);
+    }
+}
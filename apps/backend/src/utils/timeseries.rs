@@ -0,0 +1,103 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Width of a time-series bucket. Weeks start on Monday, like PostgreSQL's `date_trunc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketInterval {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl BucketInterval {
+    /// Unit name understood by `date_trunc`
+    pub fn sql_unit(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// First day of the bucket containing `date`
+    pub fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Start of the bucket `count` buckets before the one starting at `start`
+    pub fn buckets_before(self, start: NaiveDate, count: u32) -> NaiveDate {
+        match self {
+            Self::Day => start - Duration::days(count as i64),
+            Self::Week => start - Duration::weeks(count as i64),
+            Self::Month => start.checked_sub_months(Months::new(count)).unwrap_or(start),
+        }
+    }
+
+    /// Number of buckets covering `from` to `to`, both inclusive
+    pub fn bucket_count(self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let (from, to) = (self.bucket_start(from), self.bucket_start(to));
+        match self {
+            Self::Day => (to - from).num_days() + 1,
+            Self::Week => (to - from).num_weeks() + 1,
+            Self::Month => {
+                (to.year() as i64 - from.year() as i64) * 12 + to.month() as i64 - from.month() as i64 + 1
+            }
+        }
+    }
+}
+
+/// What each series of a time-series report covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeriesGrouping {
+    /// A single series with every transaction
+    #[default]
+    None,
+    /// One series per category; split transactions count each line in its own category
+    Category,
+    /// One series per tag; a transaction with several tags counts in each of them
+    Tag,
+}
+
+impl SeriesGrouping {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Category => "category",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_buckets_align_to_calendar_units() {
+        // 2025-12-17 is a Wednesday
+        assert_eq!(BucketInterval::Day.bucket_start(date(2025, 12, 17)), date(2025, 12, 17));
+        assert_eq!(BucketInterval::Week.bucket_start(date(2025, 12, 17)), date(2025, 12, 15));
+        assert_eq!(BucketInterval::Month.bucket_start(date(2025, 12, 17)), date(2025, 12, 1));
+
+        assert_eq!(BucketInterval::Month.buckets_before(date(2025, 3, 1), 3), date(2024, 12, 1));
+        assert_eq!(BucketInterval::Week.buckets_before(date(2025, 12, 15), 2), date(2025, 12, 1));
+    }
+
+    #[test]
+    fn test_counts_partial_buckets_at_both_ends() {
+        assert_eq!(BucketInterval::Day.bucket_count(date(2025, 12, 1), date(2025, 12, 31)), 31);
+        assert_eq!(BucketInterval::Week.bucket_count(date(2025, 12, 17), date(2025, 12, 22)), 2);
+        assert_eq!(BucketInterval::Month.bucket_count(date(2024, 11, 30), date(2025, 2, 1)), 4);
+    }
+}
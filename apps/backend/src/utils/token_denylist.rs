@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::{db::repositories::RevokedTokenRepository, error::AppError};
+
+/// Overlap when syncing, so entries committed slightly out of order are not missed
+const SYNC_OVERLAP_SECONDS: i64 = 5;
+
+/// In-memory copy of `revoked_access_tokens`, so checking a token's jti costs no DB query.
+/// Entries revoked on this replica are visible immediately; entries from other replicas
+/// appear after the next sync.
+#[derive(Default)]
+pub struct TokenDenylist {
+    /// jti → token expiry
+    entries: RwLock<HashMap<Uuid, NaiveDateTime>>,
+    /// Latest revoked_at seen in the database
+    last_revoked_at: Mutex<Option<NaiveDateTime>>,
+}
+
+impl TokenDenylist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the token has been revoked
+    pub fn is_revoked(&self, jti: &Uuid) -> bool {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(jti)
+    }
+
+    /// Revoke a token until its expiry (persisted for other replicas)
+    pub async fn revoke(
+        &self,
+        pool: &PgPool,
+        jti: Uuid,
+        user_id: Uuid,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), AppError> {
+        RevokedTokenRepository::create(pool, jti, user_id, expires_at).await?;
+        self.remember(jti, expires_at);
+
+        Ok(())
+    }
+
+    /// Pull entries revoked since the last sync and forget expired ones
+    pub async fn sync(&self, pool: &PgPool) -> Result<(), AppError> {
+        let since = self
+            .last_revoked_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|at| at - ChronoDuration::seconds(SYNC_OVERLAP_SECONDS));
+
+        let rows = RevokedTokenRepository::list_active_since(pool, since).await?;
+
+        if let Some(latest) = rows.iter().map(|(_, _, revoked_at)| *revoked_at).max() {
+            let mut last = self.last_revoked_at.lock().unwrap_or_else(|e| e.into_inner());
+            *last = Some(last.map_or(latest, |current| current.max(latest)));
+        }
+
+        for (jti, expires_at, _) in rows {
+            self.remember(jti, expires_at);
+        }
+        self.prune_expired();
+
+        Ok(())
+    }
+
+    /// Load the denylist, then keep it in sync every `interval`
+    pub fn spawn_sync(self: Arc<Self>, pool: PgPool, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.sync(&pool).await {
+                    tracing::warn!("Failed to sync token denylist: {:?}", e);
+                }
+                if let Err(e) = RevokedTokenRepository::delete_expired(&pool).await {
+                    tracing::warn!("Failed to delete expired denylist entries: {:?}", e);
+                }
+            }
+        });
+    }
+
+    fn remember(&self, jti: Uuid, expires_at: NaiveDateTime) {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(jti, expires_at);
+    }
+
+    fn prune_expired(&self) {
+        let now = Utc::now().naive_utc();
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, expires_at| *expires_at > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denylist_forgets_expired_tokens() {
+        let denylist = TokenDenylist::new();
+        let now = Utc::now().naive_utc();
+        let live = Uuid::new_v4();
+        let expired = Uuid::new_v4();
+
+        denylist.remember(live, now + ChronoDuration::seconds(60));
+        denylist.remember(expired, now - ChronoDuration::seconds(1));
+        assert!(denylist.is_revoked(&live));
+        assert!(denylist.is_revoked(&expired));
+
+        denylist.prune_expired();
+        assert!(denylist.is_revoked(&live));
+        assert!(!denylist.is_revoked(&expired));
+        assert!(!denylist.is_revoked(&Uuid::new_v4()));
+    }
+}
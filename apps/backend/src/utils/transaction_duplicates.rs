@@ -0,0 +1,265 @@
+use rust_decimal::Decimal;
+
+use crate::db::models::Transaction;
+
+/// Score at which two transactions are reported as a suspected duplicate
+pub const DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// Relative amount difference still treated as the same charge (fees, rounding)
+const AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+const AMOUNT_WEIGHT: f64 = 0.45;
+const DATE_WEIGHT: f64 = 0.25;
+const MERCHANT_WEIGHT: f64 = 0.30;
+/// Highest score of a pair missing a merchant name, below `DUPLICATE_THRESHOLD`: amount
+/// and date alone fit too many unrelated entries, such as two bus fares on one day
+const UNNAMED_MAX_SCORE: f64 = 0.7;
+
+/// Two transactions that look like the same spend recorded twice
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicatePair<'a> {
+    /// The one recorded first
+    pub keep: &'a Transaction,
+    pub duplicate: &'a Transaction,
+    pub score: f64,
+    /// Both were recorded from the same chat message
+    pub same_message: bool,
+}
+
+/// Lowercased alphanumeric words, so "ALFAMART - Sudirman" matches "Alfamart Sudirman"
+pub fn normalize_merchant(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Similarity of two merchant names between 0 and 1; None when either name is missing
+pub fn merchant_similarity(a: Option<&str>, b: Option<&str>) -> Option<f64> {
+    let a = a.map(normalize_merchant).filter(|name| !name.is_empty())?;
+    let b = b.map(normalize_merchant).filter(|name| !name.is_empty())?;
+
+    Some(if a == b {
+        1.0
+    } else if a.contains(&b) || b.contains(&a) {
+        0.9
+    } else {
+        let longest = a.chars().count().max(b.chars().count());
+        1.0 - levenshtein(&a, &b) as f64 / longest as f64
+    })
+}
+
+/// Edit distance between two strings, counted in characters
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// How likely `b` records the same spend as `a`, between 0 and 1. None when they cannot
+/// be duplicates: different type or currency, amounts more than 1% apart, or more than
+/// a day between them. Without both merchant names it stays below the threshold, unless
+/// both were recorded from the same chat message
+pub fn duplicate_score(a: &Transaction, b: &Transaction) -> Option<f64> {
+    if a.id == b.id || a.r#type != b.r#type || a.currency != b.currency {
+        return None;
+    }
+    if same_message(a, b) {
+        return Some(1.0);
+    }
+
+    let amount = if a.amount == b.amount {
+        1.0
+    } else if (a.amount - b.amount).abs() <= a.amount.abs().max(b.amount.abs()) * AMOUNT_TOLERANCE {
+        0.8
+    } else {
+        return None;
+    };
+
+    let date = match (a.transaction_date - b.transaction_date).num_days().abs() {
+        0 => 1.0,
+        1 => 0.7,
+        _ => return None,
+    };
+
+    let score = AMOUNT_WEIGHT * amount + DATE_WEIGHT * date;
+
+    Some(match merchant_similarity(a.merchant_name.as_deref(), b.merchant_name.as_deref()) {
+        Some(merchant) => score + MERCHANT_WEIGHT * merchant,
+        None => score / (AMOUNT_WEIGHT + DATE_WEIGHT) * UNNAMED_MAX_SCORE,
+    })
+}
+
+/// Message IDs are only unique per chat client
+fn same_message(a: &Transaction, b: &Transaction) -> bool {
+    a.client_id.is_some()
+        && a.source_message_id.is_some()
+        && a.client_id == b.client_id
+        && a.source_message_id == b.source_message_id
+}
+
+/// Pairs scoring at least the threshold, most likely first. Within a pair the earlier
+/// recorded transaction is the one to keep
+pub fn find_duplicate_pairs(transactions: &[Transaction]) -> Vec<DuplicatePair<'_>> {
+    let mut sorted: Vec<&Transaction> = transactions.iter().collect();
+    sorted.sort_by_key(|transaction| transaction.transaction_date);
+
+    let mut pairs = Vec::new();
+    for (i, a) in sorted.iter().enumerate() {
+        for b in &sorted[i + 1..] {
+            if (b.transaction_date - a.transaction_date).num_days() > 1 {
+                break;
+            }
+            let Some(score) = duplicate_score(a, b).filter(|score| *score >= DUPLICATE_THRESHOLD) else {
+                continue;
+            };
+            let (keep, duplicate) = if (a.created_at, a.id) <= (b.created_at, b.id) { (*a, *b) } else { (*b, *a) };
+            pairs.push(DuplicatePair {
+                keep,
+                duplicate,
+                score,
+                same_message: same_message(a, b),
+            });
+        }
+    }
+
+    pairs.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.keep.transaction_date.cmp(&a.keep.transaction_date)));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::db::models::{Currency, TransactionSource, TransactionType};
+
+    fn transaction(amount: i64, day: u32, merchant: Option<&str>, created_minute: u32) -> Transaction {
+        let created_at: NaiveDateTime = NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(12, created_minute, 0)
+            .unwrap();
+        Transaction {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            workspace_id: None,
+            r#type: TransactionType::Expense,
+            amount: Decimal::from(amount),
+            currency: Currency::IDR,
+            category_id: None,
+            payment_method_id: None,
+            account_id: None,
+            transfer_account_id: None,
+            merchant_name: merchant.map(str::to_string),
+            location: None,
+            description: None,
+            transaction_date: created_at.date(),
+            source: TransactionSource::Whatsapp,
+            client_id: None,
+            source_message_id: None,
+            attachment_urls: None,
+            metadata: None,
+            created_at,
+            updated_at: created_at,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_merchant_similarity_tolerates_formatting_and_typos() {
+        assert_eq!(merchant_similarity(Some("ALFAMART - Sudirman"), Some("alfamart sudirman")), Some(1.0));
+        assert_eq!(merchant_similarity(Some("Starbucks"), Some("Starbucks Coffee")), Some(0.9));
+        assert!(merchant_similarity(Some("Indomaret"), Some("Indomart")).unwrap() > 0.8);
+        assert!(merchant_similarity(Some("Indomaret"), Some("Shell")).unwrap() < 0.3);
+        assert_eq!(merchant_similarity(None, Some("Shell")), None);
+        assert_eq!(merchant_similarity(Some(" - "), None), None);
+    }
+
+    #[test]
+    fn test_duplicate_score_requires_close_amount_and_date() {
+        let base = transaction(50_000, 10, Some("Indomaret"), 0);
+
+        let same = transaction(50_000, 10, Some("indomaret"), 1);
+        assert_eq!(duplicate_score(&base, &same), Some(1.0));
+
+        let next_day_rounded = transaction(50_400, 11, Some("Indomaret"), 1);
+        assert!(duplicate_score(&base, &next_day_rounded).unwrap() >= DUPLICATE_THRESHOLD);
+
+        let other_shop = transaction(50_000, 10, Some("Shell"), 1);
+        assert!(duplicate_score(&base, &other_shop).unwrap() < DUPLICATE_THRESHOLD);
+
+        assert_eq!(duplicate_score(&base, &transaction(60_000, 10, Some("Indomaret"), 1)), None);
+        assert_eq!(duplicate_score(&base, &transaction(50_000, 12, Some("Indomaret"), 1)), None);
+
+        let mut income = same.clone();
+        income.r#type = TransactionType::Income;
+        assert_eq!(duplicate_score(&base, &income), None);
+    }
+
+    #[test]
+    fn test_same_message_is_always_a_duplicate() {
+        let mut a = transaction(50_000, 10, Some("Indomaret"), 0);
+        let mut b = transaction(75_000, 10, Some("Shell"), 1);
+        let client_id = Some(Uuid::new_v4());
+        a.client_id = client_id;
+        b.client_id = client_id;
+        a.source_message_id = Some("wamid.1".to_string());
+        b.source_message_id = Some("wamid.1".to_string());
+
+        assert_eq!(duplicate_score(&a, &b), Some(1.0));
+
+        // The same message ID from another chat client is a different message
+        b.client_id = Some(Uuid::new_v4());
+        assert_eq!(duplicate_score(&a, &b), None);
+    }
+
+    #[test]
+    fn test_unnamed_entries_pair_only_from_the_same_message() {
+        let mut a = transaction(15_000, 10, None, 0);
+        let mut b = transaction(15_000, 10, None, 5);
+        let named = transaction(15_000, 10, Some("TransJakarta"), 7);
+
+        assert!(duplicate_score(&a, &b).unwrap() < DUPLICATE_THRESHOLD);
+        assert!(duplicate_score(&a, &named).unwrap() < DUPLICATE_THRESHOLD);
+        assert!(find_duplicate_pairs(&[a.clone(), b.clone(), named]).is_empty());
+
+        let client_id = Some(Uuid::new_v4());
+        a.client_id = client_id;
+        b.client_id = client_id;
+        a.source_message_id = Some("wamid.2".to_string());
+        b.source_message_id = Some("wamid.2".to_string());
+
+        let transactions = [a, b];
+        let pairs = find_duplicate_pairs(&transactions);
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs[0].same_message);
+    }
+
+    #[test]
+    fn test_find_duplicate_pairs_keeps_the_earlier_recorded() {
+        let first = transaction(50_000, 10, Some("Indomaret"), 0);
+        let retry = transaction(50_000, 10, Some("Indomaret"), 5);
+        let unrelated = transaction(20_000, 10, Some("Shell"), 3);
+        let later = transaction(50_000, 14, Some("Indomaret"), 0);
+        let transactions = vec![retry.clone(), unrelated, later, first.clone()];
+
+        let pairs = find_duplicate_pairs(&transactions);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].keep.id, first.id);
+        assert_eq!(pairs[0].duplicate.id, retry.id);
+        assert!(!pairs[0].same_message);
+    }
+}
@@ -0,0 +1,464 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::{
+    db::models::{Currency, TransactionExportRow, TransactionFilter, TransactionSource, TransactionType},
+    error::AppError,
+};
+
+/// Column headers shared by the CSV and XLSX exports
+const COLUMNS: [&str; 15] = [
+    "id", "date", "type", "amount", "currency", "category", "payment_method", "account",
+    "to_account", "merchant", "location", "description", "tags", "source", "created_at",
+];
+
+/// OFX limits the payee name to 32 characters
+const OFX_NAME_MAX_CHARS: usize = 32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Ofx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ExportFormat::Ofx => "application/x-ofx",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Ofx => "ofx",
+        }
+    }
+}
+
+/// Encodes export rows one at a time. CSV and OFX output is produced as rows arrive;
+/// XLSX rows go to a constant-memory worksheet and the workbook is emitted on `finish`
+pub enum ExportEncoder {
+    Csv { header_written: bool },
+    Xlsx(Box<XlsxExport>),
+    Ofx(OfxStatement),
+}
+
+impl ExportEncoder {
+    pub fn new(
+        format: ExportFormat,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+        generated_at: NaiveDateTime,
+    ) -> Result<Self, AppError> {
+        Ok(match format {
+            ExportFormat::Csv => ExportEncoder::Csv { header_written: false },
+            ExportFormat::Xlsx => ExportEncoder::Xlsx(Box::new(XlsxExport::new()?)),
+            ExportFormat::Ofx => ExportEncoder::Ofx(OfxStatement::new(user_id, filter, generated_at)),
+        })
+    }
+
+    /// Append the encoding of one row to `out`
+    pub fn write_row(&mut self, row: &TransactionExportRow, out: &mut Vec<u8>) -> Result<(), AppError> {
+        match self {
+            ExportEncoder::Csv { header_written } => {
+                if !*header_written {
+                    out.extend(csv_line(COLUMNS)?);
+                    *header_written = true;
+                }
+                out.extend(csv_line(csv_record(row))?);
+            }
+            ExportEncoder::Xlsx(xlsx) => xlsx.write_row(row)?,
+            ExportEncoder::Ofx(ofx) => out.extend(ofx.transaction(row).into_bytes()),
+        }
+
+        Ok(())
+    }
+
+    /// Append whatever remains after the last row to `out`
+    pub fn finish(self, out: &mut Vec<u8>) -> Result<(), AppError> {
+        match self {
+            ExportEncoder::Csv { header_written } => {
+                if !header_written {
+                    out.extend(csv_line(COLUMNS)?);
+                }
+            }
+            ExportEncoder::Xlsx(xlsx) => out.extend(xlsx.finish()?),
+            ExportEncoder::Ofx(ofx) => out.extend(ofx.finish().into_bytes()),
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+fn csv_line<I, T>(record: I) -> Result<Vec<u8>, AppError>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(record)?;
+
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV row: {}", e)))
+}
+
+fn csv_record(row: &TransactionExportRow) -> [String; 15] {
+    [
+        row.id.to_string(),
+        row.transaction_date.to_string(),
+        type_label(&row.r#type).to_string(),
+        row.amount.round_dp(row.currency.minor_units()).to_string(),
+        row.currency.to_string(),
+        row.category.clone().unwrap_or_default(),
+        row.payment_method.clone().unwrap_or_default(),
+        row.account.clone().unwrap_or_default(),
+        row.transfer_account.clone().unwrap_or_default(),
+        row.merchant_name.clone().unwrap_or_default(),
+        row.location.clone().unwrap_or_default(),
+        row.description.clone().unwrap_or_default(),
+        row.tags.clone().unwrap_or_default(),
+        source_label(&row.source).to_string(),
+        row.created_at.to_string(),
+    ]
+}
+
+fn type_label(r#type: &TransactionType) -> &'static str {
+    match r#type {
+        TransactionType::Income => "income",
+        TransactionType::Expense => "expense",
+        TransactionType::Transfer => "transfer",
+    }
+}
+
+fn source_label(source: &TransactionSource) -> &'static str {
+    match source {
+        TransactionSource::Whatsapp => "whatsapp",
+        TransactionSource::Web => "web",
+    }
+}
+
+// ============================================================================
+// XLSX
+// ============================================================================
+
+pub struct XlsxExport {
+    workbook: Workbook,
+    next_row: u32,
+    date_format: Format,
+    datetime_format: Format,
+    amount_format: Format,
+}
+
+impl XlsxExport {
+    fn new() -> Result<Self, AppError> {
+        let mut workbook = Workbook::new();
+        let header_format = Format::new().set_bold();
+
+        let sheet = workbook.add_worksheet_with_constant_memory();
+        sheet.set_name("Transactions")?;
+        sheet.set_freeze_panes(1, 0)?;
+        for (col, title) in COLUMNS.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *title, &header_format)?;
+        }
+
+        Ok(XlsxExport {
+            workbook,
+            next_row: 1,
+            date_format: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+            amount_format: Format::new().set_num_format("#,##0.00"),
+        })
+    }
+
+    fn write_row(&mut self, row: &TransactionExportRow) -> Result<(), AppError> {
+        let sheet = self.workbook.worksheet_from_index(0)?;
+        let r = self.next_row;
+
+        sheet.write_string(r, 0, row.id.to_string())?;
+        sheet.write_datetime_with_format(r, 1, row.transaction_date, &self.date_format)?;
+        sheet.write_string(r, 2, type_label(&row.r#type))?;
+        sheet.write_number_with_format(r, 3, row.amount.to_f64().unwrap_or_default(), &self.amount_format)?;
+        sheet.write_string(r, 4, row.currency.as_str())?;
+
+        let optional = [
+            (5, &row.category),
+            (6, &row.payment_method),
+            (7, &row.account),
+            (8, &row.transfer_account),
+            (9, &row.merchant_name),
+            (10, &row.location),
+            (11, &row.description),
+            (12, &row.tags),
+        ];
+        for (col, value) in optional {
+            if let Some(value) = value {
+                sheet.write_string(r, col, value)?;
+            }
+        }
+
+        sheet.write_string(r, 13, source_label(&row.source))?;
+        sheet.write_datetime_with_format(r, 14, row.created_at, &self.datetime_format)?;
+
+        self.next_row += 1;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, AppError> {
+        Ok(self.workbook.save_to_buffer()?)
+    }
+}
+
+// ============================================================================
+// OFX 2.2
+// ============================================================================
+
+/// A single bank statement covering the exported transactions. OFX statements have one
+/// currency: the first row's, with rows in other currencies tagged via `<CURRENCY>`
+pub struct OfxStatement {
+    account_id: Uuid,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    generated_at: NaiveDateTime,
+    currency: Option<Currency>,
+    balance: Decimal,
+}
+
+impl OfxStatement {
+    fn new(account_id: Uuid, filter: &TransactionFilter, generated_at: NaiveDateTime) -> Self {
+        OfxStatement {
+            account_id,
+            from: filter.from,
+            to: filter.to,
+            generated_at,
+            currency: None,
+            balance: Decimal::ZERO,
+        }
+    }
+
+    /// `<STMTTRN>` for one row, preceded by the statement header on the first call
+    fn transaction(&mut self, row: &TransactionExportRow) -> String {
+        let (trn_type, amount) = match row.r#type {
+            TransactionType::Income => ("CREDIT", row.amount),
+            TransactionType::Expense => ("DEBIT", -row.amount),
+            // The statement spans all of the user's accounts; moving money between two
+            // of them changes nothing
+            TransactionType::Transfer => return String::new(),
+        };
+
+        let mut out = String::new();
+
+        // Rows arrive oldest first, so the first row starts the period when unfiltered
+        if self.currency.is_none() {
+            let start = self.from.unwrap_or(row.transaction_date);
+            out.push_str(&self.header(row.currency, start));
+            self.currency = Some(row.currency);
+        }
+
+        self.balance += amount;
+
+        let name = row
+            .merchant_name
+            .as_deref()
+            .or(row.category.as_deref())
+            .unwrap_or(type_label(&row.r#type));
+
+        out.push_str("<STMTTRN>\n");
+        out.push_str(&format!("<TRNTYPE>{}</TRNTYPE>\n", trn_type));
+        out.push_str(&format!("<DTPOSTED>{}</DTPOSTED>\n", row.transaction_date.format("%Y%m%d")));
+        out.push_str(&format!("<TRNAMT>{}</TRNAMT>\n", amount.round_dp(row.currency.minor_units())));
+        out.push_str(&format!("<FITID>{}</FITID>\n", row.id));
+        out.push_str(&format!(
+            "<NAME>{}</NAME>\n",
+            xml_escape(&name.chars().take(OFX_NAME_MAX_CHARS).collect::<String>())
+        ));
+        if let Some(description) = &row.description {
+            out.push_str(&format!("<MEMO>{}</MEMO>\n", xml_escape(description)));
+        }
+        if self.currency != Some(row.currency) {
+            out.push_str(&format!(
+                "<CURRENCY><CURRATE>1</CURRATE><CURSYM>{}</CURSYM></CURRENCY>\n",
+                row.currency
+            ));
+        }
+        out.push_str("</STMTTRN>\n");
+
+        out
+    }
+
+    fn header(&self, currency: Currency, start: NaiveDate) -> String {
+        let end = self.to.unwrap_or(self.generated_at.date());
+
+        format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+                "<?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n",
+                "<OFX>\n",
+                "<SIGNONMSGSRSV1><SONRS>\n",
+                "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n",
+                "<DTSERVER>{server}</DTSERVER>\n",
+                "<LANGUAGE>ENG</LANGUAGE>\n",
+                "</SONRS></SIGNONMSGSRSV1>\n",
+                "<BANKMSGSRSV1><STMTTRNRS>\n",
+                "<TRNUID>0</TRNUID>\n",
+                "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n",
+                "<STMTRS>\n",
+                "<CURDEF>{currency}</CURDEF>\n",
+                "<BANKACCTFROM><BANKID>MINTORA</BANKID><ACCTID>{account}</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM>\n",
+                "<BANKTRANLIST>\n",
+                "<DTSTART>{start}</DTSTART>\n",
+                "<DTEND>{end}</DTEND>\n",
+            ),
+            server = self.generated_at.format("%Y%m%d%H%M%S"),
+            currency = currency,
+            account = self.account_id,
+            start = start.format("%Y%m%d"),
+            end = end.format("%Y%m%d"),
+        )
+    }
+
+    /// Close the transaction list; the ledger balance is the net of the exported rows
+    fn finish(self) -> String {
+        let mut out = String::new();
+
+        if self.currency.is_none() {
+            let start = self.from.unwrap_or(self.generated_at.date());
+            out.push_str(&self.header(Currency::IDR, start));
+        }
+
+        out.push_str(&format!(
+            concat!(
+                "</BANKTRANLIST>\n",
+                "<LEDGERBAL><BALAMT>{balance}</BALAMT><DTASOF>{as_of}</DTASOF></LEDGERBAL>\n",
+                "</STMTRS>\n",
+                "</STMTTRNRS></BANKMSGSRSV1>\n",
+                "</OFX>\n",
+            ),
+            balance = self.balance.round_dp(self.currency.unwrap_or(Currency::IDR).minor_units()),
+            as_of = self.generated_at.format("%Y%m%d%H%M%S"),
+        ));
+
+        out
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(r#type: TransactionType, amount: i64, merchant: Option<&str>) -> TransactionExportRow {
+        TransactionExportRow {
+            id: Uuid::new_v4(),
+            transaction_date: NaiveDate::from_ymd_opt(2025, 12, 1).unwrap(),
+            r#type,
+            amount: Decimal::new(amount, 2),
+            currency: Currency::IDR,
+            category: Some("Food & Drinks".to_string()),
+            payment_method: Some("GoPay".to_string()),
+            account: Some("GoPay".to_string()),
+            transfer_account: None,
+            merchant_name: merchant.map(str::to_string),
+            location: None,
+            description: Some("Lunch <team>".to_string()),
+            tags: Some("office;work".to_string()),
+            source: TransactionSource::Whatsapp,
+            created_at: NaiveDate::from_ymd_opt(2025, 12, 1).unwrap().and_hms_opt(12, 30, 0).unwrap(),
+        }
+    }
+
+    fn encode(format: ExportFormat, rows: &[TransactionExportRow]) -> Vec<u8> {
+        let generated_at = NaiveDate::from_ymd_opt(2025, 12, 11).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let mut encoder =
+            ExportEncoder::new(format, Uuid::nil(), &TransactionFilter::default(), generated_at).unwrap();
+
+        let mut out = Vec::new();
+        for row in rows {
+            encoder.write_row(row, &mut out).unwrap();
+        }
+        encoder.finish(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_csv_denormalizes_names() {
+        let csv = String::from_utf8(encode(
+            ExportFormat::Csv,
+            &[row(TransactionType::Expense, 2500000, Some("Warung, \"Bu Sri\""))],
+        ))
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1].contains(
+            ",2025-12-01,expense,25000.00,IDR,Food & Drinks,GoPay,GoPay,,\"Warung, \"\"Bu Sri\"\"\",,Lunch <team>,office;work,whatsapp,"
+        ));
+
+        // An empty export is still a valid file
+        let empty = String::from_utf8(encode(ExportFormat::Csv, &[])).unwrap();
+        assert_eq!(empty.trim_end(), COLUMNS.join(","));
+    }
+
+    #[test]
+    fn test_ofx_statement() {
+        let long_name = "Toko Serba Ada Sejahtera Abadi Jaya Makmur";
+        let ofx = String::from_utf8(encode(
+            ExportFormat::Ofx,
+            &[
+                row(TransactionType::Income, 1000000000, None),
+                row(TransactionType::Expense, 2500000, Some(long_name)),
+            ],
+        ))
+        .unwrap();
+
+        assert!(ofx.starts_with("<?xml"));
+        assert!(ofx.contains("<CURDEF>IDR</CURDEF>"));
+        assert!(ofx.contains("<DTSTART>20251201</DTSTART>\n<DTEND>20251211</DTEND>"));
+        assert!(ofx.contains("<TRNTYPE>CREDIT</TRNTYPE>\n<DTPOSTED>20251201</DTPOSTED>\n<TRNAMT>10000000.00</TRNAMT>"));
+        assert!(ofx.contains("<TRNTYPE>DEBIT</TRNTYPE>\n<DTPOSTED>20251201</DTPOSTED>\n<TRNAMT>-25000.00</TRNAMT>"));
+        assert!(ofx.contains("<NAME>Food &amp; Drinks</NAME>"));
+        assert!(ofx.contains(&format!("<NAME>{}</NAME>", &long_name[..OFX_NAME_MAX_CHARS])));
+        assert!(ofx.contains("<MEMO>Lunch &lt;team&gt;</MEMO>"));
+        assert!(ofx.contains("<BALAMT>9975000.00</BALAMT>"));
+        assert!(ofx.trim_end().ends_with("</OFX>"));
+    }
+
+    #[test]
+    fn test_xlsx_is_a_workbook() {
+        let bytes = encode(ExportFormat::Xlsx, &[row(TransactionType::Expense, 2500000, None)]);
+
+        // XLSX files are ZIP archives
+        assert!(bytes.starts_with(b"PK"));
+        let archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert!(archive.file_names().any(|name| name == "xl/worksheets/sheet1.xml"));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::AppError;
+
+/// Most words a search may contain
+pub const MAX_SEARCH_TERMS: usize = 10;
+/// Longest accepted search text
+pub const MAX_SEARCH_LENGTH: usize = 200;
+
+/// Split search text into lowercase words for `transaction_search_query`. Anything that is
+/// not a letter or digit separates words, so no tsquery syntax reaches the database.
+pub fn search_terms(query: &str) -> Result<Vec<String>, AppError> {
+    if query.chars().count() > MAX_SEARCH_LENGTH {
+        return Err(AppError::ValidationError(format!(
+            "Search text must be at most {} characters",
+            MAX_SEARCH_LENGTH
+        )));
+    }
+
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split(|c: char| !c.is_alphanumeric()) {
+        let term = term.to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+
+    if terms.is_empty() {
+        return Err(AppError::ValidationError(
+            "Search text must contain at least one word".to_string(),
+        ));
+    }
+    if terms.len() > MAX_SEARCH_TERMS {
+        return Err(AppError::ValidationError(format!(
+            "Search text can contain at most {} words",
+            MAX_SEARCH_TERMS
+        )));
+    }
+
+    Ok(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_on_punctuation_and_drops_operators() {
+        assert_eq!(
+            search_terms("Kopi  Kenangan, Bandung!").unwrap(),
+            vec!["kopi", "kenangan", "bandung"]
+        );
+        assert_eq!(search_terms("a & !b | c:*").unwrap(), vec!["a", "b", "c"]);
+        assert_eq!(search_terms("Café café CAFÉ").unwrap(), vec!["café"]);
+    }
+
+    #[test]
+    fn test_rejects_empty_and_oversized_queries() {
+        assert!(search_terms("  &|! ").is_err());
+        let words: Vec<String> = (0..=MAX_SEARCH_TERMS).map(|i| format!("w{}", i)).collect();
+        assert!(search_terms(&words.join(" ")).is_err());
+        assert!(search_terms(&"a".repeat(MAX_SEARCH_LENGTH + 1)).is_err());
+    }
+}
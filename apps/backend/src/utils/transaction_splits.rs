@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use crate::{
+    db::models::{Category, CategoryType, NewTransactionSplit, Transaction, TransactionType},
+    error::AppError,
+};
+
+/// Most lines a single transaction can be split into
+pub const MAX_SPLIT_LINES: usize = 50;
+
+/// Check split lines against their transaction: at least two lines, each with a positive
+/// amount in the transaction currency's precision and an active category of a matching
+/// type, together summing to exactly the transaction amount. All problems are reported.
+pub fn validate_splits(
+    transaction: &Transaction,
+    lines: &[NewTransactionSplit],
+    categories: &[Category],
+) -> Result<(), AppError> {
+    if transaction.r#type == TransactionType::Transfer {
+        return Err(AppError::BadRequest("Transfers cannot be split".to_string()));
+    }
+    if lines.len() < 2 {
+        return Err(AppError::ValidationError(
+            "A split needs at least two lines; set the transaction category instead".to_string(),
+        ));
+    }
+    if lines.len() > MAX_SPLIT_LINES {
+        return Err(AppError::ValidationError(format!(
+            "A transaction can be split into at most {} lines",
+            MAX_SPLIT_LINES
+        )));
+    }
+
+    let categories: HashMap<Uuid, &Category> = categories.iter().map(|c| (c.id, c)).collect();
+    let precision = transaction.currency.minor_units();
+    let mut errors = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let number = index + 1;
+
+        if line.amount <= Decimal::ZERO {
+            errors.push(format!("Line {}: amount must be positive", number));
+        } else if line.amount.normalize().scale() > precision {
+            errors.push(format!(
+                "Line {}: {} amounts have at most {} decimal places",
+                number, transaction.currency, precision
+            ));
+        }
+
+        match categories.get(&line.category_id) {
+            None => errors.push(format!("Line {}: unknown category", number)),
+            Some(category) if !category.is_active => {
+                errors.push(format!("Line {}: category '{}' is inactive", number, category.name))
+            }
+            Some(category) if !category_fits(&category.r#type, &transaction.r#type) => {
+                errors.push(format!(
+                    "Line {}: category '{}' cannot be used for {} transactions",
+                    number,
+                    category.name,
+                    if transaction.r#type == TransactionType::Income { "income" } else { "expense" }
+                ))
+            }
+            Some(_) => {}
+        }
+
+        if line.description.as_deref().is_some_and(|d| d.chars().count() > 255) {
+            errors.push(format!("Line {}: description must be at most 255 characters", number));
+        }
+    }
+
+    let total: Decimal = lines.iter().map(|line| line.amount).sum();
+    if total != transaction.amount {
+        errors.push(format!(
+            "Lines add up to {} but the transaction amount is {}",
+            total, transaction.amount
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::ValidationError(errors.join("; ")));
+    }
+
+    Ok(())
+}
+
+fn category_fits(category: &CategoryType, transaction: &TransactionType) -> bool {
+    matches!(
+        (category, transaction),
+        (CategoryType::Both, _)
+            | (CategoryType::Income, TransactionType::Income)
+            | (CategoryType::Expense, TransactionType::Expense)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use chrono::NaiveDate;
+    use crate::db::models::{Currency, TransactionSource};
+
+    fn category(name: &str, r#type: CategoryType) -> Category {
+        let now = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        Category {
+            id: Uuid::new_v4(),
+            user_id: None,
+            workspace_id: None,
+            name: name.to_string(),
+            r#type,
+            icon: None,
+            color: None,
+            parent_category_id: None,
+            is_system: true,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn transaction(amount: &str) -> Transaction {
+        let now = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        Transaction {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            workspace_id: None,
+            r#type: TransactionType::Expense,
+            amount: Decimal::from_str(amount).unwrap(),
+            currency: Currency::IDR,
+            category_id: None,
+            payment_method_id: None,
+            account_id: None,
+            transfer_account_id: None,
+            merchant_name: Some("Superindo".to_string()),
+            location: None,
+            description: None,
+            transaction_date: now.date(),
+            source: TransactionSource::Web,
+            client_id: None,
+            source_message_id: None,
+            attachment_urls: None,
+            metadata: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    fn line(category: &Category, amount: &str) -> NewTransactionSplit {
+        NewTransactionSplit {
+            category_id: category.id,
+            amount: Decimal::from_str(amount).unwrap(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_split() {
+        let groceries = category("Groceries", CategoryType::Expense);
+        let household = category("Household", CategoryType::Both);
+        let categories = [groceries.clone(), household.clone()];
+
+        let lines = [line(&groceries, "150000.50"), line(&household, "49999.50")];
+        assert!(validate_splits(&transaction("200000.00"), &lines, &categories).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_split_reports_every_problem() {
+        let groceries = category("Groceries", CategoryType::Expense);
+        let salary = category("Salary", CategoryType::Income);
+        let categories = [groceries.clone(), salary.clone()];
+        let unknown = category("Unknown", CategoryType::Expense);
+
+        let lines = [
+            line(&groceries, "100000.001"),
+            line(&salary, "50000"),
+            line(&unknown, "0"),
+        ];
+        let Err(AppError::ValidationError(message)) =
+            validate_splits(&transaction("200000"), &lines, &categories)
+        else {
+            panic!("expected a validation error");
+        };
+
+        assert!(message.contains("Line 1: IDR amounts have at most 2 decimal places"));
+        assert!(message.contains("Line 2: category 'Salary' cannot be used for expense transactions"));
+        assert!(message.contains("Line 3: amount must be positive"));
+        assert!(message.contains("Line 3: unknown category"));
+        assert!(message.contains("Lines add up to 150000.001 but the transaction amount is 200000"));
+
+        // A single line is just a category
+        assert!(validate_splits(&transaction("200000"), &lines[..1], &categories).is_err());
+    }
+}
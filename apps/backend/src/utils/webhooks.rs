@@ -0,0 +1,375 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use ipnet::IpNet;
+use rand::Rng;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    config::WebhookConfig,
+    db::{
+        models::{WebhookAttempt, WebhookEvent, WebhookJob},
+        repositories::{TransactionRepository, WebhookRepository},
+    },
+    error::AppError,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`
+pub const SIGNATURE_HEADER: &str = "X-Mintora-Signature";
+pub const EVENT_HEADER: &str = "X-Mintora-Event";
+pub const DELIVERY_HEADER: &str = "X-Mintora-Delivery";
+
+const SECRET_PREFIX: &str = "whsec_";
+const SECRET_LENGTH: usize = 32;
+/// Deliveries taken per round of the worker
+const CLAIM_BATCH_SIZE: i64 = 50;
+/// A claimed delivery is not sent again before this, even if its worker stopped
+const LEASE_SECONDS: i64 = 300;
+
+/// Where webhooks may not be sent: this host, private networks, link-local (cloud
+/// metadata services), carrier-grade NAT, multicast and other special-purpose ranges
+const BLOCKED_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "100.64.0.0/10",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.0.0.0/24",
+    "192.0.2.0/24",
+    "192.168.0.0/16",
+    "198.18.0.0/15",
+    "198.51.100.0/24",
+    "203.0.113.0/24",
+    "224.0.0.0/4",
+    "240.0.0.0/4",
+    "::/128",
+    "::1/128",
+    "64:ff9b::/96",
+    "100::/64",
+    "2001:db8::/32",
+    "fc00::/7",
+    "fe80::/10",
+    "ff00::/8",
+];
+
+/// A new signing secret for an endpoint
+pub fn generate_secret() -> String {
+    let mut random_bytes = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill(&mut random_bytes[..]);
+    format!("{}{}", SECRET_PREFIX, hex::encode(random_bytes))
+}
+
+/// Signature header value for a body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before retrying after failed attempt number `attempt`: the base doubled for every
+/// earlier failure, capped at the maximum
+pub fn retry_delay(attempt: i32, base_seconds: i64, max_seconds: i64) -> i64 {
+    let doublings = attempt.saturating_sub(1).clamp(0, 30) as u32;
+    base_seconds.saturating_mul(1 << doublings).min(max_seconds)
+}
+
+/// Endpoint URLs must be absolute HTTPS URLs (plain HTTP only when allowed) whose host
+/// resolves to public addresses only
+pub async fn validate_url(url: &str, allow_http: bool) -> Result<(), AppError> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| AppError::ValidationError("Invalid webhook URL".to_string()))?;
+
+    let scheme_allowed = parsed.scheme() == "https" || (allow_http && parsed.scheme() == "http");
+    if !scheme_allowed {
+        return Err(AppError::ValidationError("Webhook URL must use https".to_string()));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(AppError::ValidationError("Webhook URL must have a host".to_string()));
+    }
+
+    check_destination(&parsed)
+        .await
+        .map_err(|reason| AppError::ValidationError(format!("Webhook URL {}", reason)))
+}
+
+/// Whether webhooks may be sent to `ip` (IPv4-mapped IPv6 addresses are judged as IPv4)
+pub fn is_public_address(ip: IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    };
+
+    !BLOCKED_NETWORKS
+        .iter()
+        .filter_map(|network| network.parse::<IpNet>().ok())
+        .any(|network| network.contains(&ip))
+}
+
+/// Resolve the URL's host and refuse it unless every address is public
+async fn check_destination(url: &reqwest::Url) -> Result<(), &'static str> {
+    let host = url.host_str().unwrap_or_default().trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map(Iterator::collect)
+            .unwrap_or_default(),
+    };
+
+    if addresses.is_empty() {
+        return Err("host could not be resolved");
+    }
+    if addresses.iter().any(|address| !is_public_address(address.ip())) {
+        return Err("must not point to a private or local address");
+    }
+
+    Ok(())
+}
+
+/// Resolves endpoint hosts for deliveries, failing for names with any private or local
+/// address, so a host re-pointed after it was checked is still refused
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addresses.iter().any(|address| !is_public_address(address.ip())) {
+                return Err(format!("{} resolves to a private or local address", name.as_str()).into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// HTTP client for deliveries: redirects are not followed (a 3xx is an unsuccessful
+/// response) and only public addresses are connected to
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .expect("Failed to build the webhook HTTP client")
+}
+
+/// Queue an event for each of `data` to the user's subscribed endpoints. Call it on the
+/// database transaction making the change the events report, so they are queued exactly
+/// when that change commits
+pub async fn publish(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    event: WebhookEvent,
+    data: Vec<JsonValue>,
+) -> Result<(), AppError> {
+    let created_at = Utc::now();
+    let events: Vec<(Uuid, JsonValue)> = data
+        .into_iter()
+        .map(|data| {
+            let event_id = Uuid::new_v4();
+            let payload = json!({
+                "id": event_id,
+                "type": event.as_str(),
+                "created_at": created_at,
+                "data": data,
+            });
+            (event_id, payload)
+        })
+        .collect();
+
+    WebhookRepository::enqueue(conn, user_id, event.as_str(), &events).await?;
+
+    Ok(())
+}
+
+/// `transaction.created` for transactions inserted on `conn`, known by id
+pub async fn publish_created_transactions(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    ids: &[Uuid],
+) -> Result<(), AppError> {
+    let event = WebhookEvent::TransactionCreated;
+    if ids.is_empty() || !WebhookRepository::has_subscribers(conn, user_id, event.as_str()).await? {
+        return Ok(());
+    }
+
+    let data = TransactionRepository::list_by_ids(conn, ids)
+        .await?
+        .into_iter()
+        .map(|transaction| json!({ "transaction": transaction }))
+        .collect();
+
+    publish(conn, user_id, event, data).await
+}
+
+/// Send every due delivery once. Returns the number of deliveries attempted.
+pub async fn process_queue(pool: &PgPool, http: &reqwest::Client, config: &WebhookConfig) -> Result<usize, AppError> {
+    let mut attempted = 0;
+
+    loop {
+        let jobs = WebhookRepository::claim_due(pool, CLAIM_BATCH_SIZE, LEASE_SECONDS).await?;
+        if jobs.is_empty() {
+            return Ok(attempted);
+        }
+
+        for job in &jobs {
+            let (succeeded, attempt) = deliver(http, job, config.timeout_seconds).await;
+            let retry_at = (!succeeded && job.attempts < config.max_attempts).then(|| {
+                let delay = retry_delay(job.attempts, config.backoff_base_seconds, config.backoff_max_seconds);
+                Utc::now().naive_utc() + chrono::Duration::seconds(delay)
+            });
+            if !succeeded {
+                tracing::warn!(
+                    "Webhook delivery {} failed (attempt {}): {}",
+                    job.delivery_id,
+                    job.attempts,
+                    attempt.error.as_deref().unwrap_or("unsuccessful response")
+                );
+            }
+            WebhookRepository::record_attempt(pool, job.delivery_id, &attempt, succeeded, retry_at).await?;
+        }
+        attempted += jobs.len();
+    }
+}
+
+/// POST the signed payload to the endpoint, if it still resolves to public addresses;
+/// any 2xx response counts as delivered. The response body is not kept.
+async fn deliver(http: &reqwest::Client, job: &WebhookJob, timeout_seconds: u64) -> (bool, WebhookAttempt) {
+    let destination = match reqwest::Url::parse(&job.url) {
+        Ok(url) => check_destination(&url).await,
+        Err(_) => Err("is invalid"),
+    };
+    if let Err(reason) = destination {
+        return (
+            false,
+            WebhookAttempt {
+                error: Some(format!("Endpoint URL {}", reason)),
+                ..Default::default()
+            },
+        );
+    }
+
+    let body = job.payload.to_string();
+    let signature = sign(&job.secret, Utc::now().timestamp(), body.as_bytes());
+
+    let result = http
+        .post(&job.url)
+        .timeout(Duration::from_secs(timeout_seconds))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .header(EVENT_HEADER, &job.event)
+        .header(DELIVERY_HEADER, job.event_id.to_string())
+        .body(body)
+        .send()
+        .await;
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            return (
+                false,
+                WebhookAttempt {
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+    };
+
+    let status = response.status();
+    let attempt = WebhookAttempt {
+        response_status: Some(status.as_u16() as i16),
+        error: (!status.is_success()).then(|| format!("Endpoint responded with {}", status)),
+    };
+
+    (status.is_success(), attempt)
+}
+
+/// Work through due deliveries every `poll_interval_seconds`
+pub fn spawn_worker(pool: PgPool, http: reqwest::Client, config: WebhookConfig) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.poll_interval_seconds));
+        loop {
+            ticker.tick().await;
+            match process_queue(&pool, &http, &config).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Attempted {} webhook deliveries", count),
+                Err(e) => tracing::warn!("Failed to process the webhook outbox: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let signature = sign("whsec_test", 1_700_000_000, br#"{"type":"transaction.created"}"#);
+
+        assert!(signature.starts_with("t=1700000000,v1="));
+        assert_eq!(signature.len(), "t=1700000000,v1=".len() + 64);
+        assert_eq!(signature, sign("whsec_test", 1_700_000_000, br#"{"type":"transaction.created"}"#));
+        assert_ne!(signature, sign("whsec_test", 1_700_000_001, br#"{"type":"transaction.created"}"#));
+        assert_ne!(signature, sign("whsec_other", 1_700_000_000, br#"{"type":"transaction.created"}"#));
+    }
+
+    #[test]
+    fn test_only_public_addresses_are_allowed() {
+        let blocked = [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.20.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ];
+        for blocked in blocked {
+            assert!(!is_public_address(blocked.parse().unwrap()), "{} should be blocked", blocked);
+        }
+        for public in ["93.184.216.34", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public_address(public.parse().unwrap()), "{} should be allowed", public);
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_the_maximum() {
+        let delays: Vec<i64> = (1..=6).map(|attempt| retry_delay(attempt, 30, 600)).collect();
+
+        assert_eq!(delays, vec![30, 60, 120, 240, 480, 600]);
+        assert_eq!(retry_delay(100, 30, 21_600), 21_600);
+    }
+
+    #[tokio::test]
+    async fn test_validate_url_requires_https_and_a_public_host() {
+        assert!(validate_url("https://93.184.216.34/mintora", false).await.is_ok());
+        assert!(validate_url("http://93.184.216.34:8080/hook", false).await.is_err());
+        assert!(validate_url("http://93.184.216.34:8080/hook", true).await.is_ok());
+        assert!(validate_url("ftp://93.184.216.34", true).await.is_err());
+        assert!(validate_url("not a url", true).await.is_err());
+
+        assert!(validate_url("http://127.0.0.1:8080/hook", true).await.is_err());
+        assert!(validate_url("http://169.254.169.254/latest/meta-data", true).await.is_err());
+        assert!(validate_url("https://[::1]/hook", false).await.is_err());
+    }
+}
@@ -0,0 +1,40 @@
+-- Migration: add_login_protection
+-- Description: Track failed login attempts per account and per IP for brute-force protection
+-- Date: 2025-12-04
+
+-- ============================================
+-- Account lockout state on users
+-- ============================================
+
+ALTER TABLE "users"
+ADD COLUMN "failed_login_attempts" integer NOT NULL DEFAULT 0,
+ADD COLUMN "locked_until" timestamp;
+
+COMMENT ON COLUMN "users"."failed_login_attempts" IS 'Consecutive failed logins, reset on successful login or unlock';
+COMMENT ON COLUMN "users"."locked_until" IS 'NULL = not locked, otherwise login is refused until this time';
+
+-- ============================================
+-- Login attempts (per-IP throttling and forensics)
+-- ============================================
+
+CREATE TABLE "login_attempts" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid,
+  "email" varchar(255) NOT NULL,
+  "ip_address" varchar(45),
+  "user_agent" text,
+  "succeeded" boolean NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_login_attempts_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE SET NULL
+);
+
+COMMENT ON TABLE "login_attempts" IS 'Every dashboard login attempt, used for per-IP throttling and account lockout';
+COMMENT ON COLUMN "login_attempts"."user_id" IS 'NULL when the email does not match any user';
+COMMENT ON COLUMN "login_attempts"."email" IS 'Email as submitted by the client';
+
+-- Indexes
+CREATE INDEX idx_login_attempts_ip_created ON "login_attempts" ("ip_address", "created_at")
+  WHERE succeeded = false;
+CREATE INDEX idx_login_attempts_user_created ON "login_attempts" ("user_id", "created_at");
+CREATE INDEX idx_login_attempts_created_at ON "login_attempts" ("created_at");
@@ -0,0 +1,16 @@
+-- Migration: create_rate_limit_buckets
+-- Description: Shared token buckets so rate limits hold across multiple backend replicas
+-- Date: 2025-12-05
+
+CREATE TABLE "rate_limit_buckets" (
+  "key" varchar(255) PRIMARY KEY,
+  "tokens" double precision NOT NULL,
+  "updated_at" timestamp NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE "rate_limit_buckets" IS 'Token bucket state for the Postgres rate limit backend';
+COMMENT ON COLUMN "rate_limit_buckets"."key" IS 'ip:<addr>, user:<uuid> or api_key:<sha256 of key>';
+COMMENT ON COLUMN "rate_limit_buckets"."tokens" IS 'Tokens left at updated_at (refilled lazily on read)';
+
+-- Indexes
+CREATE INDEX idx_rate_limit_buckets_updated_at ON "rate_limit_buckets" ("updated_at");
@@ -0,0 +1,62 @@
+-- Migration: create_user_identities
+-- Description: External OIDC identities (e.g., "Sign in with Google") linked to users
+-- Date: 2025-12-06
+
+-- ============================================
+-- Allow password-less dashboard users (OAuth/OIDC)
+-- ============================================
+
+-- Users with a password must have an email; users with an email may sign in
+-- through an external identity provider and have no password
+ALTER TABLE "users" DROP CONSTRAINT users_email_password_check;
+
+ALTER TABLE "users"
+ADD CONSTRAINT users_email_password_check
+CHECK (password_hash IS NULL OR email IS NOT NULL);
+
+COMMENT ON COLUMN "users"."password_hash" IS 'Argon2id hash - NULL for WhatsApp-only users and OAuth-only users';
+
+-- ============================================
+-- User identities
+-- ============================================
+
+CREATE TABLE "user_identities" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid NOT NULL,
+  "provider" varchar(50) NOT NULL,
+  "subject" varchar(255) NOT NULL,
+  "email" varchar(255),
+  "last_login_at" timestamp,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_user_identities_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE
+);
+
+COMMENT ON TABLE "user_identities" IS 'External identity provider accounts linked to users';
+COMMENT ON COLUMN "user_identities"."provider" IS 'Configured provider name (e.g., google)';
+COMMENT ON COLUMN "user_identities"."subject" IS 'Stable "sub" claim from the provider ID token';
+COMMENT ON COLUMN "user_identities"."email" IS 'Email reported by the provider at last login';
+
+-- Indexes
+CREATE UNIQUE INDEX idx_user_identities_provider_subject ON "user_identities" ("provider", "subject");
+CREATE INDEX idx_user_identities_user_id ON "user_identities" ("user_id");
+
+-- ============================================
+-- Pending OIDC logins (authorization code + PKCE)
+-- ============================================
+
+CREATE TABLE "oidc_login_states" (
+  "state" varchar(255) PRIMARY KEY,
+  "provider" varchar(50) NOT NULL,
+  "code_verifier" varchar(255) NOT NULL,
+  "nonce" varchar(255) NOT NULL,
+  "expires_at" timestamp NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE "oidc_login_states" IS 'Short-lived state, nonce and PKCE verifier between authorize and callback';
+COMMENT ON COLUMN "oidc_login_states"."state" IS 'Opaque CSRF token echoed back by the provider';
+
+-- Indexes
+CREATE INDEX idx_oidc_login_states_expires_at ON "oidc_login_states" ("expires_at");
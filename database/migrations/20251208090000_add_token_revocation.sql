@@ -0,0 +1,33 @@
+-- Migration: add_token_revocation
+-- Description: Token versioning on users and an access token denylist for immediate invalidation
+-- Date: 2025-12-08
+
+-- ============================================
+-- Token version on users
+-- ============================================
+
+ALTER TABLE "users"
+ADD COLUMN "token_version" integer NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN "users"."token_version" IS 'Embedded in JWTs as "ver"; bumped on suspension, password change or logout everywhere to invalidate all tokens';
+
+-- ============================================
+-- Revoked access tokens (denylist)
+-- ============================================
+
+CREATE TABLE "revoked_access_tokens" (
+  "jti" uuid PRIMARY KEY,
+  "user_id" uuid NOT NULL,
+  "expires_at" timestamp NOT NULL,
+  "revoked_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_revoked_access_tokens_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE
+);
+
+COMMENT ON TABLE "revoked_access_tokens" IS 'Access tokens revoked before expiry (e.g., on logout); rows can be dropped after expires_at';
+COMMENT ON COLUMN "revoked_access_tokens"."jti" IS 'JWT ID claim of the revoked access token';
+COMMENT ON COLUMN "revoked_access_tokens"."expires_at" IS 'Original token expiry; the entry is irrelevant afterwards';
+
+-- Indexes
+CREATE INDEX idx_revoked_access_tokens_revoked_at ON "revoked_access_tokens" ("revoked_at");
+CREATE INDEX idx_revoked_access_tokens_expires_at ON "revoked_access_tokens" ("expires_at");
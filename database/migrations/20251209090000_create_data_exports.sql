@@ -0,0 +1,32 @@
+-- Migration: create_data_exports
+-- Description: Asynchronous account data export jobs (GDPR-style data portability)
+-- Date: 2025-12-09
+
+-- ============================================
+-- Data export jobs
+-- ============================================
+
+CREATE TABLE "data_exports" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid NOT NULL,
+  "status" varchar(20) NOT NULL DEFAULT 'pending',
+  "file_path" varchar(500),
+  "file_size" bigint,
+  "error" text,
+  "expires_at" timestamp,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "completed_at" timestamp,
+
+  CONSTRAINT fk_data_exports_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT data_exports_status_check CHECK (status IN ('pending', 'processing', 'completed', 'failed'))
+);
+
+COMMENT ON TABLE "data_exports" IS 'Account data export jobs; each produces a ZIP of JSON and CSV files';
+COMMENT ON COLUMN "data_exports"."status" IS 'pending, processing, completed or failed';
+COMMENT ON COLUMN "data_exports"."file_path" IS 'Location of the generated archive on the export volume';
+COMMENT ON COLUMN "data_exports"."error" IS 'Failure reason when status is failed';
+COMMENT ON COLUMN "data_exports"."expires_at" IS 'Archive is no longer downloadable after this time';
+
+-- Indexes
+CREATE INDEX idx_data_exports_user_created ON "data_exports" ("user_id", "created_at");
+CREATE INDEX idx_data_exports_expires_at ON "data_exports" ("expires_at");
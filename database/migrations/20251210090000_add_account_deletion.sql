@@ -0,0 +1,15 @@
+-- Migration: add_account_deletion
+-- Description: Self-service account deletion with a grace period before the hard purge
+-- Date: 2025-12-10
+
+-- ============================================
+-- Scheduled purge on users
+-- ============================================
+
+ALTER TABLE "users"
+ADD COLUMN "purge_after" timestamp;
+
+COMMENT ON COLUMN "users"."purge_after" IS 'Set when the user deletes their account; logging in before this time restores it, afterwards the account is hard-deleted';
+
+-- Indexes
+CREATE INDEX idx_users_purge_after ON "users" ("purge_after") WHERE purge_after IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Migration: add_statement_import_index
+-- Description: Look up transactions by the bank statement transaction id they were imported from
+-- Date: 2025-12-11
+
+-- ============================================
+-- Statement import deduplication
+-- ============================================
+
+-- Imported and reconciled transactions carry metadata like
+-- {"statement": {"format": "camt053", "transaction_id": "...", "account": "...", "batch_id": "..."}}
+COMMENT ON COLUMN "transactions"."metadata" IS 'Flexible JSON; "import" and "statement" keys record where imported transactions came from';
+
+-- Indexes
+CREATE INDEX idx_transactions_statement_id ON "transactions" ("user_id", (metadata->'statement'->>'transaction_id'))
+WHERE metadata ? 'statement';
@@ -0,0 +1,61 @@
+-- Migration: add_multi_currency
+-- Description: ISO 4217 currency codes, daily exchange rates and a per-user base currency for reports
+-- Date: 2025-12-12
+
+-- ============================================
+-- Transaction currencies
+-- ============================================
+
+-- Normalize existing codes; anything that is not three letters falls back to IDR,
+-- keeping what was stored in metadata
+UPDATE "transactions" SET currency = upper(trim(currency)) WHERE currency <> upper(trim(currency));
+
+UPDATE "transactions"
+SET metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('original_currency', currency),
+    currency = 'IDR'
+WHERE currency !~ '^[A-Z]{3}$';
+
+ALTER TABLE "transactions"
+ADD CONSTRAINT transactions_currency_check CHECK (currency ~ '^[A-Z]{3}$');
+
+COMMENT ON COLUMN "transactions"."amount" IS 'Amount in the transaction currency; reports convert it to the user''s base currency';
+COMMENT ON COLUMN "transactions"."currency" IS 'ISO 4217 code';
+
+-- ============================================
+-- Base currency on users
+-- ============================================
+
+ALTER TABLE "users"
+ADD COLUMN "base_currency" varchar(3) NOT NULL DEFAULT 'IDR',
+ADD CONSTRAINT users_base_currency_check CHECK (base_currency ~ '^[A-Z]{3}$');
+
+COMMENT ON COLUMN "users"."base_currency" IS 'ISO 4217 code that reports convert amounts into';
+
+-- ============================================
+-- Exchange rates
+-- ============================================
+
+CREATE TABLE "exchange_rates" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "base_currency" varchar(3) NOT NULL,
+  "quote_currency" varchar(3) NOT NULL,
+  "rate" decimal(24,10) NOT NULL,
+  "rate_date" date NOT NULL,
+  "source" varchar(50) NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT exchange_rates_pair_date_unique UNIQUE ("base_currency", "quote_currency", "rate_date"),
+  CONSTRAINT exchange_rates_base_check CHECK (base_currency ~ '^[A-Z]{3}$'),
+  CONSTRAINT exchange_rates_quote_check CHECK (quote_currency ~ '^[A-Z]{3}$'),
+  CONSTRAINT exchange_rates_distinct_check CHECK (base_currency <> quote_currency),
+  CONSTRAINT exchange_rates_rate_check CHECK (rate > 0)
+);
+
+COMMENT ON TABLE "exchange_rates" IS 'Daily exchange rates used to convert transactions into a user''s base currency';
+COMMENT ON COLUMN "exchange_rates"."rate" IS 'Units of quote_currency per one unit of base_currency';
+COMMENT ON COLUMN "exchange_rates"."rate_date" IS 'Day the rate applies to; later days without a rate use the latest earlier one';
+COMMENT ON COLUMN "exchange_rates"."source" IS 'admin_upload or the name of the rate provider';
+
+-- Indexes
+CREATE INDEX idx_exchange_rates_date ON "exchange_rates" ("rate_date");
@@ -0,0 +1,62 @@
+-- Migration: create_accounts
+-- Description: Accounts (bank accounts, e-wallets, cash) with opening balances, and transfers between them
+-- Date: 2025-12-13
+
+-- ============================================
+-- Accounts
+-- ============================================
+
+CREATE TABLE "accounts" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid NOT NULL,
+  "name" varchar(100) NOT NULL,
+  "type" varchar(30) NOT NULL,
+  "payment_method_id" uuid,
+  "currency" varchar(3) NOT NULL DEFAULT 'IDR',
+  "opening_balance" decimal(15,2) NOT NULL DEFAULT 0,
+  "is_archived" boolean NOT NULL DEFAULT false,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_accounts_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_accounts_payment_method FOREIGN KEY ("payment_method_id") REFERENCES "payment_methods" ("id") ON DELETE SET NULL,
+  CONSTRAINT accounts_type_check CHECK (type IN ('cash', 'bank', 'digital_wallet', 'credit_card', 'other')),
+  CONSTRAINT accounts_currency_check CHECK (currency ~ '^[A-Z]{3}$')
+);
+
+COMMENT ON TABLE "accounts" IS 'Where a user''s money is held (BCA, GoPay, cash); balances are the opening balance plus the account''s transactions';
+COMMENT ON COLUMN "accounts"."type" IS 'cash, bank, digital_wallet, credit_card, other';
+COMMENT ON COLUMN "accounts"."payment_method_id" IS 'Transactions paid with this payment method are booked to the account when none is given';
+COMMENT ON COLUMN "accounts"."opening_balance" IS 'Balance before the first recorded transaction; negative for credit card debt';
+COMMENT ON COLUMN "accounts"."is_archived" IS 'Hidden from pickers; the history and balance remain';
+
+-- Indexes
+CREATE UNIQUE INDEX idx_accounts_user_name ON "accounts" ("user_id", lower("name"));
+CREATE UNIQUE INDEX idx_accounts_user_payment_method ON "accounts" ("user_id", "payment_method_id") WHERE payment_method_id IS NOT NULL;
+
+-- ============================================
+-- Transactions: account and transfers
+-- ============================================
+
+ALTER TABLE "transactions"
+ADD COLUMN "account_id" uuid,
+ADD COLUMN "transfer_account_id" uuid,
+ADD CONSTRAINT fk_transactions_account FOREIGN KEY ("account_id") REFERENCES "accounts" ("id"),
+ADD CONSTRAINT fk_transactions_transfer_account FOREIGN KEY ("transfer_account_id") REFERENCES "accounts" ("id");
+
+ALTER TABLE "transactions" DROP CONSTRAINT transactions_type_check;
+ALTER TABLE "transactions"
+ADD CONSTRAINT transactions_type_check CHECK (type IN ('income', 'expense', 'transfer')),
+ADD CONSTRAINT transactions_transfer_check CHECK (
+  (type = 'transfer') = (transfer_account_id IS NOT NULL)
+  AND (type <> 'transfer' OR (account_id IS NOT NULL AND account_id <> transfer_account_id))
+);
+
+COMMENT ON TABLE "transactions" IS 'Core financial transaction records (income, expenses and transfers between accounts)';
+COMMENT ON COLUMN "transactions"."type" IS 'income, expense or transfer; transfers are not counted as income or expense';
+COMMENT ON COLUMN "transactions"."account_id" IS 'Account credited (income) or debited (expense, transfer source)';
+COMMENT ON COLUMN "transactions"."transfer_account_id" IS 'Account credited by a transfer; set only for transfers';
+
+-- Indexes
+CREATE INDEX idx_transactions_account_id ON "transactions" ("account_id") WHERE account_id IS NOT NULL;
+CREATE INDEX idx_transactions_transfer_account_id ON "transactions" ("transfer_account_id") WHERE transfer_account_id IS NOT NULL;
@@ -0,0 +1,29 @@
+-- Migration: create_transaction_splits
+-- Description: Split a transaction into several category/amount lines
+-- Date: 2025-12-14
+
+-- ============================================
+-- Transaction splits
+-- ============================================
+
+CREATE TABLE "transaction_splits" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "transaction_id" uuid NOT NULL,
+  "position" smallint NOT NULL,
+  "category_id" uuid,
+  "amount" decimal(15,2) NOT NULL,
+  "description" varchar(255),
+  "created_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_transaction_splits_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_transaction_splits_category FOREIGN KEY ("category_id") REFERENCES "categories" ("id") ON DELETE SET NULL,
+  CONSTRAINT transaction_splits_position_unique UNIQUE ("transaction_id", "position"),
+  CONSTRAINT transaction_splits_amount_check CHECK (amount > 0)
+);
+
+COMMENT ON TABLE "transaction_splits" IS 'Category lines of a split transaction (e.g., groceries and household items on one receipt); their amounts sum to the transaction amount';
+COMMENT ON COLUMN "transaction_splits"."position" IS 'Line order, starting at 1';
+COMMENT ON COLUMN "transaction_splits"."amount" IS 'In the currency of the transaction';
+
+-- Indexes
+CREATE INDEX idx_transaction_splits_category_id ON "transaction_splits" ("category_id");
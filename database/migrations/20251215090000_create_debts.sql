@@ -0,0 +1,52 @@
+-- Migration: create_debts
+-- Description: Shared expenses and debts between users or with free-text contacts, and settle-ups
+-- Date: 2025-12-15
+
+-- ============================================
+-- Debts
+-- ============================================
+
+CREATE TABLE "debts" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "created_by" uuid NOT NULL,
+  "kind" varchar(20) NOT NULL,
+  "creditor_user_id" uuid,
+  "debtor_user_id" uuid,
+  "contact_name" varchar(100),
+  "amount" decimal(15,2) NOT NULL,
+  "currency" varchar(3) NOT NULL DEFAULT 'IDR',
+  "description" varchar(255),
+  "entry_date" date NOT NULL DEFAULT CURRENT_DATE,
+  "transaction_id" uuid,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_debts_created_by FOREIGN KEY ("created_by") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_debts_creditor FOREIGN KEY ("creditor_user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_debts_debtor FOREIGN KEY ("debtor_user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_debts_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE SET NULL,
+  CONSTRAINT debts_kind_check CHECK (kind IN ('debt', 'settlement')),
+  CONSTRAINT debts_amount_check CHECK (amount > 0),
+  CONSTRAINT debts_currency_check CHECK (currency ~ '^[A-Z]{3}$'),
+  CONSTRAINT debts_parties_check CHECK (
+    created_by IN (creditor_user_id, debtor_user_id)
+    AND (
+      (creditor_user_id IS NOT NULL AND debtor_user_id IS NOT NULL
+        AND creditor_user_id <> debtor_user_id AND contact_name IS NULL)
+      OR ((creditor_user_id IS NULL) <> (debtor_user_id IS NULL) AND contact_name IS NOT NULL)
+    )
+  )
+);
+
+COMMENT ON TABLE "debts" IS 'Who owes whom: debts (a shared bill, a loan) and settle-ups paying them back, between two users or a user and a contact';
+COMMENT ON COLUMN "debts"."created_by" IS 'The user who recorded the entry; always one of the parties and the only one who can delete it';
+COMMENT ON COLUMN "debts"."kind" IS 'debt: the debtor owes the creditor the amount; settlement: the debtor paid the creditor the amount back';
+COMMENT ON COLUMN "debts"."creditor_user_id" IS 'NULL when the creditor is the contact';
+COMMENT ON COLUMN "debts"."debtor_user_id" IS 'NULL when the debtor is the contact';
+COMMENT ON COLUMN "debts"."contact_name" IS 'Free-text name of a counterparty who is not a user; visible only to the creator';
+COMMENT ON COLUMN "debts"."transaction_id" IS 'Optional transaction of the creator the entry belongs to (the shared bill, the repayment)';
+
+-- Indexes
+CREATE INDEX idx_debts_creditor_user_id ON "debts" ("creditor_user_id", "entry_date" DESC) WHERE creditor_user_id IS NOT NULL;
+CREATE INDEX idx_debts_debtor_user_id ON "debts" ("debtor_user_id", "entry_date" DESC) WHERE debtor_user_id IS NOT NULL;
+CREATE INDEX idx_debts_transaction_id ON "debts" ("transaction_id") WHERE transaction_id IS NOT NULL;
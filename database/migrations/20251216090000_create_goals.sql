@@ -0,0 +1,65 @@
+-- Migration: create_goals
+-- Description: Savings goals with a target and deadline, and the contributions towards them
+-- Date: 2025-12-16
+
+-- ============================================
+-- Goals
+-- ============================================
+
+CREATE TABLE "goals" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid NOT NULL,
+  "name" varchar(100) NOT NULL,
+  "target_amount" decimal(15,2) NOT NULL,
+  "currency" varchar(3) NOT NULL DEFAULT 'IDR',
+  "start_date" date NOT NULL DEFAULT CURRENT_DATE,
+  "deadline" date NOT NULL,
+  "account_id" uuid,
+  "tag_id" uuid,
+  "is_archived" boolean NOT NULL DEFAULT false,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_goals_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_goals_account FOREIGN KEY ("account_id") REFERENCES "accounts" ("id") ON DELETE SET NULL,
+  CONSTRAINT fk_goals_tag FOREIGN KEY ("tag_id") REFERENCES "tags" ("id") ON DELETE SET NULL,
+  CONSTRAINT goals_target_amount_check CHECK (target_amount > 0),
+  CONSTRAINT goals_currency_check CHECK (currency ~ '^[A-Z]{3}$'),
+  CONSTRAINT goals_dates_check CHECK (deadline > start_date),
+  CONSTRAINT goals_link_check CHECK (account_id IS NULL OR tag_id IS NULL)
+);
+
+COMMENT ON TABLE "goals" IS 'Savings goals (e.g., a trip, an emergency fund): a target amount to reach between the start date and the deadline';
+COMMENT ON COLUMN "goals"."start_date" IS 'Linked account and tag transactions count from this date; the expected progress is measured from it';
+COMMENT ON COLUMN "goals"."account_id" IS 'Savings account whose net inflow since the start date counts towards the goal';
+COMMENT ON COLUMN "goals"."tag_id" IS 'Transactions with this tag since the start date count towards the goal';
+
+-- Indexes
+CREATE INDEX idx_goals_user_id ON "goals" ("user_id");
+CREATE UNIQUE INDEX idx_goals_user_name ON "goals" ("user_id", lower("name"));
+
+-- ============================================
+-- Goal contributions
+-- ============================================
+
+CREATE TABLE "goal_contributions" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "goal_id" uuid NOT NULL,
+  "amount" decimal(15,2) NOT NULL,
+  "contribution_date" date NOT NULL DEFAULT CURRENT_DATE,
+  "transaction_id" uuid,
+  "note" varchar(255),
+  "created_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_goal_contributions_goal FOREIGN KEY ("goal_id") REFERENCES "goals" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_goal_contributions_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE CASCADE,
+  CONSTRAINT goal_contributions_amount_check CHECK (amount <> 0)
+);
+
+COMMENT ON TABLE "goal_contributions" IS 'Money put towards (or taken out of) a goal, entered by hand or taken from a transaction';
+COMMENT ON COLUMN "goal_contributions"."amount" IS 'In the goal currency; negative for a withdrawal';
+COMMENT ON COLUMN "goal_contributions"."transaction_id" IS 'The transaction the contribution was recorded from; it is then not counted again through the linked account or tag';
+
+-- Indexes
+CREATE INDEX idx_goal_contributions_goal_id ON "goal_contributions" ("goal_id", "contribution_date");
+CREATE UNIQUE INDEX idx_goal_contributions_goal_transaction ON "goal_contributions" ("goal_id", "transaction_id") WHERE transaction_id IS NOT NULL;
@@ -0,0 +1,72 @@
+-- Migration: create_workspaces
+-- Description: Shared workspaces (households, small businesses) with role-based membership, and
+--              workspace-scoped transactions, categories and tags
+-- Date: 2025-12-17
+
+-- ============================================
+-- Workspaces
+-- ============================================
+
+CREATE TABLE "workspaces" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "name" varchar(100) NOT NULL,
+  "created_by" uuid,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_workspaces_created_by FOREIGN KEY ("created_by") REFERENCES "users" ("id") ON DELETE SET NULL
+);
+
+COMMENT ON TABLE "workspaces" IS 'A ledger shared by several users, e.g. a household or a small business';
+
+-- ============================================
+-- Workspace members
+-- ============================================
+
+CREATE TABLE "workspace_members" (
+  "workspace_id" uuid NOT NULL,
+  "user_id" uuid NOT NULL,
+  "role" varchar(20) NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+  PRIMARY KEY ("workspace_id", "user_id"),
+
+  CONSTRAINT fk_workspace_members_workspace FOREIGN KEY ("workspace_id") REFERENCES "workspaces" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_workspace_members_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT workspace_members_role_check CHECK (role IN ('owner', 'editor', 'viewer'))
+);
+
+COMMENT ON TABLE "workspace_members" IS 'Who can use a workspace, and how';
+COMMENT ON COLUMN "workspace_members"."role" IS 'owner: manage the workspace and its members; editor: add and change records; viewer: read only';
+
+-- Indexes
+CREATE INDEX idx_workspace_members_user_id ON "workspace_members" ("user_id");
+
+-- ============================================
+-- Workspace-scoped records
+-- ============================================
+
+ALTER TABLE "transactions"
+ADD COLUMN "workspace_id" uuid,
+ADD CONSTRAINT fk_transactions_workspace FOREIGN KEY ("workspace_id") REFERENCES "workspaces" ("id") ON DELETE CASCADE;
+
+ALTER TABLE "categories"
+ADD COLUMN "workspace_id" uuid,
+ADD CONSTRAINT fk_categories_workspace FOREIGN KEY ("workspace_id") REFERENCES "workspaces" ("id") ON DELETE CASCADE;
+
+ALTER TABLE "tags"
+ADD COLUMN "workspace_id" uuid,
+ADD CONSTRAINT fk_tags_workspace FOREIGN KEY ("workspace_id") REFERENCES "workspaces" ("id") ON DELETE CASCADE;
+
+COMMENT ON COLUMN "transactions"."workspace_id" IS 'Workspace ledger the transaction belongs to; NULL for the personal ledger of user_id. user_id is the author either way';
+COMMENT ON COLUMN "categories"."workspace_id" IS 'Workspace the category belongs to; NULL for a personal or system category';
+COMMENT ON COLUMN "tags"."workspace_id" IS 'Workspace the tag belongs to; NULL for a personal tag';
+
+-- Tag names are unique per ledger rather than per user
+DROP INDEX idx_tags_user_name;
+CREATE UNIQUE INDEX idx_tags_user_name ON "tags" ("user_id", "name") WHERE workspace_id IS NULL;
+CREATE UNIQUE INDEX idx_tags_workspace_name ON "tags" ("workspace_id", "name") WHERE workspace_id IS NOT NULL;
+
+-- Indexes
+CREATE INDEX idx_transactions_workspace_date ON "transactions" ("workspace_id", "transaction_date") WHERE workspace_id IS NOT NULL;
+CREATE INDEX idx_categories_workspace_id ON "categories" ("workspace_id") WHERE workspace_id IS NOT NULL;
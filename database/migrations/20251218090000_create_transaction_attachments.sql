@@ -0,0 +1,34 @@
+-- Migration: create_transaction_attachments
+-- Description: Receipt files (images and PDFs) uploaded for transactions, kept in the
+--              configured storage backend
+-- Date: 2025-12-18
+
+-- ============================================
+-- Transaction attachments
+-- ============================================
+
+CREATE TABLE "transaction_attachments" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "transaction_id" uuid NOT NULL,
+  "uploaded_by" uuid,
+  "storage_key" varchar(255) NOT NULL,
+  "file_name" varchar(255) NOT NULL,
+  "content_type" varchar(100) NOT NULL,
+  "size_bytes" bigint NOT NULL,
+  "sha256" varchar(64) NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_transaction_attachments_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_transaction_attachments_uploaded_by FOREIGN KEY ("uploaded_by") REFERENCES "users" ("id") ON DELETE SET NULL,
+  CONSTRAINT transaction_attachments_size_check CHECK (size_bytes > 0)
+);
+
+COMMENT ON TABLE "transaction_attachments" IS 'Receipts uploaded for a transaction; the file itself lives in the storage backend';
+COMMENT ON COLUMN "transaction_attachments"."storage_key" IS 'Object key in the storage backend (local directory or S3 bucket)';
+COMMENT ON COLUMN "transaction_attachments"."file_name" IS 'Name of the uploaded file, offered again on download';
+COMMENT ON COLUMN "transaction_attachments"."content_type" IS 'Detected from the file contents: image/jpeg, image/png, image/webp or application/pdf';
+COMMENT ON COLUMN "transactions"."attachment_urls" IS 'Array of external receipt image URLs (e.g., from a chat message); uploaded receipts are in transaction_attachments';
+
+-- Indexes
+CREATE INDEX idx_transaction_attachments_transaction_id ON "transaction_attachments" ("transaction_id");
+CREATE UNIQUE INDEX idx_transaction_attachments_storage_key ON "transaction_attachments" ("storage_key");
@@ -0,0 +1,48 @@
+-- Migration: create_receipt_drafts
+-- Description: Transaction drafts read from receipt images by OCR, with confidence scores
+--              and a manual review state
+-- Date: 2025-12-19
+
+-- ============================================
+-- Receipt drafts
+-- ============================================
+
+CREATE TABLE "receipt_drafts" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "attachment_id" uuid NOT NULL,
+  "transaction_id" uuid NOT NULL,
+  "status" varchar(20) NOT NULL DEFAULT 'pending',
+  "attempts" integer NOT NULL DEFAULT 0,
+  "claimed_at" timestamp,
+  "engine" varchar(50),
+  "ocr_text" text,
+  "amount" decimal(15,2),
+  "currency" varchar(3),
+  "transaction_date" date,
+  "merchant_name" varchar(255),
+  "confidence" double precision,
+  "field_confidence" jsonb,
+  "error" text,
+  "reviewed_by" uuid,
+  "reviewed_at" timestamp,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "processed_at" timestamp,
+
+  CONSTRAINT fk_receipt_drafts_attachment FOREIGN KEY ("attachment_id") REFERENCES "transaction_attachments" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_receipt_drafts_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_receipt_drafts_reviewed_by FOREIGN KEY ("reviewed_by") REFERENCES "users" ("id") ON DELETE SET NULL,
+  CONSTRAINT receipt_drafts_status_check CHECK (status IN ('pending', 'processing', 'ready', 'needs_review', 'failed', 'confirmed', 'dismissed')),
+  CONSTRAINT receipt_drafts_currency_check CHECK (currency IS NULL OR currency ~ '^[A-Z]{3}$'),
+  CONSTRAINT receipt_drafts_confidence_check CHECK (confidence IS NULL OR confidence BETWEEN 0 AND 1)
+);
+
+COMMENT ON TABLE "receipt_drafts" IS 'Transaction values read from an uploaded receipt image, waiting to be confirmed onto the transaction';
+COMMENT ON COLUMN "receipt_drafts"."status" IS 'pending/processing: queued for OCR; ready: confident result; needs_review: low confidence, shown in the review queue; failed: OCR gave up; confirmed/dismissed: reviewed by a user';
+COMMENT ON COLUMN "receipt_drafts"."claimed_at" IS 'When a worker took the draft; processing drafts claimed long ago are retried';
+COMMENT ON COLUMN "receipt_drafts"."confidence" IS 'Overall confidence between 0 and 1, weighted towards the amount';
+COMMENT ON COLUMN "receipt_drafts"."field_confidence" IS 'Confidence per field: {"amount": 0.9, "transaction_date": 0.9, "merchant_name": 0.6}';
+
+-- Indexes
+CREATE UNIQUE INDEX idx_receipt_drafts_attachment_id ON "receipt_drafts" ("attachment_id");
+CREATE INDEX idx_receipt_drafts_transaction_id ON "receipt_drafts" ("transaction_id");
+CREATE INDEX idx_receipt_drafts_queue ON "receipt_drafts" ("created_at") WHERE status IN ('pending', 'processing');
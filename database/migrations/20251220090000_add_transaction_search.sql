@@ -0,0 +1,128 @@
+-- Migration: add_transaction_search
+-- Description: Full-text search over transaction description, merchant, location and tag
+--              names, kept in a tsvector column maintained by triggers
+-- Date: 2025-12-20
+
+-- ============================================
+-- Search document
+-- ============================================
+
+-- Words are indexed both stemmed (indonesian) and as written (simple), so that stemmed
+-- matches and prefix matches on partial words both work. Merchant weighs most, then
+-- location and tags, then the free-text description.
+CREATE OR REPLACE FUNCTION transaction_search_document(
+  p_description text,
+  p_merchant_name text,
+  p_location text,
+  p_tags text
+) RETURNS tsvector
+LANGUAGE sql IMMUTABLE AS $$
+  SELECT setweight(to_tsvector('indonesian', coalesce(p_merchant_name, '')), 'A')
+      || setweight(to_tsvector('simple', coalesce(p_merchant_name, '')), 'A')
+      || setweight(to_tsvector('indonesian', coalesce(p_location, '')), 'B')
+      || setweight(to_tsvector('simple', coalesce(p_location, '')), 'B')
+      || setweight(to_tsvector('indonesian', coalesce(p_tags, '')), 'B')
+      || setweight(to_tsvector('simple', coalesce(p_tags, '')), 'B')
+      || setweight(to_tsvector('indonesian', coalesce(p_description, '')), 'C')
+      || setweight(to_tsvector('simple', coalesce(p_description, '')), 'C')
+$$;
+
+-- Every term must match, stemmed or as a prefix of an indexed word
+CREATE OR REPLACE FUNCTION transaction_search_query(p_terms text[]) RETURNS tsquery
+LANGUAGE plpgsql IMMUTABLE AS $$
+DECLARE
+  term text;
+  term_query tsquery;
+  result tsquery;
+BEGIN
+  FOREACH term IN ARRAY p_terms LOOP
+    term_query := to_tsquery('indonesian', quote_literal(term) || ':*')
+      || to_tsquery('simple', quote_literal(term) || ':*');
+    IF result IS NULL THEN
+      result := term_query;
+    ELSE
+      result := result && term_query;
+    END IF;
+  END LOOP;
+  RETURN result;
+END;
+$$;
+
+CREATE OR REPLACE FUNCTION transaction_tag_names(p_transaction_id uuid) RETURNS text
+LANGUAGE sql STABLE AS $$
+  SELECT string_agg(tg.name, ' ')
+  FROM transaction_tags tt
+  JOIN tags tg ON tg.id = tt.tag_id
+  WHERE tt.transaction_id = p_transaction_id
+$$;
+
+ALTER TABLE "transactions" ADD COLUMN "search_vector" tsvector NOT NULL DEFAULT ''::tsvector;
+
+COMMENT ON COLUMN "transactions"."search_vector" IS 'Full-text search document over description, merchant, location and tag names; maintained by triggers';
+
+UPDATE "transactions"
+SET search_vector = transaction_search_document(description, merchant_name, location, transaction_tag_names(id));
+
+-- ============================================
+-- Triggers
+-- ============================================
+
+CREATE OR REPLACE FUNCTION transactions_search_vector_trigger() RETURNS trigger
+LANGUAGE plpgsql AS $$
+BEGIN
+  NEW.search_vector := transaction_search_document(
+    NEW.description, NEW.merchant_name, NEW.location, transaction_tag_names(NEW.id)
+  );
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER trg_transactions_search_vector
+BEFORE INSERT OR UPDATE OF description, merchant_name, location ON "transactions"
+FOR EACH ROW EXECUTE FUNCTION transactions_search_vector_trigger();
+
+CREATE OR REPLACE FUNCTION transaction_tags_search_vector_trigger() RETURNS trigger
+LANGUAGE plpgsql AS $$
+DECLARE
+  changed_transaction_id uuid;
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    changed_transaction_id := OLD.transaction_id;
+  ELSE
+    changed_transaction_id := NEW.transaction_id;
+  END IF;
+
+  UPDATE transactions
+  SET search_vector = transaction_search_document(
+    description, merchant_name, location, transaction_tag_names(id)
+  )
+  WHERE id = changed_transaction_id;
+
+  RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_transaction_tags_search_vector
+AFTER INSERT OR DELETE ON "transaction_tags"
+FOR EACH ROW EXECUTE FUNCTION transaction_tags_search_vector_trigger();
+
+CREATE OR REPLACE FUNCTION tags_search_vector_trigger() RETURNS trigger
+LANGUAGE plpgsql AS $$
+BEGIN
+  UPDATE transactions t
+  SET search_vector = transaction_search_document(
+    t.description, t.merchant_name, t.location, transaction_tag_names(t.id)
+  )
+  FROM transaction_tags tt
+  WHERE tt.tag_id = NEW.id AND tt.transaction_id = t.id;
+
+  RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_tags_search_vector
+AFTER UPDATE OF name ON "tags"
+FOR EACH ROW EXECUTE FUNCTION tags_search_vector_trigger();
+
+-- Indexes
+CREATE INDEX idx_transactions_search_vector ON "transactions" USING GIN ("search_vector");
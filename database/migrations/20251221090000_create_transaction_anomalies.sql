@@ -0,0 +1,50 @@
+-- Migration: create_transaction_anomalies
+-- Description: Flags for transactions that look unusual for their ledger, raised by a
+--              background scan of new transactions
+-- Date: 2025-12-21
+
+-- ============================================
+-- Scan progress
+-- ============================================
+
+ALTER TABLE "transactions" ADD COLUMN "anomaly_checked_at" timestamp;
+
+COMMENT ON COLUMN "transactions"."anomaly_checked_at" IS 'When the anomaly scan looked at the transaction; NULL while it is waiting to be scanned';
+
+-- Existing transactions are history to compare against, not new entries to flag
+UPDATE "transactions" SET anomaly_checked_at = now();
+
+CREATE INDEX idx_transactions_anomaly_unchecked ON "transactions" ("created_at") WHERE anomaly_checked_at IS NULL;
+
+-- ============================================
+-- Anomalies
+-- ============================================
+
+CREATE TABLE "transaction_anomalies" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "transaction_id" uuid NOT NULL,
+  "kind" varchar(30) NOT NULL,
+  "details" jsonb NOT NULL DEFAULT '{}'::jsonb,
+  "related_transaction_id" uuid,
+  "status" varchar(20) NOT NULL DEFAULT 'open',
+  "dismissed_by" uuid,
+  "dismissed_at" timestamp,
+  "notified_at" timestamp,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_transaction_anomalies_transaction FOREIGN KEY ("transaction_id") REFERENCES "transactions" ("id") ON DELETE CASCADE,
+  CONSTRAINT fk_transaction_anomalies_related FOREIGN KEY ("related_transaction_id") REFERENCES "transactions" ("id") ON DELETE SET NULL,
+  CONSTRAINT fk_transaction_anomalies_dismissed_by FOREIGN KEY ("dismissed_by") REFERENCES "users" ("id") ON DELETE SET NULL,
+  CONSTRAINT transaction_anomalies_kind_check CHECK (kind IN ('unusual_amount', 'new_merchant_large_amount', 'possible_duplicate')),
+  CONSTRAINT transaction_anomalies_status_check CHECK (status IN ('open', 'dismissed'))
+);
+
+COMMENT ON TABLE "transaction_anomalies" IS 'Transactions flagged as statistically unusual for their ledger';
+COMMENT ON COLUMN "transaction_anomalies"."kind" IS 'unusual_amount: far above the category''s usual amounts; new_merchant_large_amount: first transaction with a merchant, large for the ledger; possible_duplicate: same amount and merchant entered minutes after another transaction';
+COMMENT ON COLUMN "transaction_anomalies"."details" IS 'Figures behind the flag, e.g. the median and z-score of the compared amounts';
+COMMENT ON COLUMN "transaction_anomalies"."related_transaction_id" IS 'The earlier transaction a possible duplicate repeats';
+COMMENT ON COLUMN "transaction_anomalies"."notified_at" IS 'When the author was told through their messaging channel';
+
+-- Indexes
+CREATE UNIQUE INDEX idx_transaction_anomalies_transaction_kind ON "transaction_anomalies" ("transaction_id", "kind");
+CREATE INDEX idx_transaction_anomalies_open ON "transaction_anomalies" ("created_at") WHERE status = 'open';
@@ -0,0 +1,24 @@
+-- Migration: add_transaction_deduplication
+-- Description: Remember which chat client a transaction came from so a re-sent message
+--              cannot record it twice, and link merged duplicates to the row kept
+-- Date: 2025-12-22
+
+-- ============================================
+-- Transactions
+-- ============================================
+
+ALTER TABLE "transactions" ADD COLUMN "client_id" uuid;
+ALTER TABLE "transactions" ADD COLUMN "merged_into_id" uuid;
+
+ALTER TABLE "transactions"
+  ADD CONSTRAINT fk_transactions_client FOREIGN KEY ("client_id") REFERENCES "clients" ("id") ON DELETE SET NULL;
+ALTER TABLE "transactions"
+  ADD CONSTRAINT fk_transactions_merged_into FOREIGN KEY ("merged_into_id") REFERENCES "transactions" ("id") ON DELETE SET NULL;
+
+COMMENT ON COLUMN "transactions"."client_id" IS 'Chat client the transaction was sent from; with source_message_id it identifies the message';
+COMMENT ON COLUMN "transactions"."merged_into_id" IS 'Transaction this soft-deleted duplicate was merged into';
+
+-- Deleted and merged transactions keep their message, so a retry does not bring them back
+CREATE UNIQUE INDEX idx_transactions_client_message ON "transactions" ("client_id", "source_message_id")
+  WHERE client_id IS NOT NULL AND source_message_id IS NOT NULL;
+CREATE INDEX idx_transactions_merged_into ON "transactions" ("merged_into_id") WHERE merged_into_id IS NOT NULL;
@@ -0,0 +1,25 @@
+-- Migration: create_idempotency_keys
+-- Description: Responses to POST/PUT/PATCH requests sent with an Idempotency-Key, replayed
+--              when a client retries the same request
+-- Date: 2025-12-23
+
+CREATE TABLE "idempotency_keys" (
+  "principal" varchar(255) NOT NULL,
+  "key" varchar(255) NOT NULL,
+  "request_hash" varchar(64) NOT NULL,
+  "status_code" smallint,
+  "response_headers" jsonb,
+  "response_body" bytea,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "expires_at" timestamp NOT NULL,
+  PRIMARY KEY ("principal", "key")
+);
+
+COMMENT ON TABLE "idempotency_keys" IS 'Stored responses of idempotent requests, per caller and key';
+COMMENT ON COLUMN "idempotency_keys"."principal" IS 'user:<uuid> or api_key:<sha256 of key>';
+COMMENT ON COLUMN "idempotency_keys"."request_hash" IS 'SHA-256 of method, path with query and body; a key reused for another request is rejected';
+COMMENT ON COLUMN "idempotency_keys"."status_code" IS 'NULL while the first request is still being processed';
+COMMENT ON COLUMN "idempotency_keys"."response_headers" IS 'Array of [name, value] pairs';
+
+-- Indexes
+CREATE INDEX idx_idempotency_keys_expires_at ON "idempotency_keys" ("expires_at");
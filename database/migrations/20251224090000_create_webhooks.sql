@@ -0,0 +1,62 @@
+-- Migration: create_webhooks
+-- Description: User-registered webhook endpoints and the outbox of signed event deliveries,
+--              retried with exponential backoff and kept as the delivery log
+-- Date: 2025-12-24
+
+-- ============================================
+-- Webhook endpoints
+-- ============================================
+
+CREATE TABLE "webhook_endpoints" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "user_id" uuid NOT NULL,
+  "url" varchar(2048) NOT NULL,
+  "description" varchar(255),
+  "secret" varchar(100) NOT NULL,
+  "events" varchar(50)[] NOT NULL,
+  "is_active" boolean NOT NULL DEFAULT true,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "updated_at" timestamp NOT NULL DEFAULT now(),
+
+  CONSTRAINT fk_webhook_endpoints_user FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE,
+  CONSTRAINT webhook_endpoints_events_not_empty CHECK (cardinality("events") > 0)
+);
+
+COMMENT ON TABLE "webhook_endpoints" IS 'URLs a user wants told about events, e.g. transaction.created';
+COMMENT ON COLUMN "webhook_endpoints"."secret" IS 'HMAC-SHA256 key for the X-Mintora-Signature header; shown to the user once';
+COMMENT ON COLUMN "webhook_endpoints"."events" IS 'Event types the endpoint is subscribed to';
+
+CREATE INDEX idx_webhook_endpoints_user_id ON "webhook_endpoints" ("user_id");
+
+-- ============================================
+-- Webhook deliveries (outbox and log)
+-- ============================================
+
+CREATE TABLE "webhook_deliveries" (
+  "id" uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  "endpoint_id" uuid NOT NULL,
+  "event_id" uuid NOT NULL,
+  "event" varchar(50) NOT NULL,
+  "payload" jsonb NOT NULL,
+  "status" varchar(20) NOT NULL DEFAULT 'pending',
+  "attempts" integer NOT NULL DEFAULT 0,
+  "next_attempt_at" timestamp NOT NULL DEFAULT now(),
+  "last_attempt_at" timestamp,
+  "response_status" smallint,
+  "response_body" text,
+  "error" text,
+  "created_at" timestamp NOT NULL DEFAULT now(),
+  "delivered_at" timestamp,
+
+  CONSTRAINT fk_webhook_deliveries_endpoint FOREIGN KEY ("endpoint_id") REFERENCES "webhook_endpoints" ("id") ON DELETE CASCADE,
+  CONSTRAINT webhook_deliveries_status_check CHECK ("status" IN ('pending', 'succeeded', 'failed'))
+);
+
+COMMENT ON TABLE "webhook_deliveries" IS 'One event sent to one endpoint; pending rows are the outbox';
+COMMENT ON COLUMN "webhook_deliveries"."event_id" IS 'Shared by every delivery of the same event, including redeliveries';
+COMMENT ON COLUMN "webhook_deliveries"."next_attempt_at" IS 'When a pending delivery is next sent; pushed ahead while a worker is sending it';
+COMMENT ON COLUMN "webhook_deliveries"."response_body" IS 'Start of the last response, for debugging';
+
+-- Indexes
+CREATE INDEX idx_webhook_deliveries_pending ON "webhook_deliveries" ("next_attempt_at") WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_endpoint_id ON "webhook_deliveries" ("endpoint_id", "created_at");
@@ -0,0 +1,19 @@
+-- Migration: unique_statement_entries
+-- Description: Key imported statement entries on their account and make the key unique per ledger
+-- Date: 2025-12-25
+
+-- ============================================
+-- Statement import deduplication
+-- ============================================
+
+-- Statement transaction ids are only unique within the bank account they come from, and
+-- two imports of the same file running at once must not both insert an entry. The ledger
+-- is the workspace, or the user for personal transactions.
+DROP INDEX IF EXISTS idx_transactions_statement_id;
+
+CREATE UNIQUE INDEX uq_transactions_statement_entry ON "transactions" (
+    (COALESCE(workspace_id, user_id)),
+    (COALESCE(metadata->'statement'->>'account', '')),
+    (metadata->'statement'->>'transaction_id')
+)
+WHERE metadata ? 'statement';
@@ -0,0 +1,17 @@
+-- Migration: widen_amount_columns
+-- Description: Store three decimal places for currencies with three minor units (BHD, KWD, JOD, OMR, ...)
+-- Date: 2025-12-26
+
+-- ============================================
+-- Money columns
+-- ============================================
+
+-- Amounts are rounded to their currency's minor units before they are stored; with two
+-- decimal places Postgres silently rounded three-decimal currencies a second time
+ALTER TABLE "transactions" ALTER COLUMN "amount" TYPE decimal(18,3);
+ALTER TABLE "transaction_splits" ALTER COLUMN "amount" TYPE decimal(18,3);
+ALTER TABLE "accounts" ALTER COLUMN "opening_balance" TYPE decimal(18,3);
+ALTER TABLE "debts" ALTER COLUMN "amount" TYPE decimal(18,3);
+ALTER TABLE "goals" ALTER COLUMN "target_amount" TYPE decimal(18,3);
+ALTER TABLE "goal_contributions" ALTER COLUMN "amount" TYPE decimal(18,3);
+ALTER TABLE "receipt_drafts" ALTER COLUMN "amount" TYPE decimal(18,3);
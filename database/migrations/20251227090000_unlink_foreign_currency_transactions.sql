@@ -0,0 +1,17 @@
+-- Migration: unlink_foreign_currency_transactions
+-- Description: Take transactions off accounts held in another currency
+-- Date: 2025-12-27
+
+-- ============================================
+-- Account balances
+-- ============================================
+
+-- Transactions were booked to the account linked to their payment method whatever their
+-- currency, adding e.g. USD amounts into an IDR balance. Transfers always match both
+-- accounts' currency and are left alone.
+UPDATE "transactions" AS t
+SET account_id = NULL, updated_at = now()
+FROM "accounts" AS a
+WHERE t.account_id = a.id
+  AND t.type <> 'transfer'
+  AND t.currency <> a.currency;
@@ -0,0 +1,50 @@
+-- Migration: keep_debt_history
+-- Description: Keep debt entries when one party is deleted, and let the other party dispute entries
+-- Date: 2025-12-28
+
+-- ============================================
+-- Debts
+-- ============================================
+
+-- Names of the user parties when the entry was recorded, shown once a party's account is gone
+ALTER TABLE "debts"
+  ADD COLUMN "creditor_name" varchar(255),
+  ADD COLUMN "debtor_name" varchar(255),
+  ADD COLUMN "disputed_at" timestamp;
+
+UPDATE "debts" AS d
+SET creditor_name = u.full_name
+FROM "users" AS u
+WHERE u.id = d.creditor_user_id;
+
+UPDATE "debts" AS d
+SET debtor_name = u.full_name
+FROM "users" AS u
+WHERE u.id = d.debtor_user_id;
+
+-- Purging one user deleted the other party's entries with them. The deleted party's ID is
+-- now cleared instead, so the parties check allows an entry between a user and a former
+-- user (one user ID, no contact name).
+ALTER TABLE "debts"
+  DROP CONSTRAINT fk_debts_created_by,
+  DROP CONSTRAINT fk_debts_creditor,
+  DROP CONSTRAINT fk_debts_debtor,
+  DROP CONSTRAINT debts_parties_check,
+  ALTER COLUMN "created_by" DROP NOT NULL;
+
+ALTER TABLE "debts"
+  ADD CONSTRAINT fk_debts_created_by FOREIGN KEY ("created_by") REFERENCES "users" ("id") ON DELETE SET NULL,
+  ADD CONSTRAINT fk_debts_creditor FOREIGN KEY ("creditor_user_id") REFERENCES "users" ("id") ON DELETE SET NULL,
+  ADD CONSTRAINT fk_debts_debtor FOREIGN KEY ("debtor_user_id") REFERENCES "users" ("id") ON DELETE SET NULL,
+  ADD CONSTRAINT debts_parties_check CHECK (
+    (created_by IS NULL OR created_by IN (creditor_user_id, debtor_user_id))
+    AND (creditor_user_id IS NULL OR debtor_user_id IS NULL OR creditor_user_id <> debtor_user_id)
+    AND (contact_name IS NULL OR (creditor_user_id IS NULL) <> (debtor_user_id IS NULL))
+  );
+
+COMMENT ON COLUMN "debts"."created_by" IS 'The user who recorded the entry; always one of the parties and the only one who can delete it. NULL once their account is deleted';
+COMMENT ON COLUMN "debts"."creditor_user_id" IS 'NULL when the creditor is the contact or their account was deleted';
+COMMENT ON COLUMN "debts"."debtor_user_id" IS 'NULL when the debtor is the contact or their account was deleted';
+COMMENT ON COLUMN "debts"."creditor_name" IS 'Full name of the creditor user when the entry was recorded';
+COMMENT ON COLUMN "debts"."debtor_name" IS 'Full name of the debtor user when the entry was recorded';
+COMMENT ON COLUMN "debts"."disputed_at" IS 'When the party who did not record the entry disputed it; disputed entries do not count towards balances';
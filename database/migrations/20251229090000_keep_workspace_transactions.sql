@@ -0,0 +1,50 @@
+-- Migration: keep_workspace_transactions
+-- Description: Keep workspace transactions when their author is deleted, and keep them off
+--              the author's personal accounts
+-- Date: 2025-12-29
+
+-- ============================================
+-- Workspace transaction authors
+-- ============================================
+
+-- transactions.user_id cascades, so deleting a user took the transactions they had added
+-- to shared workspaces with them. Hand those over to a remaining member of the workspace
+-- first (owners before editors before viewers, longest-standing first); only a workspace
+-- nobody is left in loses them.
+CREATE OR REPLACE FUNCTION reassign_workspace_transactions() RETURNS trigger
+LANGUAGE plpgsql AS $$
+BEGIN
+  UPDATE transactions AS t
+  SET user_id = m.user_id, updated_at = now()
+  FROM (
+    SELECT DISTINCT ON (wm.workspace_id) wm.workspace_id, wm.user_id
+    FROM workspace_members wm
+    WHERE wm.user_id <> OLD.id
+    ORDER BY wm.workspace_id,
+             CASE wm.role WHEN 'owner' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END,
+             wm.created_at
+  ) AS m
+  WHERE t.user_id = OLD.id
+    AND t.workspace_id = m.workspace_id;
+
+  RETURN OLD;
+END;
+$$;
+
+CREATE TRIGGER trg_users_reassign_workspace_transactions
+BEFORE DELETE ON "users"
+FOR EACH ROW EXECUTE FUNCTION reassign_workspace_transactions();
+
+COMMENT ON COLUMN "transactions"."workspace_id" IS 'Workspace ledger the transaction belongs to; NULL for the personal ledger of user_id. user_id is the author either way, or the member it was handed to when the author was deleted';
+
+-- ============================================
+-- Workspace transaction accounts
+-- ============================================
+
+-- Workspace transactions were booked to the author's personal account linked to their
+-- payment method. Only transfers pick an account explicitly, and those are personal.
+UPDATE "transactions"
+SET account_id = NULL, updated_at = now()
+WHERE workspace_id IS NOT NULL
+  AND account_id IS NOT NULL
+  AND type <> 'transfer';
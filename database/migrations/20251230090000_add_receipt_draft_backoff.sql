@@ -0,0 +1,16 @@
+-- Migration: add_receipt_draft_backoff
+-- Description: Wait between OCR attempts of a receipt draft instead of retrying at once
+-- Date: 2025-12-30
+
+-- ============================================
+-- Receipt drafts
+-- ============================================
+
+ALTER TABLE "receipt_drafts"
+ADD COLUMN "next_attempt_at" timestamp NOT NULL DEFAULT now();
+
+COMMENT ON COLUMN "receipt_drafts"."next_attempt_at" IS 'When a pending draft may be claimed next; pushed back after every failed OCR attempt';
+
+-- The queue is now read in next_attempt_at order
+DROP INDEX idx_receipt_drafts_queue;
+CREATE INDEX idx_receipt_drafts_queue ON "receipt_drafts" ("next_attempt_at") WHERE status IN ('pending', 'processing');
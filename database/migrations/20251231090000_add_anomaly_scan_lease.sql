@@ -0,0 +1,14 @@
+-- Migration: add_anomaly_scan_lease
+-- Description: Lease transactions to the anomaly scan and mark them checked one at a time
+-- Date: 2025-12-31
+
+-- ============================================
+-- Transactions
+-- ============================================
+
+-- The scan marked a whole batch checked before looking at it, so an error partway
+-- through left the rest of the batch never scanned. Claiming now only takes a lease;
+-- anomaly_checked_at is set once a transaction has been checked.
+ALTER TABLE "transactions" ADD COLUMN "anomaly_claimed_at" timestamp;
+
+COMMENT ON COLUMN "transactions"."anomaly_claimed_at" IS 'When an anomaly scan took the transaction; unchecked transactions claimed long ago are scanned again';
@@ -0,0 +1,13 @@
+-- Migration: clear_stored_idempotent_responses
+-- Description: Drop stored idempotent responses, which may hold tokens, API keys and webhook secrets
+-- Date: 2026-01-01
+
+-- ============================================
+-- Idempotency keys
+-- ============================================
+
+-- Responses carrying secrets are no longer stored, but earlier ones were. The table only
+-- caches responses for retries, so clearing it costs at most a replay.
+DELETE FROM "idempotency_keys";
+
+COMMENT ON COLUMN "idempotency_keys"."response_body" IS 'Never set for responses marked Cache-Control: no-store (tokens, API keys, signing secrets)';
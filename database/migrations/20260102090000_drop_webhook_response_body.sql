@@ -0,0 +1,12 @@
+-- Migration: drop_webhook_response_body
+-- Description: Stop keeping what webhook endpoints respond with
+-- Date: 2026-01-02
+
+-- ============================================
+-- Webhook deliveries
+-- ============================================
+
+-- The delivery log returned the start of each endpoint's response to the user who
+-- registered it, which let an endpoint URL be used to read other services. Only the
+-- status code is kept.
+ALTER TABLE "webhook_deliveries" DROP COLUMN "response_body";
@@ -0,0 +1,14 @@
+-- Migration: add_login_attempts_email_index
+-- Description: Look up login attempts by email, to lock out unknown emails like accounts
+-- Date: 2026-01-03
+
+-- ============================================
+-- Login attempts
+-- ============================================
+
+-- Logins to unknown emails are locked out after the same number of failures as accounts,
+-- counted in the attempts since the email's last successful login, so the lockout does
+-- not tell whether an email is registered.
+CREATE INDEX idx_login_attempts_email_created ON "login_attempts" ("email", "created_at");
+
+COMMENT ON TABLE "login_attempts" IS 'Every dashboard login attempt, used for per-IP throttling and for locking out emails without a password account';